@@ -245,6 +245,18 @@ pub struct ExtractionConfig {
     
     /// Flash loan extraction settings
     pub flash_loan: FlashLoanExtractionConfig,
+    
+    /// Execution wallet balance settings
+    #[serde(default)]
+    pub wallet: WalletExtractionConfig,
+    
+    /// Lending position settings
+    #[serde(default)]
+    pub lending: LendingExtractionConfig,
+    
+    /// Touched EVM state settings
+    #[serde(default)]
+    pub evm_state: EvmStateExtractionConfig,
 }
 
 /// AMM extraction configuration
@@ -314,6 +326,72 @@ pub struct FlashLoanExtractionConfig {
     pub max_fee_bps: u32,
 }
 
+/// Execution wallet balance extraction configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletExtractionConfig {
+    /// Enable wallet balance extraction
+    pub enabled: bool,
+    
+    /// Update frequency in seconds
+    pub update_frequency_seconds: u64,
+    
+    /// Our execution wallets; balances are read for each chain's `contracts.tokens`
+    pub wallets: Vec<String>,
+}
+
+impl Default for WalletExtractionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            update_frequency_seconds: 12,
+            wallets: Vec::new(),
+        }
+    }
+}
+
+/// Lending position extraction configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LendingExtractionConfig {
+    /// Enable Aave V3 position extraction; reserves are each chain's `contracts.tokens`
+    pub enabled: bool,
+    
+    /// Block to replay Pool events from on startup, by chain, so borrowers
+    /// whose last action predates the first extracted block are tracked;
+    /// chains without one only see positions opened or changed from then on
+    pub backfill_from_block: HashMap<Chain, u64>,
+    
+    /// Blocks per `eth_getLogs` request while backfilling
+    pub backfill_chunk_blocks: u64,
+    
+    /// Blocks between reads of reserve configuration and oracle prices
+    pub reserve_refresh_blocks: u64,
+}
+
+impl Default for LendingExtractionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backfill_from_block: HashMap::new(),
+            backfill_chunk_blocks: 2_000,
+            reserve_refresh_blocks: 25,
+        }
+    }
+}
+
+/// Touched EVM state extraction configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvmStateExtractionConfig {
+    /// Enable touched state extraction; needs providers serving `debug_traceBlockByNumber`
+    pub enabled: bool,
+    
+    /// Pools and other contracts the Intelligence layer executes against, by
+    /// chain; each chain's `contracts.dex_routers`, `flash_loan_providers`
+    /// and `tokens` are always watched
+    #[serde(default)]
+    pub contracts: HashMap<Chain, Vec<String>>,
+}
+
 /// Data feeds configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeedsConfig {
@@ -739,6 +817,9 @@ impl Default for BetaDataplaneConfig {
                     update_frequency_seconds: 5,
                     max_fee_bps: 100,
                 },
+                wallet: WalletExtractionConfig::default(),
+                lending: LendingExtractionConfig::default(),
+                evm_state: EvmStateExtractionConfig::default(),
             },
             feeds: FeedsConfig {
                 kafka: KafkaConfig {
@@ -20,6 +20,7 @@ use qenus_dataplane::{
     AmmFeature, TokenInfo, DepthCurve, SlippageInfo,
 };
 
+use super::{token1_usd_price, DEPTH_SAMPLE_SIZES};
 use crate::{
     extractors::traits::{BetaFeatureExtractor, ExtractionContext, ExtractorConfig},
     providers::EthereumRpcClient,
@@ -162,11 +163,13 @@ impl BalancerExtractor {
         }
 
         // Calculate depth curve
-        let depth = self.calculate_depth_curve(mid_price, liquidity.parse::<f64>().unwrap_or(0.0), &pool.weights);
+        let token1_usd = token1_usd_price(&token0_info.symbol, &token1_info.symbol, mid_price);
+        let depth = self.calculate_depth_curve(mid_price, token1_usd, liquidity.parse::<f64>().unwrap_or(0.0), &pool.weights);
 
         Ok(AmmFeature {
             pool_address: format!("{:?}", pool.address),
             pool_type: format!("balancer_{}", pool.pool_type),
+            pool_id: Some(pool.pool_id.clone()),
             token0: token0_info,
             token1: token1_info,
             fee_tier: Some((pool.swap_fee * 10000.0) as u32), // Convert to bps
@@ -180,8 +183,23 @@ impl BalancerExtractor {
     }
 
     /// Calculate depth curve considering pool weights
-    fn calculate_depth_curve(&self, mid_price: f64, liquidity: f64, weights: &[f64]) -> DepthCurve {
-        let mut sizes = HashMap::new();
+    fn calculate_depth_curve(&self, mid_price: f64, token1_usd: Option<f64>, liquidity: f64, weights: &[f64]) -> DepthCurve {
+        // Balancer uses weighted constant product formula
+        // Slippage depends on pool weights and liquidity
+        let slippage_at = |trade_size_usd: f64| {
+            if liquidity > 0.0 {
+                let weight_factor = weights.get(0).unwrap_or(&0.5);
+                let base_slippage = (trade_size_usd / liquidity) * 8000.0 / weight_factor;
+                base_slippage.min(1000.0) // Cap at 10%
+            } else {
+                1000.0
+            }
+        };
+
+        // Sampled quotes need token1's USD price to turn the USD sizes into token amounts
+        let mut depth = token1_usd
+            .map(|token1_usd| DepthCurve::sample(mid_price, token1_usd, &DEPTH_SAMPLE_SIZES, slippage_at))
+            .unwrap_or_default();
 
         let trade_sizes = vec![
             ("10k", 10_000.0),
@@ -191,17 +209,9 @@ impl BalancerExtractor {
         ];
 
         for (size_label, trade_size_usd) in trade_sizes {
-            // Balancer uses weighted constant product formula
-            // Slippage depends on pool weights and liquidity
-            let slippage_bps = if liquidity > 0.0 {
-                let weight_factor = weights.get(0).unwrap_or(&0.5);
-                let base_slippage = (trade_size_usd / liquidity) * 8000.0 / weight_factor;
-                base_slippage.min(1000.0) // Cap at 10%
-            } else {
-                1000.0
-            };
+            let slippage_bps = slippage_at(trade_size_usd);
 
-            sizes.insert(
+            depth.sizes.insert(
                 size_label.to_string(),
                 SlippageInfo {
                     slippage_bps,
@@ -210,7 +220,7 @@ impl BalancerExtractor {
             );
         }
 
-        DepthCurve { sizes }
+        depth
     }
 }
 
@@ -254,7 +264,7 @@ impl BetaFeatureExtractor for BalancerExtractor {
                         chain,
                         timestamp: Utc::now(),
                         feature_type: FeatureType::Amm,
-                        data: FeatureData::Amm(amm_feature),
+                        data: FeatureData::Amm(Box::new(amm_feature)),
                         source: "balancer_extractor".to_string(),
                         version: "1.0.0".to_string(),
                     };
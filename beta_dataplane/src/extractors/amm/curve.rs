@@ -8,7 +8,6 @@
 //! - Liquidity and reserves
 
 use async_trait::async_trait;
-use std::collections::HashMap;
 use std::time::Instant;
 use tracing::{debug, info, warn};
 use ethers::types::{H160, U256};
@@ -20,6 +19,7 @@ use qenus_dataplane::{
     AmmFeature, TokenInfo, DepthCurve, SlippageInfo,
 };
 
+use super::{token1_usd_price, DEPTH_SAMPLE_SIZES};
 use crate::{
     extractors::traits::{BetaFeatureExtractor, ExtractionContext, ExtractorConfig},
     providers::EthereumRpcClient,
@@ -145,11 +145,13 @@ impl CurveExtractor {
         let reserves = balances_map;
 
         // Calculate depth curve
-        let depth = self.calculate_depth_curve(mid_price, liquidity.parse::<f64>().unwrap_or(0.0));
+        let token1_usd = token1_usd_price(&token0_info.symbol, &token1_info.symbol, mid_price);
+        let depth = self.calculate_depth_curve(mid_price, token1_usd, liquidity.parse::<f64>().unwrap_or(0.0));
 
         Ok(AmmFeature {
             pool_address: format!("{:?}", pool.address),
             pool_type: format!("curve_{}", pool.pool_type),
+            pool_id: None,
             token0: token0_info,
             token1: token1_info,
             fee_tier: Some(4), // Curve typically 0.04% fee
@@ -163,8 +165,21 @@ impl CurveExtractor {
     }
 
     /// Calculate simplified depth curve
-    fn calculate_depth_curve(&self, mid_price: f64, liquidity: f64) -> DepthCurve {
-        let mut sizes = HashMap::new();
+    fn calculate_depth_curve(&self, mid_price: f64, token1_usd: Option<f64>, liquidity: f64) -> DepthCurve {
+        // Curve has better price stability than AMMs
+        let slippage_at = |trade_size_usd: f64| {
+            if liquidity > 0.0 {
+                let base_slippage = (trade_size_usd / liquidity) * 5000.0; // Lower than Uniswap
+                base_slippage.min(500.0) // Cap at 5%
+            } else {
+                500.0
+            }
+        };
+
+        // Sampled quotes need token1's USD price to turn the USD sizes into token amounts
+        let mut depth = token1_usd
+            .map(|token1_usd| DepthCurve::sample(mid_price, token1_usd, &DEPTH_SAMPLE_SIZES, slippage_at))
+            .unwrap_or_default();
 
         let trade_sizes = vec![
             ("10k", 10_000.0),
@@ -174,15 +189,9 @@ impl CurveExtractor {
         ];
 
         for (size_label, trade_size_usd) in trade_sizes {
-            // Curve has better price stability than AMMs
-            let slippage_bps = if liquidity > 0.0 {
-                let base_slippage = (trade_size_usd / liquidity) * 5000.0; // Lower than Uniswap
-                base_slippage.min(500.0) // Cap at 5%
-            } else {
-                500.0
-            };
+            let slippage_bps = slippage_at(trade_size_usd);
 
-            sizes.insert(
+            depth.sizes.insert(
                 size_label.to_string(),
                 SlippageInfo {
                     slippage_bps,
@@ -191,7 +200,7 @@ impl CurveExtractor {
             );
         }
 
-        DepthCurve { sizes }
+        depth
     }
 }
 
@@ -235,7 +244,7 @@ impl BetaFeatureExtractor for CurveExtractor {
                         chain,
                         timestamp: Utc::now(),
                         feature_type: FeatureType::Amm,
-                        data: FeatureData::Amm(amm_feature),
+                        data: FeatureData::Amm(Box::new(amm_feature)),
                         source: "curve_extractor".to_string(),
                         version: "1.0.0".to_string(),
                     };
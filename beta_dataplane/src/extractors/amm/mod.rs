@@ -7,6 +7,30 @@ pub mod uniswap_v3;
 pub mod curve;
 pub mod balancer;
 
+/// Trade values in USD at which depth curves are sampled on both sides of the book
+pub(crate) const DEPTH_SAMPLE_SIZES: [f64; 10] = [
+    1_000.0, 10_000.0, 50_000.0, 100_000.0, 250_000.0,
+    500_000.0, 1_000_000.0, 2_500_000.0, 5_000_000.0, 10_000_000.0,
+];
+
+/// Tokens valued at one dollar when pricing depth samples
+const USD_STABLECOINS: &[&str] = &["USDC", "USDT", "DAI", "FRAX", "LUSD", "USDC.e"];
+
+/// USD price of token1, read off the pool when either side is a stablecoin
+///
+/// Depth samples are only published for pools this can price; others keep
+/// just the labelled USD sizes.
+pub(crate) fn token1_usd_price(token0_symbol: &str, token1_symbol: &str, mid_price: f64) -> Option<f64> {
+    let price = if USD_STABLECOINS.contains(&token1_symbol) {
+        1.0
+    } else if USD_STABLECOINS.contains(&token0_symbol) {
+        1.0 / mid_price
+    } else {
+        return None;
+    };
+    Some(price).filter(|price| price.is_finite() && *price > 0.0)
+}
+
 // Re-export extractors
 pub use uniswap_v3::UniswapV3Extractor;
 pub use curve::CurveExtractor;
@@ -17,6 +17,7 @@ use qenus_dataplane::{
     AmmFeature, TokenInfo, DepthCurve, SlippageInfo,
 };
 
+use super::{token1_usd_price, DEPTH_SAMPLE_SIZES};
 use crate::{
     extractors::traits::{BetaFeatureExtractor, ExtractionContext, ExtractorConfig},
     providers::EthereumRpcClient,
@@ -153,7 +154,8 @@ impl UniswapV3Extractor {
         );
 
         // Calculate depth curve
-        let depth = self.calculate_depth_curve(mid_price, liquidity);
+        let token1_usd = token1_usd_price(&pool.token0_symbol, &pool.token1_symbol, mid_price);
+        let depth = self.calculate_depth_curve(mid_price, token1_usd, liquidity);
 
         // Create token info
         let token0_info = TokenInfo {
@@ -176,6 +178,7 @@ impl UniswapV3Extractor {
         Ok(AmmFeature {
             pool_address: format!("{:?}", pool.address),
             pool_type: format!("uniswap_v3_{}_bps", pool.fee),
+            pool_id: None,
             token0: token0_info,
             token1: token1_info,
             fee_tier: Some(pool.fee),
@@ -241,10 +244,27 @@ impl UniswapV3Extractor {
     }
 
     /// Calculate slippage depth curve for different trade sizes
-    fn calculate_depth_curve(&self, mid_price: f64, liquidity: U256) -> DepthCurve {
-        let mut sizes = HashMap::new();
+    fn calculate_depth_curve(&self, mid_price: f64, token1_usd: Option<f64>, liquidity: U256) -> DepthCurve {
+        // Simplified slippage calculation
+        // In production, would use actual Uniswap V3 tick math
+        let liquidity_f64 = liquidity.as_u128() as f64;
+        let liquidity_usd = liquidity_f64 * mid_price / 1e18; // Rough estimate
         
-        // Calculate slippage for standard trade sizes
+        // Estimate slippage based on trade size vs liquidity
+        let slippage_at = |trade_size_usd: f64| {
+            if liquidity_usd > 0.0 {
+                (trade_size_usd / liquidity_usd) * 10000.0
+            } else {
+                10000.0 // 100% if no liquidity
+            }
+        };
+        
+        // Sampled quotes need token1's USD price to turn the USD sizes into token amounts
+        let mut depth = token1_usd
+            .map(|token1_usd| DepthCurve::sample(mid_price, token1_usd, &DEPTH_SAMPLE_SIZES, slippage_at))
+            .unwrap_or_default();
+        
+        // Labelled standard trade sizes for older consumers
         let trade_sizes = vec![
             ("100k", 100_000.0),
             ("1m", 1_000_000.0),
@@ -252,21 +272,10 @@ impl UniswapV3Extractor {
         ];
 
         for (size_label, trade_size_usd) in trade_sizes {
-            // Simplified slippage calculation
-            // In production, would use actual Uniswap V3 tick math
-            let liquidity_f64 = liquidity.as_u128() as f64;
-            
-            // Estimate slippage based on trade size vs liquidity
-            let liquidity_usd = liquidity_f64 * mid_price / 1e18; // Rough estimate
-            let slippage_bps = if liquidity_usd > 0.0 {
-                (trade_size_usd / liquidity_usd) * 10000.0
-            } else {
-                10000.0 // 100% if no liquidity
-            };
-            
+            let slippage_bps = slippage_at(trade_size_usd);
             let price_impact = slippage_bps / 10000.0;
             
-            sizes.insert(
+            depth.sizes.insert(
                 size_label.to_string(),
                 SlippageInfo {
                     slippage_bps,
@@ -275,7 +284,7 @@ impl UniswapV3Extractor {
             );
         }
 
-        DepthCurve { sizes }
+        depth
     }
 }
 
@@ -317,7 +326,7 @@ impl BetaFeatureExtractor for UniswapV3Extractor {
                         block_number,
                         chain,
                         FeatureType::Amm,
-                        FeatureData::Amm(amm_feature),
+                        FeatureData::Amm(Box::new(amm_feature)),
                         format!("beta-{}", self.name()),
                     );
                     features.push(feature);
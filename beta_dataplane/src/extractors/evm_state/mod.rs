@@ -0,0 +1,10 @@
+//! EVM state extractors
+//!
+//! Extracts the account code, balances and storage slots that each block
+//! touched on watched contracts, so the Intelligence layer can execute intents
+//! against current state in a local EVM.
+
+pub mod touched;
+
+// Re-export extractors
+pub use touched::TouchedStateExtractor;
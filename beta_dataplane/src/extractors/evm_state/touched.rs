@@ -0,0 +1,288 @@
+//! Touched state extractor
+//!
+//! Traces each block with geth's prestateTracer twice:
+//! - Without diff mode, for the value of every slot a transaction read or
+//!   wrote before the block changed it
+//! - With diff mode, for the values transactions wrote
+//!
+//! Applying the writes over the first reads gives the post-block value of
+//! everything the block touched on the watched contracts.
+
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use tracing::info;
+use ethers::types::H160;
+use serde_json::Value;
+use uuid::Uuid;
+use chrono::Utc;
+
+use qenus_dataplane::{EvmAccountState, EvmStateFeature, Feature, FeatureData, FeatureType};
+
+use crate::{
+    extractors::traits::{BetaFeatureExtractor, ExtractionContext, ExtractorConfig},
+    providers::EthereumRpcClient,
+    Chain, Result, BetaDataplaneError,
+};
+
+/// Zero storage word, for slots a block cleared
+const ZERO_WORD: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Touched state extractor
+pub struct TouchedStateExtractor {
+    config: ExtractorConfig,
+    contracts: HashMap<Chain, HashSet<H160>>,
+    clients: HashMap<Chain, EthereumRpcClient>,
+}
+
+impl TouchedStateExtractor {
+    /// Create a new extractor watching `contracts` on each chain
+    pub fn new(config: ExtractorConfig, contracts: HashMap<Chain, HashSet<H160>>) -> Self {
+        Self {
+            config,
+            contracts,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Set the RPC client used for `chain`; it must serve `debug_traceBlockByNumber`
+    pub fn with_client(mut self, chain: Chain, client: EthereumRpcClient) -> Self {
+        self.clients.insert(chain, client);
+        self
+    }
+
+    /// Extract the post-block state the block touched on watched contracts
+    async fn extract_block(&self, chain: Chain, block_number: u64) -> Result<EvmStateFeature> {
+        let client = self.clients.get(&chain)
+            .ok_or_else(|| BetaDataplaneError::internal("RPC client not set"))?;
+        let watched = self.contracts.get(&chain)
+            .ok_or_else(|| BetaDataplaneError::internal("No watched contracts on chain"))?;
+
+        let reads = client.trace_block_prestate(block_number, false).await?;
+        let writes = client.trace_block_prestate(block_number, true).await?;
+
+        Ok(EvmStateFeature { accounts: touched_state(watched, &reads, &writes) })
+    }
+}
+
+/// Post-block state of the watched accounts in a block's prestate traces
+///
+/// `reads` holds, per transaction, every account it touched with its values
+/// before the transaction; the first transaction to touch a slot therefore
+/// holds its value before the block. `writes` holds, per transaction, the
+/// `pre` and `post` values of what it changed; a slot in `pre` that is
+/// missing from `post` was cleared.
+fn touched_state(watched: &HashSet<H160>, reads: &Value, writes: &Value) -> Vec<EvmAccountState> {
+    let mut accounts: HashMap<H160, EvmAccountState> = HashMap::new();
+
+    for transaction in traces(reads) {
+        for (address, state) in traced_accounts(transaction, watched) {
+            let account = account_entry(&mut accounts, address);
+            if account.balance.is_none() {
+                account.balance = string_field(state, "balance");
+            }
+            if account.nonce.is_none() {
+                account.nonce = state.get("nonce").and_then(Value::as_u64);
+            }
+            if account.code.is_none() {
+                account.code = string_field(state, "code");
+            }
+            for (slot, value) in storage(state) {
+                account.storage.entry(slot).or_insert(value);
+            }
+        }
+    }
+
+    for transaction in traces(writes) {
+        let post: HashMap<H160, &Value> = transaction.get("post")
+            .map(|post| traced_accounts(post, watched).collect())
+            .unwrap_or_default();
+
+        for (address, pre) in transaction.get("pre").map(|pre| traced_accounts(pre, watched)).into_iter().flatten() {
+            let written = post.get(&address).and_then(|state| state.get("storage"));
+            let account = account_entry(&mut accounts, address);
+            for (slot, _) in storage(pre) {
+                if written.and_then(|written| written.get(&slot)).is_none() {
+                    account.storage.insert(slot, ZERO_WORD.to_string());
+                }
+            }
+        }
+
+        for (address, state) in post {
+            let account = account_entry(&mut accounts, address);
+            if let Some(balance) = string_field(state, "balance") {
+                account.balance = Some(balance);
+            }
+            if let Some(nonce) = state.get("nonce").and_then(Value::as_u64) {
+                account.nonce = Some(nonce);
+            }
+            if let Some(code) = string_field(state, "code") {
+                account.code = Some(code);
+            }
+            account.storage.extend(storage(state));
+        }
+    }
+
+    accounts.into_values().collect()
+}
+
+fn account_entry(accounts: &mut HashMap<H160, EvmAccountState>, address: H160) -> &mut EvmAccountState {
+    accounts.entry(address).or_insert_with(|| EvmAccountState {
+        address: format!("{:?}", address),
+        balance: None,
+        nonce: None,
+        code: None,
+        storage: HashMap::new(),
+    })
+}
+
+/// Per-transaction results of a block trace
+///
+/// Geth wraps each result as `{txHash, result}`; older versions return
+/// `{result}` only.
+fn traces(block_trace: &Value) -> impl Iterator<Item = &Value> + '_ {
+    block_trace.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|trace| trace.get("result"))
+}
+
+/// Watched accounts of an `{address: state}` map
+fn traced_accounts<'a>(
+    accounts: &'a Value,
+    watched: &'a HashSet<H160>,
+) -> impl Iterator<Item = (H160, &'a Value)> + 'a {
+    accounts.as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(address, state)| Some((address.parse::<H160>().ok()?, state)))
+        .filter(|(address, _)| watched.contains(address))
+}
+
+/// `(slot, value)` pairs of an account's traced storage
+fn storage(state: &Value) -> impl Iterator<Item = (String, String)> + '_ {
+    state.get("storage")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(slot, value)| Some((slot.clone(), value.as_str()?.to_string())))
+}
+
+fn string_field(state: &Value, field: &str) -> Option<String> {
+    state.get(field).and_then(Value::as_str).map(str::to_string)
+}
+
+#[async_trait]
+impl BetaFeatureExtractor for TouchedStateExtractor {
+    fn name(&self) -> &'static str {
+        "touched_state"
+    }
+
+    fn feature_type(&self) -> FeatureType {
+        FeatureType::EvmState
+    }
+
+    fn supported_chains(&self) -> Vec<Chain> {
+        self.clients.keys()
+            .filter(|chain| self.contracts.get(chain).is_some_and(|contracts| !contracts.is_empty()))
+            .copied()
+            .collect()
+    }
+
+    async fn extract_for_block(
+        &self,
+        chain: Chain,
+        block_number: u64,
+        _context: &ExtractionContext,
+    ) -> Result<Vec<Feature>> {
+        let start_time = Instant::now();
+
+        let evm_state = self.extract_block(chain, block_number).await?;
+        let accounts = evm_state.accounts.len();
+        if accounts == 0 {
+            return Ok(Vec::new());
+        }
+
+        let feature = Feature {
+            id: Uuid::new_v4(),
+            block_number,
+            chain,
+            timestamp: Utc::now(),
+            feature_type: FeatureType::EvmState,
+            data: FeatureData::EvmState(evm_state),
+            source: "touched_state_extractor".to_string(),
+            version: "1.0.0".to_string(),
+        };
+
+        let elapsed = start_time.elapsed();
+        info!(
+            accounts,
+            duration_ms = elapsed.as_millis(),
+            "Touched state extraction completed"
+        );
+
+        Ok(vec![feature])
+    }
+
+    async fn extract_latest(
+        &self,
+        chain: Chain,
+        context: &ExtractionContext,
+    ) -> Result<Vec<Feature>> {
+        self.extract_for_block(chain, context.block_number, context).await
+    }
+
+    fn config(&self) -> ExtractorConfig {
+        self.config.clone()
+    }
+
+    async fn update_config(&mut self, config: ExtractorConfig) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const POOL: &str = "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640";
+    const OTHER: &str = "0x00000000000000000000000000000000000000aa";
+
+    #[test]
+    fn test_writes_apply_over_first_reads() {
+        let watched: HashSet<H160> = [POOL.parse().unwrap()].into_iter().collect();
+        let reads = json!([
+            { "txHash": "0x1", "result": {
+                POOL: { "balance": "0x10", "nonce": 1, "code": "0x60", "storage": { "0x0": "0xa", "0x1": "0xb" } },
+                OTHER: { "balance": "0x1" },
+            }},
+            // Already changed by the first transaction; not the pre-block value
+            { "txHash": "0x2", "result": { POOL: { "balance": "0x10", "storage": { "0x0": "0xc", "0x2": "0xd" } } } },
+        ]);
+        let writes = json!([
+            { "txHash": "0x1", "result": {
+                "pre": { POOL: { "storage": { "0x0": "0xa", "0x1": "0xb" } } },
+                "post": { POOL: { "storage": { "0x0": "0xc" } } },
+            }},
+            { "txHash": "0x2", "result": {
+                "pre": { POOL: { "balance": "0x10" } },
+                "post": { POOL: { "balance": "0x20" } },
+            }},
+        ]);
+
+        let accounts = touched_state(&watched, &reads, &writes);
+        assert_eq!(accounts.len(), 1);
+
+        let pool = &accounts[0];
+        assert_eq!(pool.address, POOL);
+        assert_eq!(pool.balance.as_deref(), Some("0x20"));
+        assert_eq!(pool.nonce, Some(1));
+        assert_eq!(pool.storage["0x0"], "0xc");
+        // Cleared by the first transaction
+        assert_eq!(pool.storage["0x1"], ZERO_WORD);
+        // Only read
+        assert_eq!(pool.storage["0x2"], "0xd");
+    }
+}
@@ -0,0 +1,359 @@
+//! Aave V3 position extractor
+//!
+//! Extracts what the Intelligence layer needs to track borrower health:
+//! - Pool position events (Supply, Withdraw, Borrow, Repay, LiquidationCall)
+//! - Reserve configuration (LTV, liquidation threshold and bonus, decimals)
+//! - Aave oracle prices
+//!
+//! Pool events before the first extracted block are replayed once per chain
+//! from a configured start block, so borrowers who have not acted since are
+//! tracked too. Reserve configuration and prices are re-read on an interval
+//! rather than every block.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use ethers::types::{Filter, Log, H160, H256};
+use ethers::utils::keccak256;
+use uuid::Uuid;
+use chrono::Utc;
+
+use qenus_dataplane::{
+    Feature, FeatureData, FeatureType, LendingFeature, LendingReserve, TokenInfo,
+};
+
+use crate::{
+    config::LendingExtractionConfig,
+    extractors::traits::{BetaFeatureExtractor, ExtractionContext, ExtractorConfig},
+    providers::EthereumRpcClient,
+    utils::ContractRegistry,
+    Chain, Result, BetaDataplaneError,
+};
+
+/// Pool events that change a borrower's collateral or debt
+const POSITION_EVENTS: [&str; 5] = [
+    "Supply(address,address,address,uint256,uint16)",
+    "Withdraw(address,address,address,uint256)",
+    "Borrow(address,address,address,uint256,uint8,uint256,uint16)",
+    "Repay(address,address,address,uint256,bool)",
+    "LiquidationCall(address,address,address,uint256,uint256,address,bool)",
+];
+
+/// Aave oracle prices are USD with 8 decimals
+const ORACLE_DECIMALS: i32 = 8;
+
+/// Backfilled events per feature, keeping the replay within the
+/// Intelligence layer's lending log
+const BACKFILL_EVENTS_PER_FEATURE: usize = 5_000;
+
+/// Backfill and reserve refresh progress of one chain
+#[derive(Debug, Default)]
+struct ChainProgress {
+    /// Pool events before the first extracted block have been replayed
+    backfilled: bool,
+
+    /// Block reserve configuration and prices were last read at
+    reserves_read_at: Option<u64>,
+}
+
+/// Aave V3 position extractor
+pub struct AaveV3PositionExtractor {
+    config: ExtractorConfig,
+    lending: LendingExtractionConfig,
+    tokens: HashMap<Chain, Vec<H160>>,
+    clients: HashMap<Chain, EthereumRpcClient>,
+    /// Symbol and decimals of each chain's reserves, read once per client
+    reserves: HashMap<Chain, Vec<(H160, TokenInfo)>>,
+    /// Held for a chain's whole extraction, so its backfill runs once
+    progress: HashMap<Chain, Mutex<ChainProgress>>,
+}
+
+impl AaveV3PositionExtractor {
+    /// Create a new Aave V3 position extractor tracking `tokens` as reserves
+    pub fn new(config: ExtractorConfig, lending: LendingExtractionConfig, tokens: HashMap<Chain, Vec<H160>>) -> Self {
+        Self {
+            config,
+            lending,
+            tokens,
+            clients: HashMap::new(),
+            reserves: HashMap::new(),
+            progress: HashMap::new(),
+        }
+    }
+
+    /// Set the RPC client used for `chain`
+    ///
+    /// Reads the symbol and decimals of the chain's reserves once here;
+    /// tokens whose metadata cannot be read are skipped.
+    pub async fn with_client(mut self, chain: Chain, client: EthereumRpcClient) -> Self {
+        let mut reserves = Vec::new();
+        for token_address in self.tokens.get(&chain).into_iter().flatten() {
+            let info = async {
+                Ok::<_, BetaDataplaneError>(TokenInfo {
+                    address: format!("{:?}", token_address),
+                    symbol: client.get_erc20_symbol(*token_address).await?,
+                    decimals: client.get_erc20_decimals(*token_address).await?,
+                })
+            };
+            match info.await {
+                Ok(info) => reserves.push((*token_address, info)),
+                Err(e) => warn!(chain = %chain, token = %token_address, error = %e, "Skipping reserve without readable metadata"),
+            }
+        }
+        self.reserves.insert(chain, reserves);
+        self.clients.insert(chain, client);
+        self.progress.insert(chain, Mutex::new(ChainProgress::default()));
+        self
+    }
+
+    /// Extract the pool's position features at a block
+    ///
+    /// The first call on a chain is preceded by the backfill; reserve state
+    /// is only included when due for a refresh.
+    async fn extract_pool(&self, chain: Chain, block_number: u64) -> Result<Vec<(u64, LendingFeature)>> {
+        let (client, progress) = self.clients.get(&chain).zip(self.progress.get(&chain))
+            .ok_or_else(|| BetaDataplaneError::internal("RPC client not set"))?;
+        let pool = ContractRegistry::get_aave_v3_pool(chain)
+            .ok_or_else(|| BetaDataplaneError::internal("No Aave V3 pool on chain"))?;
+
+        let mut progress = progress.lock().await;
+        let mut features = Vec::new();
+        if !progress.backfilled {
+            features = self.backfill(chain, client, pool, block_number).await?;
+            progress.backfilled = true;
+        }
+
+        let events = position_events(client, pool, block_number, block_number).await?;
+        let refresh = progress.reserves_read_at
+            .map_or(true, |read_at| block_number >= read_at + self.lending.reserve_refresh_blocks);
+        let reserves = if refresh {
+            progress.reserves_read_at = Some(block_number);
+            self.read_reserves(chain, client, pool).await
+        } else {
+            Vec::new()
+        };
+
+        features.push((block_number, lending_feature(pool, events, reserves)));
+        Ok(features)
+    }
+
+    /// Replay Pool events from the chain's backfill start block up to
+    /// `block_number`, packed into features stamped with their last block
+    async fn backfill(
+        &self,
+        chain: Chain,
+        client: &EthereumRpcClient,
+        pool: H160,
+        block_number: u64,
+    ) -> Result<Vec<(u64, LendingFeature)>> {
+        let Some(&from_block) = self.lending.backfill_from_block.get(&chain) else {
+            return Ok(Vec::new());
+        };
+        let chunk_blocks = self.lending.backfill_chunk_blocks.max(1);
+
+        let mut features = Vec::new();
+        let mut pending = Vec::new();
+        let mut replayed = 0;
+        let mut start = from_block;
+        while start < block_number {
+            let end = (start + chunk_blocks - 1).min(block_number - 1);
+            pending.extend(position_events(client, pool, start, end).await?);
+            let last = end + 1 == block_number;
+            if (pending.len() >= BACKFILL_EVENTS_PER_FEATURE || last) && !pending.is_empty() {
+                replayed += pending.len();
+                features.push((end, lending_feature(pool, std::mem::take(&mut pending), Vec::new())));
+            }
+            start = end + 1;
+        }
+
+        info!(
+            chain = %chain,
+            from_block,
+            to_block = block_number.saturating_sub(1),
+            events = replayed,
+            "Backfilled Aave V3 position events"
+        );
+        Ok(features)
+    }
+
+    /// Read configuration and oracle price of the chain's reserves
+    ///
+    /// Reserves that cannot be read are left out.
+    async fn read_reserves(&self, chain: Chain, client: &EthereumRpcClient, pool: H160) -> Vec<LendingReserve> {
+        let oracle = ContractRegistry::get_aave_v3_oracle(chain);
+        let mut reserves = Vec::new();
+        for (token_address, token) in self.reserves.get(&chain).into_iter().flatten() {
+            let configuration = match client.get_aave_reserve_data(pool, *token_address).await {
+                Ok(data) => data.configuration,
+                Err(e) => {
+                    warn!(reserve = %token.symbol, error = %e, "Failed to get Aave reserve data");
+                    continue;
+                }
+            };
+
+            let oracle_price_usd = match oracle {
+                Some(oracle) => match client.get_aave_asset_price(oracle, *token_address).await {
+                    Ok(price) => Some(price.low_u128() as f64 / 10f64.powi(ORACLE_DECIMALS)),
+                    Err(e) => {
+                        warn!(reserve = %token.symbol, error = %e, "Failed to get Aave oracle price");
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            reserves.push(LendingReserve {
+                token: token.clone(),
+                configuration: format!("{:#x}", configuration),
+                oracle_price_usd,
+            });
+        }
+        reserves
+    }
+}
+
+/// Position events the pool emitted in `from_block..=to_block`
+async fn position_events(
+    client: &EthereumRpcClient,
+    pool: H160,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<qenus_dataplane::Log>> {
+    let topics: Vec<H256> = POSITION_EVENTS.iter()
+        .map(|signature| H256::from(keccak256(signature.as_bytes())))
+        .collect();
+    let filter = Filter::new()
+        .address(pool)
+        .topic0(topics)
+        .from_block(from_block)
+        .to_block(to_block);
+    Ok(client.get_logs(filter).await?
+        .iter()
+        .map(|log| to_feature_log(log, to_block))
+        .collect())
+}
+
+/// Aave V3 feature of `pool`; reserves are empty when not refreshed
+fn lending_feature(pool: H160, events: Vec<qenus_dataplane::Log>, reserves: Vec<LendingReserve>) -> LendingFeature {
+    LendingFeature {
+        protocol: "aave_v3".to_string(),
+        pool_address: format!("{:?}", pool),
+        events,
+        reserves,
+    }
+}
+
+/// Normalize an RPC log into the dataplane schema
+fn to_feature_log(log: &Log, block_number: u64) -> qenus_dataplane::Log {
+    qenus_dataplane::Log {
+        address: format!("{:?}", log.address),
+        topics: log.topics.iter().map(|topic| format!("{:?}", topic)).collect(),
+        data: format!("0x{}", hex::encode(&log.data)),
+        block_number: log.block_number.map(|number| number.as_u64()).unwrap_or(block_number),
+        transaction_hash: log.transaction_hash.map(|hash| format!("{:?}", hash)).unwrap_or_default(),
+        transaction_index: log.transaction_index.map(|index| index.as_u64()).unwrap_or(0),
+        log_index: log.log_index.map(|index| index.as_u64()).unwrap_or(0),
+        removed: log.removed.unwrap_or(false),
+    }
+}
+
+#[async_trait]
+impl BetaFeatureExtractor for AaveV3PositionExtractor {
+    fn name(&self) -> &'static str {
+        "aave_v3_positions"
+    }
+
+    fn feature_type(&self) -> FeatureType {
+        FeatureType::Lending
+    }
+
+    fn supported_chains(&self) -> Vec<Chain> {
+        self.clients.keys().copied().collect()
+    }
+
+    async fn extract_for_block(
+        &self,
+        chain: Chain,
+        block_number: u64,
+        _context: &ExtractionContext,
+    ) -> Result<Vec<Feature>> {
+        let start_time = Instant::now();
+
+        let lending_features = self.extract_pool(chain, block_number).await?;
+        let events: usize = lending_features.iter().map(|(_, feature)| feature.events.len()).sum();
+        let features: Vec<Feature> = lending_features.into_iter()
+            .map(|(block_number, lending_feature)| Feature {
+                id: Uuid::new_v4(),
+                block_number,
+                chain,
+                timestamp: Utc::now(),
+                feature_type: FeatureType::Lending,
+                data: FeatureData::Lending(lending_feature),
+                source: "aave_v3_position_extractor".to_string(),
+                version: "1.0.0".to_string(),
+            })
+            .collect();
+
+        let elapsed = start_time.elapsed();
+        info!(
+            events,
+            duration_ms = elapsed.as_millis(),
+            "Aave V3 position extraction completed"
+        );
+
+        Ok(features)
+    }
+
+    async fn extract_latest(
+        &self,
+        chain: Chain,
+        context: &ExtractionContext,
+    ) -> Result<Vec<Feature>> {
+        self.extract_for_block(chain, context.block_number, context).await
+    }
+
+    fn config(&self) -> ExtractorConfig {
+        self.config.clone()
+    }
+
+    async fn update_config(&mut self, config: ExtractorConfig) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Bytes, U256, U64};
+
+    #[test]
+    fn test_position_event_topics() {
+        // Same topics the Intelligence layer decodes
+        assert_eq!(
+            format!("{:?}", H256::from(keccak256(POSITION_EVENTS[2].as_bytes()))),
+            "0xb3d084820fb1a9decffb176436bd02558d15fac9b0ddfed8c465bc7359d7dce0"
+        );
+    }
+
+    #[test]
+    fn test_log_normalization() {
+        let log = Log {
+            address: "0x87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2".parse().unwrap(),
+            topics: vec![H256::repeat_byte(0xab)],
+            data: Bytes::from(vec![0x01, 0x02]),
+            block_number: Some(U64::from(42)),
+            log_index: Some(U256::from(3)),
+            ..Default::default()
+        };
+
+        let normalized = to_feature_log(&log, 7);
+        assert_eq!(normalized.address, "0x87870bca3f3fd6335c3f4ce8392d69350b4fa4e2");
+        assert_eq!(normalized.topics[0], format!("0x{}", "ab".repeat(32)));
+        assert_eq!(normalized.data, "0x0102");
+        assert_eq!(normalized.block_number, 42);
+        assert_eq!(normalized.log_index, 3);
+    }
+}
@@ -0,0 +1,9 @@
+//! Lending protocol extractors
+//!
+//! Extracts borrower position events and reserve state from lending pools,
+//! so the Intelligence layer can track health factors for liquidations.
+
+pub mod aave_v3;
+
+// Re-export extractors
+pub use aave_v3::AaveV3PositionExtractor;
@@ -8,6 +8,9 @@ pub mod amm;
 pub mod bridges;
 pub mod gas;
 pub mod flash_loans;
+pub mod wallets;
+pub mod lending;
+pub mod evm_state;
 
 // Re-export commonly used types
 pub use traits::{BetaFeatureExtractor, ExtractionContext, ExtractionResult, ExtractorConfig, ExtractionMetadata};
@@ -0,0 +1,196 @@
+//! Wallet balance extractor
+//!
+//! Reads, for each configured execution wallet:
+//! - Native ETH balance
+//! - ERC-20 balances of the chain's configured tokens
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::{info, warn};
+use ethers::types::{H160, U256};
+use uuid::Uuid;
+use chrono::Utc;
+
+use qenus_dataplane::{
+    Feature, FeatureData, FeatureType, TokenBalance, TokenInfo, WalletBalanceFeature,
+};
+
+use crate::{
+    extractors::traits::{BetaFeatureExtractor, ExtractionContext, ExtractorConfig},
+    providers::EthereumRpcClient,
+    Chain, Result, BetaDataplaneError,
+};
+
+/// Wallet balance extractor
+pub struct WalletBalanceExtractor {
+    config: ExtractorConfig,
+    wallets: Vec<H160>,
+    tokens: HashMap<Chain, Vec<H160>>,
+    clients: HashMap<Chain, EthereumRpcClient>,
+    /// Symbol and decimals of each chain's tokens, read once per client
+    token_info: HashMap<Chain, Vec<(H160, TokenInfo)>>,
+}
+
+impl WalletBalanceExtractor {
+    /// Create a new wallet balance extractor
+    pub fn new(config: ExtractorConfig, wallets: Vec<H160>, tokens: HashMap<Chain, Vec<H160>>) -> Self {
+        Self {
+            config,
+            wallets,
+            tokens,
+            clients: HashMap::new(),
+            token_info: HashMap::new(),
+        }
+    }
+
+    /// Set the RPC client used for `chain`
+    ///
+    /// Reads the symbol and decimals of the chain's tokens once here; tokens
+    /// whose metadata cannot be read are skipped.
+    pub async fn with_client(mut self, chain: Chain, client: EthereumRpcClient) -> Self {
+        let mut token_info = Vec::new();
+        for token_address in self.tokens.get(&chain).into_iter().flatten() {
+            match Self::read_token_info(&client, *token_address).await {
+                Ok(info) => token_info.push((*token_address, info)),
+                Err(e) => warn!(chain = %chain, token = %token_address, error = %e, "Skipping token without readable metadata"),
+            }
+        }
+        self.token_info.insert(chain, token_info);
+        self.clients.insert(chain, client);
+        self
+    }
+
+    async fn read_token_info(client: &EthereumRpcClient, token_address: H160) -> Result<TokenInfo> {
+        Ok(TokenInfo {
+            address: format!("{:?}", token_address),
+            symbol: client.get_erc20_symbol(token_address).await?,
+            decimals: client.get_erc20_decimals(token_address).await?,
+        })
+    }
+
+    /// Extract balances of one wallet
+    async fn extract_wallet_balances(&self, wallet: H160, chain: Chain) -> Result<WalletBalanceFeature> {
+        let client = self.clients.get(&chain)
+            .ok_or_else(|| BetaDataplaneError::internal("RPC client not set"))?;
+
+        let native_balance = client.get_native_balance(wallet).await
+            .map_err(|e| BetaDataplaneError::extractor("wallet_balance", &format!("Failed to get native balance: {}", e)))?;
+
+        let mut token_balances = Vec::new();
+        for (token_address, token) in self.token_info.get(&chain).into_iter().flatten() {
+            match client.get_erc20_balance(*token_address, wallet).await {
+                Ok(balance) => token_balances.push(TokenBalance {
+                    token: token.clone(),
+                    balance: to_units(balance, token.decimals).to_string(),
+                }),
+                Err(e) => warn!(wallet = %wallet, token = %token_address, error = %e, "Failed to get token balance"),
+            }
+        }
+
+        Ok(WalletBalanceFeature {
+            wallet_address: format!("{:?}", wallet),
+            native_symbol: "ETH".to_string(),
+            native_balance: to_units(native_balance, 18).to_string(),
+            token_balances,
+        })
+    }
+}
+
+/// Raw token amount in whole units
+fn to_units(raw: U256, decimals: u8) -> f64 {
+    raw.to_string().parse::<f64>().unwrap_or(0.0) / 10f64.powi(decimals as i32)
+}
+
+#[async_trait]
+impl BetaFeatureExtractor for WalletBalanceExtractor {
+    fn name(&self) -> &'static str {
+        "wallet_balance"
+    }
+
+    fn feature_type(&self) -> FeatureType {
+        FeatureType::WalletBalance
+    }
+
+    fn supported_chains(&self) -> Vec<Chain> {
+        self.clients.keys().copied().collect()
+    }
+
+    async fn extract_for_block(
+        &self,
+        chain: Chain,
+        block_number: u64,
+        _context: &ExtractionContext,
+    ) -> Result<Vec<Feature>> {
+        let start_time = Instant::now();
+        let mut features = Vec::new();
+
+        for wallet in &self.wallets {
+            match self.extract_wallet_balances(*wallet, chain).await {
+                Ok(wallet_feature) => {
+                    let feature = Feature {
+                        id: Uuid::new_v4(),
+                        block_number,
+                        chain,
+                        timestamp: Utc::now(),
+                        feature_type: FeatureType::WalletBalance,
+                        data: FeatureData::WalletBalance(wallet_feature),
+                        source: "wallet_balance_extractor".to_string(),
+                        version: "1.0.0".to_string(),
+                    };
+                    features.push(feature);
+                }
+                Err(e) => {
+                    warn!(wallet = %wallet, error = %e, "Failed to extract wallet balances");
+                }
+            }
+        }
+
+        let elapsed = start_time.elapsed();
+        info!(
+            features_extracted = features.len(),
+            duration_ms = elapsed.as_millis(),
+            "Wallet balance extraction completed"
+        );
+
+        Ok(features)
+    }
+
+    async fn extract_latest(
+        &self,
+        chain: Chain,
+        context: &ExtractionContext,
+    ) -> Result<Vec<Feature>> {
+        self.extract_for_block(chain, context.block_number, context).await
+    }
+
+    fn config(&self) -> ExtractorConfig {
+        self.config.clone()
+    }
+
+    async fn update_config(&mut self, config: ExtractorConfig) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_balance_extractor_creation() {
+        let wallet: H160 = "0x00000000000000000000000000000000000000aa".parse().unwrap();
+        let extractor = WalletBalanceExtractor::new(ExtractorConfig::default(), vec![wallet], HashMap::new());
+        assert_eq!(extractor.name(), "wallet_balance");
+        assert_eq!(extractor.wallets.len(), 1);
+        // Chains are only supported once they have a client
+        assert!(extractor.supported_chains().is_empty());
+    }
+
+    #[test]
+    fn test_to_units() {
+        assert_eq!(to_units(U256::from(1_500_000u64), 6), 1.5);
+        assert_eq!(to_units(U256::exp10(18) * 2, 18), 2.0);
+    }
+}
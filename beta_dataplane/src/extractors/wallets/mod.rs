@@ -0,0 +1,9 @@
+//! Execution wallet extractors
+//!
+//! Extracts native and ERC-20 balances of our own execution wallets, so the
+//! Intelligence layer can size trades by the capital actually deployable.
+
+pub mod balances;
+
+// Re-export extractors
+pub use balances::WalletBalanceExtractor;
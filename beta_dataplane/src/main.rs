@@ -17,6 +17,9 @@ use qenus_beta_dataplane::{
         gas::pricing::GasPricingExtractor,
         bridges::canonical::CanonicalBridgeExtractor,
         flash_loans::{aave_v3::AaveV3FlashLoanExtractor, balancer::BalancerFlashLoanExtractor},
+        wallets::WalletBalanceExtractor,
+        lending::AaveV3PositionExtractor,
+        evm_state::TouchedStateExtractor,
     },
     feeds::FeedManager,
     monitoring::MonitoringService,
@@ -24,6 +27,8 @@ use qenus_beta_dataplane::{
     error::Result,
     Chain, OperationalMode, VERSION,
 };
+use ethers::types::H160;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -316,6 +321,9 @@ struct ChainExtractors {
     bridges: Arc<CanonicalBridgeExtractor>,
     aave_flash: Arc<AaveV3FlashLoanExtractor>,
     balancer_flash: Arc<BalancerFlashLoanExtractor>,
+    wallets: Option<Arc<WalletBalanceExtractor>>,
+    lending: Option<Arc<AaveV3PositionExtractor>>,
+    evm_state: Option<Arc<TouchedStateExtractor>>,
 }
 
 impl BetaDataplane {
@@ -327,7 +335,7 @@ impl BetaDataplane {
         let providers = Self::initialize_providers(&config, &chains).await?;
         
         // Initialize extractors
-        let extractors = Self::initialize_extractors(&providers).await?;
+        let extractors = Self::initialize_extractors(&config, &providers).await?;
         
         // Initialize feed manager
         let feed_manager = Arc::new(Self::initialize_feeds(&config)?);
@@ -401,7 +409,7 @@ impl BetaDataplane {
     }
 
     /// Initialize all feature extractors
-    async fn initialize_extractors(providers: &ChainProviders) -> Result<ChainExtractors> {
+    async fn initialize_extractors(dataplane_config: &BetaDataplaneConfig, providers: &ChainProviders) -> Result<ChainExtractors> {
         info!("Initializing feature extractors...");
         
         use qenus_beta_dataplane::extractors::ExtractorConfig;
@@ -422,6 +430,77 @@ impl BetaDataplane {
             uniswap_v3.set_client(eth_client.clone());
         }
         
+        // Wallet balances and lending positions on every chain with a provider;
+        // L2s make the same EVM calls
+        let parse = |address: &String| match address.parse() {
+            Ok(address) => Some(address),
+            Err(_) => {
+                warn!(address = %address, "Ignoring invalid address");
+                None
+            }
+        };
+        let tokens: HashMap<Chain, Vec<H160>> = dataplane_config.chains.iter()
+            .map(|(chain, chain_config)| (*chain, chain_config.contracts.tokens.iter().filter_map(parse).collect()))
+            .collect();
+        let evm_clients: Vec<(Chain, EthereumRpcClient)> = [
+            (Chain::Ethereum, providers.ethereum.clone()),
+            (Chain::Arbitrum, providers.arbitrum.as_ref().map(|client| client.evm_client())),
+            (Chain::Optimism, providers.optimism.as_ref().map(|client| client.evm_client())),
+            (Chain::Base, providers.base.as_ref().map(|client| client.evm_client())),
+        ]
+            .into_iter()
+            .filter_map(|(chain, client)| client.map(|client| (chain, client)))
+            .collect();
+        
+        let wallet_config = &dataplane_config.extraction.wallet;
+        let wallets = if wallet_config.enabled {
+            let mut extractor = WalletBalanceExtractor::new(
+                config.clone(),
+                wallet_config.wallets.iter().filter_map(parse).collect(),
+                tokens.clone(),
+            );
+            for (chain, client) in &evm_clients {
+                extractor = extractor.with_client(*chain, client.clone()).await;
+            }
+            Some(Arc::new(extractor))
+        } else {
+            None
+        };
+        
+        let evm_state_config = &dataplane_config.extraction.evm_state;
+        let evm_state = if evm_state_config.enabled {
+            let contracts: HashMap<Chain, HashSet<H160>> = dataplane_config.chains.iter()
+                .map(|(chain, chain_config)| {
+                    let contracts = &chain_config.contracts;
+                    let watched = contracts.dex_routers.iter()
+                        .chain(&contracts.flash_loan_providers)
+                        .chain(&contracts.tokens)
+                        .chain(evm_state_config.contracts.get(chain).into_iter().flatten())
+                        .filter_map(parse)
+                        .collect();
+                    (*chain, watched)
+                })
+                .collect();
+            let mut extractor = TouchedStateExtractor::new(config.clone(), contracts);
+            for (chain, client) in &evm_clients {
+                extractor = extractor.with_client(*chain, client.clone());
+            }
+            Some(Arc::new(extractor))
+        } else {
+            None
+        };
+        
+        let lending_config = &dataplane_config.extraction.lending;
+        let lending = if lending_config.enabled {
+            let mut extractor = AaveV3PositionExtractor::new(config.clone(), lending_config.clone(), tokens);
+            for (chain, client) in &evm_clients {
+                extractor = extractor.with_client(*chain, client.clone()).await;
+            }
+            Some(Arc::new(extractor))
+        } else {
+            None
+        };
+        
         info!("✅ Feature extractors initialized");
         
         Ok(ChainExtractors {
@@ -432,6 +511,9 @@ impl BetaDataplane {
             bridges: Arc::new(bridges),
             aave_flash: Arc::new(aave_flash),
             balancer_flash: Arc::new(balancer_flash),
+            wallets,
+            lending,
+            evm_state,
         })
     }
 
@@ -673,6 +755,45 @@ impl BetaDataplane {
             }
         }
 
+        // Run wallet balance extractor
+        if let Some(wallets) = self.extractors.wallets.as_ref().filter(|wallets| wallets.supports_chain(chain)) {
+            match wallets.extract_for_block(chain, block_number, &context).await {
+                Ok(features) => {
+                    if !features.is_empty() {
+                        info!(extractor = "wallets", chain = %chain, features = features.len(), "Extracted");
+                        all_features.extend(features);
+                    }
+                }
+                Err(e) => warn!(extractor = "wallets", error = %e, "Extraction failed"),
+            }
+        }
+
+        // Run Aave V3 position extractor
+        if let Some(lending) = self.extractors.lending.as_ref().filter(|lending| lending.supports_chain(chain)) {
+            match lending.extract_for_block(chain, block_number, &context).await {
+                Ok(features) => {
+                    if !features.is_empty() {
+                        info!(extractor = "lending", chain = %chain, features = features.len(), "Extracted");
+                        all_features.extend(features);
+                    }
+                }
+                Err(e) => warn!(extractor = "lending", error = %e, "Extraction failed"),
+            }
+        }
+
+        // Run touched EVM state extractor
+        if let Some(evm_state) = self.extractors.evm_state.as_ref().filter(|evm_state| evm_state.supports_chain(chain)) {
+            match evm_state.extract_for_block(chain, block_number, &context).await {
+                Ok(features) => {
+                    if !features.is_empty() {
+                        info!(extractor = "evm_state", chain = %chain, features = features.len(), "Extracted");
+                        all_features.extend(features);
+                    }
+                }
+                Err(e) => warn!(extractor = "evm_state", error = %e, "Extraction failed"),
+            }
+        }
+
         // Publish all features
         if !all_features.is_empty() {
             if !self.config.global.dry_run {
@@ -10,7 +10,7 @@ use ethers::types::{Block, Transaction, Log, Filter, TxHash, H160, U256};
 
 use crate::{
     config::ProviderConfig,
-    providers::multi_rpc::MultiRpcClient,
+    providers::{multi_rpc::MultiRpcClient, EthereumRpcClient},
     Result, BetaDataplaneError,
 };
 
@@ -114,6 +114,11 @@ impl BaseRpcClient {
     pub async fn get_metrics(&self) -> crate::providers::multi_rpc::ClientMetrics {
         self.client.get_metrics().await
     }
+
+    /// Client for generic EVM calls (balances, ERC-20 metadata) on Base
+    pub fn evm_client(&self) -> EthereumRpcClient {
+        EthereumRpcClient::from_client(self.client.clone())
+    }
 }
 
 /// Base sequencer information
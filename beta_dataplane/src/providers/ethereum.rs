@@ -33,6 +33,11 @@ impl EthereumRpcClient {
         Ok(Self { client })
     }
 
+    /// Wrap another chain's RPC client for the same EVM calls
+    pub(crate) fn from_client(client: MultiRpcClient) -> Self {
+        Self { client }
+    }
+
     /// Get current block number
     pub async fn get_current_block(&self) -> Result<u64> {
         let block_number = self.client.get_block_number().await?;
@@ -167,6 +172,19 @@ impl EthereumRpcClient {
         self.client.get_logs(&filter).await
     }
 
+    /// Trace a block with geth's prestateTracer
+    ///
+    /// Returns one result per transaction: the accounts and storage slots it
+    /// touched with their values before it ran, or with `diff_mode` the
+    /// `pre` and `post` values of what it changed.
+    pub async fn trace_block_prestate(&self, block_number: u64, diff_mode: bool) -> Result<serde_json::Value> {
+        let params = serde_json::json!([
+            format!("0x{:x}", block_number),
+            { "tracer": "prestateTracer", "tracerConfig": { "diffMode": diff_mode } },
+        ]);
+        self.client.request("debug_traceBlockByNumber", params).await
+    }
+
     /// Get transaction by hash
     pub async fn get_transaction(&self, tx_hash: TxHash) -> Result<Option<Transaction>> {
         self.client.get_transaction(tx_hash).await
@@ -296,6 +314,20 @@ impl EthereumRpcClient {
         AbiManager::decode_aave_reserve_data_output(&result)
     }
 
+    /// Get an asset's Aave V3 oracle price (USD, 8 decimals)
+    pub async fn get_aave_asset_price(&self, oracle_address: H160, asset: H160) -> Result<U256> {
+        use crate::utils::contracts::AbiManager;
+
+        let calldata = AbiManager::encode_aave_asset_price_call(asset)?;
+        let result = self.call_contract(oracle_address, calldata, None).await?;
+        AbiManager::decode_aave_asset_price_output(&result)
+    }
+
+    /// Get native (ETH) balance in wei
+    pub async fn get_native_balance(&self, address: H160) -> Result<U256> {
+        self.client.get_balance(address).await
+    }
+
     // === ERC20 Contract Calls ===
 
     /// Get ERC20 token decimals
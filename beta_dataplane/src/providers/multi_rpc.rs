@@ -344,6 +344,15 @@ impl MultiRpcClient {
         }).await
     }
 
+    /// Get native balance of an address
+    pub async fn get_balance(&self, address: ethers::types::H160) -> Result<ethers::types::U256> {
+        self.execute_with_failover(|client| {
+            Box::pin(async move {
+                client.get_balance(address, None).await
+            })
+        }).await
+    }
+
     /// Get logs with filter
     pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
         self.execute_with_failover(|client| {
@@ -368,6 +377,16 @@ impl MultiRpcClient {
         }).await
     }
 
+    /// Send a raw JSON-RPC request, for methods without a typed wrapper
+    pub async fn request(&self, method: &'static str, params: serde_json::Value) -> Result<serde_json::Value> {
+        self.execute_with_failover(|client| {
+            let params = params.clone();
+            Box::pin(async move {
+                client.request(method, params).await
+            })
+        }).await
+    }
+
     /// Get client metrics
     pub async fn get_metrics(&self) -> ClientMetrics {
         self.metrics.read().await.clone()
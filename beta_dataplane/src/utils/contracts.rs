@@ -4,7 +4,7 @@
 //! smart contracts via RPC.
 
 use ethers::abi::{Abi, Token};
-use ethers::types::{H160, U256, Bytes};
+use ethers::types::{H160, I256, U256, Bytes};
 use once_cell::sync::Lazy;
 use serde_json::json;
 
@@ -83,6 +83,20 @@ pub static ERC20_ABI: Lazy<Abi> = Lazy::new(|| {
             "name": "totalSupply",
             "outputs": [{"name": "", "type": "uint256"}],
             "type": "function"
+        },
+        {
+            "constant": false,
+            "inputs": [{"name": "_to", "type": "address"}, {"name": "_value", "type": "uint256"}],
+            "name": "transfer",
+            "outputs": [{"name": "", "type": "bool"}],
+            "type": "function"
+        },
+        {
+            "constant": false,
+            "inputs": [{"name": "_spender", "type": "address"}, {"name": "_value", "type": "uint256"}],
+            "name": "approve",
+            "outputs": [{"name": "", "type": "bool"}],
+            "type": "function"
         }
     ]))
     .expect("Valid ERC20 ABI")
@@ -122,6 +136,18 @@ pub static CURVE_POOL_ABI: Lazy<Abi> = Lazy::new(|| {
             ],
             "stateMutability": "view",
             "type": "function"
+        },
+        {
+            "name": "exchange",
+            "outputs": [{"type": "uint256", "name": ""}],
+            "inputs": [
+                {"type": "int128", "name": "i"},
+                {"type": "int128", "name": "j"},
+                {"type": "uint256", "name": "dx"},
+                {"type": "uint256", "name": "min_dy"}
+            ],
+            "stateMutability": "nonpayable",
+            "type": "function"
         }
     ]))
     .expect("Valid Curve Pool ABI")
@@ -140,6 +166,50 @@ pub static BALANCER_VAULT_ABI: Lazy<Abi> = Lazy::new(|| {
             "inputs": [{"type": "bytes32", "name": "poolId"}],
             "stateMutability": "view",
             "type": "function"
+        },
+        {
+            "name": "swap",
+            "outputs": [{"type": "uint256", "name": "amountCalculated"}],
+            "inputs": [
+                {
+                    "type": "tuple",
+                    "name": "singleSwap",
+                    "components": [
+                        {"type": "bytes32", "name": "poolId"},
+                        {"type": "uint8", "name": "kind"},
+                        {"type": "address", "name": "assetIn"},
+                        {"type": "address", "name": "assetOut"},
+                        {"type": "uint256", "name": "amount"},
+                        {"type": "bytes", "name": "userData"}
+                    ]
+                },
+                {
+                    "type": "tuple",
+                    "name": "funds",
+                    "components": [
+                        {"type": "address", "name": "sender"},
+                        {"type": "bool", "name": "fromInternalBalance"},
+                        {"type": "address", "name": "recipient"},
+                        {"type": "bool", "name": "toInternalBalance"}
+                    ]
+                },
+                {"type": "uint256", "name": "limit"},
+                {"type": "uint256", "name": "deadline"}
+            ],
+            "stateMutability": "payable",
+            "type": "function"
+        },
+        {
+            "name": "flashLoan",
+            "outputs": [],
+            "inputs": [
+                {"type": "address", "name": "recipient"},
+                {"type": "address[]", "name": "tokens"},
+                {"type": "uint256[]", "name": "amounts"},
+                {"type": "bytes", "name": "userData"}
+            ],
+            "stateMutability": "nonpayable",
+            "type": "function"
         }
     ]))
     .expect("Valid Balancer Vault ABI")
@@ -170,11 +240,92 @@ pub static AAVE_V3_POOL_ABI: Lazy<Abi> = Lazy::new(|| {
             "inputs": [{"type": "address", "name": "asset"}],
             "stateMutability": "view",
             "type": "function"
+        },
+        {
+            "name": "flashLoanSimple",
+            "outputs": [],
+            "inputs": [
+                {"type": "address", "name": "receiverAddress"},
+                {"type": "address", "name": "asset"},
+                {"type": "uint256", "name": "amount"},
+                {"type": "bytes", "name": "params"},
+                {"type": "uint16", "name": "referralCode"}
+            ],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
+        {
+            "name": "flashLoan",
+            "outputs": [],
+            "inputs": [
+                {"type": "address", "name": "receiverAddress"},
+                {"type": "address[]", "name": "assets"},
+                {"type": "uint256[]", "name": "amounts"},
+                {"type": "uint256[]", "name": "interestRateModes"},
+                {"type": "address", "name": "onBehalfOf"},
+                {"type": "bytes", "name": "params"},
+                {"type": "uint16", "name": "referralCode"}
+            ],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
+        {
+            "name": "liquidationCall",
+            "outputs": [],
+            "inputs": [
+                {"type": "address", "name": "collateralAsset"},
+                {"type": "address", "name": "debtAsset"},
+                {"type": "address", "name": "user"},
+                {"type": "uint256", "name": "debtToCover"},
+                {"type": "bool", "name": "receiveAToken"}
+            ],
+            "stateMutability": "nonpayable",
+            "type": "function"
         }
     ]))
     .expect("Valid Aave V3 Pool ABI")
 });
 
+/// Aave V3 price oracle ABI
+pub static AAVE_V3_ORACLE_ABI: Lazy<Abi> = Lazy::new(|| {
+    serde_json::from_value(json!([
+        {
+            "name": "getAssetPrice",
+            "outputs": [{"type": "uint256", "name": ""}],
+            "inputs": [{"type": "address", "name": "asset"}],
+            "stateMutability": "view",
+            "type": "function"
+        }
+    ]))
+    .expect("Valid Aave V3 Oracle ABI")
+});
+
+/// Uniswap V3 SwapRouter ABI (exactInput)
+pub static UNISWAP_V3_SWAP_ROUTER_ABI: Lazy<Abi> = Lazy::new(|| {
+    serde_json::from_value(json!([
+        {
+            "name": "exactInput",
+            "outputs": [{"type": "uint256", "name": "amountOut"}],
+            "inputs": [
+                {
+                    "type": "tuple",
+                    "name": "params",
+                    "components": [
+                        {"type": "bytes", "name": "path"},
+                        {"type": "address", "name": "recipient"},
+                        {"type": "uint256", "name": "deadline"},
+                        {"type": "uint256", "name": "amountIn"},
+                        {"type": "uint256", "name": "amountOutMinimum"}
+                    ]
+                }
+            ],
+            "stateMutability": "payable",
+            "type": "function"
+        }
+    ]))
+    .expect("Valid Uniswap V3 SwapRouter ABI")
+});
+
 /// L1 Bridge ABI (for Arbitrum/Optimism/Base canonical bridges)
 pub static L1_BRIDGE_ABI: Lazy<Abi> = Lazy::new(|| {
     serde_json::from_value(json!([
@@ -210,6 +361,16 @@ impl ContractRegistry {
         }
     }
 
+    /// Get Uniswap V3 SwapRouter address (the original router, which takes a deadline)
+    pub fn get_uniswap_v3_swap_router(chain: crate::Chain) -> Option<H160> {
+        match chain {
+            crate::Chain::Ethereum => Some("0xE592427A0AEce92De3Edee1F18E0157C05861564".parse().unwrap()),
+            crate::Chain::Arbitrum => Some("0xE592427A0AEce92De3Edee1F18E0157C05861564".parse().unwrap()),
+            crate::Chain::Optimism => Some("0xE592427A0AEce92De3Edee1F18E0157C05861564".parse().unwrap()),
+            crate::Chain::Base => None, // Only SwapRouter02 is deployed
+        }
+    }
+
     /// Get Curve registry address
     pub fn get_curve_registry(chain: crate::Chain) -> Option<H160> {
         match chain {
@@ -237,6 +398,16 @@ impl ContractRegistry {
             crate::Chain::Base => Some("0xA238Dd80C259a72e81d7e4664a9801593F98d1c5".parse().unwrap()),
         }
     }
+
+    /// Get Aave V3 price oracle address (USD prices with 8 decimals)
+    pub fn get_aave_v3_oracle(chain: crate::Chain) -> Option<H160> {
+        match chain {
+            crate::Chain::Ethereum => Some("0x54586bE62E3c3580375aE3723C145253060Ca0C2".parse().unwrap()),
+            crate::Chain::Arbitrum => Some("0xb56c2F0B653B2e0b10C9b928C8580Ac5Df02C7C7".parse().unwrap()),
+            crate::Chain::Optimism => Some("0xD81eb3728a631871a7eBBaD631b5f424909f0c77".parse().unwrap()),
+            crate::Chain::Base => Some("0x2Cc0Fc26eD4563A5ce5e8bdcfe1A2878676Ae156".parse().unwrap()),
+        }
+    }
 }
 
 impl Default for ContractRegistry {
@@ -381,6 +552,16 @@ impl AbiManager {
         }
     }
 
+    /// Encode ERC20 transfer(to, value) call
+    pub fn encode_erc20_transfer_call(to: H160, value: U256) -> Result<Bytes> {
+        Self::encode_function_call(&ERC20_ABI, "transfer", &[Token::Address(to), Token::Uint(value)])
+    }
+
+    /// Encode ERC20 approve(spender, value) call
+    pub fn encode_erc20_approve_call(spender: H160, value: U256) -> Result<Bytes> {
+        Self::encode_function_call(&ERC20_ABI, "approve", &[Token::Address(spender), Token::Uint(value)])
+    }
+
     /// Encode ERC20 symbol() call
     pub fn encode_erc20_symbol_call() -> Result<Bytes> {
         Self::encode_function_call(&ERC20_ABI, "symbol", &[])
@@ -540,6 +721,216 @@ impl AbiManager {
             },
         })
     }
+
+    /// Encode getAssetPrice(asset) call for the Aave V3 oracle
+    pub fn encode_aave_asset_price_call(asset: H160) -> Result<Bytes> {
+        Self::encode_function_call(&AAVE_V3_ORACLE_ABI, "getAssetPrice", &[Token::Address(asset)])
+    }
+
+    /// Decode getAssetPrice() output
+    pub fn decode_aave_asset_price_output(output: &[u8]) -> Result<U256> {
+        let tokens = Self::decode_function_output(&AAVE_V3_ORACLE_ABI, "getAssetPrice", output)?;
+
+        match tokens.first() {
+            Some(Token::Uint(price)) => Ok(*price),
+            _ => Err(BetaDataplaneError::internal("Invalid getAssetPrice output")),
+        }
+    }
+
+    /// Encode flashLoanSimple(receiver, asset, amount, params, referralCode) for Aave V3
+    pub fn encode_aave_flash_loan_simple_call(
+        receiver: H160,
+        asset: H160,
+        amount: U256,
+        params: Bytes,
+    ) -> Result<Bytes> {
+        Self::encode_function_call(&AAVE_V3_POOL_ABI, "flashLoanSimple", &[
+            Token::Address(receiver),
+            Token::Address(asset),
+            Token::Uint(amount),
+            Token::Bytes(params.to_vec()),
+            Token::Uint(U256::zero()),
+        ])
+    }
+
+    /// Encode flashLoan(...) for Aave V3 with no debt opened (all modes 0)
+    pub fn encode_aave_flash_loan_call(
+        receiver: H160,
+        assets: &[H160],
+        amounts: &[U256],
+        params: Bytes,
+    ) -> Result<Bytes> {
+        if assets.len() != amounts.len() {
+            return Err(BetaDataplaneError::internal("Flash loan assets and amounts differ in length"));
+        }
+
+        Self::encode_function_call(&AAVE_V3_POOL_ABI, "flashLoan", &[
+            Token::Address(receiver),
+            Token::Array(assets.iter().map(|a| Token::Address(*a)).collect()),
+            Token::Array(amounts.iter().map(|a| Token::Uint(*a)).collect()),
+            Token::Array(assets.iter().map(|_| Token::Uint(U256::zero())).collect()),
+            Token::Address(receiver),
+            Token::Bytes(params.to_vec()),
+            Token::Uint(U256::zero()),
+        ])
+    }
+
+    /// Encode liquidationCall(collateral, debt, user, debtToCover, receiveAToken) for Aave V3
+    pub fn encode_aave_liquidation_call(
+        collateral_asset: H160,
+        debt_asset: H160,
+        user: H160,
+        debt_to_cover: U256,
+        receive_a_token: bool,
+    ) -> Result<Bytes> {
+        Self::encode_function_call(&AAVE_V3_POOL_ABI, "liquidationCall", &[
+            Token::Address(collateral_asset),
+            Token::Address(debt_asset),
+            Token::Address(user),
+            Token::Uint(debt_to_cover),
+            Token::Bool(receive_a_token),
+        ])
+    }
+
+    // === Swap Encoders ===
+
+    /// Encode a Uniswap V3 path: token, fee (uint24), token, fee, ..., token
+    pub fn encode_uniswap_v3_path(tokens: &[H160], fees: &[u32]) -> Result<Bytes> {
+        if tokens.len() < 2 || fees.len() != tokens.len() - 1 {
+            return Err(BetaDataplaneError::internal(
+                "Uniswap V3 path needs n tokens and n-1 fees"
+            ));
+        }
+
+        let mut path = Vec::with_capacity(tokens.len() * 20 + fees.len() * 3);
+        for (i, token) in tokens.iter().enumerate() {
+            path.extend_from_slice(token.as_bytes());
+            if let Some(fee) = fees.get(i) {
+                if *fee >= 1 << 24 {
+                    return Err(BetaDataplaneError::internal(format!("Fee {} does not fit uint24", fee)));
+                }
+                path.extend_from_slice(&fee.to_be_bytes()[1..]);
+            }
+        }
+
+        Ok(Bytes::from(path))
+    }
+
+    /// Encode SwapRouter exactInput((path, recipient, deadline, amountIn, amountOutMinimum))
+    pub fn encode_uniswap_v3_exact_input_call(
+        path: Bytes,
+        recipient: H160,
+        deadline: u64,
+        amount_in: U256,
+        amount_out_minimum: U256,
+    ) -> Result<Bytes> {
+        Self::encode_function_call(&UNISWAP_V3_SWAP_ROUTER_ABI, "exactInput", &[Token::Tuple(vec![
+            Token::Bytes(path.to_vec()),
+            Token::Address(recipient),
+            Token::Uint(U256::from(deadline)),
+            Token::Uint(amount_in),
+            Token::Uint(amount_out_minimum),
+        ])])
+    }
+
+    /// Encode exchange(i, j, dx, min_dy) for Curve
+    pub fn encode_curve_exchange_call(i: i128, j: i128, dx: U256, min_dy: U256) -> Result<Bytes> {
+        Self::encode_function_call(&CURVE_POOL_ABI, "exchange", &[
+            Token::Int(I256::from(i).into_raw()),
+            Token::Int(I256::from(j).into_raw()),
+            Token::Uint(dx),
+            Token::Uint(min_dy),
+        ])
+    }
+
+    /// Encode Vault swap(singleSwap, funds, limit, deadline) for Balancer
+    pub fn encode_balancer_swap_call(
+        swap: &BalancerSingleSwap,
+        funds: &BalancerFundManagement,
+        limit: U256,
+        deadline: u64,
+    ) -> Result<Bytes> {
+        Self::encode_function_call(&BALANCER_VAULT_ABI, "swap", &[
+            Token::Tuple(vec![
+                Token::FixedBytes(swap.pool_id.to_vec()),
+                Token::Uint(U256::from(swap.kind as u8)),
+                Token::Address(swap.asset_in),
+                Token::Address(swap.asset_out),
+                Token::Uint(swap.amount),
+                Token::Bytes(swap.user_data.to_vec()),
+            ]),
+            funds.to_token(),
+            Token::Uint(limit),
+            Token::Uint(U256::from(deadline)),
+        ])
+    }
+
+    /// Encode Vault flashLoan(recipient, tokens, amounts, userData) for Balancer
+    pub fn encode_balancer_flash_loan_call(
+        recipient: H160,
+        tokens: &[H160],
+        amounts: &[U256],
+        user_data: Bytes,
+    ) -> Result<Bytes> {
+        if tokens.len() != amounts.len() {
+            return Err(BetaDataplaneError::internal("Flash loan tokens and amounts differ in length"));
+        }
+
+        Self::encode_function_call(&BALANCER_VAULT_ABI, "flashLoan", &[
+            Token::Address(recipient),
+            Token::Array(tokens.iter().map(|t| Token::Address(*t)).collect()),
+            Token::Array(amounts.iter().map(|a| Token::Uint(*a)).collect()),
+            Token::Bytes(user_data.to_vec()),
+        ])
+    }
+}
+
+/// Balancer swap kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalancerSwapKind {
+    GivenIn = 0,
+    GivenOut = 1,
+}
+
+/// Balancer Vault SingleSwap struct
+#[derive(Debug, Clone)]
+pub struct BalancerSingleSwap {
+    pub pool_id: [u8; 32],
+    pub kind: BalancerSwapKind,
+    pub asset_in: H160,
+    pub asset_out: H160,
+    pub amount: U256,
+    pub user_data: Bytes,
+}
+
+/// Balancer Vault FundManagement struct
+#[derive(Debug, Clone)]
+pub struct BalancerFundManagement {
+    pub sender: H160,
+    pub from_internal_balance: bool,
+    pub recipient: H160,
+    pub to_internal_balance: bool,
+}
+
+impl BalancerFundManagement {
+    /// Send from and receive to `account`'s external balances
+    pub fn external(account: H160) -> Self {
+        Self {
+            sender: account,
+            from_internal_balance: false,
+            recipient: account,
+            to_internal_balance: false,
+        }
+    }
+
+    fn to_token(&self) -> Token {
+        Token::Tuple(vec![
+            Token::Address(self.sender),
+            Token::Bool(self.from_internal_balance),
+            Token::Address(self.recipient),
+            Token::Bool(self.to_internal_balance),
+        ])
+    }
 }
 
 /// Uniswap V3 slot0 data
@@ -569,3 +960,70 @@ pub struct AaveReserveData {
     pub liquidity_index: U256,
     pub a_token_address: H160,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(byte: u8) -> H160 {
+        H160::repeat_byte(byte)
+    }
+
+    #[test]
+    fn test_function_selectors() {
+        let selector = |bytes: Bytes| hex::encode(&bytes[..4]);
+
+        let path = AbiManager::encode_uniswap_v3_path(&[addr(1), addr(2)], &[500]).unwrap();
+        let exact_input = AbiManager::encode_uniswap_v3_exact_input_call(
+            path, addr(9), 1_700_000_000, U256::from(10u64).pow(18.into()), U256::one(),
+        ).unwrap();
+        assert_eq!(selector(exact_input), "c04b8d59");
+
+        let exchange = AbiManager::encode_curve_exchange_call(0, 1, U256::from(1_000_000u64), U256::one()).unwrap();
+        assert_eq!(selector(exchange), "3df02124");
+
+        let swap = BalancerSingleSwap {
+            pool_id: [7u8; 32],
+            kind: BalancerSwapKind::GivenIn,
+            asset_in: addr(1),
+            asset_out: addr(2),
+            amount: U256::from(1000u64),
+            user_data: Bytes::new(),
+        };
+        let encoded = AbiManager::encode_balancer_swap_call(
+            &swap, &BalancerFundManagement::external(addr(9)), U256::one(), 1_700_000_000,
+        ).unwrap();
+        assert_eq!(selector(encoded), "52bbbe29");
+
+        let transfer = AbiManager::encode_erc20_transfer_call(addr(9), U256::from(1000u64)).unwrap();
+        assert_eq!(selector(transfer), "a9059cbb");
+
+        let approve = AbiManager::encode_erc20_approve_call(addr(9), U256::from(1000u64)).unwrap();
+        assert_eq!(selector(approve), "095ea7b3");
+
+        let balancer_flash = AbiManager::encode_balancer_flash_loan_call(
+            addr(9), &[addr(1)], &[U256::from(1000u64)], Bytes::new(),
+        ).unwrap();
+        assert_eq!(selector(balancer_flash), "5c38449e");
+
+        let aave_flash = AbiManager::encode_aave_flash_loan_simple_call(
+            addr(9), addr(1), U256::from(1000u64), Bytes::new(),
+        ).unwrap();
+        assert_eq!(selector(aave_flash), "42b0b77c");
+
+        let liquidation = AbiManager::encode_aave_liquidation_call(
+            addr(1), addr(2), addr(3), U256::from(1000u64), false,
+        ).unwrap();
+        assert_eq!(selector(liquidation), "00a718a9");
+    }
+
+    #[test]
+    fn test_uniswap_v3_path_layout() {
+        let path = AbiManager::encode_uniswap_v3_path(&[addr(1), addr(2), addr(3)], &[500, 3000]).unwrap();
+
+        assert_eq!(path.len(), 20 * 3 + 3 * 2);
+        assert_eq!(&path[20..23], &[0x00, 0x01, 0xf4]);
+        assert_eq!(&path[43..46], &[0x00, 0x0b, 0xb8]);
+        assert!(AbiManager::encode_uniswap_v3_path(&[addr(1)], &[]).is_err());
+    }
+}
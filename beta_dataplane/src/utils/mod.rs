@@ -9,7 +9,9 @@ pub mod validation;
 pub mod retry;
 
 // Re-export commonly used types
-pub use contracts::{ContractRegistry, AbiManager, UniswapV3Slot0};
+pub use contracts::{
+    ContractRegistry, AbiManager, UniswapV3Slot0, BalancerSwapKind, BalancerSingleSwap, BalancerFundManagement,
+};
 // TODO: Implement remaining utilities
 // pub use math::{PriceCalculator, SlippageCalculator, LiquidityCalculator};
 // pub use validation::{DataValidator, SchemaValidator};
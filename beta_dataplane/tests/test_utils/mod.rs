@@ -14,7 +14,7 @@ pub fn create_sample_amm_feature(block_number: u64) -> Feature {
         chain: Chain::Ethereum,
         block_number,
         timestamp: Utc::now(),
-        data: FeatureData::Amm(qenus_dataplane::AmmFeature {
+        data: FeatureData::Amm(Box::new(qenus_dataplane::AmmFeature {
             protocol: "uniswap_v3".to_string(),
             pool: format!("0x{:040x}", block_number),
             token0: "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(), // USDC
@@ -22,7 +22,7 @@ pub fn create_sample_amm_feature(block_number: u64) -> Feature {
             price: 1850.0,
             liquidity: 5000000.0,
             fee_tier: 3000,
-        }),
+        })),
         source: "test_extractor".to_string(),
         version: "1.0.0".to_string(),
     }
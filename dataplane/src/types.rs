@@ -90,17 +90,23 @@ pub enum FeatureType {
     Gas,
     FlashLoan,
     SequencerHealth,
+    WalletBalance,
+    Lending,
+    EvmState,
 }
 
 /// Feature data payload - extensible union type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum FeatureData {
-    Amm(AmmFeature),
+    Amm(Box<AmmFeature>),
     Bridge(BridgeFeature),
     Gas(GasFeature),
     FlashLoan(FlashLoanFeature),
     SequencerHealth(SequencerHealthFeature),
+    WalletBalance(WalletBalanceFeature),
+    Lending(LendingFeature),
+    EvmState(EvmStateFeature),
 }
 
 /// AMM pool state and metrics
@@ -108,6 +114,8 @@ pub enum FeatureData {
 pub struct AmmFeature {
     pub pool_address: String,
     pub pool_type: String, // "uniswap_v3", "curve", "balancer", etc.
+    #[serde(default)]
+    pub pool_id: Option<String>, // Balancer 32-byte pool id, 0x-prefixed hex
     pub token0: TokenInfo,
     pub token1: TokenInfo,
     pub fee_tier: Option<u32>, // Fee in basis points
@@ -127,10 +135,134 @@ pub struct TokenInfo {
     pub decimals: u8,
 }
 
-/// Slippage depth curve for different trade sizes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Pool depth: sampled quotes on both sides of the book, plus legacy labelled sizes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DepthCurve {
+    #[serde(default)]
     pub sizes: HashMap<String, SlippageInfo>, // "100k", "1m", "10m" -> slippage
+
+    /// Quotes selling token0 for token1
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub zero_for_one: Vec<DepthPoint>,
+
+    /// Quotes selling token1 for token0
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub one_for_zero: Vec<DepthPoint>,
+}
+
+/// One sampled swap quote, in whole token units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DepthPoint {
+    pub amount_in: f64,
+    pub amount_out: f64,
+}
+
+impl DepthCurve {
+    /// Sample both sides of the book from a slippage model
+    ///
+    /// `sizes_usd` are trade values in USD and `slippage_bps` maps such a value
+    /// to slippage. Each size is converted to token units through `token1_usd`,
+    /// the USD price of token1, and quoted against `mid_price` (token0 in
+    /// token1). Empty if either price is unusable.
+    pub fn sample(mid_price: f64, token1_usd: f64, sizes_usd: &[f64], slippage_bps: impl Fn(f64) -> f64) -> Self {
+        let mut curve = Self::default();
+        if !(mid_price.is_finite() && mid_price > 0.0 && token1_usd.is_finite() && token1_usd > 0.0) {
+            return curve;
+        }
+
+        for &size_usd in sizes_usd {
+            let fill = (1.0 - slippage_bps(size_usd) / 10000.0).max(0.0);
+            let amount1 = size_usd / token1_usd;
+            let amount0 = amount1 / mid_price;
+            curve.zero_for_one.push(DepthPoint { amount_in: amount0, amount_out: amount1 * fill });
+            curve.one_for_zero.push(DepthPoint { amount_in: amount1, amount_out: amount0 * fill });
+        }
+        curve
+    }
+
+    /// Whether the curve carries sampled quotes for this side of the book
+    pub fn has_samples(&self, zero_for_one: bool) -> bool {
+        !self.side(zero_for_one).is_empty()
+    }
+
+    /// Output of selling `amount_in` (token0 if `zero_for_one`, else token1)
+    ///
+    /// Interpolates linearly between samples starting from the origin, and
+    /// continues past the largest sample at the last segment's marginal rate.
+    /// Samples are sorted and made monotone first. None without samples.
+    pub fn amount_out(&self, zero_for_one: bool, amount_in: f64) -> Option<f64> {
+        let points = self.side(zero_for_one);
+        if points.is_empty() || amount_in <= 0.0 {
+            return (!points.is_empty()).then_some(0.0);
+        }
+
+        let mut prev = DepthPoint { amount_in: 0.0, amount_out: 0.0 };
+        for point in &points {
+            if amount_in <= point.amount_in {
+                let t = (amount_in - prev.amount_in) / (point.amount_in - prev.amount_in);
+                return Some(prev.amount_out + t * (point.amount_out - prev.amount_out));
+            }
+            prev = *point;
+        }
+
+        // Past the largest sample: the last segment's marginal rate
+        let before = points.len().checked_sub(2).map(|i| points[i]).unwrap_or(DepthPoint { amount_in: 0.0, amount_out: 0.0 });
+        let rate = (prev.amount_out - before.amount_out) / (prev.amount_in - before.amount_in);
+        Some(prev.amount_out + (amount_in - prev.amount_in) * rate)
+    }
+
+    /// Slippage of selling `amount_in` against `mid_price`, in basis points
+    pub fn slippage_bps(&self, zero_for_one: bool, amount_in: f64, mid_price: f64) -> Option<f64> {
+        let rate = if zero_for_one { mid_price } else { 1.0 / mid_price };
+        if !(rate.is_finite() && rate > 0.0) || amount_in <= 0.0 {
+            return None;
+        }
+        let amount_out = self.amount_out(zero_for_one, amount_in)?;
+        Some(((1.0 - amount_out / (amount_in * rate)) * 10000.0).max(0.0))
+    }
+
+    /// Legacy labelled sizes as (size, slippage bps), ascending in size
+    pub fn labelled_slippage(&self) -> Vec<(f64, f64)> {
+        let mut points: Vec<(f64, f64)> = self.sizes.iter()
+            .filter_map(|(label, info)| parse_size_label(label).map(|size| (size, info.slippage_bps)))
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points
+    }
+
+    /// Valid samples of one side, ascending in amount in, with non-decreasing output
+    fn side(&self, zero_for_one: bool) -> Vec<DepthPoint> {
+        let raw = if zero_for_one { &self.zero_for_one } else { &self.one_for_zero };
+        let mut points: Vec<DepthPoint> = raw.iter()
+            .copied()
+            .filter(|p| p.amount_in.is_finite() && p.amount_out.is_finite() && p.amount_in > 0.0 && p.amount_out >= 0.0)
+            .collect();
+        points.sort_by(|a, b| a.amount_in.total_cmp(&b.amount_in));
+        points.dedup_by(|b, a| a.amount_in == b.amount_in);
+
+        let mut max_out: f64 = 0.0;
+        for point in &mut points {
+            max_out = max_out.max(point.amount_out);
+            point.amount_out = max_out;
+        }
+        points
+    }
+}
+
+/// Parse a depth label such as "100k" or "1m" into a trade size
+pub fn parse_size_label(label: &str) -> Option<f64> {
+    let label = label.trim().to_lowercase();
+    let (number, multiplier) = if let Some(n) = label.strip_suffix('k') {
+        (n, 1e3)
+    } else if let Some(n) = label.strip_suffix('m') {
+        (n, 1e6)
+    } else if let Some(n) = label.strip_suffix('b') {
+        (n, 1e9)
+    } else {
+        (label.as_str(), 1.0)
+    };
+
+    number.parse::<f64>().ok().map(|n| n * multiplier).filter(|size| *size > 0.0)
 }
 
 /// Slippage information for a given trade size
@@ -191,6 +323,59 @@ pub struct SequencerHealthFeature {
     pub pending_tx_count: u64,
 }
 
+/// Balances held by one of our own execution wallets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletBalanceFeature {
+    pub wallet_address: String,
+    pub native_symbol: String, // "ETH" on all supported chains
+    pub native_balance: String, // in whole units, not wei
+    pub token_balances: Vec<TokenBalance>,
+}
+
+/// ERC-20 balance of a wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBalance {
+    pub token: TokenInfo,
+    pub balance: String, // decimal-adjusted
+}
+
+/// Lending pool activity in one block: position events and reserve parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LendingFeature {
+    pub protocol: String, // "aave_v3"
+    pub pool_address: String,
+    pub events: Vec<Log>, // raw Pool logs (Supply, Withdraw, Borrow, Repay, LiquidationCall)
+    pub reserves: Vec<LendingReserve>,
+}
+
+/// Configuration and oracle price of one lending reserve
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LendingReserve {
+    pub token: TokenInfo,
+    pub configuration: String, // raw reserve configuration bitmap, 0x-prefixed hex
+    pub oracle_price_usd: Option<f64>,
+}
+
+/// Post-block state of watched contracts, limited to what the block touched
+///
+/// Carries the accounts and storage slots transactions in the block read or
+/// wrote, with their values after the block, so a local EVM can execute
+/// against them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvmStateFeature {
+    pub accounts: Vec<EvmAccountState>,
+}
+
+/// Touched state of one account; fields the block did not touch are None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvmAccountState {
+    pub address: String,
+    pub balance: Option<String>, // wei, 0x-prefixed hex
+    pub nonce: Option<u64>,
+    pub code: Option<String>, // 0x-prefixed hex
+    pub storage: HashMap<String, String>, // slot -> value, 0x-prefixed hex words
+}
+
 /// Sequencer operational status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -271,6 +456,9 @@ impl Feature {
             FeatureType::Gas => "gas",
             FeatureType::FlashLoan => "flash_loan",
             FeatureType::SequencerHealth => "sequencer_health",
+            FeatureType::WalletBalance => "wallet_balance",
+            FeatureType::Lending => "lending",
+            FeatureType::EvmState => "evm_state",
         }
     }
 
@@ -320,8 +508,121 @@ impl Feature {
                     ));
                 }
             }
+            FeatureData::WalletBalance(wallet) => {
+                if wallet.wallet_address.is_empty() {
+                    return Err(crate::DataplaneError::schema_validation(
+                        "Wallet address cannot be empty",
+                    ));
+                }
+            }
+            FeatureData::Lending(lending) => {
+                if lending.pool_address.is_empty() {
+                    return Err(crate::DataplaneError::schema_validation(
+                        "Lending pool address cannot be empty",
+                    ));
+                }
+                if lending.reserves.iter().any(|reserve| reserve.oracle_price_usd.is_some_and(|price| price < 0.0)) {
+                    return Err(crate::DataplaneError::schema_validation(
+                        "Oracle price cannot be negative",
+                    ));
+                }
+            }
+            FeatureData::EvmState(evm_state) => {
+                if evm_state.accounts.iter().any(|account| account.address.is_empty()) {
+                    return Err(crate::DataplaneError::schema_validation(
+                        "Account address cannot be empty",
+                    ));
+                }
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_curve_interpolates_both_sides() {
+        // 10bps per $100k, at 2000 token1 per token0 and a dollar token1
+        let curve = DepthCurve::sample(2000.0, 1.0, &[100_000.0, 1_000_000.0], |size| size / 100_000.0 * 10.0);
+
+        // Selling 250 token0 (500k) sits between the samples, linear in output
+        let out = curve.amount_out(true, 250.0).unwrap();
+        assert!((out - 495_500.0).abs() < 1e-6, "{}", out);
+        let slippage = curve.slippage_bps(true, 250.0, 2000.0).unwrap();
+        assert!((slippage - 90.0).abs() < 1e-6, "{}", slippage);
+
+        // Selling 50k token1 is half the first sample
+        let out = curve.amount_out(false, 50_000.0).unwrap();
+        assert!((out - 25.0 * (1.0 - 0.001)).abs() < 1e-9, "{}", out);
+
+        // Past the last sample at the last segment's marginal rate, never decreasing
+        let past = curve.amount_out(false, 2_000_000.0).unwrap();
+        assert!(past >= curve.amount_out(false, 1_000_000.0).unwrap());
+        assert!(curve.slippage_bps(false, 2_000_000.0, 2000.0).unwrap() > 100.0);
+    }
+
+    #[test]
+    fn test_depth_curve_samples_non_stable_token1_in_token_units() {
+        // WBTC/WETH at 20 WETH per WBTC, WETH at $2000: $100k is 50 WETH or 2.5 WBTC
+        let curve = DepthCurve::sample(20.0, 2000.0, &[100_000.0, 1_000_000.0], |size| size / 100_000.0 * 10.0);
+
+        assert_eq!(curve.zero_for_one[0].amount_in, 2.5);
+        assert_eq!(curve.one_for_zero[0].amount_in, 50.0);
+        let slippage = curve.slippage_bps(false, 50.0, 20.0).unwrap();
+        assert!((slippage - 10.0).abs() < 1e-6, "{}", slippage);
+
+        // 12.5 WBTC is $500k, priced between the samples as for a dollar token1
+        let slippage = curve.slippage_bps(true, 12.5, 20.0).unwrap();
+        assert!((slippage - 90.0).abs() < 1e-6, "{}", slippage);
+
+        assert!(!DepthCurve::sample(20.0, 0.0, &[100_000.0], |_| 1.0).has_samples(true));
+    }
+
+    #[test]
+    fn test_depth_curve_reads_legacy_labels() {
+        let json = r#"{"sizes": {"1m": {"slippage_bps": 20.0, "price_impact": 0.002}, "100k": {"slippage_bps": 2.0, "price_impact": 0.0002}}}"#;
+        let curve: DepthCurve = serde_json::from_str(json).unwrap();
+
+        assert_eq!(curve.labelled_slippage(), vec![(100_000.0, 2.0), (1_000_000.0, 20.0)]);
+        assert!(!curve.has_samples(true));
+        assert_eq!(curve.amount_out(true, 1.0), None);
+        assert_eq!(parse_size_label("2.5M"), Some(2_500_000.0));
+    }
+
+    #[test]
+    fn test_lending_feature_survives_untagged_decoding() {
+        let data = FeatureData::Lending(LendingFeature {
+            protocol: "aave_v3".to_string(),
+            pool_address: "0x87870bca3f3fd6335c3f4ce8392d69350b4fa4e2".to_string(),
+            events: Vec::new(),
+            reserves: vec![LendingReserve {
+                token: TokenInfo { address: "0xc02a".to_string(), symbol: "WETH".to_string(), decimals: 18 },
+                configuration: "0x0".to_string(),
+                oracle_price_usd: Some(3000.0),
+            }],
+        });
+
+        let decoded: FeatureData = serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+        assert!(matches!(decoded, FeatureData::Lending(lending) if lending.reserves[0].oracle_price_usd == Some(3000.0)));
+    }
+
+    #[test]
+    fn test_evm_state_feature_survives_untagged_decoding() {
+        let data = FeatureData::EvmState(EvmStateFeature {
+            accounts: vec![EvmAccountState {
+                address: "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640".to_string(),
+                balance: None,
+                nonce: Some(1),
+                code: None,
+                storage: [("0x0".to_string(), "0x1".to_string())].into_iter().collect(),
+            }],
+        });
+
+        let decoded: FeatureData = serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+        assert!(matches!(decoded, FeatureData::EvmState(evm_state) if evm_state.accounts[0].storage["0x0"] == "0x1"));
+    }
+}
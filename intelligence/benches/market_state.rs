@@ -0,0 +1,258 @@
+//! Market state ingest and detector-read throughput
+//!
+//! A block is one AMM feature per pool plus a gas and a sequencer feature on
+//! each chain. The contended benches ingest with one task per chain while
+//! detector tasks read pools, gas and sequencer health in a loop, from sealed
+//! block views as the detectors do. `detect/getters_while_ingesting` runs the
+//! same pass through the locked getters for comparison.
+//!
+//! The runtime has 8 worker threads whatever the core count, so on fewer
+//! cores the reader tasks compete with ingest for CPU time as well as for
+//! state. The README compares results with the pre-sharding `MarketState`.
+//!
+//! Run with `cargo bench -p qenus-intelligence --bench market_state`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use qenus_dataplane::{
+    AmmFeature, Chain, DepthCurve, Feature, FeatureData, FeatureType, GasFeature, SequencerHealthFeature,
+    SequencerStatus, SlippageInfo, TokenInfo,
+};
+use qenus_intelligence::MarketState;
+use tokio::runtime::Runtime;
+
+const CHAINS: [Chain; 4] = [Chain::Ethereum, Chain::Arbitrum, Chain::Optimism, Chain::Base];
+
+/// Pools per chain
+const POOLS: usize = 100;
+
+/// Detector tasks reading while a block is ingested
+const READERS: usize = 4;
+
+/// Detector passes per reader in the detect benches
+const PASSES: usize = 50;
+
+fn pool(chain: Chain, block: u64, index: usize) -> Feature {
+    let mut sizes = HashMap::new();
+    for (label, slippage) in [("100k", 2.0), ("1m", 15.0), ("10m", 120.0)] {
+        sizes.insert(label.to_string(), SlippageInfo { slippage_bps: slippage, price_impact: slippage });
+    }
+    Feature::new(
+        block,
+        chain,
+        FeatureType::Amm,
+        FeatureData::Amm(Box::new(AmmFeature {
+            pool_address: format!("0x{:040x}", index),
+            pool_type: if index.is_multiple_of(3) { "curve" } else { "uniswap_v3" }.to_string(),
+            pool_id: None,
+            token0: TokenInfo { address: format!("0x{:040x}", 1), symbol: "WETH".to_string(), decimals: 18 },
+            token1: TokenInfo { address: format!("0x{:040x}", 2), symbol: format!("TKN{}", index % 20), decimals: 6 },
+            fee_tier: Some(500),
+            reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+            mid_price: 2000.0 + block as f64 + index as f64,
+            liquidity: "1000000".to_string(),
+            depth: DepthCurve { sizes, ..Default::default() },
+            volume_24h: None,
+            fees_24h: None,
+        })),
+        "bench".to_string(),
+    )
+}
+
+fn gas(chain: Chain, block: u64) -> Feature {
+    Feature::new(block, chain, FeatureType::Gas, FeatureData::Gas(GasFeature {
+        base_fee: 20.0 + block as f64 % 7.0,
+        priority_fee: 1.0,
+        gas_used_ratio: 0.5,
+        next_base_fee_estimate: 20.0,
+        fast_gas_price: 22.0,
+        standard_gas_price: 21.0,
+        safe_gas_price: 20.0,
+        pending_tx_count: 0,
+    }), "bench".to_string())
+}
+
+fn sequencer(chain: Chain, block: u64) -> Feature {
+    Feature::new(block, chain, FeatureType::SequencerHealth, FeatureData::SequencerHealth(SequencerHealthFeature {
+        sequencer_address: format!("0x{:040x}", 3),
+        status: SequencerStatus::Healthy,
+        block_interval_avg: 2.0,
+        block_interval_variance: 0.1,
+        uptime_percentage: 100.0,
+        last_block_time: Utc::now(),
+        pending_tx_count: 0,
+    }), "bench".to_string())
+}
+
+/// One block's features for `chain`
+fn block(chain: Chain, number: u64) -> Vec<Feature> {
+    let mut features: Vec<Feature> = (0..POOLS).map(|index| pool(chain, number, index)).collect();
+    features.push(gas(chain, number));
+    features.push(sequencer(chain, number));
+    features
+}
+
+async fn warm_state() -> Arc<MarketState> {
+    let state = Arc::new(MarketState::new(3600));
+    for chain in CHAINS {
+        for feature in block(chain, 1) {
+            state.ingest_feature(feature).await.unwrap();
+        }
+    }
+    state
+}
+
+/// What a detector reads on every pass, through the locked getters
+async fn getter_pass(state: &MarketState) -> usize {
+    let mut seen = 0;
+    for chain in CHAINS {
+        seen += state.get_amm_pools(chain).await.len();
+        seen += state.get_gas_price(chain).await.is_some() as usize;
+        seen += state.is_sequencer_healthy(chain).await as usize;
+    }
+    seen
+}
+
+/// What a detector reads on every pass, from the last sealed block of each chain
+fn detector_pass(state: &MarketState) -> usize {
+    let fresh_since = state.fresh_since();
+    let mut seen = 0;
+    for chain in CHAINS {
+        if let Some(view) = state.block_view(chain) {
+            seen += view.fresh_pools(fresh_since).count();
+            seen += view.gas_price(fresh_since).is_some() as usize;
+            seen += view.is_sequencer_healthy(fresh_since) as usize;
+        }
+    }
+    seen
+}
+
+/// Ingest a block on every chain, one task per chain
+async fn ingest_block(state: &Arc<MarketState>, number: u64) {
+    let writers: Vec<_> = CHAINS.into_iter().map(|chain| {
+        let state = state.clone();
+        let features = block(chain, number);
+        tokio::spawn(async move {
+            for feature in features {
+                state.ingest_feature(feature).await.unwrap();
+            }
+        })
+    }).collect();
+    for writer in writers {
+        writer.await.unwrap();
+    }
+}
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_multi_thread().worker_threads(8).enable_all().build().unwrap()
+}
+
+fn bench_ingest(c: &mut Criterion) {
+    let runtime = runtime();
+    let mut group = c.benchmark_group("ingest");
+    group.throughput(Throughput::Elements((CHAINS.len() * (POOLS + 2)) as u64));
+
+    let state = runtime.block_on(warm_state());
+    let mut number = 2;
+    group.bench_function("sequential", |b| {
+        b.to_async(&runtime).iter(|| {
+            number += 1;
+            let state = state.clone();
+            let features: Vec<Feature> = CHAINS.into_iter().flat_map(|chain| block(chain, number)).collect();
+            async move {
+                for feature in features {
+                    state.ingest_feature(feature).await.unwrap();
+                }
+            }
+        })
+    });
+
+    for readers in [0, READERS] {
+        group.bench_with_input(BenchmarkId::new("per_chain_tasks", format!("{}_readers", readers)), &readers, |b, &readers| {
+            b.to_async(&runtime).iter(|| {
+                number += 1;
+                let state = state.clone();
+                async move {
+                    let done = Arc::new(AtomicBool::new(false));
+                    let detectors: Vec<_> = (0..readers).map(|_| {
+                        let (state, done) = (state.clone(), done.clone());
+                        tokio::spawn(async move {
+                            while !done.load(Ordering::Relaxed) {
+                                std::hint::black_box(detector_pass(&state));
+                                tokio::task::yield_now().await;
+                            }
+                        })
+                    }).collect();
+                    ingest_block(&state, number).await;
+                    done.store(true, Ordering::Relaxed);
+                    for detector in detectors {
+                        detector.await.unwrap();
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_detect(c: &mut Criterion) {
+    let runtime = runtime();
+    let mut group = c.benchmark_group("detect");
+    group.throughput(Throughput::Elements((READERS * PASSES) as u64));
+
+    let state = runtime.block_on(warm_state());
+    for chain in CHAINS {
+        state.end_block(chain, 1);
+    }
+    for (name, ingesting, getters) in [
+        ("idle", false, false),
+        ("while_ingesting", true, false),
+        ("getters_while_ingesting", true, true),
+    ] {
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| {
+                let state = state.clone();
+                async move {
+                    let done = Arc::new(AtomicBool::new(false));
+                    let writer = ingesting.then(|| {
+                        let (state, done) = (state.clone(), done.clone());
+                        tokio::spawn(async move {
+                            let mut number = 2;
+                            while !done.load(Ordering::Relaxed) {
+                                number += 1;
+                                ingest_block(&state, number).await;
+                            }
+                        })
+                    });
+                    let detectors: Vec<_> = (0..READERS).map(|_| {
+                        let state = state.clone();
+                        tokio::spawn(async move {
+                            for _ in 0..PASSES {
+                                if getters {
+                                    std::hint::black_box(getter_pass(&state).await);
+                                } else {
+                                    std::hint::black_box(detector_pass(&state));
+                                }
+                            }
+                        })
+                    }).collect();
+                    for detector in detectors {
+                        detector.await.unwrap();
+                    }
+                    done.store(true, Ordering::Relaxed);
+                    if let Some(writer) = writer {
+                        writer.await.unwrap();
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ingest, bench_detect);
+criterion_main!(benches);
@@ -0,0 +1,615 @@
+//! Operator HTTP API
+//!
+//! Serves JSON views of the market state, recent candidates with their
+//! evaluation and decision reasoning, open intents, positions, strategy
+//! budgets and model performance. Admin actions pause or resume strategies, engage the kill
+//! switch and force a state snapshot to disk; they need the configured admin
+//! token and are refused when none is set.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::budget::BudgetUsage;
+use crate::config::IntelligenceConfig;
+use crate::decision::{DecisionEngine, PolicyCheck, TradeDecision};
+use crate::detectors::{DetectorManager, DetectorMetrics};
+use crate::error::{IntelligenceError, Result};
+use crate::feedback::{FeedbackProcessor, ModelPerformance};
+use crate::state::{FeedStatus, MarketState, MarketStateSnapshot, MarketStateStats};
+use crate::types::{Candidate, EvaluationResult, StrategyConfig, TradeIntent};
+use crate::verifier::VerificationReport;
+
+/// Candidates returned when no limit is given
+const DEFAULT_CANDIDATE_LIMIT: usize = 50;
+
+/// What happened to one detected candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateRecord {
+    /// Ties the candidate to its evaluation, decision, intent and audit record
+    pub correlation_id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+    pub candidate: Candidate,
+
+    /// Simulation result, if the candidate got that far
+    pub evaluation: Option<EvaluationResult>,
+
+    /// Decision outcome, if the candidate got that far
+    pub should_execute: Option<bool>,
+    pub score: Option<f64>,
+    pub reasoning: Vec<String>,
+    pub warnings: Vec<String>,
+    pub checks: Vec<PolicyCheck>,
+
+    /// Intent built from an approved decision
+    pub intent_id: Option<Uuid>,
+
+    /// Pre-emission EVM verification of the built intent
+    #[serde(default)]
+    pub verification: Option<VerificationReport>,
+
+    /// Why the pipeline stopped early
+    pub error: Option<String>,
+}
+
+impl CandidateRecord {
+    /// Record a decided candidate
+    pub fn decided(decision: &TradeDecision, intent_id: Option<Uuid>) -> Self {
+        Self {
+            correlation_id: Uuid::new_v4(),
+            recorded_at: Utc::now(),
+            candidate: decision.candidate.clone(),
+            evaluation: Some(decision.evaluation.clone()),
+            should_execute: Some(decision.should_execute),
+            score: Some(decision.score),
+            reasoning: decision.reasoning.clone(),
+            warnings: decision.warnings.clone(),
+            checks: decision.checks.clone(),
+            intent_id,
+            verification: None,
+            error: None,
+        }
+    }
+
+    /// Record a candidate that was skipped or failed before a decision
+    pub fn stopped(candidate: Candidate, evaluation: Option<EvaluationResult>, reason: impl Into<String>) -> Self {
+        Self {
+            correlation_id: Uuid::new_v4(),
+            recorded_at: Utc::now(),
+            candidate,
+            evaluation,
+            should_execute: None,
+            score: None,
+            reasoning: Vec::new(),
+            warnings: Vec::new(),
+            checks: Vec::new(),
+            intent_id: None,
+            verification: None,
+            error: Some(reason.into()),
+        }
+    }
+
+    /// Record a rebalancing intent, which has no detected candidate behind it
+    ///
+    /// The candidate is rebuilt from the intent so the record carries its
+    /// asset and chains; `emitted` is false when the intent was only planned.
+    pub fn rebalance(intent: &TradeIntent, emitted: bool) -> Self {
+        let candidate = Candidate {
+            strategy: intent.strategy.clone(),
+            asset: intent.asset.clone(),
+            spread_bps: 0.0,
+            legs: intent.legs.iter()
+                .map(|leg| {
+                    let domain = match leg.destination_domain {
+                        Some(destination) => format!("{:?} -> {:?}", leg.domain, destination),
+                        None => format!("{:?}", leg.domain),
+                    };
+                    (domain, format!("{:?}", leg.action).to_lowercase())
+                })
+                .collect(),
+            detected_at: intent.created_at,
+            confidence: 1.0,
+            first_seen_at: None,
+            details: None,
+        };
+        let reasoning = intent.legs.iter()
+            .map(|leg| format!(
+                "Move ${:.0} {} via {} (cost ${:.2})", intent.size_usd, intent.asset, leg.protocol, -intent.expected_pnl_usd
+            ))
+            .collect();
+
+        Self {
+            correlation_id: Uuid::new_v4(),
+            recorded_at: Utc::now(),
+            candidate,
+            evaluation: None,
+            should_execute: Some(true),
+            score: None,
+            reasoning,
+            warnings: Vec::new(),
+            checks: Vec::new(),
+            intent_id: emitted.then_some(intent.intent_id),
+            verification: None,
+            error: None,
+        }
+    }
+}
+
+/// Engaged kill switch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillSwitch {
+    pub engaged_at: DateTime<Utc>,
+    pub reason: String,
+}
+
+/// Strategy as seen by the operator
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyStatus {
+    pub name: String,
+    pub enabled: bool,
+    pub paused: bool,
+}
+
+/// Everything written by a forced snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct OperatorSnapshot {
+    pub market: MarketStateSnapshot,
+    pub feeds: Vec<FeedStatus>,
+    pub positions: HashMap<String, f64>,
+    pub budgets: Vec<BudgetUsage>,
+    pub open_intents: Vec<TradeIntent>,
+    pub performance: ModelPerformance,
+    pub detectors: HashMap<String, DetectorMetrics>,
+    pub paused_strategies: Vec<String>,
+    pub kill_switch: Option<KillSwitch>,
+}
+
+/// Shared state behind the operator API
+///
+/// The detection loop records candidate outcomes here and checks pauses and
+/// the kill switch before emitting intents.
+pub struct OperatorState {
+    market_state: Arc<MarketState>,
+    detector_manager: Arc<DetectorManager>,
+    decision_engine: Arc<DecisionEngine>,
+    feedback: Arc<FeedbackProcessor>,
+    strategies: HashMap<String, StrategyConfig>,
+    snapshot_dir: PathBuf,
+    admin_token: Option<String>,
+    max_recent: usize,
+    recent: RwLock<VecDeque<CandidateRecord>>,
+    paused: RwLock<HashSet<String>>,
+    kill_switch: RwLock<Option<KillSwitch>>,
+}
+
+impl OperatorState {
+    pub fn new(
+        config: &IntelligenceConfig,
+        market_state: Arc<MarketState>,
+        detector_manager: Arc<DetectorManager>,
+        decision_engine: Arc<DecisionEngine>,
+        feedback: Arc<FeedbackProcessor>,
+    ) -> Self {
+        Self {
+            market_state,
+            detector_manager,
+            decision_engine,
+            feedback,
+            strategies: config.strategies.clone(),
+            snapshot_dir: PathBuf::from(&config.api.snapshot_dir),
+            admin_token: config.api.admin_token.clone(),
+            max_recent: config.api.recent_candidates,
+            recent: RwLock::new(VecDeque::new()),
+            paused: RwLock::new(HashSet::new()),
+            kill_switch: RwLock::new(None),
+        }
+    }
+
+    /// Remember a candidate outcome, dropping the oldest past capacity
+    pub async fn record(&self, record: CandidateRecord) {
+        let mut recent = self.recent.write().await;
+        recent.push_front(record);
+        recent.truncate(self.max_recent);
+    }
+
+    /// Most recent candidate outcomes, newest first
+    pub async fn recent_candidates(&self, limit: usize) -> Vec<CandidateRecord> {
+        self.recent.read().await.iter().take(limit).cloned().collect()
+    }
+
+    /// Whether a strategy was paused by the operator
+    pub async fn is_paused(&self, strategy: &str) -> bool {
+        self.paused.read().await.contains(strategy)
+    }
+
+    /// Stop emitting intents for a strategy
+    pub async fn pause(&self, strategy: &str) -> Result<()> {
+        self.known_strategy(strategy)?;
+        self.paused.write().await.insert(strategy.to_string());
+        warn!("⏸️  Strategy {} paused by operator", strategy);
+        Ok(())
+    }
+
+    /// Resume a paused strategy
+    pub async fn resume(&self, strategy: &str) -> Result<()> {
+        self.known_strategy(strategy)?;
+        self.paused.write().await.remove(strategy);
+        info!("▶️  Strategy {} resumed by operator", strategy);
+        Ok(())
+    }
+
+    /// Stop emitting intents for every strategy
+    pub async fn engage_kill_switch(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        warn!("🛑 Kill switch engaged: {}", reason);
+        *self.kill_switch.write().await = Some(KillSwitch { engaged_at: Utc::now(), reason });
+    }
+
+    /// Allow intents again
+    pub async fn release_kill_switch(&self) {
+        info!("Kill switch released");
+        *self.kill_switch.write().await = None;
+    }
+
+    /// Current kill switch, if engaged
+    pub async fn kill_switch(&self) -> Option<KillSwitch> {
+        self.kill_switch.read().await.clone()
+    }
+
+    /// Status of every configured strategy
+    pub async fn strategies(&self) -> Vec<StrategyStatus> {
+        let paused = self.paused.read().await;
+        let mut strategies: Vec<StrategyStatus> = self.strategies.values()
+            .map(|strategy| StrategyStatus {
+                name: strategy.name.clone(),
+                enabled: strategy.enabled,
+                paused: paused.contains(&strategy.name),
+            })
+            .collect();
+        strategies.sort_by(|a, b| a.name.cmp(&b.name));
+        strategies
+    }
+
+    /// Collect a full snapshot of state, positions and model health
+    pub async fn snapshot(&self) -> OperatorSnapshot {
+        let mut paused_strategies: Vec<String> = self.paused.read().await.iter().cloned().collect();
+        paused_strategies.sort();
+
+        OperatorSnapshot {
+            market: self.market_state.snapshot().await,
+            feeds: self.market_state.feed_status().await,
+            positions: self.decision_engine.positions().await,
+            budgets: self.decision_engine.budget_usage(&self.strategies).await,
+            open_intents: self.feedback.open_intents().await,
+            performance: self.feedback.get_performance().await,
+            detectors: self.detector_manager.metrics().await,
+            paused_strategies,
+            kill_switch: self.kill_switch().await,
+        }
+    }
+
+    /// Write a snapshot as JSON into the snapshot directory
+    pub async fn write_snapshot(&self) -> Result<PathBuf> {
+        let snapshot = self.snapshot().await;
+        let path = self.snapshot_dir.join(format!(
+            "snapshot-{}.json",
+            snapshot.market.taken_at.format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+
+        tokio::fs::create_dir_all(&self.snapshot_dir).await?;
+        tokio::fs::write(&path, serde_json::to_vec_pretty(&snapshot)?).await?;
+
+        info!("📸 Wrote state snapshot to {}", path.display());
+        Ok(path)
+    }
+
+    fn known_strategy(&self, strategy: &str) -> Result<()> {
+        if self.strategies.contains_key(strategy) {
+            Ok(())
+        } else {
+            Err(IntelligenceError::InvalidStrategy(strategy.to_string()))
+        }
+    }
+
+    fn authorize(&self, headers: &HeaderMap) -> std::result::Result<(), ApiError> {
+        let Some(token) = &self.admin_token else {
+            return Err(ApiError(StatusCode::FORBIDDEN, "admin actions are disabled: no admin token configured".to_string()));
+        };
+
+        let provided = headers.get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        if provided == Some(token.as_str()) {
+            Ok(())
+        } else {
+            Err(ApiError(StatusCode::UNAUTHORIZED, "missing or invalid admin token".to_string()))
+        }
+    }
+}
+
+/// Error response with a JSON body
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<IntelligenceError> for ApiError {
+    fn from(error: IntelligenceError) -> Self {
+        let status = match error {
+            IntelligenceError::InvalidStrategy(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self(status, error.to_string())
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Build the operator API router
+pub fn router(state: Arc<OperatorState>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/api/market/stats", get(market_stats))
+        .route("/api/candidates", get(candidates))
+        .route("/api/intents", get(open_intents))
+        .route("/api/positions", get(positions))
+        .route("/api/budgets", get(budgets))
+        .route("/api/performance", get(performance))
+        .route("/api/detectors", get(detectors))
+        .route("/api/strategies", get(strategies))
+        .route("/api/strategies/:name/pause", post(pause_strategy))
+        .route("/api/strategies/:name/resume", post(resume_strategy))
+        .route("/api/kill-switch", get(kill_switch).post(set_kill_switch))
+        .route("/api/snapshot", post(force_snapshot))
+        .with_state(state)
+}
+
+/// Serve the operator API until the task is dropped
+pub async fn serve(state: Arc<OperatorState>, bind_addr: &str) -> Result<()> {
+    let addr: SocketAddr = bind_addr.parse()
+        .map_err(|e| IntelligenceError::internal(format!("Invalid API bind address {}: {}", bind_addr, e)))?;
+
+    info!("🌐 Operator API listening on http://{}", addr);
+    axum::Server::try_bind(&addr)
+        .map_err(|e| IntelligenceError::internal(format!("Failed to bind operator API: {}", e)))?
+        .serve(router(state).into_make_service())
+        .await
+        .map_err(|e| IntelligenceError::internal(format!("Operator API failed: {}", e)))
+}
+
+#[derive(Debug, Serialize)]
+struct MarketStatsResponse {
+    stats: MarketStateStats,
+    feeds: Vec<FeedStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CandidateQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KillSwitchRequest {
+    engaged: bool,
+    reason: Option<String>,
+}
+
+async fn health(State(state): State<Arc<OperatorState>>) -> Json<serde_json::Value> {
+    Json(json!({
+        "status": "ok",
+        "version": crate::VERSION,
+        "kill_switch": state.kill_switch().await.is_some(),
+    }))
+}
+
+async fn market_stats(State(state): State<Arc<OperatorState>>) -> Json<MarketStatsResponse> {
+    Json(MarketStatsResponse {
+        stats: state.market_state.get_stats().await,
+        feeds: state.market_state.feed_status().await,
+    })
+}
+
+async fn candidates(
+    State(state): State<Arc<OperatorState>>,
+    Query(query): Query<CandidateQuery>,
+) -> Json<Vec<CandidateRecord>> {
+    Json(state.recent_candidates(query.limit.unwrap_or(DEFAULT_CANDIDATE_LIMIT)).await)
+}
+
+async fn open_intents(State(state): State<Arc<OperatorState>>) -> Json<Vec<TradeIntent>> {
+    Json(state.feedback.open_intents().await)
+}
+
+async fn positions(State(state): State<Arc<OperatorState>>) -> Json<HashMap<String, f64>> {
+    Json(state.decision_engine.positions().await)
+}
+
+async fn budgets(State(state): State<Arc<OperatorState>>) -> Json<Vec<BudgetUsage>> {
+    Json(state.decision_engine.budget_usage(&state.strategies).await)
+}
+
+async fn performance(State(state): State<Arc<OperatorState>>) -> Json<ModelPerformance> {
+    Json(state.feedback.get_performance().await)
+}
+
+async fn detectors(State(state): State<Arc<OperatorState>>) -> Json<HashMap<String, DetectorMetrics>> {
+    Json(state.detector_manager.metrics().await)
+}
+
+async fn strategies(State(state): State<Arc<OperatorState>>) -> Json<Vec<StrategyStatus>> {
+    Json(state.strategies().await)
+}
+
+async fn pause_strategy(
+    State(state): State<Arc<OperatorState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Vec<StrategyStatus>>> {
+    state.authorize(&headers)?;
+    state.pause(&name).await?;
+    Ok(Json(state.strategies().await))
+}
+
+async fn resume_strategy(
+    State(state): State<Arc<OperatorState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Vec<StrategyStatus>>> {
+    state.authorize(&headers)?;
+    state.resume(&name).await?;
+    Ok(Json(state.strategies().await))
+}
+
+async fn kill_switch(State(state): State<Arc<OperatorState>>) -> Json<Option<KillSwitch>> {
+    Json(state.kill_switch().await)
+}
+
+async fn set_kill_switch(
+    State(state): State<Arc<OperatorState>>,
+    headers: HeaderMap,
+    Json(request): Json<KillSwitchRequest>,
+) -> ApiResult<Json<Option<KillSwitch>>> {
+    state.authorize(&headers)?;
+    if request.engaged {
+        state.engage_kill_switch(request.reason.unwrap_or_else(|| "operator request".to_string())).await;
+    } else {
+        state.release_kill_switch().await;
+    }
+    Ok(Json(state.kill_switch().await))
+}
+
+async fn force_snapshot(
+    State(state): State<Arc<OperatorState>>,
+    headers: HeaderMap,
+) -> ApiResult<Json<serde_json::Value>> {
+    state.authorize(&headers)?;
+    let path = state.write_snapshot().await?;
+    Ok(Json(json!({ "path": path.display().to_string() })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn operator(admin_token: Option<&str>) -> Arc<OperatorState> {
+        let mut config = IntelligenceConfig::default();
+        config.api.admin_token = admin_token.map(str::to_string);
+        config.api.recent_candidates = 2;
+
+        let market_state = Arc::new(MarketState::new(30));
+        Arc::new(OperatorState::new(
+            &config,
+            market_state.clone(),
+            Arc::new(DetectorManager::new(None, None, market_state.clone())),
+            Arc::new(DecisionEngine::new(market_state, 1_000_000.0)),
+            Arc::new(FeedbackProcessor::new()),
+        ))
+    }
+
+    fn candidate(strategy: &str) -> Candidate {
+        Candidate {
+            strategy: strategy.to_string(),
+            asset: "WETH".to_string(),
+            spread_bps: 12.0,
+            legs: Vec::new(),
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        }
+    }
+
+    async fn send(state: &Arc<OperatorState>, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_recent_candidates_are_bounded() {
+        let state = operator(None);
+        for strategy in ["a", "b", "c"] {
+            state.record(CandidateRecord::stopped(candidate(strategy), None, "paused")).await;
+        }
+
+        let request = Request::get("/api/candidates?limit=10").body(Body::empty()).unwrap();
+        let (status, body) = send(&state, request).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let strategies: Vec<&str> = body.as_array().unwrap().iter()
+            .map(|record| record["candidate"]["strategy"].as_str().unwrap())
+            .collect();
+        assert_eq!(strategies, vec!["c", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_admin_actions_require_token() {
+        let state = operator(Some("secret"));
+
+        let request = Request::post("/api/strategies/dex_arb/pause").body(Body::empty()).unwrap();
+        let (status, _) = send(&state, request).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(!state.is_paused("dex_arb").await);
+
+        let request = Request::post("/api/strategies/dex_arb/pause")
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .body(Body::empty())
+            .unwrap();
+        let (status, _) = send(&state, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(state.is_paused("dex_arb").await);
+
+        let request = Request::post("/api/strategies/unknown/pause")
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .body(Body::empty())
+            .unwrap();
+        let (status, _) = send(&state, request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_admin_actions_refused_without_token() {
+        let state = operator(None);
+
+        let request = Request::post("/api/strategies/dex_arb/pause").body(Body::empty()).unwrap();
+        let (status, _) = send(&state, request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(!state.is_paused("dex_arb").await);
+    }
+
+    #[tokio::test]
+    async fn test_kill_switch() {
+        let state = operator(Some("secret"));
+
+        let request = Request::post("/api/kill-switch")
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"engaged": true, "reason": "bad fills"}"#))
+            .unwrap();
+        let (status, body) = send(&state, request).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reason"], "bad fills");
+        assert!(state.kill_switch().await.is_some());
+    }
+}
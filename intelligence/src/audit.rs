@@ -0,0 +1,340 @@
+//! Decision audit log
+//!
+//! Every detected candidate, including those filtered out before simulation,
+//! and every rebalancing intent produces one append-only record of its
+//! evaluation, policy checks, decision and intent, together with the market
+//! inputs at decision time. Records are written as JSONL to rotating
+//! files and can be filtered back out for post-mortems.
+
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use qenus_dataplane::Chain;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::CandidateRecord;
+use crate::config::AuditConfig;
+use crate::decision::candidate_chains;
+use crate::error::Result;
+use crate::state::{AmmState, GasState, MarketState, SequencerState};
+use crate::types::Candidate;
+use crate::verifier::VerificationStatus;
+
+/// How far a candidate got through the pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Approved and an intent was emitted
+    Emitted,
+
+    /// Approved but no intent was emitted (dry run or kill switch)
+    Approved,
+
+    /// Approved but dropped by pre-emission verification
+    Dropped,
+
+    /// Rejected by one or more policy checks
+    Rejected,
+
+    /// Stopped before a decision (paused, simulation failure, ...)
+    Stopped,
+}
+
+/// Market state the decision was based on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketInputs {
+    pub chains: Vec<Chain>,
+    pub gas: Vec<(Chain, GasState)>,
+    pub sequencers: Vec<(Chain, SequencerState)>,
+
+    /// Pools on the involved chains that trade the candidate's asset
+    pub pools: Vec<(Chain, AmmState)>,
+}
+
+impl MarketInputs {
+    /// Capture the inputs relevant to a candidate
+    ///
+    /// Call it before the candidate is simulated and decided, so the record
+    /// holds what the decision saw rather than the state after it.
+    pub async fn capture(market_state: &MarketState, candidate: &Candidate) -> Self {
+        let asset = &candidate.asset;
+        let mut inputs = MarketInputs {
+            chains: candidate_chains(candidate),
+            ..Default::default()
+        };
+
+        for &chain in &inputs.chains {
+            if let Some(gas) = market_state.get_gas_state(chain).await {
+                inputs.gas.push((chain, gas));
+            }
+            if let Some(sequencer) = market_state.get_sequencer_state(chain).await {
+                inputs.sequencers.push((chain, sequencer));
+            }
+            inputs.pools.extend(
+                market_state.get_amm_pools(chain).await.into_iter()
+                    .filter(|pool| &pool.token0_symbol == asset || &pool.token1_symbol == asset)
+                    .map(|pool| (chain, pool)),
+            );
+        }
+
+        inputs
+    }
+}
+
+/// One audited candidate → evaluation → decision → intent chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    #[serde(flatten)]
+    pub record: CandidateRecord,
+
+    pub outcome: AuditOutcome,
+
+    /// Names of failed policy checks
+    pub rejections: Vec<String>,
+
+    pub market_inputs: MarketInputs,
+}
+
+impl AuditRecord {
+    pub fn new(record: CandidateRecord, market_inputs: MarketInputs) -> Self {
+        let dropped = record.verification.as_ref()
+            .filter(|report| report.status == VerificationStatus::Dropped);
+        let outcome = match (record.should_execute, record.intent_id) {
+            (None, _) => AuditOutcome::Stopped,
+            (Some(false), _) => AuditOutcome::Rejected,
+            (Some(true), Some(_)) => AuditOutcome::Emitted,
+            (Some(true), None) if dropped.is_some() => AuditOutcome::Dropped,
+            (Some(true), None) => AuditOutcome::Approved,
+        };
+        let mut rejections: Vec<String> = record.checks.iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.clone())
+            .collect();
+        if let Some(report) = dropped {
+            let reason = if report.revert_reason.is_some() { "reverted" } else { "deviation" };
+            rejections.push(format!("verification:{}", reason));
+        }
+
+        Self { record, outcome, rejections, market_inputs }
+    }
+}
+
+/// Filter for reading the audit log back
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub strategy: Option<String>,
+    pub asset: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+
+    /// Failed check name prefix (e.g. "min_profit" or "sequencer_healthy"),
+    /// or a substring of the stop reason
+    pub rejection: Option<String>,
+
+    pub outcome: Option<AuditOutcome>,
+
+    /// Keep only the most recent matches
+    pub limit: Option<usize>,
+
+    /// Correlation id or intent id of one record
+    pub id: Option<Uuid>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, record: &AuditRecord) -> bool {
+        let candidate = &record.record.candidate;
+        let recorded_at = record.record.recorded_at;
+
+        self.strategy.as_ref().is_none_or(|s| &candidate.strategy == s)
+            && self.asset.as_ref().is_none_or(|a| &candidate.asset == a)
+            && self.since.is_none_or(|since| recorded_at >= since)
+            && self.until.is_none_or(|until| recorded_at < until)
+            && self.outcome.is_none_or(|outcome| record.outcome == outcome)
+            && self.id.is_none_or(|id| record.record.correlation_id == id || record.record.intent_id == Some(id))
+            && self.rejection.as_ref().is_none_or(|reason| {
+                record.rejections.iter().any(|name| name.starts_with(reason.as_str()))
+                    || record.record.error.as_ref().is_some_and(|e| e.contains(reason.as_str()))
+            })
+    }
+}
+
+/// File currently being appended to
+struct ActiveFile {
+    file: tokio::fs::File,
+    bytes: u64,
+}
+
+/// Append-only JSONL audit log with size-based rotation
+pub struct AuditLog {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    max_files: usize,
+    active: Mutex<Option<ActiveFile>>,
+}
+
+impl AuditLog {
+    pub fn new(config: &AuditConfig) -> Self {
+        Self {
+            dir: PathBuf::from(&config.dir),
+            max_file_bytes: config.max_file_bytes,
+            max_files: config.max_files,
+            active: Mutex::new(None),
+        }
+    }
+
+    /// Append one record, rotating to a new file when the current one is full
+    pub async fn append(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut active = self.active.lock().await;
+        let full = active.as_ref().is_none_or(|file| file.bytes + line.len() as u64 > self.max_file_bytes);
+        if full {
+            *active = Some(self.rotate().await?);
+        }
+
+        if let Some(active) = active.as_mut() {
+            active.file.write_all(&line).await?;
+            active.file.flush().await?;
+            active.bytes += line.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// Read every record in `dir` matching the query, oldest first
+    pub async fn query(dir: impl AsRef<Path>, query: &AuditQuery) -> Result<Vec<AuditRecord>> {
+        let mut records = Vec::new();
+
+        for path in log_files(dir.as_ref()).await? {
+            let contents = tokio::fs::read_to_string(&path).await?;
+            for (line_no, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<AuditRecord>(line) {
+                    Ok(record) if query.matches(&record) => records.push(record),
+                    Ok(_) => {}
+                    // A crash can leave a partial last line
+                    Err(e) => warn!("Skipping bad audit line {}:{}: {}", path.display(), line_no + 1, e),
+                }
+            }
+        }
+
+        if let Some(limit) = query.limit {
+            let skip = records.len().saturating_sub(limit);
+            records.drain(..skip);
+        }
+        Ok(records)
+    }
+
+    /// Start a new file and prune the oldest beyond `max_files`
+    async fn rotate(&self) -> Result<ActiveFile> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let path = self.dir.join(format!("audit-{}.jsonl", Utc::now().format("%Y%m%dT%H%M%S%.6fZ")));
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        let bytes = file.metadata().await?.len();
+        info!("Writing decision audit log to {}", path.display());
+
+        let files = log_files(&self.dir).await?;
+        for old in files.iter().take(files.len().saturating_sub(self.max_files.max(1))) {
+            tokio::fs::remove_file(old).await?;
+        }
+
+        Ok(ActiveFile { file, bytes })
+    }
+}
+
+/// Audit files in `dir`, oldest first
+async fn log_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(e.into()),
+    };
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name.starts_with("audit-") && name.ends_with(".jsonl") {
+            files.push(path);
+        }
+    }
+
+    // Timestamped names sort chronologically
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::PolicyCheck;
+
+    fn record(strategy: &str, failed_check: Option<&str>) -> AuditRecord {
+        let candidate = Candidate {
+            strategy: strategy.to_string(),
+            asset: "WETH".to_string(),
+            spread_bps: 15.0,
+            legs: vec![("Ethereum".to_string(), "buy".to_string())],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        };
+        let mut record = CandidateRecord::stopped(candidate, None, "test");
+        record.error = None;
+        record.should_execute = Some(failed_check.is_none());
+        record.checks = vec![
+            PolicyCheck { name: "min_profit_usd".to_string(), passed: true, value: 900.0, threshold: 500.0 },
+            PolicyCheck {
+                name: failed_check.unwrap_or("max_gas_pct").to_string(),
+                passed: failed_check.is_none(),
+                value: 1.0,
+                threshold: 1.0,
+            },
+        ];
+        AuditRecord::new(record, MarketInputs::default())
+    }
+
+    #[tokio::test]
+    async fn test_rotation_and_query() {
+        let dir = std::env::temp_dir().join(format!("qenus-audit-{}", uuid::Uuid::new_v4()));
+        let config = AuditConfig {
+            enabled: true,
+            dir: dir.to_string_lossy().to_string(),
+            max_file_bytes: 1,
+            max_files: 2,
+        };
+        let log = AuditLog::new(&config);
+
+        log.append(&record("dex_arb", None)).await.unwrap();
+        log.append(&record("dex_arb", Some("sequencer_healthy:Arbitrum"))).await.unwrap();
+        log.append(&record("triangle_arb", Some("max_slippage_bps"))).await.unwrap();
+
+        // One record per file, oldest pruned
+        assert_eq!(log_files(&dir).await.unwrap().len(), 2);
+
+        let all = AuditLog::query(&dir, &AuditQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].outcome, AuditOutcome::Rejected);
+
+        let query = AuditQuery { rejection: Some("sequencer_healthy".to_string()), ..Default::default() };
+        let matched = AuditLog::query(&dir, &query).await.unwrap();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].rejections, vec!["sequencer_healthy:Arbitrum".to_string()]);
+
+        let query = AuditQuery { strategy: Some("triangle_arb".to_string()), ..Default::default() };
+        assert_eq!(AuditLog::query(&dir, &query).await.unwrap().len(), 1);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
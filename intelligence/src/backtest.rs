@@ -0,0 +1,670 @@
+//! Backtesting engine
+//!
+//! Replays a recorded feature range on its own clock through the detectors,
+//! simulator and decision engine, fills approved intents with a configurable
+//! execution model (latency, extra slippage, failures) and attributes the
+//! resulting PnL by strategy, asset, chain and cost component.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use qenus_dataplane::{Chain, Feature};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::config::{BacktestConfig, IntelligenceConfig};
+use crate::decision::{candidate_chains, DecisionEngine, TradeDecision};
+use crate::detectors::DetectorManager;
+use crate::error::Result;
+use crate::intent_builder::IntentBuilder;
+use crate::simulator::{OrderingModel, TradeSimulator};
+use crate::state::MarketState;
+use crate::types::{Candidate, CostBreakdown, EvaluationResult};
+
+/// Read a JSONL file of recorded features, oldest first
+pub async fn load_features(path: impl AsRef<Path>) -> Result<Vec<Feature>> {
+    let path = path.as_ref();
+    let contents = tokio::fs::read_to_string(path).await?;
+
+    let mut features = Vec::new();
+    for (line_no, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Feature>(line) {
+            Ok(feature) => features.push(feature),
+            Err(e) => warn!("Skipping malformed feature at {}:{}: {}", path.display(), line_no + 1, e),
+        }
+    }
+
+    features.sort_by_key(|feature| feature.timestamp);
+    Ok(features)
+}
+
+/// How an approved intent ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillOutcome {
+    /// Executed at the market seen after the latency
+    Filled,
+
+    /// Failed on-chain (execution model failure draw)
+    Failed,
+
+    /// The opportunity was gone by the time the intent landed
+    Missed,
+}
+
+/// One approved intent and its simulated execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestFill {
+    /// Recorded time the intent landed
+    pub timestamp: DateTime<Utc>,
+    pub strategy: String,
+    pub asset: String,
+    pub chains: Vec<Chain>,
+    pub outcome: FillOutcome,
+    pub size_usd: f64,
+
+    /// PnL the decision was approved on
+    pub simulated_pnl_usd: f64,
+
+    /// PnL after latency, extra slippage and failures
+    pub realized_pnl_usd: f64,
+    pub costs: CostBreakdown,
+}
+
+/// Cumulative PnL after a fill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub equity_usd: f64,
+
+    /// Distance below the running peak
+    pub drawdown_usd: f64,
+}
+
+/// One peak-to-recovery drawdown episode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Drawdown {
+    pub peak_at: DateTime<Utc>,
+    pub trough_at: DateTime<Utc>,
+
+    /// None if equity had not recovered by the end of the range
+    pub recovered_at: Option<DateTime<Utc>>,
+    pub depth_usd: f64,
+}
+
+/// PnL attributed to one strategy, asset or chain
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PnlBucket {
+    pub trades: u64,
+    pub wins: u64,
+    pub pnl_usd: f64,
+}
+
+impl PnlBucket {
+    fn add(&mut self, pnl_usd: f64, weight: f64) {
+        self.trades += 1;
+        if pnl_usd > 0.0 {
+            self.wins += 1;
+        }
+        self.pnl_usd += pnl_usd * weight;
+    }
+}
+
+/// Pipeline counts over the replayed range
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestStats {
+    pub features: u64,
+
+    /// Features the market state refused to ingest
+    pub rejected_features: u64,
+    pub scans: u64,
+    pub candidates: u64,
+    pub approved: u64,
+}
+
+/// Backtest results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    /// First and last replayed feature
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub execution_model: BacktestConfig,
+    pub stats: BacktestStats,
+    pub total_pnl_usd: f64,
+
+    /// Share of fills with positive realized PnL
+    pub hit_rate: f64,
+    pub max_drawdown_usd: f64,
+
+    /// Costs summed over every fill
+    pub costs: CostBreakdown,
+    pub by_strategy: BTreeMap<String, PnlBucket>,
+    pub by_asset: BTreeMap<String, PnlBucket>,
+
+    /// Multi-chain trades split their PnL evenly across their chains
+    pub by_chain: BTreeMap<String, PnlBucket>,
+    pub equity_curve: Vec<EquityPoint>,
+    pub drawdowns: Vec<Drawdown>,
+    pub fills: Vec<BacktestFill>,
+}
+
+impl BacktestReport {
+    /// Build the report from fills in landing order
+    pub fn from_fills(
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        execution_model: BacktestConfig,
+        stats: BacktestStats,
+        fills: Vec<BacktestFill>,
+    ) -> Self {
+        let mut costs = CostBreakdown::default();
+        let mut by_strategy: BTreeMap<String, PnlBucket> = BTreeMap::new();
+        let mut by_asset: BTreeMap<String, PnlBucket> = BTreeMap::new();
+        let mut by_chain: BTreeMap<String, PnlBucket> = BTreeMap::new();
+        let mut equity_curve = Vec::with_capacity(fills.len());
+        let mut drawdowns: Vec<Drawdown> = Vec::new();
+
+        let mut equity = 0.0;
+        let mut peak = (0.0, start);
+        let mut open: Option<Drawdown> = None;
+
+        for fill in &fills {
+            let pnl = fill.realized_pnl_usd;
+            costs.gas_usd += fill.costs.gas_usd;
+            costs.protocol_fees_usd += fill.costs.protocol_fees_usd;
+            costs.bridge_fees_usd += fill.costs.bridge_fees_usd;
+            costs.flashloan_fees_usd += fill.costs.flashloan_fees_usd;
+            costs.slippage_usd += fill.costs.slippage_usd;
+            costs.total_usd += fill.costs.total_usd;
+
+            by_strategy.entry(fill.strategy.clone()).or_default().add(pnl, 1.0);
+            by_asset.entry(fill.asset.clone()).or_default().add(pnl, 1.0);
+            for chain in &fill.chains {
+                by_chain.entry(format!("{:?}", chain)).or_default().add(pnl, 1.0 / fill.chains.len() as f64);
+            }
+
+            equity += pnl;
+            if equity >= peak.0 {
+                if let Some(mut drawdown) = open.take() {
+                    drawdown.recovered_at = Some(fill.timestamp);
+                    drawdowns.push(drawdown);
+                }
+                peak = (equity, Some(fill.timestamp));
+            } else {
+                let drawdown = open.get_or_insert_with(|| Drawdown {
+                    peak_at: peak.1.unwrap_or(fill.timestamp),
+                    trough_at: fill.timestamp,
+                    recovered_at: None,
+                    depth_usd: 0.0,
+                });
+                if peak.0 - equity > drawdown.depth_usd {
+                    drawdown.depth_usd = peak.0 - equity;
+                    drawdown.trough_at = fill.timestamp;
+                }
+            }
+
+            equity_curve.push(EquityPoint {
+                timestamp: fill.timestamp,
+                equity_usd: equity,
+                drawdown_usd: peak.0 - equity,
+            });
+        }
+        drawdowns.extend(open);
+
+        let wins = fills.iter().filter(|fill| fill.realized_pnl_usd > 0.0).count();
+        let hit_rate = if fills.is_empty() { 0.0 } else { wins as f64 / fills.len() as f64 };
+
+        Self {
+            start,
+            end,
+            execution_model,
+            stats,
+            total_pnl_usd: equity,
+            hit_rate,
+            max_drawdown_usd: drawdowns.iter().map(|drawdown| drawdown.depth_usd).fold(0.0, f64::max),
+            costs,
+            by_strategy,
+            by_asset,
+            by_chain,
+            equity_curve,
+            drawdowns,
+            fills,
+        }
+    }
+
+    /// Write report.json plus equity, fills and attribution CSVs into a new run directory under `dir`
+    pub async fn write(&self, dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let run_dir = dir.as_ref().join(format!("backtest-{}", Utc::now().format("%Y%m%dT%H%M%SZ")));
+        tokio::fs::create_dir_all(&run_dir).await?;
+
+        tokio::fs::write(run_dir.join("report.json"), serde_json::to_vec_pretty(self)?).await?;
+        tokio::fs::write(run_dir.join("equity.csv"), self.equity_csv()).await?;
+        tokio::fs::write(run_dir.join("fills.csv"), self.fills_csv()).await?;
+        tokio::fs::write(run_dir.join("attribution.csv"), self.attribution_csv()).await?;
+
+        Ok(run_dir)
+    }
+
+    fn equity_csv(&self) -> String {
+        let mut csv = String::from("timestamp,equity_usd,drawdown_usd\n");
+        for point in &self.equity_curve {
+            csv.push_str(&format!("{},{:.6},{:.6}\n", point.timestamp.to_rfc3339(), point.equity_usd, point.drawdown_usd));
+        }
+        csv
+    }
+
+    fn fills_csv(&self) -> String {
+        let mut csv = String::from(
+            "timestamp,strategy,asset,chains,outcome,size_usd,simulated_pnl_usd,realized_pnl_usd,\
+             gas_usd,protocol_fees_usd,bridge_fees_usd,flashloan_fees_usd,slippage_usd,total_usd\n",
+        );
+        for fill in &self.fills {
+            let chains: Vec<String> = fill.chains.iter().map(|chain| format!("{:?}", chain)).collect();
+            csv.push_str(&format!(
+                "{},{},{},{},{:?},{:.2},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6}\n",
+                fill.timestamp.to_rfc3339(), fill.strategy, fill.asset, chains.join("|"), fill.outcome,
+                fill.size_usd, fill.simulated_pnl_usd, fill.realized_pnl_usd, fill.costs.gas_usd,
+                fill.costs.protocol_fees_usd, fill.costs.bridge_fees_usd, fill.costs.flashloan_fees_usd,
+                fill.costs.slippage_usd, fill.costs.total_usd,
+            ));
+        }
+        csv
+    }
+
+    fn attribution_csv(&self) -> String {
+        let mut csv = String::from("dimension,key,trades,wins,pnl_usd\n");
+        for (dimension, buckets) in [("strategy", &self.by_strategy), ("asset", &self.by_asset), ("chain", &self.by_chain)] {
+            for (key, bucket) in buckets {
+                csv.push_str(&format!("{},{},{},{},{:.6}\n", dimension, key, bucket.trades, bucket.wins, bucket.pnl_usd));
+            }
+        }
+        csv
+    }
+}
+
+/// Approved intent waiting out the execution latency
+struct PendingFill {
+    fill_at: DateTime<Utc>,
+    decision: TradeDecision,
+    intent_id: Uuid,
+}
+
+/// Detection, simulation and decision stages on a private market state
+struct ReplayPipeline {
+    market_state: Arc<MarketState>,
+    detectors: DetectorManager,
+    simulator: TradeSimulator,
+    decision_engine: DecisionEngine,
+    intent_builder: IntentBuilder,
+}
+
+/// Replays recorded features through the full decision pipeline
+pub struct Backtester {
+    config: IntelligenceConfig,
+}
+
+impl Backtester {
+    pub fn new(config: IntelligenceConfig) -> Self {
+        Self { config }
+    }
+
+    /// Replay `features` (oldest first) between `from` and `until`
+    pub async fn run(
+        &self,
+        features: Vec<Feature>,
+        from: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<BacktestReport> {
+        let model = &self.config.backtest;
+        let market_state = Arc::new(MarketState::new(self.config.market_state_ttl_secs));
+        let max_position_per_asset = self.config.strategies.values()
+            .map(|s| s.max_position_usd)
+            .fold(0.0, f64::max);
+        let pipeline = ReplayPipeline {
+            detectors: DetectorManager::from_config(&self.config, market_state.clone()),
+            simulator: TradeSimulator::new(market_state.clone()),
+            decision_engine: DecisionEngine::new(market_state.clone(), max_position_per_asset),
+            intent_builder: IntentBuilder::new(market_state.clone()),
+            market_state,
+        };
+
+        let scan_interval = Duration::seconds(self.config.detection.interval_secs.max(1) as i64);
+        let latency = Duration::milliseconds(model.latency_ms as i64);
+        let mut rng = StdRng::seed_from_u64(model.seed);
+
+        let mut stats = BacktestStats::default();
+        let mut pending: Vec<PendingFill> = Vec::new();
+        let mut fills = Vec::new();
+        let mut next_scan: Option<DateTime<Utc>> = None;
+        let (mut start, mut end) = (None, None);
+
+        let in_range = |feature: &Feature| {
+            from.is_none_or(|from| feature.timestamp >= from) && until.is_none_or(|until| feature.timestamp <= until)
+        };
+
+        // The recording knows where each block ends: seal it after its last feature
+        let features: Vec<Feature> = features.into_iter().filter(in_range).collect();
+        let mut last_of_block: HashMap<(Chain, u64), usize> = HashMap::new();
+        for (index, feature) in features.iter().enumerate() {
+            last_of_block.insert((feature.chain, feature.block_number), index);
+        }
+
+        for (index, feature) in features.into_iter().enumerate() {
+            let now = feature.timestamp;
+            start.get_or_insert(now);
+
+            // Scan once every feature of the previous timestamp is in
+            if let Some(last) = end.filter(|last| now > *last && next_scan.is_none_or(|next_scan| *last >= next_scan)) {
+                next_scan = Some(last + scan_interval);
+                pipeline.market_state.set_clock(last);
+                for (decision, intent_id) in self.scan(&pipeline, &mut stats).await? {
+                    pending.push(PendingFill { fill_at: last + latency, decision, intent_id });
+                }
+            }
+            end = Some(now);
+
+            // Intents land on the market as it was when their latency elapsed
+            while pending.first().is_some_and(|fill| fill.fill_at <= now) {
+                let fill = pending.remove(0);
+                pipeline.market_state.set_clock(fill.fill_at);
+                fills.push(self.fill(&pipeline, fill, &mut rng).await);
+            }
+
+            pipeline.market_state.set_clock(now);
+            stats.features += 1;
+            let block = (feature.chain, feature.block_number);
+            if let Err(e) = pipeline.market_state.ingest_feature(feature).await {
+                debug!("Rejected recorded feature: {}", e);
+                stats.rejected_features += 1;
+            }
+            if last_of_block.get(&block) == Some(&index) {
+                pipeline.market_state.end_block(block.0, block.1);
+            }
+        }
+
+        if let Some(last) = end.filter(|last| next_scan.is_none_or(|next_scan| *last >= next_scan)) {
+            pipeline.market_state.set_clock(last);
+            for (decision, intent_id) in self.scan(&pipeline, &mut stats).await? {
+                pending.push(PendingFill { fill_at: last + latency, decision, intent_id });
+            }
+        }
+
+        // Land what is still in flight at the end of the range
+        for fill in pending {
+            pipeline.market_state.set_clock(fill.fill_at);
+            fills.push(self.fill(&pipeline, fill, &mut rng).await);
+        }
+
+        let report = BacktestReport::from_fills(start, end, model.clone(), stats, fills);
+        info!(
+            "Backtest: {} features, {} fills, PnL ${:.2}, hit rate {:.1}%, max drawdown ${:.2}",
+            report.stats.features, report.fills.len(), report.total_pnl_usd,
+            report.hit_rate * 100.0, report.max_drawdown_usd
+        );
+        Ok(report)
+    }
+
+    /// Run every detector and return the decisions approved for execution, with their intent ids
+    ///
+    /// Approved intents reserve their strategy's budget until they fill.
+    async fn scan(&self, pipeline: &ReplayPipeline, stats: &mut BacktestStats) -> Result<Vec<(TradeDecision, Uuid)>> {
+        stats.scans += 1;
+        let candidates = pipeline.detectors.detect_all().await?;
+        let mut approved = Vec::new();
+
+        for candidate in candidates.into_iter().take(self.config.detection.max_candidates_per_cycle) {
+            if candidate.confidence < self.config.detection.min_confidence {
+                continue;
+            }
+            stats.candidates += 1;
+
+            let Some(strategy_config) = self.config.get_strategy(&candidate.strategy) else {
+                continue;
+            };
+            let evaluation = match pipeline.simulator.evaluate(&candidate).await {
+                Ok(evaluation) => evaluation,
+                Err(e) => {
+                    debug!("Simulation failed for {} on {}: {}", candidate.strategy, candidate.asset, e);
+                    continue;
+                }
+            };
+
+            let decision = pipeline.decision_engine.decide(candidate, evaluation, strategy_config).await?;
+            if !decision.should_execute {
+                continue;
+            }
+            // Only intents that pass flow validation would have been emitted
+            let intent = match pipeline.intent_builder.build(&decision).await {
+                Ok(intent) => intent,
+                Err(e) => {
+                    debug!("Intent build failed: {}", e);
+                    continue;
+                }
+            };
+            pipeline.decision_engine.reserve_budget(&decision, &intent).await;
+
+            stats.approved += 1;
+            approved.push((decision, intent.intent_id));
+        }
+
+        Ok(approved)
+    }
+
+    /// Execute one intent under the execution model
+    async fn fill(&self, pipeline: &ReplayPipeline, pending: PendingFill, rng: &mut StdRng) -> BacktestFill {
+        let model = &self.config.backtest;
+        let decision = pending.decision;
+        let simulated = &decision.evaluation;
+
+        let failed = rng.gen::<f64>() < model.failure_rate;
+        let repriced = if failed {
+            None
+        } else {
+            self.reprice(pipeline, &decision.candidate).await
+        };
+
+        let (outcome, size_usd, realized_pnl_usd, costs) = match repriced {
+            Some(evaluation) => {
+                let extra_slippage = evaluation.optimal_size_usd * model.slippage_bps / 10000.0;
+                let mut costs = evaluation.costs;
+                costs.slippage_usd += extra_slippage;
+                costs.total_usd += extra_slippage;
+                (FillOutcome::Filled, evaluation.optimal_size_usd, evaluation.net_pnl_usd - extra_slippage, costs)
+            }
+            None => {
+                // Lost private-mempool bundles never land; anything else reverts on-chain and pays gas
+                let lands = simulated.competition.as_ref()
+                    .is_none_or(|competition| competition.ordering != OrderingModel::PrivateMempool);
+                let gas_usd = if lands { simulated.costs.gas_usd } else { 0.0 };
+                let costs = CostBreakdown { gas_usd, total_usd: gas_usd, ..Default::default() };
+                let outcome = if failed { FillOutcome::Failed } else { FillOutcome::Missed };
+                (outcome, simulated.optimal_size_usd, -gas_usd, costs)
+            }
+        };
+
+        pipeline.decision_engine.budgets().settle(
+            &decision.candidate.strategy,
+            pending.intent_id,
+            costs.gas_usd,
+            realized_pnl_usd,
+            size_usd,
+            pending.fill_at,
+        ).await;
+
+        BacktestFill {
+            timestamp: pending.fill_at,
+            strategy: decision.candidate.strategy.clone(),
+            asset: decision.candidate.asset.clone(),
+            chains: candidate_chains(&decision.candidate),
+            outcome,
+            size_usd,
+            simulated_pnl_usd: simulated.net_pnl_usd,
+            realized_pnl_usd,
+            costs,
+        }
+    }
+
+    /// Re-detect the opportunity at landing time and simulate it on that market
+    async fn reprice(&self, pipeline: &ReplayPipeline, candidate: &Candidate) -> Option<EvaluationResult> {
+        let current = pipeline.detectors.detect_all().await.ok()?
+            .into_iter()
+            .find(|c| c.fingerprint() == candidate.fingerprint())?;
+        pipeline.simulator.evaluate(&current).await.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use qenus_dataplane::{
+        AmmFeature, DepthCurve, FeatureData, FeatureType, FlashLoanFeature, SequencerHealthFeature,
+        SequencerStatus, SlippageInfo, TokenInfo,
+    };
+
+    fn fill(minute: i64, strategy: &str, chains: Vec<Chain>, pnl: f64) -> BacktestFill {
+        BacktestFill {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + minute * 60, 0).unwrap(),
+            strategy: strategy.to_string(),
+            asset: "WETH".to_string(),
+            chains,
+            outcome: FillOutcome::Filled,
+            size_usd: 100_000.0,
+            simulated_pnl_usd: pnl,
+            realized_pnl_usd: pnl,
+            costs: CostBreakdown { gas_usd: 10.0, slippage_usd: 5.0, total_usd: 15.0, ..Default::default() },
+        }
+    }
+
+    fn at(seconds: i64, feature_type: FeatureType, data: FeatureData) -> Feature {
+        let mut feature = Feature::new(1 + seconds as u64, Chain::Ethereum, feature_type, data, "recorded".to_string());
+        feature.timestamp = DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap();
+        feature
+    }
+
+    fn token(symbol: &str) -> TokenInfo {
+        let address = format!("0x{:0>40}", hex::encode(symbol));
+        TokenInfo { address, symbol: symbol.to_string(), decimals: 18 }
+    }
+
+    fn pool(seconds: i64, address: &str, pool_type: &str, mid_price: f64) -> Feature {
+        let sizes = [("1m".to_string(), SlippageInfo { slippage_bps: 20.0, price_impact: 0.002 })];
+        at(seconds, FeatureType::Amm, FeatureData::Amm(Box::new(AmmFeature {
+            pool_address: address.to_string(),
+            pool_type: pool_type.to_string(),
+            pool_id: None,
+            token0: token("WETH"),
+            token1: token("USDC"),
+            fee_tier: Some(500),
+            reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+            mid_price,
+            liquidity: "1000000".to_string(),
+            depth: DepthCurve { sizes: sizes.into_iter().collect::<HashMap<_, _>>(), ..Default::default() },
+            volume_24h: None,
+            fees_24h: None,
+        })))
+    }
+
+    fn recorded_market(seconds: i64) -> Vec<Feature> {
+        vec![
+            at(seconds, FeatureType::SequencerHealth, FeatureData::SequencerHealth(SequencerHealthFeature {
+                sequencer_address: String::new(),
+                status: SequencerStatus::Healthy,
+                block_interval_avg: 12.0,
+                block_interval_variance: 0.0,
+                uptime_percentage: 100.0,
+                last_block_time: DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap(),
+                pending_tx_count: 0,
+            })),
+            at(seconds, FeatureType::FlashLoan, FeatureData::FlashLoan(FlashLoanFeature {
+                provider: "balancer".to_string(),
+                provider_address: String::new(),
+                asset: token("USDC"),
+                available_liquidity: "50000000".to_string(),
+                fee_bps: 0,
+                max_loan_amount: "50000000".to_string(),
+                is_active: true,
+            })),
+            pool(seconds, "0xuni", "uniswap_v3", 2000.0),
+            pool(seconds, "0xcurve", "curve", 2010.0),
+        ]
+    }
+
+    fn flash_arb_only() -> IntelligenceConfig {
+        let mut config = IntelligenceConfig::default();
+        for strategy in config.strategies.values_mut() {
+            strategy.enabled = strategy.name == "flash_arb";
+            // The competition model expects a spread this wide to be contested
+            strategy.risk_limits.min_success_prob = 0.1;
+        }
+        config.backtest.failure_rate = 0.0;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_replays_on_recorded_clock() {
+        // Recorded long ago: only the recorded clock keeps this state fresh
+        let mut features = recorded_market(0);
+        features.extend(recorded_market(10));
+        features.extend(recorded_market(20));
+
+        let report = Backtester::new(flash_arb_only()).run(features.clone(), None, None).await.unwrap();
+        assert_eq!(report.stats.features, 12);
+        assert_eq!(report.stats.scans, 3);
+        assert!(report.stats.approved > 0, "{:?}", report.stats);
+        assert!(report.fills.iter().all(|fill| fill.strategy == "flash_arb"));
+        assert_eq!(report.fills[0].outcome, FillOutcome::Filled);
+        // Scanned once the first snapshot was complete, landed after the latency
+        assert_eq!(report.fills[0].timestamp, DateTime::from_timestamp(1_700_000_002, 0).unwrap());
+        assert_eq!(report.fills.len(), 3);
+
+        // A range that ends before the second snapshot sees only the first
+        let until = DateTime::from_timestamp(1_700_000_005, 0);
+        let report = Backtester::new(flash_arb_only()).run(features, None, until).await.unwrap();
+        assert_eq!(report.stats.features, 4);
+    }
+
+    #[test]
+    fn test_report_equity_drawdowns_and_attribution() {
+        let fills = vec![
+            fill(0, "dex_arb", vec![Chain::Ethereum], 100.0),
+            fill(1, "dex_arb", vec![Chain::Ethereum], -60.0),
+            fill(2, "triangle_arb", vec![Chain::Arbitrum, Chain::Ethereum], -20.0),
+            fill(3, "dex_arb", vec![Chain::Ethereum], 200.0),
+            fill(4, "triangle_arb", vec![Chain::Arbitrum, Chain::Ethereum], -50.0),
+        ];
+        let report = BacktestReport::from_fills(None, None, BacktestConfig::default(), BacktestStats::default(), fills);
+
+        assert!((report.total_pnl_usd - 170.0).abs() < 1e-9);
+        assert!((report.hit_rate - 0.4).abs() < 1e-9);
+        assert!((report.costs.total_usd - 75.0).abs() < 1e-9);
+
+        let equity: Vec<f64> = report.equity_curve.iter().map(|point| point.equity_usd).collect();
+        assert_eq!(equity, vec![100.0, 40.0, 20.0, 220.0, 170.0]);
+
+        // One recovered 80 drawdown, one still open at the end
+        assert_eq!(report.drawdowns.len(), 2);
+        assert!((report.drawdowns[0].depth_usd - 80.0).abs() < 1e-9);
+        assert_eq!(report.drawdowns[0].trough_at, report.fills[2].timestamp);
+        assert_eq!(report.drawdowns[0].recovered_at, Some(report.fills[3].timestamp));
+        assert!(report.drawdowns[1].recovered_at.is_none());
+        assert!((report.max_drawdown_usd - 80.0).abs() < 1e-9);
+
+        assert!((report.by_strategy["dex_arb"].pnl_usd - 240.0).abs() < 1e-9);
+        assert_eq!(report.by_strategy["triangle_arb"].wins, 0);
+        // Cross-chain PnL is split between its chains
+        assert!((report.by_chain["Arbitrum"].pnl_usd + 35.0).abs() < 1e-9);
+        assert!((report.by_chain["Ethereum"].pnl_usd - 205.0).abs() < 1e-9);
+
+        let csv = report.attribution_csv();
+        assert!(csv.contains("strategy,dex_arb,3,2,240.000000"));
+        assert_eq!(report.fills_csv().lines().count(), 6);
+    }
+}
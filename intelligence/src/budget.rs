@@ -0,0 +1,345 @@
+//! Per-strategy capital and gas budgets
+//!
+//! Without budgets every strategy draws on the same per-asset position limit,
+//! so an aggressive strategy can starve the others. The ledger tracks what
+//! each strategy holds and spends: capital and intent slots reserved by open
+//! intents, and gas charged over the last hour and day. The decision engine
+//! checks trades against what is left, emitted intents reserve from it and
+//! execution receipts settle it. Optionally, capital is periodically moved
+//! between strategies according to their trailing risk-adjusted returns.
+
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::config::ReallocationConfig;
+use crate::types::StrategyConfig;
+
+/// Floor on the spread of returns, so a few identical returns do not rate as riskless
+const MIN_RETURN_STDEV: f64 = 1e-4;
+
+/// Capital and intent slot held by an open intent
+///
+/// Reservations lapse with the intent's TTL, so intents that never report
+/// back do not hold the budget forever.
+#[derive(Debug, Clone)]
+struct Reservation {
+    capital_usd: f64,
+    expires_at: DateTime<Utc>,
+}
+
+/// Gas charged to a strategy, estimated on emission and replaced by the actual cost on settlement
+#[derive(Debug, Clone)]
+struct GasCharge {
+    at: DateTime<Utc>,
+    intent_id: Uuid,
+    gas_usd: f64,
+}
+
+/// Realized result of a settled intent
+#[derive(Debug, Clone)]
+struct Settlement {
+    at: DateTime<Utc>,
+    pnl_usd: f64,
+    size_usd: f64,
+}
+
+#[derive(Debug, Default)]
+struct Ledger {
+    open: HashMap<Uuid, Reservation>,
+    gas: VecDeque<GasCharge>,
+    settled: VecDeque<Settlement>,
+    /// Capital set by reallocation, replacing the configured allocation
+    allocation_usd: Option<f64>,
+}
+
+impl Ledger {
+    fn prune(&mut self, now: DateTime<Utc>, return_window: Duration) {
+        self.open.retain(|_, reservation| reservation.expires_at > now);
+        let day_ago = now - Duration::days(1);
+        while self.gas.front().is_some_and(|charge| charge.at <= day_ago) {
+            self.gas.pop_front();
+        }
+        let window_start = now - return_window;
+        while self.settled.front().is_some_and(|settlement| settlement.at <= window_start) {
+            self.settled.pop_front();
+        }
+    }
+
+    fn gas_since(&self, since: DateTime<Utc>) -> f64 {
+        self.gas.iter().filter(|charge| charge.at > since).map(|charge| charge.gas_usd).sum()
+    }
+
+    /// Sharpe ratio of per-intent returns on size, once there are enough of them
+    fn sharpe(&self, min_samples: usize) -> Option<f64> {
+        let returns: Vec<f64> = self.settled.iter()
+            .filter(|settlement| settlement.size_usd > 0.0)
+            .map(|settlement| settlement.pnl_usd / settlement.size_usd)
+            .collect();
+        if returns.is_empty() || returns.len() < min_samples {
+            return None;
+        }
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+        Some(mean / variance.sqrt().max(MIN_RETURN_STDEV))
+    }
+}
+
+/// Budget use of one strategy
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetUsage {
+    pub strategy: String,
+
+    /// Capital allocation in effect, after any reallocation
+    pub capital_usd: Option<f64>,
+    /// Capital held by open intents
+    pub capital_in_use_usd: f64,
+
+    pub open_intents: usize,
+    pub max_concurrent_intents: Option<usize>,
+
+    /// Gas charged over the last hour and day
+    pub gas_last_hour_usd: f64,
+    pub max_gas_usd_per_hour: Option<f64>,
+    pub gas_last_day_usd: f64,
+    pub max_gas_usd_per_day: Option<f64>,
+
+    /// Settled intents over the return window, their PnL and Sharpe ratio
+    pub settled_intents: usize,
+    pub trailing_pnl_usd: f64,
+    pub trailing_sharpe: Option<f64>,
+}
+
+/// Capital, intent slots and gas spend of every strategy
+pub struct StrategyBudgets {
+    ledgers: RwLock<HashMap<String, Ledger>>,
+    return_window: Duration,
+    min_samples: usize,
+}
+
+impl StrategyBudgets {
+    pub fn new(config: &ReallocationConfig) -> Self {
+        Self {
+            ledgers: RwLock::new(HashMap::new()),
+            return_window: Duration::hours(config.window_hours),
+            min_samples: config.min_samples,
+        }
+    }
+
+    /// Current use of a strategy's budget
+    pub async fn usage(&self, strategy: &StrategyConfig, now: DateTime<Utc>) -> BudgetUsage {
+        let mut ledgers = self.ledgers.write().await;
+        let ledger = ledgers.entry(strategy.name.clone()).or_default();
+        ledger.prune(now, self.return_window);
+
+        let budget = &strategy.budget;
+        BudgetUsage {
+            strategy: strategy.name.clone(),
+            capital_usd: budget.capital_usd.map(|configured| ledger.allocation_usd.unwrap_or(configured)),
+            capital_in_use_usd: ledger.open.values().map(|reservation| reservation.capital_usd).sum(),
+            open_intents: ledger.open.len(),
+            max_concurrent_intents: budget.max_concurrent_intents,
+            gas_last_hour_usd: ledger.gas_since(now - Duration::hours(1)),
+            max_gas_usd_per_hour: budget.max_gas_usd_per_hour,
+            gas_last_day_usd: ledger.gas_since(now - Duration::days(1)),
+            max_gas_usd_per_day: budget.max_gas_usd_per_day,
+            settled_intents: ledger.settled.len(),
+            trailing_pnl_usd: ledger.settled.iter().map(|settlement| settlement.pnl_usd).sum(),
+            trailing_sharpe: ledger.sharpe(self.min_samples),
+        }
+    }
+
+    /// Budget use of every configured strategy, by name
+    pub async fn report(&self, strategies: &HashMap<String, StrategyConfig>, now: DateTime<Utc>) -> Vec<BudgetUsage> {
+        let mut report = Vec::with_capacity(strategies.len());
+        for strategy in strategies.values() {
+            report.push(self.usage(strategy, now).await);
+        }
+        report.sort_by(|a, b| a.strategy.cmp(&b.strategy));
+        report
+    }
+
+    /// Hold capital and an intent slot for an emitted intent, and charge its estimated gas
+    pub async fn reserve(
+        &self,
+        strategy: &str,
+        intent_id: Uuid,
+        capital_usd: f64,
+        gas_usd: f64,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) {
+        let mut ledgers = self.ledgers.write().await;
+        let ledger = ledgers.entry(strategy.to_string()).or_default();
+        ledger.prune(now, self.return_window);
+        ledger.open.insert(intent_id, Reservation { capital_usd, expires_at });
+        ledger.gas.push_back(GasCharge { at: now, intent_id, gas_usd });
+    }
+
+    /// Release an executed intent's reservation and record its actual gas and PnL
+    pub async fn settle(
+        &self,
+        strategy: &str,
+        intent_id: Uuid,
+        gas_usd: f64,
+        pnl_usd: f64,
+        size_usd: f64,
+        at: DateTime<Utc>,
+    ) {
+        let mut ledgers = self.ledgers.write().await;
+        let ledger = ledgers.entry(strategy.to_string()).or_default();
+        ledger.open.remove(&intent_id);
+        match ledger.gas.iter_mut().find(|charge| charge.intent_id == intent_id) {
+            Some(charge) => charge.gas_usd = gas_usd,
+            None => ledger.gas.push_back(GasCharge { at, intent_id, gas_usd }),
+        }
+        ledger.settled.push_back(Settlement { at, pnl_usd, size_usd });
+    }
+
+    /// Move pooled capital toward the strategies with the best trailing Sharpe ratio
+    ///
+    /// Enabled strategies with a configured `capital_usd` and enough settled
+    /// intents share the sum of their configured allocations: each keeps
+    /// `min_share` of it and the rest is split in proportion to positive
+    /// Sharpe ratios. Strategies short of samples keep their configured
+    /// capital. Returns the new allocations, by name.
+    pub async fn reallocate(
+        &self,
+        strategies: &HashMap<String, StrategyConfig>,
+        min_share: f64,
+        now: DateTime<Utc>,
+    ) -> Vec<(String, f64)> {
+        let mut ledgers = self.ledgers.write().await;
+        let mut rated = Vec::new();
+        for strategy in strategies.values().filter(|strategy| strategy.enabled) {
+            let Some(configured_usd) = strategy.budget.capital_usd else {
+                continue;
+            };
+            let ledger = ledgers.entry(strategy.name.clone()).or_default();
+            ledger.prune(now, self.return_window);
+            match ledger.sharpe(self.min_samples) {
+                Some(sharpe) => rated.push((strategy.name.clone(), configured_usd, sharpe)),
+                None => ledger.allocation_usd = None,
+            }
+        }
+        rated.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let allocations = split_capital(&rated, min_share);
+        for (name, _, _) in &rated {
+            if let Some(ledger) = ledgers.get_mut(name) {
+                ledger.allocation_usd = allocations.iter()
+                    .find(|(allocated, _)| allocated == name)
+                    .map(|(_, capital_usd)| *capital_usd);
+            }
+        }
+        allocations
+    }
+}
+
+impl Default for StrategyBudgets {
+    fn default() -> Self {
+        Self::new(&ReallocationConfig::default())
+    }
+}
+
+/// Split the pooled capital of `(name, configured capital, Sharpe)` entries
+///
+/// Nothing moves with fewer than two strategies or no positive Sharpe ratio.
+fn split_capital(rated: &[(String, f64, f64)], min_share: f64) -> Vec<(String, f64)> {
+    let scores: f64 = rated.iter().map(|(_, _, sharpe)| sharpe.max(0.0)).sum();
+    if rated.len() < 2 || scores <= 0.0 {
+        return Vec::new();
+    }
+
+    let pool_usd: f64 = rated.iter().map(|(_, configured_usd, _)| configured_usd).sum();
+    let floor = min_share.clamp(0.0, 1.0 / rated.len() as f64);
+    let shared = 1.0 - floor * rated.len() as f64;
+    rated.iter()
+        .map(|(name, _, sharpe)| (name.clone(), pool_usd * (floor + shared * sharpe.max(0.0) / scores)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{RiskLimits, StrategyBudget};
+
+    fn strategy(name: &str, budget: StrategyBudget) -> StrategyConfig {
+        StrategyConfig {
+            name: name.to_string(),
+            enabled: true,
+            min_profit_usd: 10.0,
+            min_profit_bps: 1.0,
+            max_position_usd: 1_000_000.0,
+            approved_assets: vec!["WETH".to_string()],
+            approved_chains: vec![],
+            risk_limits: RiskLimits::default(),
+            budget,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reservations_settle_and_lapse() {
+        let budgets = StrategyBudgets::default();
+        let config = strategy("dex_arb", StrategyBudget {
+            capital_usd: Some(500_000.0),
+            max_gas_usd_per_hour: Some(100.0),
+            ..Default::default()
+        });
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let (settled, lapsed) = (Uuid::new_v4(), Uuid::new_v4());
+
+        budgets.reserve("dex_arb", settled, 200_000.0, 20.0, start + Duration::seconds(30), start).await;
+        budgets.reserve("dex_arb", lapsed, 100_000.0, 15.0, start + Duration::seconds(30), start).await;
+        let usage = budgets.usage(&config, start).await;
+        assert_eq!(usage.open_intents, 2);
+        assert_eq!(usage.capital_in_use_usd, 300_000.0);
+        assert_eq!(usage.gas_last_hour_usd, 35.0);
+
+        // The receipt replaces the gas estimate with the actual cost
+        budgets.settle("dex_arb", settled, 25.0, 120.0, 200_000.0, start + Duration::seconds(10)).await;
+        let usage = budgets.usage(&config, start + Duration::seconds(20)).await;
+        assert_eq!(usage.open_intents, 1);
+        assert_eq!(usage.capital_in_use_usd, 100_000.0);
+        assert_eq!(usage.gas_last_hour_usd, 40.0);
+        assert_eq!(usage.trailing_pnl_usd, 120.0);
+
+        // The other intent never reports back: its slot lapses, its gas rolls out of the hour
+        let usage = budgets.usage(&config, start + Duration::minutes(61)).await;
+        assert_eq!(usage.open_intents, 0);
+        assert_eq!(usage.gas_last_hour_usd, 0.0);
+        assert_eq!(usage.gas_last_day_usd, 40.0);
+    }
+
+    #[tokio::test]
+    async fn test_reallocation_favours_risk_adjusted_returns() {
+        let budgets = StrategyBudgets::new(&ReallocationConfig { min_samples: 4, ..Default::default() });
+        let capital = |usd| StrategyBudget { capital_usd: Some(usd), ..Default::default() };
+        let strategies: HashMap<String, StrategyConfig> = [
+            strategy("steady", capital(400_000.0)),
+            strategy("noisy", capital(400_000.0)),
+            strategy("new", capital(200_000.0)),
+        ].into_iter().map(|s| (s.name.clone(), s)).collect();
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        // Same mean return, very different spread
+        for (i, (steady, noisy)) in [(90.0, 600.0), (110.0, -400.0), (95.0, 500.0), (105.0, -300.0)].into_iter().enumerate() {
+            let at = now - Duration::minutes(i as i64);
+            budgets.settle("steady", Uuid::new_v4(), 5.0, steady, 100_000.0, at).await;
+            budgets.settle("noisy", Uuid::new_v4(), 5.0, noisy, 100_000.0, at).await;
+        }
+        budgets.settle("new", Uuid::new_v4(), 5.0, 1_000.0, 100_000.0, now).await;
+
+        let allocations = budgets.reallocate(&strategies, 0.1, now).await;
+        assert_eq!(allocations.len(), 2);
+        let steady = budgets.usage(&strategies["steady"], now).await.capital_usd.unwrap();
+        let noisy = budgets.usage(&strategies["noisy"], now).await.capital_usd.unwrap();
+        assert!(steady > 600_000.0 && noisy >= 80_000.0, "steady {} noisy {}", steady, noisy);
+        assert!((steady + noisy - 800_000.0).abs() < 1e-6);
+        // Too few samples to move
+        assert_eq!(budgets.usage(&strategies["new"], now).await.capital_usd, Some(200_000.0));
+    }
+}
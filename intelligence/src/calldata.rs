@@ -0,0 +1,374 @@
+//! Calldata encoding for intent legs
+//!
+//! Turns a resolved leg (pool, tokens, base-unit amounts) into the exact
+//! contract call Orchestration submits, using the beta_dataplane `AbiManager`
+//! encoders for Uniswap V3, Curve, Balancer and Aave.
+
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Bytes, H160, U256};
+use qenus_beta_dataplane::utils::contracts::{
+    AbiManager, BalancerFundManagement, BalancerSingleSwap, BalancerSwapKind, ContractRegistry,
+};
+use qenus_dataplane::Chain;
+
+use crate::error::{IntelligenceError, Result};
+use crate::state::AmmState;
+
+/// Token resolved to its on-chain address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenRef {
+    pub address: H160,
+    pub decimals: u8,
+}
+
+/// Contract call for one leg
+#[derive(Debug, Clone)]
+pub struct EncodedCall {
+    pub target: H160,
+    pub calldata: Bytes,
+}
+
+/// Parse a 0x-prefixed 20-byte address
+pub fn parse_address(address: &str) -> Option<H160> {
+    address.parse().ok()
+}
+
+/// Token `symbol` of a pool, if the pool knows its address
+pub fn pool_token(pool: &AmmState, symbol: &str) -> Option<TokenRef> {
+    let (address, decimals) = if pool.token0_symbol == symbol {
+        (&pool.token0_address, pool.token0_decimals)
+    } else if pool.token1_symbol == symbol {
+        (&pool.token1_address, pool.token1_decimals)
+    } else {
+        return None;
+    };
+
+    parse_address(address).map(|address| TokenRef { address, decimals })
+}
+
+/// Convert a token amount to integer base units
+///
+/// Goes through the decimal string so large amounts do not overflow u128.
+/// Digits beyond f64's 15 significant digits are zero-filled rather than
+/// carrying binary rounding noise into the amount.
+pub fn to_base_units(amount: f64, decimals: u8) -> Option<U256> {
+    if !amount.is_finite() || amount < 0.0 {
+        return None;
+    }
+
+    let decimals = decimals as usize;
+    let whole_digits = if amount >= 1.0 { amount.log10().floor() as usize + 1 } else { 0 };
+    let precision = decimals.min(15usize.saturating_sub(whole_digits));
+
+    let formatted = format!("{:.*}", precision, amount);
+    let (whole, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let digits = format!("{}{}{}", whole, fraction, "0".repeat(decimals - precision));
+    let digits = digits.trim_start_matches('0');
+
+    if digits.is_empty() {
+        Some(U256::zero())
+    } else {
+        U256::from_dec_str(digits).ok()
+    }
+}
+
+/// Encode a single-pool swap of `amount_in` token_in for at least `min_out` token_out
+#[allow(clippy::too_many_arguments)]
+pub fn encode_swap(
+    chain: Chain,
+    pool: &AmmState,
+    token_in: TokenRef,
+    token_out: TokenRef,
+    amount_in: U256,
+    min_out: U256,
+    recipient: H160,
+    deadline: u64,
+) -> Result<EncodedCall> {
+    let pool_type = pool.pool_type.to_lowercase();
+
+    if pool_type.contains("uniswap") {
+        let fee = pool.fee_tier
+            .ok_or_else(|| IntelligenceError::internal(format!("Pool {} has no fee tier", pool.pool_address)))?;
+        let target = ContractRegistry::get_uniswap_v3_swap_router(chain)
+            .ok_or_else(|| IntelligenceError::internal(format!("No Uniswap V3 SwapRouter on {:?}", chain)))?;
+        let path = AbiManager::encode_uniswap_v3_path(&[token_in.address, token_out.address], &[fee])?;
+        let calldata = AbiManager::encode_uniswap_v3_exact_input_call(path, recipient, deadline, amount_in, min_out)?;
+        Ok(EncodedCall { target, calldata })
+    } else if pool_type.contains("curve") {
+        let target = parse_address(&pool.pool_address)
+            .ok_or_else(|| IntelligenceError::internal(format!("Invalid Curve pool address {}", pool.pool_address)))?;
+        // Index within the pool's coins; pools are ingested as (coin 0, coin 1) pairs
+        let (i, j) = match (parse_address(&pool.token0_address), parse_address(&pool.token1_address)) {
+            (Some(token0), Some(token1)) if token0 == token_in.address && token1 == token_out.address => (0, 1),
+            (Some(token0), Some(token1)) if token1 == token_in.address && token0 == token_out.address => (1, 0),
+            _ => {
+                return Err(IntelligenceError::internal(format!(
+                    "Curve pool {} does not trade {:#x} for {:#x}", pool.pool_address, token_in.address, token_out.address
+                )));
+            }
+        };
+        let calldata = AbiManager::encode_curve_exchange_call(i, j, amount_in, min_out)?;
+        Ok(EncodedCall { target, calldata })
+    } else if pool_type.contains("balancer") {
+        let pool_id = pool.pool_id.as_deref()
+            .and_then(balancer_pool_id)
+            .ok_or_else(|| IntelligenceError::internal(format!("Balancer pool {} has no valid pool id", pool.pool_address)))?;
+        let swap = BalancerSingleSwap {
+            pool_id,
+            kind: BalancerSwapKind::GivenIn,
+            asset_in: token_in.address,
+            asset_out: token_out.address,
+            amount: amount_in,
+            user_data: Bytes::new(),
+        };
+        let calldata = AbiManager::encode_balancer_swap_call(
+            &swap, &BalancerFundManagement::external(recipient), min_out, deadline,
+        )?;
+        Ok(EncodedCall { target: ContractRegistry::get_balancer_vault(chain), calldata })
+    } else {
+        Err(IntelligenceError::internal(format!("No swap encoder for pool type {}", pool.pool_type)))
+    }
+}
+
+/// Encode a flash loan of `amount` of `asset` to `receiver`
+///
+/// `callback` is what the receiver runs while it holds the loan, repayment
+/// included. It travels in the loan's `userData` (Balancer) or `params`
+/// (Aave), so the borrow, the calls and the repayment form one transaction.
+pub fn encode_flash_loan(
+    chain: Chain,
+    provider: &str,
+    asset: TokenRef,
+    amount: U256,
+    receiver: H160,
+    callback: &[EncodedCall],
+) -> Result<EncodedCall> {
+    let params = encode_callback(callback);
+    if provider.contains("balancer") {
+        let calldata = AbiManager::encode_balancer_flash_loan_call(receiver, &[asset.address], &[amount], params)?;
+        Ok(EncodedCall { target: ContractRegistry::get_balancer_vault(chain), calldata })
+    } else if provider.contains("aave") {
+        let target = ContractRegistry::get_aave_v3_pool(chain)
+            .ok_or_else(|| IntelligenceError::internal(format!("No Aave V3 pool on {:?}", chain)))?;
+        let calldata = AbiManager::encode_aave_flash_loan_simple_call(receiver, asset.address, amount, params)?;
+        Ok(EncodedCall { target, calldata })
+    } else {
+        Err(IntelligenceError::internal(format!("No flash loan encoder for provider {}", provider)))
+    }
+}
+
+/// Encode the call that settles a flash loan of `amount` (fee included)
+///
+/// Balancer expects the tokens back in the Vault before the callback
+/// returns; Aave pulls them, so the receiver approves the pool instead.
+pub fn encode_flash_repay(chain: Chain, provider: &str, asset: TokenRef, amount: U256) -> Result<EncodedCall> {
+    let calldata = if provider.contains("balancer") {
+        AbiManager::encode_erc20_transfer_call(ContractRegistry::get_balancer_vault(chain), amount)?
+    } else if provider.contains("aave") {
+        let pool = ContractRegistry::get_aave_v3_pool(chain)
+            .ok_or_else(|| IntelligenceError::internal(format!("No Aave V3 pool on {:?}", chain)))?;
+        AbiManager::encode_erc20_approve_call(pool, amount)?
+    } else {
+        return Err(IntelligenceError::internal(format!("No flash loan encoder for provider {}", provider)));
+    };
+    Ok(EncodedCall { target: asset.address, calldata })
+}
+
+/// Flash-loan callback payload: `abi.encode(address[] targets, bytes[] calldatas)`
+pub fn encode_callback(calls: &[EncodedCall]) -> Bytes {
+    abi::encode(&[
+        Token::Array(calls.iter().map(|call| Token::Address(call.target)).collect()),
+        Token::Array(calls.iter().map(|call| Token::Bytes(call.calldata.to_vec())).collect()),
+    ]).into()
+}
+
+/// Calls carried in a flash-loan callback payload
+pub fn decode_callback(params: &[u8]) -> Result<Vec<EncodedCall>> {
+    let malformed = || IntelligenceError::internal("Malformed flash loan callback payload");
+    let tokens = abi::decode(
+        &[ParamType::Array(Box::new(ParamType::Address)), ParamType::Array(Box::new(ParamType::Bytes))],
+        params,
+    ).map_err(|_| malformed())?;
+    let (Some(targets), Some(calldatas)) = (tokens[0].clone().into_array(), tokens[1].clone().into_array()) else {
+        return Err(malformed());
+    };
+    if targets.len() != calldatas.len() {
+        return Err(malformed());
+    }
+    targets.into_iter().zip(calldatas)
+        .map(|(target, calldata)| match (target.into_address(), calldata.into_bytes()) {
+            (Some(target), Some(calldata)) => Ok(EncodedCall { target, calldata: calldata.into() }),
+            _ => Err(malformed()),
+        })
+        .collect()
+}
+
+/// Encode an Aave V3 liquidationCall repaying `debt_to_cover` of `user`'s debt
+pub fn encode_liquidation(
+    chain: Chain,
+    collateral: TokenRef,
+    debt: TokenRef,
+    user: H160,
+    debt_to_cover: U256,
+) -> Result<EncodedCall> {
+    let target = ContractRegistry::get_aave_v3_pool(chain)
+        .ok_or_else(|| IntelligenceError::internal(format!("No Aave V3 pool on {:?}", chain)))?;
+    let calldata = AbiManager::encode_aave_liquidation_call(
+        collateral.address, debt.address, user, debt_to_cover, false,
+    )?;
+    Ok(EncodedCall { target, calldata })
+}
+
+/// Balancer pools are keyed by their 32-byte pool id, not their address
+fn balancer_pool_id(pool_id: &str) -> Option<[u8; 32]> {
+    let bytes = hex::decode(pool_id.trim_start_matches("0x")).ok()?;
+    bytes.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use chrono::Utc;
+
+    fn pool(pool_type: &str, address: &str) -> AmmState {
+        AmmState {
+            pool_address: address.to_string(),
+            pool_type: pool_type.to_string(),
+            pool_id: None,
+            token0_symbol: "USDC".to_string(),
+            token1_symbol: "WETH".to_string(),
+            token0_address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
+            token1_address: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(),
+            token0_decimals: 6,
+            token1_decimals: 18,
+            mid_price: 0.0003,
+            liquidity: "0".to_string(),
+            fee_tier: Some(500),
+            depth: HashMap::new(),
+            depth_curve: Default::default(),
+            last_update: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_to_base_units() {
+        assert_eq!(to_base_units(1.5, 6), Some(U256::from(1_500_000u64)));
+        assert_eq!(to_base_units(0.0, 18), Some(U256::zero()));
+        assert_eq!(to_base_units(2.0, 18), Some(U256::from(2u64) * U256::exp10(18)));
+        assert_eq!(to_base_units(9.99, 18), Some(U256::from(999u64) * U256::exp10(16)));
+        assert_eq!(to_base_units(-1.0, 18), None);
+    }
+
+    #[test]
+    fn test_encode_swaps_by_pool_type() {
+        let uniswap = pool("uniswap_v3", "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let usdc = pool_token(&uniswap, "USDC").unwrap();
+        let weth = pool_token(&uniswap, "WETH").unwrap();
+        let recipient = H160::repeat_byte(9);
+
+        let call = encode_swap(
+            Chain::Ethereum, &uniswap, usdc, weth, U256::from(1_000_000u64), U256::one(), recipient, 1_700_000_000,
+        ).unwrap();
+        assert_eq!(call.target, ContractRegistry::get_uniswap_v3_swap_router(Chain::Ethereum).unwrap());
+        assert_eq!(hex::encode(&call.calldata[..4]), "c04b8d59");
+
+        let curve = pool("curve", "0xbEbc44782C7dB0a1A60Cb6fe97d0b483032FF1C7");
+        let call = encode_swap(
+            Chain::Ethereum, &curve, weth, usdc, U256::from(10u64), U256::one(), recipient, 1_700_000_000,
+        ).unwrap();
+        assert_eq!(call.target, parse_address(&curve.pool_address).unwrap());
+        // exchange(1, 0, ...): selling coin 1
+        assert_eq!(call.calldata[4 + 31], 1);
+        assert_eq!(call.calldata[4 + 63], 0);
+
+        // Neither token is a coin of the pool
+        let dai = TokenRef { address: H160::repeat_byte(0xda), decimals: 18 };
+        assert!(encode_swap(Chain::Ethereum, &curve, dai, usdc, U256::one(), U256::one(), recipient, 0).is_err());
+
+        // Base has no SwapRouter with a deadline
+        assert!(encode_swap(Chain::Base, &uniswap, usdc, weth, U256::one(), U256::one(), recipient, 0).is_err());
+    }
+
+    #[test]
+    fn test_flash_loan_carries_callback() {
+        let uniswap = pool("uniswap_v3", "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let usdc = pool_token(&uniswap, "USDC").unwrap();
+        let weth = pool_token(&uniswap, "WETH").unwrap();
+        let executor = H160::repeat_byte(9);
+
+        let swap = encode_swap(
+            Chain::Ethereum, &uniswap, usdc, weth, U256::from(1_000_000u64), U256::one(), executor, 1_700_000_000,
+        ).unwrap();
+        let repay = encode_flash_repay(Chain::Ethereum, "balancer", usdc, U256::from(1_000_000u64)).unwrap();
+        assert_eq!(repay.target, usdc.address);
+        assert_eq!(hex::encode(&repay.calldata[..4]), "a9059cbb");
+
+        let loan = encode_flash_loan(
+            Chain::Ethereum, "balancer", usdc, U256::from(1_000_000u64), executor, &[swap.clone(), repay.clone()],
+        ).unwrap();
+        let user_data = abi::decode(
+            &[
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::Address)),
+                ParamType::Array(Box::new(ParamType::Uint(256))),
+                ParamType::Bytes,
+            ],
+            &loan.calldata[4..],
+        ).unwrap()[3].clone().into_bytes().unwrap();
+        let callback = decode_callback(&user_data).unwrap();
+        assert_eq!(callback.len(), 2);
+        assert_eq!((callback[0].target, &callback[0].calldata), (swap.target, &swap.calldata));
+        assert_eq!((callback[1].target, &callback[1].calldata), (repay.target, &repay.calldata));
+
+        // Aave pulls the repayment: approve the pool rather than transfer
+        let repay = encode_flash_repay(Chain::Ethereum, "aave_v3", usdc, U256::one()).unwrap();
+        assert_eq!(hex::encode(&repay.calldata[..4]), "095ea7b3");
+    }
+
+    #[tokio::test]
+    async fn test_balancer_swap_uses_pool_id_from_extractor() {
+        use qenus_dataplane::{AmmFeature, DepthCurve, Feature, FeatureData, FeatureType, TokenInfo};
+        use crate::state::MarketState;
+
+        // Shaped like the Balancer extractor's output: 20-byte address, 32-byte id
+        let pool_id = "0x5c6ee304399dbdb9c8ef030ab642b10820db8f56000200000000000000000014";
+        let feature = Feature::new(
+            1,
+            Chain::Ethereum,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: format!("{:?}", H160::from_slice(&hex::decode(&pool_id[2..42]).unwrap())),
+                pool_type: "balancer_weighted".to_string(),
+                pool_id: Some(pool_id.to_string()),
+                token0: TokenInfo { address: "0xba100000625a3754423978a60c9317c58a424e3d".to_string(), symbol: "BAL".to_string(), decimals: 18 },
+                token1: TokenInfo { address: "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".to_string(), symbol: "WETH".to_string(), decimals: 18 },
+                fee_tier: Some(100),
+                reserves: [("BAL".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price: 0.002,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve::default(),
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        );
+
+        let market_state = MarketState::new(30);
+        market_state.ingest_feature(feature).await.unwrap();
+        let pools = market_state.get_amm_pools(Chain::Ethereum).await;
+        let balancer = &pools[0];
+        let bal = pool_token(balancer, "BAL").unwrap();
+        let weth = pool_token(balancer, "WETH").unwrap();
+
+        let call = encode_swap(
+            Chain::Ethereum, balancer, bal, weth, U256::exp10(18), U256::one(), H160::repeat_byte(9), 1_700_000_000,
+        ).unwrap();
+        assert_eq!(call.target, ContractRegistry::get_balancer_vault(Chain::Ethereum));
+        assert!(hex::encode(&call.calldata).contains(&pool_id[2..]));
+
+        // Without an id there is nothing to key the Vault swap by
+        let no_id = AmmState { pool_id: None, ..balancer.clone() };
+        assert!(encode_swap(Chain::Ethereum, &no_id, bal, weth, U256::one(), U256::one(), H160::zero(), 0).is_err());
+    }
+}
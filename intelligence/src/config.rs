@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use qenus_dataplane::Chain;
 
 use crate::error::{IntelligenceError, Result};
-use crate::types::{StrategyConfig, RiskLimits};
+use crate::types::{StrategyConfig, StrategyBudget, RiskLimits};
 
 /// Intelligence layer configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -24,6 +24,41 @@ pub struct IntelligenceConfig {
     
     /// Detection settings
     pub detection: DetectionConfig,
+    
+    /// Operator HTTP API settings
+    #[serde(default)]
+    pub api: ApiConfig,
+    
+    /// Decision audit log settings
+    #[serde(default)]
+    pub audit: AuditConfig,
+    
+    /// On-chain execution settings
+    #[serde(default)]
+    pub execution: ExecutionConfig,
+    
+    /// Pre-emission EVM verification settings
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    
+    /// Backtest execution model
+    #[serde(default)]
+    pub backtest: BacktestConfig,
+    
+    /// Paper-trading execution model
+    #[serde(default)]
+    pub paper_trading: PaperTradingConfig,
+    
+    /// Cross-chain inventory rebalancing
+    #[serde(default)]
+    pub rebalance: RebalanceConfig,
+    
+    #[serde(default)]
+    pub scripting: ScriptingConfig,
+    
+    /// Capital reallocation between strategy budgets
+    #[serde(default)]
+    pub reallocation: ReallocationConfig,
 }
 
 /// Beta dataplane connection configuration
@@ -53,6 +88,412 @@ pub struct DetectionConfig {
     
     /// Minimum confidence threshold
     pub min_confidence: f64,
+    
+    /// Time budget for a single detector scan (milliseconds)
+    #[serde(default = "default_detector_timeout_ms")]
+    pub detector_timeout_ms: u64,
+    
+    /// Per-detector time budget overrides (milliseconds)
+    #[serde(default)]
+    pub detector_timeouts_ms: HashMap<String, u64>,
+    
+    /// Consecutive failures or timeouts before a detector is disabled
+    #[serde(default = "default_max_detector_failures")]
+    pub max_detector_failures: u32,
+    
+    /// Initial disable period, doubled each time a detector is disabled again (seconds)
+    #[serde(default = "default_detector_backoff_secs")]
+    pub detector_backoff_secs: u64,
+    
+    /// Upper bound on the disable period (seconds)
+    #[serde(default = "default_max_detector_backoff_secs")]
+    pub max_detector_backoff_secs: u64,
+    
+    /// Re-run affected detectors on market state changes between interval scans
+    #[serde(default = "default_event_driven")]
+    pub event_driven: bool,
+    
+    /// How long to collect changes from one block before evaluating (milliseconds)
+    #[serde(default = "default_coalesce_window_ms")]
+    pub coalesce_window_ms: u64,
+    
+    /// Ignore keys whose updates within a batch add up to less than this (bps of price, base fee, fee or liquidity)
+    #[serde(default = "default_min_change_bps")]
+    pub min_change_bps: f64,
+    
+    /// Quiet period after an opportunity's intent expires before it may be emitted again (seconds)
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    
+    /// Forget an opportunity not detected for this long (seconds)
+    #[serde(default = "default_opportunity_expiry_secs")]
+    pub opportunity_expiry_secs: u64,
+}
+
+fn default_detector_timeout_ms() -> u64 {
+    2_000
+}
+
+fn default_max_detector_failures() -> u32 {
+    3
+}
+
+fn default_detector_backoff_secs() -> u64 {
+    30
+}
+
+fn default_max_detector_backoff_secs() -> u64 {
+    600
+}
+
+fn default_event_driven() -> bool {
+    true
+}
+
+fn default_coalesce_window_ms() -> u64 {
+    250
+}
+
+fn default_min_change_bps() -> f64 {
+    1.0
+}
+
+fn default_cooldown_secs() -> u64 {
+    10
+}
+
+fn default_opportunity_expiry_secs() -> u64 {
+    60
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 5,
+            max_candidates_per_cycle: 100,
+            min_confidence: 0.7,
+            detector_timeout_ms: default_detector_timeout_ms(),
+            detector_timeouts_ms: HashMap::new(),
+            max_detector_failures: default_max_detector_failures(),
+            detector_backoff_secs: default_detector_backoff_secs(),
+            max_detector_backoff_secs: default_max_detector_backoff_secs(),
+            event_driven: default_event_driven(),
+            coalesce_window_ms: default_coalesce_window_ms(),
+            min_change_bps: default_min_change_bps(),
+            cooldown_secs: default_cooldown_secs(),
+            opportunity_expiry_secs: default_opportunity_expiry_secs(),
+        }
+    }
+}
+
+/// Operator HTTP API configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Serve the operator API
+    pub enabled: bool,
+    
+    /// Listen address
+    pub bind_addr: String,
+    
+    /// Recent candidates kept for inspection
+    pub recent_candidates: usize,
+    
+    /// Directory forced snapshots are written to
+    pub snapshot_dir: String,
+    
+    /// Bearer token required for admin actions (none = admin actions refused)
+    pub admin_token: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bind_addr: "127.0.0.1:8088".to_string(),
+            recent_candidates: 500,
+            snapshot_dir: "snapshots".to_string(),
+            admin_token: None,
+        }
+    }
+}
+
+/// Decision audit log configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Write an audit record for every candidate
+    pub enabled: bool,
+    
+    /// Directory of rotating JSONL files
+    pub dir: String,
+    
+    /// Start a new file past this size
+    pub max_file_bytes: u64,
+    
+    /// Files kept before the oldest is deleted
+    pub max_files: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: "audit".to_string(),
+            max_file_bytes: 64 * 1024 * 1024,
+            max_files: 50,
+        }
+    }
+}
+
+/// On-chain execution configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ExecutionConfig {
+    /// Executor contract that receives swap outputs and flash loans;
+    /// legs carry calldata only when this is set
+    pub executor_address: Option<String>,
+}
+
+/// What to do with an intent whose simulated output misses the prediction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviationAction {
+    /// Do not emit the intent
+    Drop,
+    
+    /// Emit with expected outputs and PnL taken from the simulation
+    Reprice,
+}
+
+/// Pre-emission EVM verification configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct VerificationConfig {
+    /// Execute intent legs in a local EVM before emitting them
+    pub enabled: bool,
+    
+    /// Largest accepted gap between predicted and simulated output
+    pub tolerance_bps: f64,
+    
+    /// Action when the gap is larger than `tolerance_bps`
+    pub on_deviation: DeviationAction,
+    
+    /// JSON dump of account code, balances and storage to seed the EVM state cache
+    ///
+    /// The dataplane's `evm_state` feed keeps the cache current; the dump
+    /// covers contracts the feed has not touched yet. Intents with a leg on a
+    /// chain with no state at all are skipped.
+    pub state_file: Option<String>,
+    
+    /// Gas limit per simulated leg
+    pub gas_limit: u64,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tolerance_bps: 50.0,
+            on_deviation: DeviationAction::Drop,
+            state_file: None,
+            gas_limit: 5_000_000,
+        }
+    }
+}
+
+/// Backtest execution model
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BacktestConfig {
+    /// Delay between the decision and the fill, in recorded time
+    pub latency_ms: u64,
+    
+    /// Slippage on the filled size beyond what the simulation predicted
+    pub slippage_bps: f64,
+    
+    /// Probability a fill fails on-chain and only burns gas
+    pub failure_rate: f64,
+    
+    /// Seed for failure draws, so runs are reproducible
+    pub seed: u64,
+    
+    /// Directory reports are written to
+    pub output_dir: String,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            latency_ms: 2_000,
+            slippage_bps: 2.0,
+            failure_rate: 0.05,
+            seed: 42,
+            output_dir: "backtests".to_string(),
+        }
+    }
+}
+
+/// Paper-trading configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PaperTradingConfig {
+    /// Paper-trade intents instead of emitting them: fill against later
+    /// market state and feed the receipts back
+    pub enabled: bool,
+    
+    /// Delay between emission and the synthetic fill
+    pub delay_ms: u64,
+    
+    /// Strategies paper-traded instead of executed (empty = all)
+    pub strategies: Vec<String>,
+}
+
+impl Default for PaperTradingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_ms: 12_000,
+            strategies: Vec::new(),
+        }
+    }
+}
+
+impl PaperTradingConfig {
+    /// Whether `strategy`'s intents are paper-traded
+    pub fn covers(&self, strategy: &str) -> bool {
+        self.enabled && (self.strategies.is_empty() || self.strategies.iter().any(|name| name == strategy))
+    }
+}
+
+/// Holding of one asset on one chain
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InventoryAmount {
+    pub chain: Chain,
+    pub asset: String,
+    
+    /// In leg amount units (USD)
+    pub amount_usd: f64,
+}
+
+/// Cross-chain inventory rebalancing configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RebalanceConfig {
+    /// Propose bridge transfers that move inventory back to its targets
+    pub enabled: bool,
+    
+    /// How often to plan
+    pub interval_secs: u64,
+    
+    /// Target inventory per chain and asset; untargeted holdings count as surplus
+    pub targets: Vec<InventoryAmount>,
+    
+    /// Balances the inventory ledger starts from
+    pub opening_balances: Vec<InventoryAmount>,
+    
+    /// Deviation from a target tolerated before it is topped up
+    ///
+    /// Also the slack allowed when deciding from wallet balances whether an
+    /// in-flight transfer has landed.
+    pub tolerance_pct: f64,
+    
+    /// Transfers must settle within this time; bridges slower than it are not used
+    pub time_budget_secs: u64,
+    
+    /// Smallest transfer worth paying gas for
+    pub min_transfer_usd: f64,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 300,
+            targets: Vec::new(),
+            opening_balances: Vec::new(),
+            tolerance_pct: 10.0,
+            time_budget_secs: 3_600,
+            min_transfer_usd: 1_000.0,
+        }
+    }
+}
+
+/// Scripted strategy configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ScriptingConfig {
+    /// Run the strategy scripts found in `dir`
+    pub enabled: bool,
+    
+    /// Directory of `*.expr` strategy scripts
+    pub dir: String,
+    
+    /// How often to pick up added, changed or removed scripts
+    pub reload_interval_secs: u64,
+    
+    /// Larger scripts are rejected
+    pub max_script_bytes: usize,
+    
+    /// Scripts with more operations than this are rejected
+    pub max_operations: usize,
+    
+    /// Largest value (string or tuple) a script may store in a variable
+    pub max_value_bytes: usize,
+    
+    /// Time budget for one script run (milliseconds), checked on every
+    /// function call and assignment
+    pub max_eval_ms: u64,
+    
+    /// Candidates one script run may emit
+    pub max_candidates: usize,
+}
+
+impl Default for ScriptingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "strategies".to_string(),
+            reload_interval_secs: 5,
+            max_script_bytes: 16 * 1024,
+            max_operations: 2_000,
+            max_value_bytes: 4 * 1024,
+            max_eval_ms: 50,
+            max_candidates: 16,
+        }
+    }
+}
+
+/// Moves capital between strategy budgets by trailing risk-adjusted returns
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReallocationConfig {
+    /// Reallocate the capital of strategies that have a `capital_usd` budget
+    pub enabled: bool,
+    
+    /// How often to reallocate
+    pub interval_secs: u64,
+    
+    /// Trailing window of settled intents returns are measured over
+    pub window_hours: i64,
+    
+    /// Settled intents a strategy needs in the window before its allocation moves
+    pub min_samples: usize,
+    
+    /// Share of the pooled capital every strategy keeps however it performs
+    pub min_share: f64,
+}
+
+impl Default for ReallocationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 3600,
+            window_hours: 24 * 7,
+            min_samples: 20,
+            min_share: 0.1,
+        }
+    }
 }
 
 impl Default for IntelligenceConfig {
@@ -68,15 +509,21 @@ impl Default for IntelligenceConfig {
                     "qenus.beta.features.bridge".to_string(),
                     "qenus.beta.features.flashloan".to_string(),
                     "qenus.beta.features.sequencer".to_string(),
+                    "qenus.beta.features.wallet".to_string(),
                 ],
                 grpc_endpoint: Some("http://localhost:50053".to_string()),
                 mode: "mock".to_string(), // Default to mock for development
             },
-            detection: DetectionConfig {
-                interval_secs: 5,
-                max_candidates_per_cycle: 100,
-                min_confidence: 0.7,
-            },
+            detection: DetectionConfig::default(),
+            api: ApiConfig::default(),
+            audit: AuditConfig::default(),
+            execution: ExecutionConfig::default(),
+            verification: VerificationConfig::default(),
+            backtest: BacktestConfig::default(),
+            paper_trading: PaperTradingConfig::default(),
+            rebalance: RebalanceConfig::default(),
+            scripting: ScriptingConfig::default(),
+            reallocation: ReallocationConfig::default(),
         }
     }
 }
@@ -158,7 +605,11 @@ impl IntelligenceConfig {
                     max_gas_pct: 50.0, // Gas can be up to 50% of profit
                     max_bridge_latency_secs: 300, // 5 minutes
                     min_success_prob: 0.8,
+                    max_revert_prob: 0.05,
+                    kelly_fraction: None,
+                    max_cvar_usd: None,
                 },
+                budget: StrategyBudget::default(),
             },
         );
         
@@ -190,7 +641,113 @@ impl IntelligenceConfig {
                     max_gas_pct: 30.0,
                     max_bridge_latency_secs: 0, // No bridge for same-chain
                     min_success_prob: 0.85, // Higher confidence for same-chain
+                    max_revert_prob: 0.05,
+                    kelly_fraction: None,
+                    max_cvar_usd: None,
+                },
+                budget: StrategyBudget::default(),
+            },
+        );
+        
+        // Aave V3 liquidations (needs Pool events and reserve configs)
+        strategies.insert(
+            "aave_liquidation".to_string(),
+            StrategyConfig {
+                name: "aave_liquidation".to_string(),
+                enabled: false,
+                min_profit_usd: 100.0,
+                min_profit_bps: 100.0, // Liquidation bonus must clear 1%
+                max_position_usd: 1_000_000.0,
+                approved_assets: vec![
+                    "WETH".to_string(),
+                    "USDC".to_string(),
+                    "USDT".to_string(),
+                    "DAI".to_string(),
+                    "WBTC".to_string(),
+                ],
+                approved_chains: vec![
+                    Chain::Ethereum,
+                    Chain::Arbitrum,
+                    Chain::Optimism,
+                    Chain::Base,
+                ],
+                risk_limits: RiskLimits {
+                    max_slippage_bps: 100.0,
+                    max_gas_pct: 50.0,
+                    max_bridge_latency_secs: 0,
+                    min_success_prob: 0.6, // Liquidations are a public race
+                    max_revert_prob: 0.05,
+                    kelly_fraction: None,
+                    max_cvar_usd: None,
+                },
+                budget: StrategyBudget::default(),
+            },
+        );
+        
+        // Stablecoin depeg / peg restoration (approved assets are the watched stablecoins)
+        strategies.insert(
+            "stablecoin_depeg".to_string(),
+            StrategyConfig {
+                name: "stablecoin_depeg".to_string(),
+                enabled: false,
+                min_profit_usd: 200.0,
+                min_profit_bps: 20.0, // Deviation from peg that counts as off peg
+                max_position_usd: 1_000_000.0,
+                approved_assets: vec![
+                    "USDC".to_string(),
+                    "USDT".to_string(),
+                    "DAI".to_string(),
+                    "FRAX".to_string(),
+                    "LUSD".to_string(),
+                ],
+                approved_chains: vec![
+                    Chain::Ethereum,
+                    Chain::Arbitrum,
+                    Chain::Optimism,
+                    Chain::Base,
+                ],
+                risk_limits: RiskLimits {
+                    max_slippage_bps: 30.0,
+                    max_gas_pct: 30.0,
+                    max_bridge_latency_secs: 0,
+                    min_success_prob: 0.6, // Restoration is a directional bet
+                    max_revert_prob: 0.05,
+                    kelly_fraction: None,
+                    max_cvar_usd: None,
+                },
+                budget: StrategyBudget::default(),
+            },
+        );
+        
+        // Flash-loan-funded atomic DEX arbitrage (max_position_usd caps the borrow)
+        strategies.insert(
+            "flash_arb".to_string(),
+            StrategyConfig {
+                name: "flash_arb".to_string(),
+                enabled: false,
+                min_profit_usd: 50.0,
+                min_profit_bps: 5.0,
+                max_position_usd: 2_000_000.0,
+                approved_assets: vec![
+                    "WETH".to_string(),
+                    "WBTC".to_string(),
+                ],
+                approved_chains: vec![
+                    Chain::Ethereum,
+                    Chain::Arbitrum,
+                    Chain::Optimism,
+                    Chain::Base,
+                ],
+                risk_limits: RiskLimits {
+                    max_slippage_bps: 50.0,
+                    max_gas_pct: 50.0,
+                    max_bridge_latency_secs: 0,
+                    min_success_prob: 0.5, // A missed cycle costs at most gas
+                    max_revert_prob: 0.05, // Reverts burn gas on-chain
+                    kelly_fraction: None,
+                    max_cvar_usd: None,
                 },
+                budget: StrategyBudget::default(),
             },
         );
         
@@ -247,7 +804,7 @@ mod tests {
         let config = IntelligenceConfig::default();
         let enabled = config.enabled_strategies();
         
-        // Both default strategies should be enabled
+        // Arbitrage strategies are enabled, liquidations are opt-in
         assert_eq!(enabled.len(), 2);
     }
     
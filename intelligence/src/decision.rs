@@ -5,15 +5,18 @@
 
 use std::sync::Arc;
 use std::collections::HashMap;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
 use crate::{
-    EvaluationResult, Candidate, StrategyConfig, RiskLimits, Result, IntelligenceError,
+    EvaluationResult, Candidate, StrategyConfig, RiskLimits, Result, IntelligenceError, TradeIntent,
 };
+use crate::budget::{BudgetUsage, StrategyBudgets};
 use crate::state::MarketState;
 
 /// Decision made by the engine
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeDecision {
     /// Whether to execute this trade
     pub should_execute: bool,
@@ -30,10 +33,35 @@ pub struct TradeDecision {
     /// Why this decision was made
     pub reasoning: Vec<String>,
     
+    /// Machine-readable result of every policy check
+    pub checks: Vec<PolicyCheck>,
+    
     /// Any warnings
     pub warnings: Vec<String>,
 }
 
+/// Result of one policy check against its threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyCheck {
+    /// Check name, e.g. "min_profit_usd" or "sequencer_healthy:Arbitrum"
+    pub name: String,
+    
+    /// Whether the check passed
+    pub passed: bool,
+    
+    /// Observed value (1.0/0.0 for yes/no checks)
+    pub value: f64,
+    
+    /// Threshold the value was compared against
+    pub threshold: f64,
+}
+
+impl PolicyCheck {
+    fn new(name: impl Into<String>, passed: bool, value: f64, threshold: f64) -> Self {
+        Self { name: name.into(), passed, value, threshold }
+    }
+}
+
 /// Position tracker for exposure management
 #[derive(Debug, Clone)]
 pub struct PositionTracker {
@@ -73,6 +101,7 @@ impl PositionTracker {
 pub struct DecisionEngine {
     market_state: Arc<MarketState>,
     position_tracker: Arc<tokio::sync::RwLock<PositionTracker>>,
+    budgets: Arc<StrategyBudgets>,
 }
 
 impl DecisionEngine {
@@ -83,9 +112,16 @@ impl DecisionEngine {
             position_tracker: Arc::new(tokio::sync::RwLock::new(
                 PositionTracker::new(max_position_per_asset)
             )),
+            budgets: Arc::new(StrategyBudgets::default()),
         }
     }
 
+    /// Share strategy budgets with the feedback processor that settles them
+    pub fn with_budgets(mut self, budgets: Arc<StrategyBudgets>) -> Self {
+        self.budgets = budgets;
+        self
+    }
+
     /// Evaluate whether to execute a trade
     pub async fn decide(
         &self,
@@ -95,11 +131,47 @@ impl DecisionEngine {
     ) -> Result<TradeDecision> {
         let mut reasoning = Vec::new();
         let mut warnings = Vec::new();
+        let mut checks = Vec::new();
         let mut should_execute = true;
         
         debug!("Evaluating decision for {} on {}", candidate.strategy, candidate.asset);
         
+        let usage = self.budgets.usage(strategy_config, self.market_state.now()).await;
+        
+        // 0. Risk-adjusted size: fractional Kelly, capped by the CVaR budget.
+        // The bankroll is what is left of the strategy's capital allocation,
+        // or its position limit when it has none. Every later check applies
+        // to the resized trade.
+        let mut evaluation = evaluation;
+        if let Some(kelly_fraction) = strategy_config.risk_limits.kelly_fraction {
+            let bankroll_usd = usage.capital_usd
+                .map(|capital| (capital - usage.capital_in_use_usd).max(0.0))
+                .unwrap_or(strategy_config.max_position_usd);
+            let sizing = RiskSizing::new(
+                &evaluation,
+                candidate.spread_bps,
+                candidate.is_atomic(),
+                kelly_fraction,
+                bankroll_usd,
+                strategy_config.risk_limits.max_cvar_usd,
+            );
+            reasoning.extend(sizing.explain(evaluation.optimal_size_usd));
+            checks.push(PolicyCheck::new("positive_edge", sizing.edge_usd > 0.0, sizing.edge_usd, 0.0));
+            
+            if sizing.scale <= 0.0 {
+                should_execute = false;
+            } else if sizing.scale < 1.0 {
+                evaluation = scale_evaluation(&evaluation, sizing.scale);
+            }
+        }
+        
         // 1. Check minimum profit threshold
+        checks.push(PolicyCheck::new(
+            "min_profit_usd",
+            evaluation.net_pnl_usd >= strategy_config.min_profit_usd,
+            evaluation.net_pnl_usd,
+            strategy_config.min_profit_usd,
+        ));
         if evaluation.net_pnl_usd < strategy_config.min_profit_usd {
             reasoning.push(format!(
                 "❌ PnL ${:.2} < min ${:.2}",
@@ -116,6 +188,12 @@ impl DecisionEngine {
         }
         
         // 2. Check minimum profit in basis points
+        checks.push(PolicyCheck::new(
+            "min_profit_bps",
+            evaluation.net_bps >= strategy_config.min_profit_bps,
+            evaluation.net_bps,
+            strategy_config.min_profit_bps,
+        ));
         if evaluation.net_bps < strategy_config.min_profit_bps {
             reasoning.push(format!(
                 "❌ Net spread {:.2}bps < min {:.2}bps",
@@ -132,10 +210,13 @@ impl DecisionEngine {
         }
         
         // 3. Check slippage limit
-        let total_slippage_bps: f64 = evaluation.execution_path
-            .iter()
-            .map(|step| step.slippage_bps)
-            .sum();
+        let total_slippage_bps = evaluation.total_slippage_bps();
+        checks.push(PolicyCheck::new(
+            "max_slippage_bps",
+            total_slippage_bps <= strategy_config.risk_limits.max_slippage_bps,
+            total_slippage_bps,
+            strategy_config.risk_limits.max_slippage_bps,
+        ));
         
         if total_slippage_bps > strategy_config.risk_limits.max_slippage_bps {
             reasoning.push(format!(
@@ -158,6 +239,12 @@ impl DecisionEngine {
         } else {
             100.0 // If no profit, gas is 100% of "profit"
         };
+        checks.push(PolicyCheck::new(
+            "max_gas_pct",
+            gas_pct <= strategy_config.risk_limits.max_gas_pct,
+            gas_pct,
+            strategy_config.risk_limits.max_gas_pct,
+        ));
         
         if gas_pct > strategy_config.risk_limits.max_gas_pct {
             reasoning.push(format!(
@@ -175,6 +262,12 @@ impl DecisionEngine {
         }
         
         // 5. Check success probability
+        checks.push(PolicyCheck::new(
+            "min_success_prob",
+            evaluation.success_prob >= strategy_config.risk_limits.min_success_prob,
+            evaluation.success_prob,
+            strategy_config.risk_limits.min_success_prob,
+        ));
         if evaluation.success_prob < strategy_config.risk_limits.min_success_prob {
             reasoning.push(format!(
                 "❌ Success prob {:.2} < min {:.2}",
@@ -190,33 +283,93 @@ impl DecisionEngine {
             ));
         }
         
-        // 6. Check position limits
-        let position_tracker = self.position_tracker.read().await;
-        let current_position = position_tracker.get_position(&candidate.asset);
-        let can_take = position_tracker.can_take_position(&candidate.asset, evaluation.optimal_size_usd);
-        drop(position_tracker);
-        
-        if !can_take {
+        // 6. Check position limits (atomic trades hold no position past the transaction)
+        if candidate.is_atomic() {
             reasoning.push(format!(
-                "❌ Position limit: current ${:.0} + ${:.0} > max ${:.0}",
-                current_position,
-                evaluation.optimal_size_usd,
-                strategy_config.max_position_usd
+                "✅ Atomic trade on borrowed funds: position limit not applied to ${:.0}",
+                evaluation.optimal_size_usd
             ));
-            should_execute = false;
         } else {
+            let position_tracker = self.position_tracker.read().await;
+            let current_position = position_tracker.get_position(&candidate.asset);
+            let can_take = position_tracker.can_take_position(&candidate.asset, evaluation.optimal_size_usd);
+            checks.push(PolicyCheck::new(
+                "max_position_usd",
+                can_take,
+                current_position + evaluation.optimal_size_usd,
+                position_tracker.max_position_per_asset,
+            ));
+            drop(position_tracker);
+        
+            if !can_take {
+                reasoning.push(format!(
+                    "❌ Position limit: current ${:.0} + ${:.0} > max ${:.0}",
+                    current_position,
+                    evaluation.optimal_size_usd,
+                    strategy_config.max_position_usd
+                ));
+                should_execute = false;
+            } else {
+                reasoning.push(format!(
+                    "✅ Position ok: ${:.0} + ${:.0} <= ${:.0}",
+                    current_position,
+                    evaluation.optimal_size_usd,
+                    strategy_config.max_position_usd
+                ));
+            }
+        
+        }
+        
+        // Atomic strategies pay for every landed revert, so they carry a revert budget
+        if let Some(revert_prob) = evaluation.revert_prob {
+            let within_budget = revert_prob <= strategy_config.risk_limits.max_revert_prob;
+            checks.push(PolicyCheck::new(
+                "max_revert_prob",
+                within_budget,
+                revert_prob,
+                strategy_config.risk_limits.max_revert_prob,
+            ));
+            if !within_budget {
+                reasoning.push(format!(
+                    "❌ Revert prob {:.3} > max {:.3}",
+                    revert_prob,
+                    strategy_config.risk_limits.max_revert_prob
+                ));
+                should_execute = false;
+            } else {
+                reasoning.push(format!(
+                    "✅ Revert prob {:.3} <= max {:.3}",
+                    revert_prob,
+                    strategy_config.risk_limits.max_revert_prob
+                ));
+            }
+        }
+        
+        // Per-strategy budgets, including this trade
+        for check in budget_checks(&usage, &evaluation, candidate.is_atomic()) {
             reasoning.push(format!(
-                "✅ Position ok: ${:.0} + ${:.0} <= ${:.0}",
-                current_position,
-                evaluation.optimal_size_usd,
-                strategy_config.max_position_usd
+                "{} Budget {}: {:.2} {} {:.2}",
+                if check.passed { "✅" } else { "❌" },
+                check.name,
+                check.value,
+                if check.passed { "<=" } else { ">" },
+                check.threshold
             ));
+            should_execute &= check.passed;
+            checks.push(check);
         }
         
         // 7. Check sequencer health for involved chains
         let chains_involved = self.extract_chains(&candidate);
         for chain in chains_involved {
-            if !self.market_state.is_sequencer_healthy(chain).await {
+            let healthy = self.market_state.is_sequencer_healthy(chain).await;
+            checks.push(PolicyCheck::new(
+                format!("sequencer_healthy:{:?}", chain),
+                healthy,
+                if healthy { 1.0 } else { 0.0 },
+                1.0,
+            ));
+            if !healthy {
                 reasoning.push(format!(
                     "❌ Sequencer unhealthy on {:?}",
                     chain
@@ -226,7 +379,9 @@ impl DecisionEngine {
         }
         
         // 8. Check if asset is approved
-        if !strategy_config.approved_assets.contains(&candidate.asset) {
+        let approved = strategy_config.approved_assets.contains(&candidate.asset);
+        checks.push(PolicyCheck::new("approved_asset", approved, if approved { 1.0 } else { 0.0 }, 1.0));
+        if !approved {
             reasoning.push(format!(
                 "❌ Asset {} not in approved list",
                 candidate.asset
@@ -274,6 +429,7 @@ impl DecisionEngine {
             candidate,
             score,
             reasoning,
+            checks,
             warnings,
         })
     }
@@ -305,8 +461,10 @@ impl DecisionEngine {
                 break;
             }
             
-            // Double-check position limits
-            if position_tracker.can_take_position(
+            // Atomic trades reserve nothing
+            if decision.candidate.is_atomic() {
+                selected.push(decision);
+            } else if position_tracker.can_take_position(
                 &decision.candidate.asset,
                 decision.evaluation.optimal_size_usd
             ) {
@@ -345,35 +503,39 @@ impl DecisionEngine {
         }
     }
     
+    /// Current open positions by asset (USD value)
+    pub async fn positions(&self) -> HashMap<String, f64> {
+        self.position_tracker.read().await.positions.clone()
+    }
+    
+    /// Hold an emitted intent's capital and intent slot, and charge its estimated gas, against its strategy's budget
+    pub async fn reserve_budget(&self, decision: &TradeDecision, intent: &TradeIntent) {
+        let now = self.market_state.now();
+        // Atomic trades run on borrowed funds and hold no capital
+        let capital_usd = if decision.candidate.is_atomic() { 0.0 } else { decision.evaluation.optimal_size_usd };
+        self.budgets.reserve(
+            &intent.strategy,
+            intent.intent_id,
+            capital_usd,
+            decision.evaluation.costs.gas_usd,
+            now + Duration::seconds(intent.ttl_seconds as i64),
+            now,
+        ).await;
+    }
+    
+    /// Budget use of every configured strategy
+    pub async fn budget_usage(&self, strategies: &HashMap<String, StrategyConfig>) -> Vec<BudgetUsage> {
+        self.budgets.report(strategies, self.market_state.now()).await
+    }
+    
+    /// Budgets checked by this engine
+    pub fn budgets(&self) -> &Arc<StrategyBudgets> {
+        &self.budgets
+    }
+    
     /// Extract chains involved in a candidate
     fn extract_chains(&self, candidate: &Candidate) -> Vec<qenus_dataplane::Chain> {
-        // Parse chain names from legs
-        let mut chains = Vec::new();
-        
-        for (domain, _) in &candidate.legs {
-            if domain.contains("Ethereum") {
-                chains.push(qenus_dataplane::Chain::Ethereum);
-            }
-            if domain.contains("Arbitrum") {
-                chains.push(qenus_dataplane::Chain::Arbitrum);
-            }
-            if domain.contains("Optimism") {
-                chains.push(qenus_dataplane::Chain::Optimism);
-            }
-            if domain.contains("Base") {
-                chains.push(qenus_dataplane::Chain::Base);
-            }
-        }
-        
-        // Remove duplicates manually since Chain doesn't implement Ord
-        let mut unique_chains = Vec::new();
-        for chain in chains {
-            if !unique_chains.contains(&chain) {
-                unique_chains.push(chain);
-            }
-        }
-        chains = unique_chains;
-        chains
+        candidate_chains(candidate)
     }
     
     /// Calculate decision score for ranking
@@ -392,17 +554,262 @@ impl DecisionEngine {
     }
 }
 
+/// Budget limits a trade would reach, for the limits the strategy sets
+fn budget_checks(usage: &BudgetUsage, evaluation: &EvaluationResult, atomic: bool) -> Vec<PolicyCheck> {
+    let gas_usd = evaluation.costs.gas_usd;
+    let mut limits = Vec::new();
+    if let Some(capital_usd) = usage.capital_usd.filter(|_| !atomic) {
+        limits.push(("strategy_capital_usd", usage.capital_in_use_usd + evaluation.optimal_size_usd, capital_usd));
+    }
+    if let Some(max_intents) = usage.max_concurrent_intents {
+        limits.push(("max_concurrent_intents", (usage.open_intents + 1) as f64, max_intents as f64));
+    }
+    if let Some(max_gas_usd) = usage.max_gas_usd_per_hour {
+        limits.push(("max_gas_usd_per_hour", usage.gas_last_hour_usd + gas_usd, max_gas_usd));
+    }
+    if let Some(max_gas_usd) = usage.max_gas_usd_per_day {
+        limits.push(("max_gas_usd_per_day", usage.gas_last_day_usd + gas_usd, max_gas_usd));
+    }
+    limits.into_iter()
+        .map(|(name, value, limit)| PolicyCheck::new(name, value <= limit, value, limit))
+        .collect()
+}
+
+/// Tail mass the CVaR budget covers (CVaR at 95%)
+const CVAR_TAIL: f64 = 0.05;
+
+/// Risk-adjusted trade size as a fraction of the simulator's optimum
+///
+/// A trade is modelled with two outcomes: with `success_prob` it makes the
+/// simulated net PnL, otherwise it loses its costs. Atomic trades revert as a
+/// whole and only lose gas; other trades pay all costs and are assumed to
+/// unwind against the full spread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskSizing {
+    pub success_prob: f64,
+    /// PnL on success, at the simulated size
+    pub win_usd: f64,
+    /// Loss on failure, at the simulated size
+    pub loss_usd: f64,
+    /// Expected PnL and its standard deviation
+    pub edge_usd: f64,
+    pub stdev_usd: f64,
+    /// Full-Kelly fraction of the bankroll to put at risk
+    pub kelly: f64,
+    /// Scale allowed by fractional Kelly
+    pub kelly_scale: f64,
+    pub bankroll_usd: f64,
+    pub kelly_fraction: f64,
+    /// Expected loss in the worst 5% of outcomes, at the simulated size
+    pub cvar_usd: f64,
+    pub max_cvar_usd: Option<f64>,
+    /// Scale allowed by the CVaR budget
+    pub cvar_scale: f64,
+    /// Applied scale, at most 1; 0 when the trade has no edge
+    pub scale: f64,
+}
+
+impl RiskSizing {
+    pub fn new(
+        evaluation: &EvaluationResult,
+        spread_bps: f64,
+        atomic: bool,
+        kelly_fraction: f64,
+        bankroll_usd: f64,
+        max_cvar_usd: Option<f64>,
+    ) -> Self {
+        let p = evaluation.success_prob.clamp(0.0, 1.0);
+        let q = 1.0 - p;
+        let win_usd = evaluation.net_pnl_usd;
+        let loss_usd = if atomic {
+            evaluation.costs.gas_usd
+        } else {
+            evaluation.costs.total_usd + evaluation.optimal_size_usd * spread_bps.abs() / 10000.0
+        };
+        
+        let edge_usd = p * win_usd - q * loss_usd;
+        let stdev_usd = (p * q).sqrt() * (win_usd + loss_usd).abs();
+        
+        // Kelly for a bet paying win/loss: f* = p - q / b = edge / win
+        let kelly = if win_usd > 0.0 { (edge_usd / win_usd).max(0.0) } else { 0.0 };
+        let kelly_scale = if loss_usd > 0.0 {
+            kelly_fraction * kelly * bankroll_usd / loss_usd
+        } else {
+            f64::INFINITY
+        };
+        
+        let cvar_usd = if q >= CVAR_TAIL {
+            loss_usd
+        } else {
+            (q * loss_usd - (CVAR_TAIL - q) * win_usd) / CVAR_TAIL
+        };
+        let cvar_scale = match max_cvar_usd {
+            Some(budget) if cvar_usd > 0.0 => budget.max(0.0) / cvar_usd,
+            _ => f64::INFINITY,
+        };
+        
+        let scale = if edge_usd > 0.0 && win_usd > 0.0 {
+            kelly_scale.min(cvar_scale).min(1.0)
+        } else {
+            0.0
+        };
+        
+        Self {
+            success_prob: p,
+            win_usd,
+            loss_usd,
+            edge_usd,
+            stdev_usd,
+            kelly,
+            kelly_scale,
+            bankroll_usd,
+            kelly_fraction,
+            cvar_usd,
+            max_cvar_usd,
+            cvar_scale,
+            scale,
+        }
+    }
+    
+    /// Sizing steps for the decision reasoning
+    pub fn explain(&self, size_usd: f64) -> Vec<String> {
+        let mut lines = vec![format!(
+            "📐 Outcomes: p={:.2} win ${:.2} / lose ${:.2} → edge ${:.2} ± ${:.2}",
+            self.success_prob, self.win_usd, self.loss_usd, self.edge_usd, self.stdev_usd
+        )];
+        
+        if self.scale <= 0.0 {
+            lines.push(format!("❌ No edge: expected PnL ${:.2} at success prob {:.2}", self.edge_usd, self.success_prob));
+            return lines;
+        }
+        
+        lines.push(format!(
+            "📐 Kelly f*={:.3} × {:.2} of ${:.0} bankroll risks ${:.0} → {:.0}% of size",
+            self.kelly,
+            self.kelly_fraction,
+            self.bankroll_usd,
+            self.kelly_fraction * self.kelly * self.bankroll_usd,
+            self.kelly_scale.min(1.0) * 100.0
+        ));
+        if let Some(budget) = self.max_cvar_usd {
+            lines.push(format!(
+                "📐 CVaR95 ${:.2} at full size vs budget ${:.0} → {:.0}% of size",
+                self.cvar_usd, budget, self.cvar_scale.min(1.0) * 100.0
+            ));
+        }
+        lines.push(format!(
+            "📐 Size ${:.0} → ${:.0} ({:.0}%)",
+            size_usd, size_usd * self.scale, self.scale * 100.0
+        ));
+        lines
+    }
+}
+
+/// The same trade at `scale` × its size
+///
+/// Fees and slippage scale with size; gas is per transaction and does not.
+/// Step amounts scale linearly.
+fn scale_evaluation(evaluation: &EvaluationResult, scale: f64) -> EvaluationResult {
+    let gross_usd = evaluation.net_pnl_usd + evaluation.costs.total_usd;
+    let mut scaled = evaluation.clone();
+    
+    let costs = &mut scaled.costs;
+    costs.protocol_fees_usd *= scale;
+    costs.bridge_fees_usd *= scale;
+    costs.flashloan_fees_usd *= scale;
+    costs.slippage_usd *= scale;
+    costs.total_usd = costs.gas_usd + costs.protocol_fees_usd + costs.bridge_fees_usd
+        + costs.flashloan_fees_usd + costs.slippage_usd;
+    
+    scaled.optimal_size_usd *= scale;
+    scaled.net_pnl_usd = gross_usd * scale - scaled.costs.total_usd;
+    scaled.net_bps = if scaled.optimal_size_usd > 0.0 {
+        scaled.net_pnl_usd / scaled.optimal_size_usd * 10000.0
+    } else {
+        0.0
+    };
+    for step in &mut scaled.execution_path {
+        step.amount_in *= scale;
+        step.amount_out *= scale;
+    }
+    scaled
+}
+
 impl Default for DecisionEngine {
     fn default() -> Self {
         Self::new(Arc::new(MarketState::default()), 5_000_000.0)
     }
 }
 
+/// Chains named in a candidate's legs
+pub(crate) fn candidate_chains(candidate: &Candidate) -> Vec<qenus_dataplane::Chain> {
+    // Parse chain names from legs
+    let mut chains = Vec::new();
+    
+    for (domain, _) in &candidate.legs {
+        if domain.contains("Ethereum") {
+            chains.push(qenus_dataplane::Chain::Ethereum);
+        }
+        if domain.contains("Arbitrum") {
+            chains.push(qenus_dataplane::Chain::Arbitrum);
+        }
+        if domain.contains("Optimism") {
+            chains.push(qenus_dataplane::Chain::Optimism);
+        }
+        if domain.contains("Base") {
+            chains.push(qenus_dataplane::Chain::Base);
+        }
+    }
+    
+    // Remove duplicates manually since Chain doesn't implement Ord
+    let mut unique_chains = Vec::new();
+    for chain in chains {
+        if !unique_chains.contains(&chain) {
+            unique_chains.push(chain);
+        }
+    }
+    chains = unique_chains;
+    chains
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
     use crate::{CostBreakdown, SimulatedStep};
+    use qenus_dataplane::{AmmFeature, DepthCurve, Feature, FeatureData, FeatureType, TokenInfo};
+    
+    /// Intent builder over a WETH/USDC pool, so test paths resolve to tokens
+    async fn intent_builder() -> crate::IntentBuilder {
+        let market_state = Arc::new(MarketState::new(30));
+        let token = |address: &str, symbol: &str, decimals| TokenInfo {
+            address: address.to_string(),
+            symbol: symbol.to_string(),
+            decimals,
+        };
+        let pool = Feature::new(
+            1,
+            qenus_dataplane::Chain::Ethereum,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640".to_string(),
+                pool_type: "uniswap_v3".to_string(),
+                pool_id: None,
+                token0: token("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", "WETH", 18),
+                token1: token("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", "USDC", 6),
+                fee_tier: Some(500),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price: 2000.0,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve::default(),
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        );
+        market_state.ingest_feature(pool).await.unwrap();
+        crate::IntentBuilder::new(market_state)
+    }
     
     fn create_test_evaluation(net_pnl_usd: f64, net_bps: f64) -> EvaluationResult {
         EvaluationResult {
@@ -428,8 +835,13 @@ mod tests {
                     amount_out: 100_500.0,
                     slippage_bps: 5.0,
                     cost_usd: 100.0,
+                    pool: None,
+                    asset_in: Some("WETH".to_string()),
+                    asset_out: Some("USDC".to_string()),
                 },
             ],
+            competition: None,
+            revert_prob: None,
         }
     }
     
@@ -445,6 +857,8 @@ mod tests {
             legs: vec![],
             detected_at: Utc::now(),
             confidence: 0.9,
+            first_seen_at: None,
+            details: None,
         };
         
         let evaluation = create_test_evaluation(600.0, 12.0);
@@ -458,6 +872,7 @@ mod tests {
             approved_assets: vec!["USDC".to_string()],
             approved_chains: vec![qenus_dataplane::Chain::Ethereum],
             risk_limits: RiskLimits::default(),
+            budget: Default::default(),
         };
         
         let decision = engine.decide(candidate, evaluation, &config).await.unwrap();
@@ -478,6 +893,8 @@ mod tests {
             legs: vec![],
             detected_at: Utc::now(),
             confidence: 0.9,
+            first_seen_at: None,
+            details: None,
         };
         
         let evaluation = create_test_evaluation(100.0, 2.0); // Too low
@@ -491,6 +908,7 @@ mod tests {
             approved_assets: vec!["USDC".to_string()],
             approved_chains: vec![qenus_dataplane::Chain::Ethereum],
             risk_limits: RiskLimits::default(),
+            budget: Default::default(),
         };
         
         let decision = engine.decide(candidate, evaluation, &config).await.unwrap();
@@ -498,4 +916,208 @@ mod tests {
         assert!(!decision.should_execute);
         assert!(decision.reasoning.iter().any(|r| r.contains("PnL")));
     }
+    
+    #[tokio::test]
+    async fn test_atomic_trade_skips_position_limit_but_not_revert_budget() {
+        let market_state = Arc::new(MarketState::new(30));
+        // Position limit well below the borrow size
+        let engine = DecisionEngine::new(market_state, 50_000.0);
+        
+        let candidate = Candidate {
+            strategy: "flash_arb".to_string(),
+            asset: "USDC".to_string(),
+            spread_bps: 15.0,
+            legs: vec![],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: Some(crate::CandidateDetails::FlashArb(crate::FlashArbDetails {
+                chain: qenus_dataplane::Chain::Ethereum,
+                borrow_asset: "WETH".to_string(),
+                buy_pool: "0xbuy".to_string(),
+                sell_pool: "0xsell".to_string(),
+                gross_spread_bps: 40.0,
+                size_usd: 100_000.0,
+            })),
+        };
+        
+        let config = StrategyConfig {
+            name: "flash_arb".to_string(),
+            enabled: true,
+            min_profit_usd: 50.0,
+            min_profit_bps: 5.0,
+            max_position_usd: 50_000.0,
+            approved_assets: vec!["USDC".to_string()],
+            approved_chains: vec![qenus_dataplane::Chain::Ethereum],
+            risk_limits: RiskLimits::default(),
+            budget: Default::default(),
+        };
+        
+        let mut evaluation = create_test_evaluation(600.0, 12.0);
+        evaluation.revert_prob = Some(0.01);
+        let decision = engine.decide(candidate.clone(), evaluation, &config).await.unwrap();
+        assert!(decision.should_execute);
+        assert!(!decision.checks.iter().any(|check| check.name == "max_position_usd"));
+        
+        // Selecting it reserves no position
+        engine.select_best(vec![decision], 5).await.unwrap();
+        assert_eq!(engine.positions().await.get("USDC").copied().unwrap_or(0.0), 0.0);
+        
+        let mut evaluation = create_test_evaluation(600.0, 12.0);
+        evaluation.revert_prob = Some(0.1);
+        let decision = engine.decide(candidate, evaluation, &config).await.unwrap();
+        assert!(!decision.should_execute);
+        assert!(decision.reasoning.iter().any(|r| r.contains("Revert prob")));
+    }
+    
+    #[tokio::test]
+    async fn test_kelly_sizing_capped_by_cvar_budget() {
+        let engine = DecisionEngine::new(Arc::new(MarketState::new(30)), 5_000_000.0);
+        let candidate = Candidate {
+            strategy: "triangle_arb".to_string(),
+            asset: "USDC".to_string(),
+            spread_bps: 30.0,
+            legs: vec![],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        };
+        
+        let mut evaluation = create_test_evaluation(1_500.0, 15.0);
+        evaluation.optimal_size_usd = 1_000_000.0;
+        evaluation.success_prob = 0.9;
+        evaluation.costs = CostBreakdown {
+            gas_usd: 100.0,
+            protocol_fees_usd: 400.0,
+            bridge_fees_usd: 200.0,
+            flashloan_fees_usd: 0.0,
+            slippage_usd: 100.0,
+            total_usd: 800.0,
+        };
+        
+        let mut config = StrategyConfig {
+            name: "test".to_string(),
+            enabled: true,
+            min_profit_usd: 100.0,
+            min_profit_bps: 5.0,
+            max_position_usd: 1_000_000.0,
+            approved_assets: vec!["USDC".to_string()],
+            approved_chains: vec![qenus_dataplane::Chain::Ethereum],
+            risk_limits: RiskLimits {
+                kelly_fraction: Some(0.25),
+                max_cvar_usd: Some(1_000.0),
+                ..RiskLimits::default()
+            },
+            budget: Default::default(),
+        };
+        
+        // Failure loses $800 of costs plus the 30bps spread on $1M: CVaR95 is $3,800
+        let decision = engine.decide(candidate.clone(), evaluation.clone(), &config).await.unwrap();
+        let scale = 1_000.0 / 3_800.0;
+        assert!(decision.should_execute, "{:?}", decision.reasoning);
+        assert!((decision.evaluation.optimal_size_usd - 1_000_000.0 * scale).abs() < 1e-6);
+        assert!((decision.evaluation.net_pnl_usd - (2_300.0 * scale - (100.0 + 700.0 * scale))).abs() < 1e-6);
+        assert!(decision.reasoning.iter().any(|r| r.starts_with("📐 CVaR95 $3800.00")));
+        
+        // A coin flip with a larger loss than win has no edge
+        evaluation.success_prob = 0.5;
+        config.risk_limits.min_success_prob = 0.0;
+        let decision = engine.decide(candidate, evaluation, &config).await.unwrap();
+        assert!(!decision.should_execute);
+        assert!(decision.checks.iter().any(|check| check.name == "positive_edge" && !check.passed));
+    }
+    
+    #[tokio::test]
+    async fn test_strategy_budgets_limit_open_intents_and_capital() {
+        let engine = DecisionEngine::new(Arc::new(MarketState::new(30)), 5_000_000.0);
+        let candidate = Candidate {
+            strategy: "dex_arb".to_string(),
+            asset: "USDC".to_string(),
+            spread_bps: 15.0,
+            legs: vec![],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        };
+        let config = StrategyConfig {
+            name: "dex_arb".to_string(),
+            enabled: true,
+            min_profit_usd: 500.0,
+            min_profit_bps: 10.0,
+            max_position_usd: 1_000_000.0,
+            approved_assets: vec!["USDC".to_string()],
+            approved_chains: vec![qenus_dataplane::Chain::Ethereum],
+            risk_limits: RiskLimits::default(),
+            budget: crate::StrategyBudget {
+                capital_usd: Some(150_000.0),
+                max_concurrent_intents: Some(2),
+                ..Default::default()
+            },
+        };
+        
+        let decision = engine.decide(candidate.clone(), create_test_evaluation(600.0, 12.0), &config).await.unwrap();
+        assert!(decision.should_execute, "{:?}", decision.reasoning);
+        let intent = intent_builder().await.build(&decision).await.unwrap();
+        engine.reserve_budget(&decision, &intent).await;
+        
+        // A second $100k trade would hold $200k of the $150k allocation
+        let decision = engine.decide(candidate, create_test_evaluation(600.0, 12.0), &config).await.unwrap();
+        assert!(!decision.should_execute);
+        let capital = decision.checks.iter().find(|check| check.name == "strategy_capital_usd").unwrap();
+        assert!(!capital.passed && capital.value == 200_000.0);
+        assert!(decision.checks.iter().any(|check| check.name == "max_concurrent_intents" && check.passed));
+        
+        let usage = engine.budget_usage(&[(config.name.clone(), config)].into_iter().collect()).await;
+        assert_eq!(usage[0].open_intents, 1);
+        assert_eq!(usage[0].gas_last_day_usd, 50.0);
+    }
+    
+    #[tokio::test]
+    async fn test_kelly_bankroll_is_unused_capital_allocation() {
+        let engine = DecisionEngine::new(Arc::new(MarketState::new(30)), 5_000_000.0);
+        let candidate = Candidate {
+            strategy: "dex_arb".to_string(),
+            asset: "USDC".to_string(),
+            spread_bps: 15.0,
+            legs: vec![],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        };
+        let config = StrategyConfig {
+            name: "dex_arb".to_string(),
+            enabled: true,
+            min_profit_usd: 10.0,
+            min_profit_bps: 1.0,
+            max_position_usd: 1_000_000.0,
+            approved_assets: vec!["USDC".to_string()],
+            approved_chains: vec![qenus_dataplane::Chain::Ethereum],
+            risk_limits: RiskLimits {
+                kelly_fraction: Some(0.25),
+                ..RiskLimits::default()
+            },
+            budget: crate::StrategyBudget {
+                capital_usd: Some(150_000.0),
+                ..Default::default()
+            },
+        };
+        let bankroll = |decision: &TradeDecision| decision.reasoning.iter()
+            .find(|line| line.starts_with("📐 Kelly"))
+            .cloned()
+            .unwrap();
+        
+        // Sized against the allocation, not the $1M position limit
+        let decision = engine.decide(candidate.clone(), create_test_evaluation(600.0, 12.0), &config).await.unwrap();
+        assert!(bankroll(&decision).contains("of $150000 bankroll"), "{:?}", decision.reasoning);
+        let intent = intent_builder().await.build(&decision).await.unwrap();
+        engine.reserve_budget(&decision, &intent).await;
+        
+        // Capital held by the open intent is no longer at the strategy's disposal
+        let held = decision.evaluation.optimal_size_usd;
+        let decision = engine.decide(candidate, create_test_evaluation(600.0, 12.0), &config).await.unwrap();
+        assert!(bankroll(&decision).contains(&format!("of ${:.0} bankroll", 150_000.0 - held)), "{:?}", decision.reasoning);
+    }
 }
@@ -3,15 +3,95 @@
 //! Detectors scan MarketState and identify arbitrage opportunities
 //! based on configured strategies from the business module.
 
+use std::collections::HashSet;
 use std::sync::Arc;
+use async_trait::async_trait;
 use chrono::Utc;
 use tracing::{debug, info, warn};
 use qenus_dataplane::Chain;
 
 use crate::error::{IntelligenceError, Result};
-use crate::state::MarketState;
+use crate::state::{ChangeKey, MarketChange, MarketState};
 use crate::types::{Candidate, StrategyConfig};
 
+/// A strategy detector run by the [`DetectorManager`]
+#[async_trait]
+pub trait Detector: Send + Sync {
+    /// Detector name (the strategy it serves)
+    fn name(&self) -> &str;
+    
+    /// Scan market state for candidates
+    async fn detect(&self) -> Result<Vec<Candidate>>;
+    
+    /// Whether a market change can affect this detector's opportunities
+    fn is_affected_by(&self, _change: &MarketChange) -> bool {
+        true
+    }
+    
+    /// Re-scan only the opportunities touched by `scope`
+    async fn detect_scoped(&self, _scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        self.detect().await
+    }
+}
+
+/// Chains and assets touched by a batch of market changes
+#[derive(Debug, Clone, Default)]
+pub struct DetectionScope {
+    /// Affected chains (None = every chain)
+    pub chains: Option<HashSet<Chain>>,
+    
+    /// Affected assets (None = every asset)
+    pub assets: Option<HashSet<String>>,
+}
+
+impl DetectionScope {
+    /// Scope covering the whole market (a full scan)
+    pub fn all() -> Self {
+        Self::default()
+    }
+    
+    /// Scope of a batch of changes
+    ///
+    /// Gas and sequencer changes affect every asset on their chain.
+    pub fn from_changes(changes: &[MarketChange]) -> Self {
+        let mut chains = HashSet::new();
+        let mut assets = Some(HashSet::new());
+        
+        for change in changes {
+            chains.insert(change.chain);
+            
+            match (&change.key, assets.as_mut()) {
+                (ChangeKey::Pool { token0, token1, .. }, Some(assets)) => {
+                    assets.insert(token0.clone());
+                    assets.insert(token1.clone());
+                }
+                (ChangeKey::Bridge { dest_chain, token }, Some(assets)) => {
+                    chains.insert(*dest_chain);
+                    assets.insert(token.clone());
+                }
+                (ChangeKey::FlashLoan { asset, .. }, Some(assets)) => {
+                    assets.insert(asset.clone());
+                }
+                (ChangeKey::Gas, _) | (ChangeKey::Sequencer, _) => assets = None,
+                (ChangeKey::Bridge { dest_chain, .. }, None) => {
+                    chains.insert(*dest_chain);
+                }
+                _ => {}
+            }
+        }
+        
+        Self { chains: Some(chains), assets }
+    }
+    
+    pub fn includes_chain(&self, chain: Chain) -> bool {
+        self.chains.as_ref().map(|chains| chains.contains(&chain)).unwrap_or(true)
+    }
+    
+    pub fn includes_asset(&self, asset: &str) -> bool {
+        self.assets.as_ref().map(|assets| assets.contains(asset)).unwrap_or(true)
+    }
+}
+
 /// Triangle arbitrage detector: L2 → Bridge → L1 → Bridge → L2
 pub struct TriangleArbDetector {
     config: StrategyConfig,
@@ -29,6 +109,11 @@ impl TriangleArbDetector {
     
     /// Detect triangle arbitrage opportunities
     pub async fn detect(&self) -> Result<Vec<Candidate>> {
+        self.detect_in(&DetectionScope::all()).await
+    }
+    
+    /// Detect opportunities on assets and chain pairs within `scope`
+    async fn detect_in(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
         if !self.config.enabled {
             return Ok(Vec::new());
         }
@@ -37,6 +122,10 @@ impl TriangleArbDetector {
         
         // For each approved asset
         for asset in &self.config.approved_assets {
+            if !scope.includes_asset(asset) {
+                continue;
+            }
+            
             // For each pair of approved chains
             let chains: Vec<Chain> = self.config.approved_chains.clone();
             
@@ -49,17 +138,30 @@ impl TriangleArbDetector {
                     let chain_a = chains[i];
                     let chain_b = chains[j];
                     
+                    if !scope.includes_chain(chain_a) && !scope.includes_chain(chain_b) {
+                        continue;
+                    }
+                    
+                    // Read both chains as of their last sealed block
+                    let (Some(view_a), Some(view_b)) = (
+                        self.market_state.block_view(chain_a),
+                        self.market_state.block_view(chain_b),
+                    ) else {
+                        continue;
+                    };
+                    let fresh_since = self.market_state.fresh_since();
+                    
                     // Skip if sequencers are not healthy
-                    if !self.market_state.is_sequencer_healthy(chain_a).await {
+                    if !view_a.is_sequencer_healthy(fresh_since) {
                         continue;
                     }
-                    if !self.market_state.is_sequencer_healthy(chain_b).await {
+                    if !view_b.is_sequencer_healthy(fresh_since) {
                         continue;
                     }
                     
                     // Get prices on both chains
-                    let price_a = self.market_state.get_price(chain_a, asset).await;
-                    let price_b = self.market_state.get_price(chain_b, asset).await;
+                    let price_a = view_a.price(asset, fresh_since);
+                    let price_b = view_b.price(asset, fresh_since);
                     
                     if let (Some(p_a), Some(p_b)) = (price_a, price_b) {
                         // Calculate spread
@@ -68,14 +170,12 @@ impl TriangleArbDetector {
                         // Check if spread exceeds minimum threshold
                         if spread_bps >= self.config.min_profit_bps {
                             // Get bridge fees
-                            let bridge_fee_ab = self.market_state
-                                .get_bridge_fee(chain_a, chain_b, asset)
-                                .await
+                            let bridge_fee_ab = view_a
+                                .bridge_fee(chain_b, fresh_since)
                                 .unwrap_or(100);
                             
-                            let bridge_fee_ba = self.market_state
-                                .get_bridge_fee(chain_b, chain_a, asset)
-                                .await
+                            let bridge_fee_ba = view_b
+                                .bridge_fee(chain_a, fresh_since)
                                 .unwrap_or(100);
                             
                             let total_bridge_fees_bps = bridge_fee_ab + bridge_fee_ba;
@@ -98,6 +198,8 @@ impl TriangleArbDetector {
                                     ],
                                     detected_at: Utc::now(),
                                     confidence: 0.8,
+                                    first_seen_at: None,
+                                    details: None,
                                 });
                             }
                         }
@@ -110,9 +212,49 @@ impl TriangleArbDetector {
     }
 }
 
+#[async_trait]
+impl Detector for TriangleArbDetector {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    
+    async fn detect(&self) -> Result<Vec<Candidate>> {
+        TriangleArbDetector::detect(self).await
+    }
+    
+    fn is_affected_by(&self, change: &MarketChange) -> bool {
+        if !self.config.approved_chains.contains(&change.chain) {
+            return false;
+        }
+        
+        match &change.key {
+            ChangeKey::Pool { token0, token1, .. } => {
+                self.config.approved_assets.contains(token0) || self.config.approved_assets.contains(token1)
+            }
+            ChangeKey::Bridge { .. } | ChangeKey::Sequencer => true,
+            ChangeKey::Gas | ChangeKey::FlashLoan { .. } | ChangeKey::Wallet { .. } |
+            ChangeKey::Lending { .. } | ChangeKey::EvmState => false,
+        }
+    }
+    
+    async fn detect_scoped(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        self.detect_in(scope).await
+    }
+}
+
 pub mod dex_arb;
 pub mod manager;
+pub mod liquidation;
+pub mod depeg;
+pub mod flash_arb;
+pub mod trigger;
+pub mod script;
 
 pub use dex_arb::DexArbDetector;
-pub use manager::DetectorManager;
+pub use manager::{DetectorManager, DetectorMetrics};
+pub use trigger::{ChangeBatch, ChangeCoalescer};
+pub use liquidation::{LiquidationDetector, AaveReserveConfig, AavePoolEvent};
+pub use depeg::DepegDetector;
+pub use flash_arb::FlashArbDetector;
+pub use script::ScriptDetector;
 
@@ -0,0 +1,547 @@
+//! Stablecoin depeg and peg-restoration detector
+//!
+//! Measures each stablecoin's deviation from peg across every stable-stable
+//! pool (Curve 3pool, Uniswap stable pools, Balancer stable pools) and keeps a
+//! short history per coin. A deviation confirmed by several pools is a depeg;
+//! a deviation in one pool while the rest hold the peg is a pool imbalance.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+use qenus_dataplane::Chain;
+
+use crate::error::Result;
+use crate::simulator::amm::{depth_slippage_bps, pool_fee_bps};
+use crate::detectors::{DetectionScope, Detector};
+use crate::state::{AmmState, ChangeKey, MarketChange, MarketState};
+use crate::types::{Candidate, CandidateDetails, DepegDetails, DepegKind, RiskSeverity, StrategyConfig};
+
+/// Pools that must agree before a deviation counts as a depeg
+const MIN_CONFIRMING_POOLS: usize = 2;
+
+/// Reference size used to rank pools by depth
+const DEPTH_REFERENCE_USD: f64 = 1_000_000.0;
+
+/// Passes of the joint peg estimate
+const CONSENSUS_ITERATIONS: usize = 20;
+
+/// Samples kept per stablecoin
+const HISTORY_LEN: usize = 360;
+
+/// Deviation observed for one stablecoin in one pool
+#[derive(Debug, Clone)]
+struct PoolQuote {
+    pool: AmmState,
+    counter_asset: String,
+    deviation_bps: f64,
+}
+
+/// Deviation history of one stablecoin
+#[derive(Debug, Clone, Default)]
+struct PegHistory {
+    samples: VecDeque<(DateTime<Utc>, f64)>,
+    off_peg_since: Option<DateTime<Utc>>,
+}
+
+impl PegHistory {
+    fn record(&mut self, at: DateTime<Utc>, deviation_bps: f64, threshold_bps: f64) {
+        self.samples.push_back((at, deviation_bps));
+        while self.samples.len() > HISTORY_LEN {
+            self.samples.pop_front();
+        }
+
+        if deviation_bps.abs() >= threshold_bps {
+            self.off_peg_since.get_or_insert(at);
+        } else {
+            self.off_peg_since = None;
+        }
+    }
+
+    /// Worst deviation since the coin went off peg
+    fn worst_since_off_peg(&self) -> f64 {
+        let since = match self.off_peg_since {
+            Some(since) => since,
+            None => return 0.0,
+        };
+
+        self.samples.iter()
+            .filter(|(at, _)| *at >= since)
+            .map(|(_, deviation)| deviation.abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Stablecoin depeg detector
+pub struct DepegDetector {
+    config: StrategyConfig,
+    market_state: Arc<MarketState>,
+
+    /// Deviation history by (chain, stablecoin)
+    history: RwLock<HashMap<(Chain, String), PegHistory>>,
+}
+
+impl DepegDetector {
+    /// Create a new depeg detector
+    ///
+    /// `approved_assets` lists the stablecoins to watch and `min_profit_bps`
+    /// is the deviation threshold.
+    pub fn new(config: StrategyConfig, market_state: Arc<MarketState>) -> Self {
+        Self {
+            config,
+            market_state,
+            history: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Detect depegs and single-pool imbalances
+    pub async fn detect(&self) -> Result<Vec<Candidate>> {
+        self.detect_in(&DetectionScope::all()).await
+    }
+
+    /// Detect on chains within `scope`
+    async fn detect_in(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        if !self.config.enabled {
+            return Ok(Vec::new());
+        }
+
+        let mut candidates = Vec::new();
+        let now = Utc::now();
+
+        for chain in &self.config.approved_chains {
+            if !scope.includes_chain(*chain) {
+                continue;
+            }
+
+            let Some(view) = self.market_state.block_view(*chain) else {
+                continue;
+            };
+            let fresh_since = self.market_state.fresh_since();
+
+            if *chain != Chain::Ethereum && !view.is_sequencer_healthy(fresh_since) {
+                continue;
+            }
+
+            let pools: Vec<_> = view.fresh_pools(fresh_since)
+                .filter(|pool| self.is_stable(&pool.token0_symbol) && self.is_stable(&pool.token1_symbol))
+                .cloned()
+                .collect();
+
+            let consensus = self.consensus_deviations(&pools);
+
+            for stablecoin in &self.config.approved_assets {
+                let deviation_bps = match consensus.get(stablecoin) {
+                    Some(deviation) => *deviation,
+                    None => continue,
+                };
+
+                let (duration_secs, recovering) = {
+                    let mut history = self.history.write().await;
+                    let entry = history.entry((*chain, stablecoin.clone())).or_default();
+                    entry.record(now, deviation_bps, self.config.min_profit_bps);
+
+                    let duration = entry.off_peg_since.map(|since| (now - since).num_seconds()).unwrap_or(0);
+                    (duration, deviation_bps.abs() < entry.worst_since_off_peg())
+                };
+
+                // Quotes corrected for counterparts that are off peg themselves
+                let quotes = adjust_quotes(&self.quotes_for(stablecoin, &pools), &consensus);
+
+                if let Some(candidate) = self.build_candidate(
+                    *chain, stablecoin, deviation_bps, &quotes, duration_secs, recovering
+                ) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        if !candidates.is_empty() {
+            info!("Depeg detector found {} candidates", candidates.len());
+        }
+
+        Ok(candidates)
+    }
+
+    fn is_stable(&self, symbol: &str) -> bool {
+        self.config.approved_assets.iter().any(|asset| asset == symbol)
+    }
+
+    /// Price of `stablecoin` in each pool, as log-bps from 1.0 against the counterpart
+    fn quotes_for(&self, stablecoin: &str, pools: &[AmmState]) -> Vec<PoolQuote> {
+        pools.iter()
+            .filter(|pool| pool.mid_price > 0.0)
+            .filter_map(|pool| {
+                // mid_price is token0 quoted in token1
+                let (price, counter_asset) = if pool.token0_symbol == stablecoin {
+                    (pool.mid_price, pool.token1_symbol.clone())
+                } else if pool.token1_symbol == stablecoin {
+                    (1.0 / pool.mid_price, pool.token0_symbol.clone())
+                } else {
+                    return None;
+                };
+
+                Some(PoolQuote {
+                    pool: pool.clone(),
+                    counter_asset,
+                    // Log price keeps quotes symmetric across token order
+                    deviation_bps: price.ln() * 10000.0,
+                })
+            })
+            .collect()
+    }
+
+    /// Deviation of each stablecoin from peg, solved jointly across pools
+    ///
+    /// Each pool only prices one stablecoin against another, so a depegged coin
+    /// makes its counterparts look rich. Every coin is re-estimated as the
+    /// depth-weighted median of (counterpart deviation + pool quote), then the
+    /// set is re-centred so the median coin sits on peg.
+    fn consensus_deviations(&self, pools: &[AmmState]) -> HashMap<String, f64> {
+        let quotes: HashMap<String, Vec<PoolQuote>> = self.config.approved_assets.iter()
+            .map(|coin| (coin.clone(), self.quotes_for(coin, pools)))
+            .filter(|(_, quotes)| !quotes.is_empty())
+            .collect();
+
+        let mut deviations: HashMap<String, f64> = quotes.keys().map(|coin| (coin.clone(), 0.0)).collect();
+
+        for _ in 0..CONSENSUS_ITERATIONS {
+            for coin in &self.config.approved_assets {
+                let coin_quotes = match quotes.get(coin) {
+                    Some(coin_quotes) => coin_quotes,
+                    None => continue,
+                };
+                let adjusted = adjust_quotes(coin_quotes, &deviations);
+                if let Some(deviation) = weighted_median(&adjusted) {
+                    deviations.insert(coin.clone(), deviation);
+                }
+            }
+
+            let mut sorted: Vec<f64> = deviations.values().copied().collect();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            if let Some(centre) = sorted.get(sorted.len() / 2).copied() {
+                for deviation in deviations.values_mut() {
+                    *deviation -= centre;
+                }
+            }
+        }
+
+        deviations
+    }
+
+    fn build_candidate(
+        &self,
+        chain: Chain,
+        stablecoin: &str,
+        deviation_bps: f64,
+        quotes: &[PoolQuote],
+        depeg_duration_secs: i64,
+        recovering: bool,
+    ) -> Option<Candidate> {
+        let threshold = self.config.min_profit_bps;
+        let off_peg: Vec<_> = quotes.iter()
+            .filter(|quote| quote.deviation_bps.abs() >= threshold && quote.deviation_bps.signum() == deviation_bps.signum())
+            .collect();
+
+        let deepest = quotes.iter()
+            .min_by(|a, b| depth_cost(&a.pool).partial_cmp(&depth_cost(&b.pool)).unwrap_or(std::cmp::Ordering::Equal))?;
+
+        let (kind, entry, exit, edge_bps, confirming) = if deviation_bps.abs() >= threshold && off_peg.len() >= MIN_CONFIRMING_POOLS {
+            // Market-wide depeg: enter and later exit through the deepest pool
+            (DepegKind::Depeg, deepest, deepest, deviation_bps.abs(), off_peg.len())
+        } else if deviation_bps.abs() < threshold {
+            // One pool off peg while consensus holds: trade it against the deepest other pool
+            let imbalanced = quotes.iter()
+                .filter(|quote| (quote.deviation_bps - deviation_bps).abs() >= threshold)
+                .max_by(|a, b| {
+                    (a.deviation_bps - deviation_bps).abs()
+                        .partial_cmp(&(b.deviation_bps - deviation_bps).abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })?;
+            let exit = quotes.iter()
+                .filter(|quote| quote.pool.pool_address != imbalanced.pool.pool_address)
+                .min_by(|a, b| depth_cost(&a.pool).partial_cmp(&depth_cost(&b.pool)).unwrap_or(std::cmp::Ordering::Equal))?;
+
+            (DepegKind::PoolImbalance, imbalanced, exit, (imbalanced.deviation_bps - deviation_bps).abs(), quotes.len() - 1)
+        } else {
+            // Consensus is off peg but only one pool shows it: not enough evidence
+            return None;
+        };
+
+        let severity = severity_for(kind, edge_bps, depeg_duration_secs);
+        let size_usd = self.bounded_size(&entry.pool, edge_bps, severity);
+        if size_usd * edge_bps / 10000.0 < self.config.min_profit_usd {
+            debug!("{} off peg by {:.1}bps on {:?} but exposure bound leaves no profit", stablecoin, edge_bps, chain);
+            return None;
+        }
+
+        let domain = format!("{:?}", chain);
+        let legs = match kind {
+            DepegKind::Depeg => vec![(domain.clone(), "swap".to_string()), (domain, "swap".to_string())],
+            DepegKind::PoolImbalance => vec![(domain.clone(), "buy".to_string()), (domain, "sell".to_string())],
+        };
+
+        // Restoration is a directional bet, imbalance is closed immediately
+        let confidence = match (kind, recovering) {
+            (DepegKind::PoolImbalance, _) => 0.9,
+            (DepegKind::Depeg, true) => 0.75,
+            (DepegKind::Depeg, false) => 0.6,
+        };
+
+        Some(Candidate {
+            strategy: "stablecoin_depeg".to_string(),
+            asset: stablecoin.to_string(),
+            spread_bps: edge_bps,
+            legs,
+            detected_at: Utc::now(),
+            confidence,
+            first_seen_at: None,
+            details: Some(CandidateDetails::Depeg(DepegDetails {
+                chain,
+                stablecoin: stablecoin.to_string(),
+                counter_asset: entry.counter_asset.clone(),
+                kind,
+                deviation_bps,
+                pool_deviation_bps: entry.deviation_bps,
+                confirming_pools: confirming,
+                entry_pool: entry.pool.pool_address.clone(),
+                exit_pool: exit.pool.pool_address.clone(),
+                size_usd,
+                severity,
+                depeg_duration_secs,
+                recovering,
+            })),
+        })
+    }
+
+    /// Largest size whose fee and slippage stay within half the edge, scaled down by severity
+    fn bounded_size(&self, pool: &AmmState, edge_bps: f64, severity: RiskSeverity) -> f64 {
+        let exposure = match severity {
+            RiskSeverity::Low => 1.0,
+            RiskSeverity::Medium => 0.5,
+            RiskSeverity::High => 0.25,
+            // A collapsing peg is not something to catch
+            RiskSeverity::Critical => 0.0,
+        };
+
+        let budget_bps = edge_bps / 2.0 - pool_fee_bps(pool);
+        if budget_bps <= 0.0 || exposure == 0.0 {
+            return 0.0;
+        }
+
+        let (mut low, mut high) = (0.0, self.config.max_position_usd);
+        if depth_slippage_bps(pool, high) <= budget_bps {
+            return high * exposure;
+        }
+        for _ in 0..40 {
+            let mid = (low + high) / 2.0;
+            if depth_slippage_bps(pool, mid) <= budget_bps {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        low * exposure
+    }
+}
+
+/// Shift each quote by its counterpart's own deviation from peg
+fn adjust_quotes(quotes: &[PoolQuote], deviations: &HashMap<String, f64>) -> Vec<PoolQuote> {
+    quotes.iter()
+        .map(|quote| PoolQuote {
+            deviation_bps: quote.deviation_bps + deviations.get(&quote.counter_asset).copied().unwrap_or(0.0),
+            ..quote.clone()
+        })
+        .collect()
+}
+
+/// Slippage at the reference size; lower is deeper
+fn depth_cost(pool: &AmmState) -> f64 {
+    depth_slippage_bps(pool, DEPTH_REFERENCE_USD) + pool_fee_bps(pool)
+}
+
+/// Median deviation weighted by pool depth
+fn weighted_median(quotes: &[PoolQuote]) -> Option<f64> {
+    if quotes.is_empty() {
+        return None;
+    }
+
+    let mut weighted: Vec<_> = quotes.iter()
+        .map(|quote| (quote.deviation_bps, 1.0 / depth_cost(&quote.pool).max(0.01)))
+        .collect();
+    weighted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let half = weighted.iter().map(|(_, w)| w).sum::<f64>() / 2.0;
+    let mut cumulative = 0.0;
+    for (deviation, weight) in &weighted {
+        cumulative += weight;
+        if cumulative >= half {
+            return Some(*deviation);
+        }
+    }
+
+    weighted.last().map(|(deviation, _)| *deviation)
+}
+
+/// Severity from deviation size, escalated for long-running depegs
+fn severity_for(kind: DepegKind, edge_bps: f64, duration_secs: i64) -> RiskSeverity {
+    let severity = if edge_bps < 50.0 {
+        RiskSeverity::Low
+    } else if edge_bps < 200.0 {
+        RiskSeverity::Medium
+    } else if edge_bps < 500.0 {
+        RiskSeverity::High
+    } else {
+        RiskSeverity::Critical
+    };
+
+    match (kind, severity) {
+        (DepegKind::Depeg, RiskSeverity::Low) if duration_secs > 3600 => RiskSeverity::Medium,
+        (DepegKind::Depeg, RiskSeverity::Medium) if duration_secs > 3600 => RiskSeverity::High,
+        _ => severity,
+    }
+}
+
+#[async_trait]
+impl Detector for DepegDetector {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn detect(&self) -> Result<Vec<Candidate>> {
+        DepegDetector::detect(self).await
+    }
+
+    fn is_affected_by(&self, change: &MarketChange) -> bool {
+        if !self.config.approved_chains.contains(&change.chain) {
+            return false;
+        }
+
+        match &change.key {
+            ChangeKey::Pool { token0, token1, .. } => self.is_stable(token0) && self.is_stable(token1),
+            ChangeKey::Sequencer => true,
+            _ => false,
+        }
+    }
+
+    async fn detect_scoped(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        self.detect_in(scope).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RiskLimits;
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            name: "stablecoin_depeg".to_string(),
+            enabled: true,
+            min_profit_usd: 100.0,
+            min_profit_bps: 20.0,
+            max_position_usd: 1_000_000.0,
+            approved_assets: vec!["USDC".to_string(), "USDT".to_string(), "DAI".to_string()],
+            approved_chains: vec![Chain::Ethereum],
+            risk_limits: RiskLimits::default(),
+            budget: Default::default(),
+        }
+    }
+
+    fn pool(address: &str, pool_type: &str, token0: &str, token1: &str, mid_price: f64, slippage_1m: f64) -> AmmState {
+        let mut depth = HashMap::new();
+        depth.insert("1m".to_string(), (slippage_1m, slippage_1m / 10000.0));
+
+        AmmState {
+            pool_address: address.to_string(),
+            pool_type: pool_type.to_string(),
+            pool_id: None,
+            token0_symbol: token0.to_string(),
+            token1_symbol: token1.to_string(),
+            token0_address: String::new(),
+            token1_address: String::new(),
+            token0_decimals: 18,
+            token1_decimals: 18,
+            mid_price,
+            liquidity: "0".to_string(),
+            fee_tier: Some(1),
+            depth,
+            depth_curve: Default::default(),
+            last_update: Utc::now(),
+        }
+    }
+
+    fn detector() -> DepegDetector {
+        DepegDetector::new(test_config(), Arc::new(MarketState::new(30)))
+    }
+
+    #[test]
+    fn test_depeg_confirmed_by_multiple_pools() {
+        let detector = detector();
+        let pools = vec![
+            pool("0x3pool", "curve", "USDC", "USDT", 0.985, 2.0),
+            pool("0xuni", "uniswap_v3", "USDC", "DAI", 0.985, 5.0),
+            pool("0xbal", "balancer", "DAI", "USDT", 1.0, 10.0),
+        ];
+
+        let consensus = detector.consensus_deviations(&pools);
+        let usdc = consensus["USDC"];
+        assert!(usdc < -100.0);
+
+        // Counterparts are not mistaken for a premium
+        assert!(consensus["USDT"].abs() < 1.0);
+        assert!(consensus["DAI"].abs() < 1.0);
+
+        let quotes = adjust_quotes(&detector.quotes_for("USDC", &pools), &consensus);
+        let candidate = detector.build_candidate(Chain::Ethereum, "USDC", usdc, &quotes, 0, false).unwrap();
+
+        match candidate.details {
+            Some(CandidateDetails::Depeg(details)) => {
+                assert_eq!(details.kind, DepegKind::Depeg);
+                assert_eq!(details.entry_pool, "0x3pool");
+                assert_eq!(details.severity, RiskSeverity::Medium);
+                assert!(details.size_usd > 0.0 && details.size_usd <= 500_000.0);
+            }
+            other => panic!("unexpected details: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_single_pool_imbalance() {
+        let detector = detector();
+        let pools = vec![
+            pool("0x3pool", "curve", "USDC", "USDT", 1.0001, 2.0),
+            pool("0xuni", "uniswap_v3", "USDC", "DAI", 0.9999, 5.0),
+            pool("0xbal", "balancer", "USDC", "USDT", 0.994, 40.0),
+        ];
+
+        let consensus = detector.consensus_deviations(&pools);
+        let quotes = adjust_quotes(&detector.quotes_for("USDC", &pools), &consensus);
+        let candidate = detector.build_candidate(Chain::Ethereum, "USDC", consensus["USDC"], &quotes, 0, false).unwrap();
+
+        match candidate.details {
+            Some(CandidateDetails::Depeg(details)) => {
+                assert_eq!(details.kind, DepegKind::PoolImbalance);
+                assert_eq!(details.entry_pool, "0xbal");
+                assert_eq!(details.exit_pool, "0x3pool");
+            }
+            other => panic!("unexpected details: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_history_tracks_recovery() {
+        let mut history = PegHistory::default();
+        let start = Utc::now();
+
+        history.record(start, -150.0, 20.0);
+        history.record(start + chrono::Duration::seconds(60), -90.0, 20.0);
+        assert_eq!(history.off_peg_since, Some(start));
+        assert_eq!(history.worst_since_off_peg(), 150.0);
+
+        history.record(start + chrono::Duration::seconds(120), -5.0, 20.0);
+        assert!(history.off_peg_since.is_none());
+    }
+}
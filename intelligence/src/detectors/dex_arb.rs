@@ -1,12 +1,14 @@
 //! DEX arbitrage detector
 
 use std::sync::Arc;
+use async_trait::async_trait;
 use chrono::Utc;
 use tracing::info;
 use qenus_dataplane::Chain;
 
 use crate::error::Result;
-use crate::state::MarketState;
+use crate::detectors::{DetectionScope, Detector};
+use crate::state::{ChangeKey, MarketChange, MarketState};
 use crate::types::{Candidate, StrategyConfig};
 
 /// DEX arbitrage detector: Uniswap → Curve → Balancer (same chain)
@@ -26,6 +28,11 @@ impl DexArbDetector {
     
     /// Detect DEX arbitrage opportunities
     pub async fn detect(&self) -> Result<Vec<Candidate>> {
+        self.detect_in(&DetectionScope::all()).await
+    }
+    
+    /// Detect opportunities on assets and chains within `scope`
+    async fn detect_in(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
         if !self.config.enabled {
             return Ok(Vec::new());
         }
@@ -34,21 +41,30 @@ impl DexArbDetector {
         
         // For each approved asset
         for asset in &self.config.approved_assets {
+            if !scope.includes_asset(asset) {
+                continue;
+            }
+            
             // For each approved chain
             for chain in &self.config.approved_chains {
-                // Skip if sequencer is not healthy
-                if !self.market_state.is_sequencer_healthy(*chain).await {
+                if !scope.includes_chain(*chain) {
                     continue;
                 }
                 
-                // Get all AMM pools on this chain
-                let pools = self.market_state.get_amm_pools(*chain).await;
+                // Read the chain as of its last sealed block
+                let Some(view) = self.market_state.block_view(*chain) else {
+                    continue;
+                };
+                let fresh_since = self.market_state.fresh_since();
                 
-                // Find pools with our asset
-                let relevant_pools: Vec<_> = pools.iter()
-                    .filter(|pool| {
-                        pool.token0_symbol == *asset || pool.token1_symbol == *asset
-                    })
+                // Skip if sequencer is not healthy
+                if !view.is_sequencer_healthy(fresh_since) {
+                    continue;
+                }
+                
+                // Find fresh pools with our asset
+                let relevant_pools: Vec<_> = view.pools_trading(asset)
+                    .filter(|pool| pool.last_update >= fresh_since)
                     .collect();
                 
                 // Compare prices across pools
@@ -96,6 +112,8 @@ impl DexArbDetector {
                                         ],
                                         detected_at: Utc::now(),
                                         confidence: 0.9,
+                                        first_seen_at: None,
+                                        details: None,
                                     });
                                 }
                             }
@@ -109,3 +127,31 @@ impl DexArbDetector {
     }
 }
 
+#[async_trait]
+impl Detector for DexArbDetector {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+    
+    async fn detect(&self) -> Result<Vec<Candidate>> {
+        DexArbDetector::detect(self).await
+    }
+    
+    fn is_affected_by(&self, change: &MarketChange) -> bool {
+        if !self.config.approved_chains.contains(&change.chain) {
+            return false;
+        }
+        
+        match &change.key {
+            ChangeKey::Pool { token0, token1, .. } => {
+                self.config.approved_assets.contains(token0) || self.config.approved_assets.contains(token1)
+            }
+            ChangeKey::Sequencer => true,
+            _ => false,
+        }
+    }
+    
+    async fn detect_scoped(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        self.detect_in(scope).await
+    }
+}
@@ -0,0 +1,365 @@
+//! Flash-loan-funded atomic DEX arbitrage detector
+//!
+//! Looks for one pair priced differently in two pools on the same chain.
+//! The cycle borrows the pair's counter asset, buys the candidate asset in
+//! the cheaper pool, sells it in the dearer one and repays the loan, all in
+//! one transaction, so no capital or inventory is held between blocks.
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Utc;
+use tracing::info;
+use qenus_dataplane::Chain;
+
+use crate::error::Result;
+use crate::detectors::{DetectionScope, Detector};
+use crate::simulator::amm::{pool_fee_bps, swap_slippage_bps};
+use crate::simulator::flashloan::{FlashLoanRoute, FlashLoanSimulator};
+use crate::state::{AmmState, ChangeKey, MarketChange, MarketState};
+use crate::types::{Candidate, CandidateDetails, FlashArbDetails, StrategyConfig};
+
+/// Smallest borrow worth a transaction
+const MIN_BORROW_USD: f64 = 1_000.0;
+
+/// Flash-loan-funded arbitrage detector: borrow → buy → sell → repay (same chain)
+pub struct FlashArbDetector {
+    config: StrategyConfig,
+    market_state: Arc<MarketState>,
+    flashloans: FlashLoanSimulator,
+}
+
+impl FlashArbDetector {
+    /// Create a new flash arbitrage detector
+    pub fn new(config: StrategyConfig, market_state: Arc<MarketState>) -> Self {
+        Self {
+            config,
+            flashloans: FlashLoanSimulator::new(market_state.clone()),
+            market_state,
+        }
+    }
+
+    /// Detect flash arbitrage opportunities
+    pub async fn detect(&self) -> Result<Vec<Candidate>> {
+        self.detect_in(&DetectionScope::all()).await
+    }
+
+    /// Detect opportunities on assets and chains within `scope`
+    async fn detect_in(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        if !self.config.enabled {
+            return Ok(Vec::new());
+        }
+
+        let mut candidates = Vec::new();
+
+        for chain in &self.config.approved_chains {
+            if !scope.includes_chain(*chain) {
+                continue;
+            }
+
+            // Read the chain as of its last sealed block
+            let Some(view) = self.market_state.block_view(*chain) else {
+                continue;
+            };
+            let fresh_since = self.market_state.fresh_since();
+
+            // Skip if sequencer is not healthy
+            if !view.is_sequencer_healthy(fresh_since) {
+                continue;
+            }
+
+            for asset in &self.config.approved_assets {
+                let relevant_pools: Vec<_> = view.fresh_pools(fresh_since)
+                    .filter(|pool| counter_asset(pool, asset).is_some())
+                    .collect();
+
+                for i in 0..relevant_pools.len() {
+                    for j in (i + 1)..relevant_pools.len() {
+                        let (pool_a, pool_b) = (relevant_pools[i], relevant_pools[j]);
+
+                        // A flash loan change scopes on the borrowed asset, not the traded one
+                        let borrow_asset = counter_asset(pool_a, asset).unwrap_or_default();
+                        if !scope.includes_asset(asset) && !scope.includes_asset(borrow_asset) {
+                            continue;
+                        }
+
+                        if let Some(candidate) = self.candidate_for(*chain, asset, pool_a, pool_b).await {
+                            candidates.push(candidate);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// Candidate for the price gap between two pools of the same pair, if it survives costs
+    async fn candidate_for(&self, chain: Chain, asset: &str, pool_a: &AmmState, pool_b: &AmmState) -> Option<Candidate> {
+        let borrow_asset = counter_asset(pool_a, asset)?;
+        if counter_asset(pool_b, asset) != Some(borrow_asset) || pool_a.pool_address == pool_b.pool_address {
+            return None;
+        }
+
+        let price_a = asset_price(pool_a, asset)?;
+        let price_b = asset_price(pool_b, asset)?;
+        let (buy_pool, buy_price, sell_pool, sell_price) = if price_a <= price_b {
+            (pool_a, price_a, pool_b, price_b)
+        } else {
+            (pool_b, price_b, pool_a, price_a)
+        };
+
+        let gross_spread_bps = (sell_price - buy_price) / buy_price * 10000.0;
+        let swap_fees_bps = pool_fee_bps(buy_pool) + pool_fee_bps(sell_pool);
+        if gross_spread_bps - swap_fees_bps < self.config.min_profit_bps {
+            return None;
+        }
+
+        let (size_usd, route, net_spread_bps) = self.size_borrow(chain, asset, borrow_asset, buy_pool, sell_pool, gross_spread_bps).await?;
+
+        info!(
+            "Flash arb: {} {:?} borrow {:.0} {} from {} buy@{} sell@{} spread={:.2}bps net={:.2}bps",
+            asset, chain, size_usd, borrow_asset, route.provider, buy_pool.pool_type,
+            sell_pool.pool_type, gross_spread_bps, net_spread_bps
+        );
+
+        Some(Candidate {
+            strategy: "flash_arb".to_string(),
+            asset: asset.to_string(),
+            spread_bps: net_spread_bps,
+            legs: vec![
+                (format!("{} on {:?}", route.provider, chain), "flash_loan".to_string()),
+                (format!("{} on {:?}", buy_pool.pool_type, chain), "buy".to_string()),
+                (format!("{} on {:?}", sell_pool.pool_type, chain), "sell".to_string()),
+                (format!("{} on {:?}", route.provider, chain), "flash_repay".to_string()),
+            ],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: Some(CandidateDetails::FlashArb(FlashArbDetails {
+                chain,
+                borrow_asset: borrow_asset.to_string(),
+                buy_pool: buy_pool.pool_address.clone(),
+                sell_pool: sell_pool.pool_address.clone(),
+                gross_spread_bps,
+                size_usd,
+            })),
+        })
+    }
+
+    /// Largest borrow, halving from the position cap, whose net spread clears the threshold
+    async fn size_borrow(
+        &self,
+        chain: Chain,
+        asset: &str,
+        borrow_asset: &str,
+        buy_pool: &AmmState,
+        sell_pool: &AmmState,
+        gross_spread_bps: f64,
+    ) -> Option<(f64, FlashLoanRoute, f64)> {
+        let swap_fees_bps = pool_fee_bps(buy_pool) + pool_fee_bps(sell_pool);
+        let mut size_usd = self.config.max_position_usd;
+
+        while size_usd >= MIN_BORROW_USD {
+            if let Some(route) = self.flashloans.best_route(chain, borrow_asset, size_usd).await {
+                let net_spread_bps = gross_spread_bps
+                    - swap_fees_bps
+                    - swap_slippage_bps(buy_pool, borrow_asset, size_usd)
+                    - swap_slippage_bps(sell_pool, asset, size_usd)
+                    - route.fee_bps as f64;
+
+                if net_spread_bps >= self.config.min_profit_bps {
+                    return Some((size_usd, route, net_spread_bps));
+                }
+            }
+
+            size_usd /= 2.0;
+        }
+
+        None
+    }
+}
+
+/// The other token of a pool trading `asset`
+fn counter_asset<'a>(pool: &'a AmmState, asset: &str) -> Option<&'a str> {
+    if pool.token0_symbol == asset {
+        Some(&pool.token1_symbol)
+    } else if pool.token1_symbol == asset {
+        Some(&pool.token0_symbol)
+    } else {
+        None
+    }
+}
+
+/// Price of `asset` in the pool's counter asset (mid_price quotes token0 in token1)
+fn asset_price(pool: &AmmState, asset: &str) -> Option<f64> {
+    let price = if pool.token0_symbol == asset { pool.mid_price } else { 1.0 / pool.mid_price };
+    (price.is_finite() && price > 0.0).then_some(price)
+}
+
+#[async_trait]
+impl Detector for FlashArbDetector {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn detect(&self) -> Result<Vec<Candidate>> {
+        FlashArbDetector::detect(self).await
+    }
+
+    fn is_affected_by(&self, change: &MarketChange) -> bool {
+        if !self.config.approved_chains.contains(&change.chain) {
+            return false;
+        }
+
+        match &change.key {
+            ChangeKey::Pool { token0, token1, .. } => {
+                self.config.approved_assets.contains(token0) || self.config.approved_assets.contains(token1)
+            }
+            ChangeKey::FlashLoan { .. } | ChangeKey::Sequencer => true,
+            _ => false,
+        }
+    }
+
+    async fn detect_scoped(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        self.detect_in(scope).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use qenus_dataplane::{
+        AmmFeature, DepthCurve, Feature, FeatureData, FeatureType, FlashLoanFeature, SequencerHealthFeature,
+        SequencerStatus, SlippageInfo, TokenInfo,
+    };
+    use crate::types::RiskLimits;
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            name: "flash_arb".to_string(),
+            enabled: true,
+            min_profit_usd: 50.0,
+            min_profit_bps: 5.0,
+            max_position_usd: 2_000_000.0,
+            approved_assets: vec!["WETH".to_string()],
+            approved_chains: vec![Chain::Ethereum],
+            risk_limits: RiskLimits::default(),
+            budget: Default::default(),
+        }
+    }
+
+    fn token(symbol: &str) -> TokenInfo {
+        TokenInfo { address: String::new(), symbol: symbol.to_string(), decimals: 18 }
+    }
+
+    fn pool(address: &str, pool_type: &str, mid_price: f64, slippage_1m: f64) -> Feature {
+        let sizes = [("1m".to_string(), SlippageInfo { slippage_bps: slippage_1m, price_impact: slippage_1m / 10000.0 })];
+        Feature::new(
+            1,
+            Chain::Ethereum,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: address.to_string(),
+                pool_type: pool_type.to_string(),
+                pool_id: None,
+                token0: token("WETH"),
+                token1: token("USDC"),
+                fee_tier: Some(500),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve { sizes: sizes.into_iter().collect::<HashMap<_, _>>(), ..Default::default() },
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        )
+    }
+
+    fn flash_loan(provider: &str, fee_bps: u32, liquidity: &str) -> Feature {
+        Feature::new(
+            1,
+            Chain::Ethereum,
+            FeatureType::FlashLoan,
+            FeatureData::FlashLoan(FlashLoanFeature {
+                provider: provider.to_string(),
+                provider_address: String::new(),
+                asset: token("USDC"),
+                available_liquidity: liquidity.to_string(),
+                fee_bps,
+                max_loan_amount: liquidity.to_string(),
+                is_active: true,
+            }),
+            "test".to_string(),
+        )
+    }
+
+    fn healthy_sequencer() -> Feature {
+        Feature::new(
+            1,
+            Chain::Ethereum,
+            FeatureType::SequencerHealth,
+            FeatureData::SequencerHealth(SequencerHealthFeature {
+                sequencer_address: String::new(),
+                status: SequencerStatus::Healthy,
+                block_interval_avg: 12.0,
+                block_interval_variance: 0.0,
+                uptime_percentage: 100.0,
+                last_block_time: Utc::now(),
+                pending_tx_count: 0,
+            }),
+            "test".to_string(),
+        )
+    }
+
+    async fn market(features: Vec<Feature>) -> Arc<MarketState> {
+        let market_state = Arc::new(MarketState::new(30));
+        for feature in features {
+            market_state.ingest_feature(feature).await.unwrap();
+        }
+        market_state.end_block(Chain::Ethereum, 1);
+        market_state
+    }
+
+    #[tokio::test]
+    async fn test_borrows_from_cheapest_route_and_buys_low() {
+        let market_state = market(vec![
+            healthy_sequencer(),
+            pool("0xuni", "uniswap_v3", 2000.0, 20.0),
+            pool("0xcurve", "curve", 2010.0, 20.0),
+            flash_loan("aave_v3", 5, "50000000"),
+            flash_loan("balancer", 0, "800000"),
+        ]).await;
+
+        let candidates = FlashArbDetector::new(test_config(), market_state).detect().await.unwrap();
+        assert_eq!(candidates.len(), 1);
+
+        let candidate = &candidates[0];
+        assert!(candidate.is_atomic());
+        assert_eq!(candidate.legs[0].0, "balancer on Ethereum");
+        assert_eq!(candidate.legs[1].0, "uniswap_v3 on Ethereum");
+
+        let Some(CandidateDetails::FlashArb(details)) = &candidate.details else { panic!("expected flash arb details") };
+        assert_eq!(details.borrow_asset, "USDC");
+        assert_eq!(details.buy_pool, "0xuni");
+        // Balancer is free but only lends 800k: the borrow halves until it fits
+        assert_eq!(details.size_usd, 500_000.0);
+        assert!((details.gross_spread_bps - 50.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_no_candidate_without_flash_liquidity_or_sequencer() {
+        let pools = || vec![pool("0xuni", "uniswap_v3", 2000.0, 10.0), pool("0xcurve", "curve", 2010.0, 10.0)];
+
+        let mut features = pools();
+        features.push(healthy_sequencer());
+        let detector = FlashArbDetector::new(test_config(), market(features).await);
+        assert!(detector.detect().await.unwrap().is_empty());
+
+        let mut features = pools();
+        features.push(flash_loan("aave_v3", 5, "50000000"));
+        let detector = FlashArbDetector::new(test_config(), market(features).await);
+        assert!(detector.detect().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,743 @@
+//! Aave V3 liquidation detector
+//!
+//! Tracks borrower positions from Aave V3 Pool events (Supply, Withdraw,
+//! Borrow, Repay, LiquidationCall) and flags positions whose health factor
+//! has dropped below 1.0. Balances are tracked as event principal, so interest
+//! accrued since the last event is not included.
+//!
+//! Events, reserve configurations and oracle prices arrive as dataplane
+//! lending features; each detection pass first applies the ones MarketState
+//! received since the last pass.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use qenus_dataplane::{Chain, LendingFeature, Log};
+
+use crate::error::{IntelligenceError, Result};
+use crate::detectors::{DetectionScope, Detector};
+use crate::state::{ChangeKey, MarketChange, MarketState};
+use crate::types::{Candidate, CandidateDetails, LiquidationDetails, StrategyConfig};
+
+/// Supply(address,address,address,uint256,uint16)
+pub const SUPPLY_TOPIC: &str = "0x2b627736bca15cd5381dcf80b0bf11fd197d01a037c52b927a881a10fb73ba61";
+
+/// Withdraw(address,address,address,uint256)
+pub const WITHDRAW_TOPIC: &str = "0x3115d1449a7b732c986cba18244e897a450f61e1bb8d589cd2e69e6c8924f9f7";
+
+/// Borrow(address,address,address,uint256,uint8,uint256,uint16)
+pub const BORROW_TOPIC: &str = "0xb3d084820fb1a9decffb176436bd02558d15fac9b0ddfed8c465bc7359d7dce0";
+
+/// Repay(address,address,address,uint256,bool)
+pub const REPAY_TOPIC: &str = "0xa534c8dbe71f871f9f3530e97a74601fea17b426cae02e1c5aee42c96c784051";
+
+/// LiquidationCall(address,address,address,uint256,uint256,address,bool)
+pub const LIQUIDATION_CALL_TOPIC: &str = "0xe413a321e8681d831f4dbccbca790d2952b56f977908e45be37335533e005286";
+
+/// Health factor below which the full debt can be liquidated (Aave V3 CLOSE_FACTOR_HF_THRESHOLD)
+const CLOSE_FACTOR_HF_THRESHOLD: f64 = 0.95;
+
+/// Share of debt liquidatable above the close factor threshold
+const DEFAULT_CLOSE_FACTOR: f64 = 0.5;
+
+/// Aave V3 reserve configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AaveReserveConfig {
+    /// Underlying asset address
+    pub address: String,
+
+    /// Asset symbol (matches MarketState symbols)
+    pub symbol: String,
+
+    /// Token decimals
+    pub decimals: u8,
+
+    /// Loan-to-value in basis points
+    pub ltv_bps: u32,
+
+    /// Liquidation threshold in basis points
+    pub liquidation_threshold_bps: u32,
+
+    /// Liquidation bonus above par in basis points (500 = 5%)
+    pub liquidation_bonus_bps: u32,
+}
+
+impl AaveReserveConfig {
+    /// Decode from the `configuration` bitmap returned by `getReserveData`
+    pub fn from_configuration(address: &str, symbol: &str, configuration: u128) -> Self {
+        let bits = |offset: u32, width: u32| ((configuration >> offset) & ((1u128 << width) - 1)) as u32;
+
+        Self {
+            address: address.to_lowercase(),
+            symbol: symbol.to_string(),
+            decimals: bits(48, 8) as u8,
+            ltv_bps: bits(0, 16),
+            liquidation_threshold_bps: bits(16, 16),
+            // Aave stores the bonus as 10000 + bonus
+            liquidation_bonus_bps: bits(32, 16).saturating_sub(10_000),
+        }
+    }
+}
+
+/// Decoded Aave V3 Pool event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AavePoolEvent {
+    Supply { reserve: String, on_behalf_of: String, amount: u128 },
+    Withdraw { reserve: String, user: String, amount: u128 },
+    Borrow { reserve: String, on_behalf_of: String, amount: u128 },
+    Repay { reserve: String, user: String, amount: u128, use_a_tokens: bool },
+    LiquidationCall {
+        collateral_asset: String,
+        debt_asset: String,
+        user: String,
+        debt_to_cover: u128,
+        liquidated_collateral_amount: u128,
+    },
+}
+
+impl AavePoolEvent {
+    /// Decode a raw log; returns `Ok(None)` for unrelated events
+    pub fn from_log(log: &Log) -> Result<Option<Self>> {
+        let topic0 = match log.topics.first() {
+            Some(topic) => topic.to_lowercase(),
+            None => return Ok(None),
+        };
+
+        let event = match topic0.as_str() {
+            SUPPLY_TOPIC => Self::Supply {
+                reserve: topic_address(log, 1)?,
+                on_behalf_of: topic_address(log, 2)?,
+                amount: data_word(log, 1)?,
+            },
+            WITHDRAW_TOPIC => Self::Withdraw {
+                reserve: topic_address(log, 1)?,
+                user: topic_address(log, 2)?,
+                amount: data_word(log, 0)?,
+            },
+            BORROW_TOPIC => Self::Borrow {
+                reserve: topic_address(log, 1)?,
+                on_behalf_of: topic_address(log, 2)?,
+                amount: data_word(log, 1)?,
+            },
+            REPAY_TOPIC => Self::Repay {
+                reserve: topic_address(log, 1)?,
+                user: topic_address(log, 2)?,
+                amount: data_word(log, 0)?,
+                use_a_tokens: data_word(log, 1)? != 0,
+            },
+            LIQUIDATION_CALL_TOPIC => Self::LiquidationCall {
+                collateral_asset: topic_address(log, 1)?,
+                debt_asset: topic_address(log, 2)?,
+                user: topic_address(log, 3)?,
+                debt_to_cover: data_word(log, 0)?,
+                liquidated_collateral_amount: data_word(log, 1)?,
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// Read an indexed address topic
+fn topic_address(log: &Log, index: usize) -> Result<String> {
+    let topic = log.topics.get(index)
+        .ok_or_else(|| IntelligenceError::detection(format!("Aave log missing topic {}", index)))?;
+    let hex = topic.trim_start_matches("0x");
+
+    if hex.len() != 64 {
+        return Err(IntelligenceError::detection(format!("Malformed topic: {}", topic)));
+    }
+
+    Ok(format!("0x{}", hex[24..].to_lowercase()))
+}
+
+/// Read a 32-byte data word as an unsigned integer
+fn data_word(log: &Log, index: usize) -> Result<u128> {
+    let hex = log.data.trim_start_matches("0x");
+    let word = hex.get(index * 64..(index + 1) * 64)
+        .ok_or_else(|| IntelligenceError::detection(format!("Aave log missing data word {}", index)))?;
+
+    // Amounts above u128 are not realistic token balances
+    if word[..32].chars().any(|c| c != '0') {
+        return Err(IntelligenceError::detection(format!("Data word {} overflows u128", index)));
+    }
+
+    u128::from_str_radix(&word[32..], 16)
+        .map_err(|e| IntelligenceError::detection(format!("Malformed data word: {}", e)))
+}
+
+/// Collateral and debt balances of a borrower, keyed by reserve address
+#[derive(Debug, Clone, Default)]
+struct BorrowerPosition {
+    collateral: HashMap<String, u128>,
+    debt: HashMap<String, u128>,
+}
+
+impl BorrowerPosition {
+    fn add(balances: &mut HashMap<String, u128>, reserve: &str, amount: u128) {
+        *balances.entry(reserve.to_string()).or_insert(0) += amount;
+    }
+
+    fn sub(balances: &mut HashMap<String, u128>, reserve: &str, amount: u128) {
+        if let Some(balance) = balances.get_mut(reserve) {
+            *balance = balance.saturating_sub(amount);
+            if *balance == 0 {
+                balances.remove(reserve);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.collateral.is_empty() && self.debt.is_empty()
+    }
+}
+
+/// Position valued in USD
+struct ValuedPosition {
+    health_factor: f64,
+    collateral: Vec<(AaveReserveConfig, f64)>,
+    debt: Vec<(AaveReserveConfig, f64)>,
+}
+
+/// Aave V3 liquidation detector: flash loan → liquidationCall → swap → repay
+pub struct LiquidationDetector {
+    config: StrategyConfig,
+    market_state: Arc<MarketState>,
+
+    /// Positions by (chain, borrower)
+    positions: RwLock<HashMap<(Chain, String), BorrowerPosition>>,
+
+    /// Reserve configuration by (chain, reserve address)
+    reserves: RwLock<HashMap<(Chain, String), AaveReserveConfig>>,
+
+    /// Aave oracle prices in USD by (chain, reserve address)
+    oracle_prices: RwLock<HashMap<(Chain, String), f64>>,
+
+    /// Next MarketState lending log entry to apply, per chain
+    lending_cursors: RwLock<HashMap<Chain, u64>>,
+}
+
+impl LiquidationDetector {
+    /// Create a new liquidation detector
+    pub fn new(config: StrategyConfig, market_state: Arc<MarketState>) -> Self {
+        Self {
+            config,
+            market_state,
+            positions: RwLock::new(HashMap::new()),
+            reserves: RwLock::new(HashMap::new()),
+            oracle_prices: RwLock::new(HashMap::new()),
+            lending_cursors: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Register or update a reserve's configuration
+    pub async fn register_reserve(&self, chain: Chain, reserve: AaveReserveConfig) {
+        let mut reserves = self.reserves.write().await;
+        reserves.insert((chain, reserve.address.to_lowercase()), reserve);
+    }
+
+    /// Update the Aave oracle price of a reserve
+    pub async fn update_oracle_price(&self, chain: Chain, reserve: &str, price_usd: f64) {
+        let mut prices = self.oracle_prices.write().await;
+        prices.insert((chain, reserve.to_lowercase()), price_usd);
+    }
+
+    /// Apply the lending features MarketState received since the last call
+    async fn sync_lending(&self) {
+        // Held throughout so concurrent passes apply each feature once
+        let mut cursors = self.lending_cursors.write().await;
+
+        for chain in &self.config.approved_chains {
+            let cursor = cursors.get(chain).copied().unwrap_or(0);
+            let (features, next) = self.market_state.lending_since(*chain, cursor);
+            let missed = next.saturating_sub(cursor).saturating_sub(features.len() as u64);
+            if missed > 0 {
+                warn!("Liquidation detector missed {} lending updates on {:?}; positions may be incomplete", missed, chain);
+            }
+
+            for feature in features.iter().filter(|feature| feature.protocol == "aave_v3") {
+                self.apply_lending(*chain, feature).await;
+            }
+            cursors.insert(*chain, next);
+        }
+    }
+
+    /// Register a lending feature's reserves and prices, then its events
+    async fn apply_lending(&self, chain: Chain, feature: &LendingFeature) {
+        for reserve in &feature.reserves {
+            let configuration = reserve.configuration.trim_start_matches("0x");
+            // Fields past bit 128 are not used here
+            let low_bits = configuration.get(configuration.len().saturating_sub(32)..).unwrap_or_default();
+            match u128::from_str_radix(low_bits, 16) {
+                Ok(bits) => {
+                    let config = AaveReserveConfig::from_configuration(&reserve.token.address, &reserve.token.symbol, bits);
+                    self.register_reserve(chain, config).await;
+                }
+                Err(_) => warn!("Malformed configuration for reserve {}: {}", reserve.token.symbol, reserve.configuration),
+            }
+
+            if let Some(price) = reserve.oracle_price_usd {
+                self.update_oracle_price(chain, &reserve.token.address, price).await;
+            }
+        }
+
+        for log in &feature.events {
+            if let Err(e) = self.ingest_log(chain, log).await {
+                warn!("Skipping Aave log {}#{}: {}", log.transaction_hash, log.log_index, e);
+            }
+        }
+    }
+
+    /// Ingest a raw Pool log; returns whether it was an Aave position event
+    pub async fn ingest_log(&self, chain: Chain, log: &Log) -> Result<bool> {
+        if log.removed {
+            // Reorged logs are re-delivered by the observer; ignore the removal
+            return Ok(false);
+        }
+
+        match AavePoolEvent::from_log(log)? {
+            Some(event) => {
+                self.ingest_event(chain, event).await;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Apply a decoded Pool event to the position book
+    pub async fn ingest_event(&self, chain: Chain, event: AavePoolEvent) {
+        let mut positions = self.positions.write().await;
+
+        let user = match &event {
+            AavePoolEvent::Supply { on_behalf_of, .. } | AavePoolEvent::Borrow { on_behalf_of, .. } => on_behalf_of,
+            AavePoolEvent::Withdraw { user, .. } | AavePoolEvent::Repay { user, .. } |
+            AavePoolEvent::LiquidationCall { user, .. } => user,
+        }.to_lowercase();
+
+        let key = (chain, user);
+        let position = positions.entry(key.clone()).or_default();
+
+        match &event {
+            AavePoolEvent::Supply { reserve, amount, .. } => {
+                BorrowerPosition::add(&mut position.collateral, &reserve.to_lowercase(), *amount);
+            }
+            AavePoolEvent::Withdraw { reserve, amount, .. } => {
+                BorrowerPosition::sub(&mut position.collateral, &reserve.to_lowercase(), *amount);
+            }
+            AavePoolEvent::Borrow { reserve, amount, .. } => {
+                BorrowerPosition::add(&mut position.debt, &reserve.to_lowercase(), *amount);
+            }
+            AavePoolEvent::Repay { reserve, amount, use_a_tokens, .. } => {
+                BorrowerPosition::sub(&mut position.debt, &reserve.to_lowercase(), *amount);
+                if *use_a_tokens {
+                    BorrowerPosition::sub(&mut position.collateral, &reserve.to_lowercase(), *amount);
+                }
+            }
+            AavePoolEvent::LiquidationCall { collateral_asset, debt_asset, debt_to_cover, liquidated_collateral_amount, .. } => {
+                BorrowerPosition::sub(&mut position.debt, &debt_asset.to_lowercase(), *debt_to_cover);
+                BorrowerPosition::sub(&mut position.collateral, &collateral_asset.to_lowercase(), *liquidated_collateral_amount);
+            }
+        }
+
+        if position.is_empty() {
+            positions.remove(&key);
+        }
+    }
+
+    /// Number of tracked borrower positions
+    pub async fn position_count(&self) -> usize {
+        self.positions.read().await.len()
+    }
+
+    /// Health factor of a borrower (None without debt or prices)
+    pub async fn health_factor(&self, chain: Chain, user: &str) -> Option<f64> {
+        let position = self.positions.read().await.get(&(chain, user.to_lowercase())).cloned()?;
+        self.value_position(chain, &position).await.map(|valued| valued.health_factor)
+    }
+
+    /// Detect liquidatable positions
+    pub async fn detect(&self) -> Result<Vec<Candidate>> {
+        self.detect_in(&DetectionScope::all()).await
+    }
+
+    /// Detect liquidatable positions on chains within `scope`
+    async fn detect_in(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        if !self.config.enabled {
+            return Ok(Vec::new());
+        }
+
+        self.sync_lending().await;
+
+        let positions: Vec<_> = {
+            let positions = self.positions.read().await;
+            positions.iter()
+                .filter(|((chain, _), _)| self.config.approved_chains.contains(chain) && scope.includes_chain(*chain))
+                .map(|(key, position)| (key.clone(), position.clone()))
+                .collect()
+        };
+
+        let mut candidates = Vec::new();
+
+        for ((chain, user), position) in positions {
+            // L1 has no sequencer feed
+            if chain != Chain::Ethereum && !self.market_state.block_view(chain)
+                .is_some_and(|view| view.is_sequencer_healthy(self.market_state.fresh_since()))
+            {
+                continue;
+            }
+
+            let valued = match self.value_position(chain, &position).await {
+                Some(valued) if valued.health_factor < 1.0 => valued,
+                _ => continue,
+            };
+
+            if let Some(candidate) = self.build_candidate(chain, &user, valued) {
+                candidates.push(candidate);
+            }
+        }
+
+        if !candidates.is_empty() {
+            info!("Liquidation detector found {} candidates", candidates.len());
+        }
+
+        Ok(candidates)
+    }
+
+    /// Pick the largest debt/collateral pair and size the liquidation
+    fn build_candidate(&self, chain: Chain, user: &str, valued: ValuedPosition) -> Option<Candidate> {
+        let largest = |entries: &[(AaveReserveConfig, f64)]| {
+            entries.iter()
+                .filter(|(reserve, _)| self.config.approved_assets.contains(&reserve.symbol))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .cloned()
+        };
+
+        let (debt_reserve, debt_usd) = largest(&valued.debt)?;
+        let (collateral_reserve, collateral_usd) = largest(&valued.collateral)?;
+
+        let close_factor = if valued.health_factor > CLOSE_FACTOR_HF_THRESHOLD {
+            DEFAULT_CLOSE_FACTOR
+        } else {
+            1.0
+        };
+
+        let bonus = 1.0 + collateral_reserve.liquidation_bonus_bps as f64 / 10000.0;
+        let mut debt_to_cover_usd = (debt_usd * close_factor).min(self.config.max_position_usd);
+
+        // Seized collateral cannot exceed what the borrower holds
+        if debt_to_cover_usd * bonus > collateral_usd {
+            debt_to_cover_usd = collateral_usd / bonus;
+        }
+        let collateral_seized_usd = debt_to_cover_usd * bonus;
+
+        let spread_bps = collateral_reserve.liquidation_bonus_bps as f64;
+        if spread_bps < self.config.min_profit_bps || debt_to_cover_usd <= 0.0 {
+            return None;
+        }
+
+        debug!(
+            "Liquidatable position {} on {:?}: hf={:.4}, debt={} ${:.0}, collateral={} ${:.0}",
+            user, chain, valued.health_factor, debt_reserve.symbol, debt_to_cover_usd,
+            collateral_reserve.symbol, collateral_seized_usd
+        );
+
+        let domain = format!("{:?}", chain);
+
+        Some(Candidate {
+            strategy: "aave_liquidation".to_string(),
+            asset: debt_reserve.symbol.clone(),
+            spread_bps,
+            legs: vec![
+                (domain.clone(), "flash_loan".to_string()),
+                (domain.clone(), "liquidate".to_string()),
+                (domain.clone(), "swap".to_string()),
+                (domain, "flash_repay".to_string()),
+            ],
+            detected_at: Utc::now(),
+            // Deeper underwater positions are less likely to be taken first
+            confidence: if valued.health_factor < CLOSE_FACTOR_HF_THRESHOLD { 0.85 } else { 0.8 },
+            first_seen_at: None,
+            details: Some(CandidateDetails::Liquidation(LiquidationDetails {
+                chain,
+                borrower: user.to_string(),
+                debt_asset: debt_reserve.symbol,
+                collateral_asset: collateral_reserve.symbol,
+                health_factor: valued.health_factor,
+                debt_to_cover_usd,
+                collateral_seized_usd,
+                liquidation_bonus_bps: collateral_reserve.liquidation_bonus_bps,
+            })),
+        })
+    }
+
+    /// Value a position in USD and compute its health factor
+    ///
+    /// Returns None if the position has no debt or any reserve is unpriced.
+    async fn value_position(&self, chain: Chain, position: &BorrowerPosition) -> Option<ValuedPosition> {
+        if position.debt.is_empty() {
+            return None;
+        }
+
+        let mut collateral = Vec::new();
+        let mut debt = Vec::new();
+        let mut weighted_collateral_usd = 0.0;
+        let mut total_debt_usd = 0.0;
+
+        for (reserve, amount) in &position.collateral {
+            let (config, value_usd) = self.value_balance(chain, reserve, *amount).await?;
+            weighted_collateral_usd += value_usd * config.liquidation_threshold_bps as f64 / 10000.0;
+            collateral.push((config, value_usd));
+        }
+
+        for (reserve, amount) in &position.debt {
+            let (config, value_usd) = self.value_balance(chain, reserve, *amount).await?;
+            total_debt_usd += value_usd;
+            debt.push((config, value_usd));
+        }
+
+        if total_debt_usd <= 0.0 {
+            return None;
+        }
+
+        Some(ValuedPosition {
+            health_factor: weighted_collateral_usd / total_debt_usd,
+            collateral,
+            debt,
+        })
+    }
+
+    /// Value a reserve balance, preferring the Aave oracle over AMM prices
+    async fn value_balance(&self, chain: Chain, reserve: &str, amount: u128) -> Option<(AaveReserveConfig, f64)> {
+        let config = self.reserves.read().await.get(&(chain, reserve.to_string())).cloned()?;
+
+        let oracle_price = self.oracle_prices.read().await.get(&(chain, reserve.to_string())).copied();
+        let price = match oracle_price {
+            Some(price) => price,
+            None => self.market_state.block_view(chain)?
+                .price(&config.symbol, self.market_state.fresh_since())?,
+        };
+
+        let units = amount as f64 / 10f64.powi(config.decimals as i32);
+        Some((config, units * price))
+    }
+}
+
+#[async_trait]
+impl Detector for LiquidationDetector {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn detect(&self) -> Result<Vec<Candidate>> {
+        LiquidationDetector::detect(self).await
+    }
+
+    fn is_affected_by(&self, change: &MarketChange) -> bool {
+        if !self.config.approved_chains.contains(&change.chain) {
+            return false;
+        }
+
+        // Pool prices back up missing oracle prices
+        match &change.key {
+            ChangeKey::Pool { token0, token1, .. } => {
+                self.config.approved_assets.contains(token0) || self.config.approved_assets.contains(token1)
+            }
+            ChangeKey::Sequencer | ChangeKey::Lending { .. } => true,
+            _ => false,
+        }
+    }
+
+    async fn detect_scoped(&self, scope: &DetectionScope) -> Result<Vec<Candidate>> {
+        self.detect_in(scope).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RiskLimits;
+
+    const WETH: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+    const BORROWER: &str = "0x00000000000000000000000000000000000000b0";
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            name: "aave_liquidation".to_string(),
+            enabled: true,
+            min_profit_usd: 100.0,
+            min_profit_bps: 10.0,
+            max_position_usd: 1_000_000.0,
+            approved_assets: vec!["WETH".to_string(), "USDC".to_string()],
+            approved_chains: vec![Chain::Ethereum],
+            risk_limits: RiskLimits::default(),
+            budget: Default::default(),
+        }
+    }
+
+    async fn test_detector() -> LiquidationDetector {
+        let detector = LiquidationDetector::new(test_config(), Arc::new(MarketState::new(30)));
+
+        detector.register_reserve(Chain::Ethereum, AaveReserveConfig {
+            address: WETH.to_string(),
+            symbol: "WETH".to_string(),
+            decimals: 18,
+            ltv_bps: 8000,
+            liquidation_threshold_bps: 8250,
+            liquidation_bonus_bps: 500,
+        }).await;
+        detector.register_reserve(Chain::Ethereum, AaveReserveConfig {
+            address: USDC.to_string(),
+            symbol: "USDC".to_string(),
+            decimals: 6,
+            ltv_bps: 7700,
+            liquidation_threshold_bps: 8000,
+            liquidation_bonus_bps: 450,
+        }).await;
+        detector.update_oracle_price(Chain::Ethereum, USDC, 1.0).await;
+
+        detector
+    }
+
+    fn word(value: u128) -> String {
+        format!("{:064x}", value)
+    }
+
+    fn padded(address: &str) -> String {
+        format!("0x{:0>64}", address.trim_start_matches("0x"))
+    }
+
+    #[test]
+    fn test_reserve_configuration_decoding() {
+        // LTV 80.00%, LT 82.50%, bonus 105.00%, 18 decimals
+        let configuration: u128 = 8000 | (8250 << 16) | (10500 << 32) | (18 << 48);
+        let reserve = AaveReserveConfig::from_configuration(WETH, "WETH", configuration);
+
+        assert_eq!(reserve.ltv_bps, 8000);
+        assert_eq!(reserve.liquidation_threshold_bps, 8250);
+        assert_eq!(reserve.liquidation_bonus_bps, 500);
+        assert_eq!(reserve.decimals, 18);
+    }
+
+    #[test]
+    fn test_borrow_log_decoding() {
+        let log = Log {
+            address: "0x87870bca3f3fd6335c3f4ce8392d69350b4fa4e2".to_string(),
+            topics: vec![
+                BORROW_TOPIC.to_string(),
+                padded(USDC),
+                padded(BORROWER),
+                padded("0x0"),
+            ],
+            data: format!("0x{}{}{}{}", word(0xabc), word(5_000_000_000), word(2), word(0)),
+            block_number: 1,
+            transaction_hash: "0x1".to_string(),
+            transaction_index: 0,
+            log_index: 0,
+            removed: false,
+        };
+
+        let event = AavePoolEvent::from_log(&log).unwrap().unwrap();
+        assert_eq!(event, AavePoolEvent::Borrow {
+            reserve: USDC.to_string(),
+            on_behalf_of: BORROWER.to_string(),
+            amount: 5_000_000_000,
+        });
+    }
+
+    #[tokio::test]
+    async fn test_detects_unhealthy_position() {
+        let detector = test_detector().await;
+
+        // 10 WETH collateral, 25k USDC debt
+        detector.ingest_event(Chain::Ethereum, AavePoolEvent::Supply {
+            reserve: WETH.to_string(),
+            on_behalf_of: BORROWER.to_string(),
+            amount: 10 * 10u128.pow(18),
+        }).await;
+        detector.ingest_event(Chain::Ethereum, AavePoolEvent::Borrow {
+            reserve: USDC.to_string(),
+            on_behalf_of: BORROWER.to_string(),
+            amount: 25_000 * 10u128.pow(6),
+        }).await;
+
+        // Healthy at $3500: 35k * 0.825 / 25k = 1.155
+        detector.update_oracle_price(Chain::Ethereum, WETH, 3500.0).await;
+        let hf = detector.health_factor(Chain::Ethereum, BORROWER).await.unwrap();
+        assert!(hf > 1.0);
+        assert!(detector.detect().await.unwrap().is_empty());
+
+        // Price drop pushes HF to 0.957: half the debt can be covered
+        detector.update_oracle_price(Chain::Ethereum, WETH, 2900.0).await;
+        let candidates = detector.detect().await.unwrap();
+        assert_eq!(candidates.len(), 1);
+
+        match &candidates[0].details {
+            Some(CandidateDetails::Liquidation(details)) => {
+                assert!((details.debt_to_cover_usd - 12_500.0).abs() < 1e-6);
+                assert!((details.collateral_seized_usd - 13_125.0).abs() < 1e-6);
+                assert_eq!(details.collateral_asset, "WETH");
+            }
+            other => panic!("unexpected details: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detects_from_ingested_lending_features() {
+        use qenus_dataplane::{Feature, FeatureData, FeatureType, LendingReserve, TokenInfo};
+
+        let market_state = Arc::new(MarketState::new(30));
+        let detector = LiquidationDetector::new(test_config(), market_state.clone());
+
+        let position_log = |topic: &str, reserve: &str, amount: u128| Log {
+            address: "0x87870bca3f3fd6335c3f4ce8392d69350b4fa4e2".to_string(),
+            topics: vec![topic.to_string(), padded(reserve), padded(BORROWER), padded("0x0")],
+            data: format!("0x{}{}{}{}", word(0xabc), word(amount), word(2), word(0)),
+            block_number: 1,
+            transaction_hash: "0x1".to_string(),
+            transaction_index: 0,
+            log_index: 0,
+            removed: false,
+        };
+        let reserve = |address: &str, symbol: &str, configuration: u128, price: f64| LendingReserve {
+            token: TokenInfo { address: address.to_string(), symbol: symbol.to_string(), decimals: 0 },
+            configuration: format!("{:#x}", configuration),
+            oracle_price_usd: Some(price),
+        };
+        let lending = |block: u64, events: Vec<Log>, weth_price: f64| Feature::new(
+            block,
+            Chain::Ethereum,
+            FeatureType::Lending,
+            FeatureData::Lending(LendingFeature {
+                protocol: "aave_v3".to_string(),
+                pool_address: "0x87870bca3f3fd6335c3f4ce8392d69350b4fa4e2".to_string(),
+                events,
+                reserves: vec![
+                    reserve(WETH, "WETH", 8000 | (8250 << 16) | (10500 << 32) | (18 << 48), weth_price),
+                    reserve(USDC, "USDC", 7700 | (8000 << 16) | (10450 << 32) | (6 << 48), 1.0),
+                ],
+            }),
+            "test".to_string(),
+        );
+
+        // 10 WETH collateral, 25k USDC debt, healthy at $3500
+        market_state.ingest_feature(lending(1, vec![
+            position_log(SUPPLY_TOPIC, WETH, 10 * 10u128.pow(18)),
+            position_log(BORROW_TOPIC, USDC, 25_000 * 10u128.pow(6)),
+        ], 3500.0)).await.unwrap();
+        assert!(detector.detect().await.unwrap().is_empty());
+        assert_eq!(detector.position_count().await, 1);
+
+        // A later block's oracle drop reaches the detector the same way
+        market_state.ingest_feature(lending(2, Vec::new(), 2900.0)).await.unwrap();
+        let candidates = detector.detect().await.unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(detector.position_count().await, 1);
+    }
+}
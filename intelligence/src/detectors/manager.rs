@@ -1,17 +1,82 @@
 //! Detector manager - orchestrates all detectors
+//!
+//! Each detector run is spawned as its own task, so detectors run in parallel
+//! on the runtime's workers and a time budget holds even against a detector
+//! that does not yield. A detector that keeps failing or timing out is
+//! disabled with exponential backoff so it cannot hold up the rest of the
+//! pipeline.
+//!
+//! Detectors read each chain's last sealed block. Changes from a block that is
+//! still being ingested are held back until that block is sealed, so a batch
+//! is scanned against the block it came from and never against half of it.
 
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{info, warn};
+use std::time::{Duration, Instant};
+use qenus_dataplane::Chain;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
 
-use crate::error::Result;
-use crate::state::MarketState;
+use crate::config::{DetectionConfig, IntelligenceConfig};
+use crate::error::{IntelligenceError, Result};
+use crate::state::{ChangeKey, MarketChange, MarketState};
 use crate::types::{Candidate, StrategyConfig};
-use crate::detectors::{TriangleArbDetector, dex_arb::DexArbDetector};
+use crate::detectors::{DetectionScope, Detector, TriangleArbDetector, dex_arb::DexArbDetector, liquidation::LiquidationDetector, depeg::DepegDetector, flash_arb::FlashArbDetector, script::ScriptDetector};
+
+/// Per-detector run metrics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DetectorMetrics {
+    /// Completed or attempted scans
+    pub runs: u64,
+
+    /// Candidates produced over all runs
+    pub candidates_total: u64,
+
+    /// Candidates produced by the last run
+    pub last_candidates: usize,
+
+    /// Scans that returned an error
+    pub errors: u64,
+
+    /// Scans that exceeded their time budget
+    pub timeouts: u64,
+
+    /// Duration of the last scan in milliseconds
+    pub last_duration_ms: u64,
+
+    /// Exponential moving average of scan duration in milliseconds
+    pub avg_duration_ms: f64,
+
+    /// Failures since the last successful scan
+    pub consecutive_failures: u32,
+
+    /// Times the detector has been disabled since it last succeeded
+    pub times_disabled: u32,
+
+    /// Detector is skipped until this time
+    pub disabled_until: Option<DateTime<Utc>>,
+
+    /// Last error or timeout message
+    pub last_error: Option<String>,
+}
+
+/// Detector with its run metrics
+struct ManagedDetector {
+    detector: Arc<dyn Detector>,
+    metrics: RwLock<DetectorMetrics>,
+}
 
 /// Detector manager - orchestrates all detectors
 pub struct DetectorManager {
-    triangle_detector: Option<TriangleArbDetector>,
-    dex_detector: Option<DexArbDetector>,
+    detectors: Vec<ManagedDetector>,
+    limits: DetectionConfig,
+    market_state: Arc<MarketState>,
+
+    /// Latest change per key from blocks not sealed yet
+    deferred: RwLock<HashMap<(Chain, ChangeKey), MarketChange>>,
 }
 
 impl DetectorManager {
@@ -21,38 +86,478 @@ impl DetectorManager {
         dex_config: Option<StrategyConfig>,
         market_state: Arc<MarketState>,
     ) -> Self {
-        Self {
-            triangle_detector: triangle_config.map(|cfg| {
-                TriangleArbDetector::new(cfg, market_state.clone())
-            }),
-            dex_detector: dex_config.map(|cfg| {
-                DexArbDetector::new(cfg, market_state)
-            }),
+        let mut manager = Self {
+            detectors: Vec::new(),
+            limits: DetectionConfig::default(),
+            market_state: market_state.clone(),
+            deferred: RwLock::new(HashMap::new()),
+        };
+
+        if let Some(cfg) = triangle_config {
+            manager = manager.with_detector(Arc::new(TriangleArbDetector::new(cfg, market_state.clone())));
+        }
+        if let Some(cfg) = dex_config {
+            manager = manager.with_detector(Arc::new(DexArbDetector::new(cfg, market_state)));
+        }
+
+        manager
+    }
+
+    /// Create a manager running every enabled strategy in `config`
+    pub fn from_config(config: &IntelligenceConfig, market_state: Arc<MarketState>) -> Self {
+        let mut manager = Self::new(
+            config.get_strategy("triangle_arb").cloned(),
+            config.get_strategy("dex_arb").cloned(),
+            market_state.clone(),
+        ).with_detection_config(&config.detection);
+
+        if let Some(cfg) = config.get_strategy("aave_liquidation").filter(|cfg| cfg.enabled) {
+            manager = manager.with_liquidation_detector(Arc::new(LiquidationDetector::new(cfg.clone(), market_state.clone())));
         }
+        if let Some(cfg) = config.get_strategy("stablecoin_depeg").filter(|cfg| cfg.enabled) {
+            manager = manager.with_depeg_detector(DepegDetector::new(cfg.clone(), market_state.clone()));
+        }
+        if let Some(cfg) = config.get_strategy("flash_arb").filter(|cfg| cfg.enabled) {
+            manager = manager.with_detector(Arc::new(FlashArbDetector::new(cfg.clone(), market_state.clone())));
+        }
+        if config.scripting.enabled {
+            info!("Running strategy scripts from {}", config.scripting.dir);
+            manager = manager.with_detector(Arc::new(ScriptDetector::new(config.scripting.clone(), market_state)));
+        }
+
+        manager
+    }
+
+    /// Use time budgets and failure limits from the detection config
+    pub fn with_detection_config(mut self, detection: &DetectionConfig) -> Self {
+        self.limits = detection.clone();
+        self
+    }
+
+    /// Attach any detector
+    pub fn with_detector(mut self, detector: Arc<dyn Detector>) -> Self {
+        self.detectors.push(ManagedDetector {
+            detector,
+            metrics: RwLock::new(DetectorMetrics::default()),
+        });
+        self
     }
-    
-    /// Run all enabled detectors
+
+    /// Attach a liquidation detector
+    ///
+    /// It applies the lending features MarketState received (Pool events,
+    /// reserve configurations and oracle prices) at the start of each pass.
+    pub fn with_liquidation_detector(self, detector: Arc<LiquidationDetector>) -> Self {
+        self.with_detector(detector)
+    }
+
+    /// Attach a stablecoin depeg detector
+    pub fn with_depeg_detector(self, detector: DepegDetector) -> Self {
+        self.with_detector(Arc::new(detector))
+    }
+
+    /// Run all enabled detectors in parallel
     pub async fn detect_all(&self) -> Result<Vec<Candidate>> {
-        let mut all_candidates = Vec::new();
-        
-        // Run triangle arb detector
-        if let Some(detector) = &self.triangle_detector {
-            match detector.detect().await {
-                Ok(candidates) => all_candidates.extend(candidates),
-                Err(e) => warn!("Triangle arb detector failed: {}", e),
+        let scope = DetectionScope::all();
+        let results = join_all(self.detectors.iter().map(|managed| self.run_detector(managed, &scope))).await;
+        let all_candidates: Vec<Candidate> = results.into_iter().flatten().collect();
+
+        info!("Detected {} total candidates", all_candidates.len());
+        Ok(all_candidates)
+    }
+
+    /// Re-run only the detectors affected by a batch of market changes,
+    /// scoped to the chains and assets that changed
+    ///
+    /// Changes whose block is not sealed yet wait for a later batch; changes
+    /// held from earlier batches run once their block is sealed.
+    pub async fn detect_changes(&self, changes: &[MarketChange]) -> Result<Vec<Candidate>> {
+        let changes = self.sealed_changes(changes).await;
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let scope = DetectionScope::from_changes(&changes);
+        let affected: Vec<_> = self.detectors.iter()
+            .filter(|managed| changes.iter().any(|change| managed.detector.is_affected_by(change)))
+            .collect();
+
+        if affected.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let results = join_all(affected.iter().map(|managed| self.run_detector(managed, &scope))).await;
+        let all_candidates: Vec<Candidate> = results.into_iter().flatten().collect();
+
+        debug!(
+            "{} changes re-ran {} detectors, {} candidates",
+            changes.len(), affected.len(), all_candidates.len()
+        );
+        Ok(all_candidates)
+    }
+
+    /// Add `changes` to the held ones and take those whose block is sealed
+    async fn sealed_changes(&self, changes: &[MarketChange]) -> Vec<MarketChange> {
+        let mut deferred = self.deferred.write().await;
+        for change in changes {
+            deferred.insert((change.chain, change.key.clone()), change.clone());
+        }
+
+        let sealed: HashMap<Chain, u64> = deferred.keys()
+            .filter_map(|(chain, _)| Some((*chain, self.market_state.block_view(*chain)?.block_number)))
+            .collect();
+        let (ready, waiting): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(&mut *deferred).into_iter()
+            .partition(|(_, change)| sealed.get(&change.chain).is_some_and(|block| *block >= change.block_number));
+        *deferred = waiting;
+        ready.into_values().collect()
+    }
+
+    /// Metrics of every detector by name
+    pub async fn metrics(&self) -> HashMap<String, DetectorMetrics> {
+        let mut metrics = HashMap::new();
+        for managed in &self.detectors {
+            metrics.insert(managed.detector.name().to_string(), managed.metrics.read().await.clone());
+        }
+        metrics
+    }
+
+    /// Run one detector on its own task within its budget and record the outcome
+    ///
+    /// A run over budget is aborted at its next await point; a detector stuck
+    /// in CPU work keeps its worker until it yields, but no longer holds up
+    /// the pass.
+    async fn run_detector(&self, managed: &ManagedDetector, scope: &DetectionScope) -> Vec<Candidate> {
+        let name = managed.detector.name();
+
+        if let Some(until) = managed.metrics.read().await.disabled_until {
+            if Utc::now() < until {
+                debug!("Detector {} disabled until {}", name, until);
+                return Vec::new();
             }
         }
-        
-        // Run DEX arb detector
-        if let Some(detector) = &self.dex_detector {
-            match detector.detect().await {
-                Ok(candidates) => all_candidates.extend(candidates),
-                Err(e) => warn!("DEX arb detector failed: {}", e),
+
+        let budget = Duration::from_millis(
+            self.limits.detector_timeouts_ms.get(name).copied().unwrap_or(self.limits.detector_timeout_ms)
+        );
+        let started = Instant::now();
+        let detector = managed.detector.clone();
+        let task_scope = scope.clone();
+        let mut run = tokio::spawn(async move { detector.detect_scoped(&task_scope).await });
+        let outcome = match tokio::time::timeout(budget, &mut run).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(e)) => Ok(Err(IntelligenceError::detection(format!("detector task failed: {}", e)))),
+            Err(elapsed) => {
+                run.abort();
+                Err(elapsed)
+            }
+        };
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        let mut metrics = managed.metrics.write().await;
+        metrics.runs += 1;
+        metrics.last_duration_ms = elapsed_ms;
+        metrics.avg_duration_ms = if metrics.runs == 1 {
+            elapsed_ms as f64
+        } else {
+            0.8 * metrics.avg_duration_ms + 0.2 * elapsed_ms as f64
+        };
+
+        match outcome {
+            Ok(Ok(candidates)) => {
+                debug!("Detector {} found {} candidates in {}ms", name, candidates.len(), elapsed_ms);
+                metrics.last_candidates = candidates.len();
+                metrics.candidates_total += candidates.len() as u64;
+                metrics.consecutive_failures = 0;
+                metrics.times_disabled = 0;
+                metrics.disabled_until = None;
+                candidates
+            }
+            Ok(Err(e)) => {
+                warn!("Detector {} failed: {}", name, e);
+                metrics.errors += 1;
+                self.record_failure(name, &mut metrics, e.to_string());
+                Vec::new()
+            }
+            Err(_) => {
+                warn!("Detector {} timed out after {}ms", name, budget.as_millis());
+                metrics.timeouts += 1;
+                self.record_failure(name, &mut metrics, format!("timed out after {}ms", budget.as_millis()));
+                Vec::new()
             }
         }
-        
-        info!("Detected {} total candidates", all_candidates.len());
-        Ok(all_candidates)
+    }
+
+    /// Count a failure and disable the detector once it keeps failing
+    fn record_failure(&self, name: &str, metrics: &mut DetectorMetrics, error: String) {
+        metrics.last_candidates = 0;
+        metrics.last_error = Some(error);
+        metrics.consecutive_failures += 1;
+
+        if metrics.consecutive_failures < self.limits.max_detector_failures {
+            return;
+        }
+
+        let backoff_secs = self.limits.detector_backoff_secs
+            .saturating_mul(1u64 << metrics.times_disabled.min(16))
+            .min(self.limits.max_detector_backoff_secs);
+
+        warn!(
+            "Disabling detector {} for {}s after {} consecutive failures",
+            name, backoff_secs, metrics.consecutive_failures
+        );
+
+        metrics.disabled_until = Some(Utc::now() + chrono::Duration::seconds(backoff_secs as i64));
+        metrics.times_disabled += 1;
+        metrics.consecutive_failures = 0;
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct MockDetector {
+        name: String,
+        delay_ms: u64,
+        fail: bool,
+        chain: Option<qenus_dataplane::Chain>,
+    }
+
+    #[async_trait]
+    impl Detector for MockDetector {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn is_affected_by(&self, change: &MarketChange) -> bool {
+            self.chain.map(|chain| chain == change.chain).unwrap_or(true)
+        }
+
+        async fn detect(&self) -> Result<Vec<Candidate>> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            if self.fail {
+                return Err(IntelligenceError::detection("boom"));
+            }
+
+            Ok(vec![Candidate {
+                strategy: self.name.clone(),
+                asset: "WETH".to_string(),
+                spread_bps: 10.0,
+                legs: Vec::new(),
+                detected_at: Utc::now(),
+                confidence: 0.9,
+                first_seen_at: None,
+                details: None,
+            }])
+        }
+    }
+
+    /// Detector that holds its worker thread without yielding
+    struct BlockingDetector;
+
+    #[async_trait]
+    impl Detector for BlockingDetector {
+        fn name(&self) -> &str {
+            "blocking"
+        }
+
+        async fn detect(&self) -> Result<Vec<Candidate>> {
+            std::thread::sleep(Duration::from_millis(300));
+            Ok(Vec::new())
+        }
+    }
+
+    fn mock(name: &str, delay_ms: u64, fail: bool) -> Arc<dyn Detector> {
+        Arc::new(MockDetector { name: name.to_string(), delay_ms, fail, chain: None })
+    }
+
+    fn manager(detectors: Vec<Arc<dyn Detector>>) -> DetectorManager {
+        let detection = DetectionConfig {
+            detector_timeout_ms: 50,
+            max_detector_failures: 2,
+            ..DetectionConfig::default()
+        };
+
+        detectors.into_iter().fold(
+            DetectorManager::new(None, None, Arc::new(MarketState::new(30))).with_detection_config(&detection),
+            |manager, detector| manager.with_detector(detector),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_slow_detector_does_not_block_others() {
+        let manager = manager(vec![mock("fast", 0, false), mock("slow", 1_000, false)]);
+
+        let started = Instant::now();
+        let candidates = manager.detect_all().await.unwrap();
+
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].strategy, "fast");
+
+        let metrics = manager.metrics().await;
+        assert_eq!(metrics["slow"].timeouts, 1);
+        assert_eq!(metrics["fast"].candidates_total, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_blocking_detector_times_out_without_stalling_the_pass() {
+        let manager = manager(vec![Arc::new(BlockingDetector), mock("fast", 20, false)]);
+
+        let started = Instant::now();
+        let candidates = manager.detect_all().await.unwrap();
+
+        // The pass ends at the budget, not when the blocking detector yields
+        assert!(started.elapsed() < Duration::from_millis(250));
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(manager.metrics().await["blocking"].timeouts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failing_detector_is_disabled() {
+        let manager = manager(vec![mock("broken", 0, true), mock("ok", 0, false)]);
+
+        for _ in 0..3 {
+            let candidates = manager.detect_all().await.unwrap();
+            assert_eq!(candidates.len(), 1);
+        }
+
+        let metrics = manager.metrics().await;
+        // Third run skipped: disabled after the second failure
+        assert_eq!(metrics["broken"].runs, 2);
+        assert_eq!(metrics["broken"].errors, 2);
+        assert!(metrics["broken"].disabled_until.is_some());
+        assert_eq!(metrics["ok"].runs, 3);
+    }
+
+    #[tokio::test]
+    async fn test_changes_only_run_affected_detectors() {
+        use qenus_dataplane::{Feature, FeatureData, FeatureType, GasFeature};
+
+        let arbitrum: Arc<dyn Detector> = Arc::new(MockDetector {
+            name: "arbitrum_only".to_string(),
+            delay_ms: 0,
+            fail: false,
+            chain: Some(Chain::Arbitrum),
+        });
+        let manager = manager(vec![arbitrum, mock("everywhere", 0, false)]);
+        let gas = Feature::new(1, Chain::Ethereum, FeatureType::Gas, FeatureData::Gas(GasFeature {
+            base_fee: 20.0,
+            priority_fee: 1.0,
+            gas_used_ratio: 0.5,
+            next_base_fee_estimate: 20.0,
+            fast_gas_price: 22.0,
+            standard_gas_price: 21.0,
+            safe_gas_price: 20.0,
+            pending_tx_count: 0,
+        }), "test".to_string());
+        manager.market_state.ingest_feature(gas).await.unwrap();
+        manager.market_state.end_block(Chain::Ethereum, 1);
+
+        let change = MarketChange {
+            chain: Chain::Ethereum,
+            block_number: 1,
+            key: ChangeKey::Gas,
+            magnitude_bps: Some(50.0),
+            timestamp: Utc::now(),
+        };
+        let candidates = manager.detect_changes(&[change]).await.unwrap();
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].strategy, "everywhere");
+        assert_eq!(manager.metrics().await["arbitrum_only"].runs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_holds_changes_until_their_block_is_sealed() {
+        use qenus_dataplane::{
+            AmmFeature, Chain, DepthCurve, Feature, FeatureData, FeatureType, SequencerHealthFeature,
+            SequencerStatus, TokenInfo,
+        };
+        use crate::types::RiskLimits;
+
+        let token = |symbol: &str| TokenInfo { address: String::new(), symbol: symbol.to_string(), decimals: 18 };
+        let pool = |address: &str, pool_type: &str, mid_price: f64| Feature::new(
+            7,
+            Chain::Ethereum,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: address.to_string(),
+                pool_type: pool_type.to_string(),
+                pool_id: None,
+                token0: token("WETH"),
+                token1: token("USDC"),
+                fee_tier: Some(5),
+                reserves: [("WETH".to_string(), "1000".to_string())].into_iter().collect(),
+                mid_price,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve::default(),
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        );
+        let sequencer = |block_number: u64| Feature::new(
+            block_number,
+            Chain::Ethereum,
+            FeatureType::SequencerHealth,
+            FeatureData::SequencerHealth(SequencerHealthFeature {
+                sequencer_address: String::new(),
+                status: SequencerStatus::Healthy,
+                block_interval_avg: 12.0,
+                block_interval_variance: 0.0,
+                uptime_percentage: 100.0,
+                last_block_time: Utc::now(),
+                pending_tx_count: 0,
+            }),
+            "test".to_string(),
+        );
+
+        let market_state = Arc::new(MarketState::new(30));
+        for feature in [sequencer(7), pool("0xuni", "uniswap_v3", 2000.0), pool("0xcurve", "curve", 2020.0)] {
+            market_state.ingest_feature(feature).await.unwrap();
+        }
+
+        let dex_config = StrategyConfig {
+            name: "dex_arb".to_string(),
+            enabled: true,
+            min_profit_usd: 10.0,
+            min_profit_bps: 5.0,
+            max_position_usd: 100_000.0,
+            approved_assets: vec!["WETH".to_string()],
+            approved_chains: vec![Chain::Ethereum],
+            risk_limits: RiskLimits::default(),
+            budget: Default::default(),
+        };
+        let manager = DetectorManager::new(None, Some(dex_config), market_state.clone());
+
+        // Block 7 is still open: no block 8 has arrived to seal it, so
+        // detection neither sees it nor seals it
+        let change = |block_number: u64, key: ChangeKey| MarketChange {
+            chain: Chain::Ethereum,
+            block_number,
+            key,
+            magnitude_bps: None,
+            timestamp: Utc::now(),
+        };
+        let pool_change = change(7, ChangeKey::Pool {
+            address: "0xcurve".to_string(),
+            token0: "WETH".to_string(),
+            token1: "USDC".to_string(),
+        });
+        assert!(manager.detect_changes(&[pool_change]).await.unwrap().is_empty());
+        assert!(manager.detect_all().await.unwrap().is_empty());
+        assert!(market_state.block_view(Chain::Ethereum).is_none());
+
+        // Block 8 seals block 7: the held change runs against it, while the
+        // sequencer change from block 8 waits in turn
+        market_state.ingest_feature(sequencer(8)).await.unwrap();
+        let candidates = manager.detect_changes(&[change(8, ChangeKey::Sequencer)]).await.unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(market_state.block_view(Chain::Ethereum).unwrap().block_number, 7);
+        assert_eq!(manager.deferred.read().await.len(), 1);
+
+        assert_eq!(manager.detect_all().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,591 @@
+//! Scripted strategy detector
+//!
+//! Runs user-written strategies from a directory of `*.expr` files without a
+//! rebuild. Scripts are evalexpr programs: expressions and variable
+//! assignments chained with `;`, with no loops, recursion or I/O, so a
+//! script's work is bounded by its size. On top of that every script is
+//! capped in operations, tree depth, stored value size and run time. The run
+//! time limit is checked by the evaluation itself, on every function call and
+//! assignment, so a script over it stops rather than running on unobserved.
+//!
+//! Scripts see the market through read-only functions over a view taken
+//! before each run:
+//!
+//! - `price(chain, asset)`: USD price
+//! - `min_price(chain, base, quote)`, `max_price(chain, base, quote)`: price of
+//!   `base` in `quote` across the pair's pools, and `min_price_pool` /
+//!   `max_price_pool` for the quoting pool's address
+//! - `pool_price(chain, pool)`, `slippage_bps(chain, pool, asset_in, size_usd)`
+//! - `gas_price(chain)`, `base_fee(chain)` in gwei
+//! - `bridge_fee_bps(from, to)`, `bridge_time_secs(from, to)`
+//! - `flashloan_liquidity(chain, asset)`, `flashloan_fee_bps(chain, asset)`
+//! - `sequencer_healthy(chain)`
+//!
+//! Function arguments are always evaluated (`if` included), so lookups with
+//! no fresh data return NaN (or `""` for pool addresses) rather than failing,
+//! and comparisons against them are false. Candidates are produced with
+//! `emit(strategy, asset, spread_bps, confidence, domain, action, ...)` or
+//! `emit_if(condition, strategy, ...)`, and are simulated by the named
+//! strategy's simulator like any other candidate.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use evalexpr::{Context, ContextWithMutableVariables, EvalexprError, EvalexprResult, Node, Value};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use qenus_dataplane::Chain;
+
+use crate::config::ScriptingConfig;
+use crate::error::{IntelligenceError, Result};
+use crate::detectors::Detector;
+use crate::simulator::amm::{swap_slippage_bps, usd_price};
+use crate::state::{AmmState, BridgeState, FlashLoanState, MarketState};
+use crate::types::Candidate;
+
+/// File extension of strategy scripts
+const SCRIPT_EXTENSION: &str = "expr";
+
+/// Deepest expression nesting accepted; evaluation recurses per level
+const MAX_TREE_DEPTH: usize = 64;
+
+const CHAINS: [Chain; 4] = [Chain::Ethereum, Chain::Arbitrum, Chain::Optimism, Chain::Base];
+
+/// Detector running every script in the configured directory
+pub struct ScriptDetector {
+    config: ScriptingConfig,
+    market_state: Arc<MarketState>,
+    scripts: RwLock<HashMap<String, Arc<Node>>>,
+    /// Modification time of the last load attempt per file, good or bad
+    attempted: RwLock<HashMap<PathBuf, SystemTime>>,
+    last_reload: RwLock<Option<Instant>>,
+}
+
+impl ScriptDetector {
+    /// Create a detector over `config.dir`; scripts load on the first scan
+    pub fn new(config: ScriptingConfig, market_state: Arc<MarketState>) -> Self {
+        Self {
+            config,
+            market_state,
+            scripts: RwLock::new(HashMap::new()),
+            attempted: RwLock::new(HashMap::new()),
+            last_reload: RwLock::new(None),
+        }
+    }
+
+    /// Names of the loaded scripts
+    pub async fn script_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.scripts.read().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Pick up added, changed and removed scripts
+    ///
+    /// A script that fails to load keeps its previous version running.
+    pub async fn reload(&self) -> Result<()> {
+        *self.last_reload.write().await = Some(Instant::now());
+
+        let dir = Path::new(&self.config.dir);
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                return Err(IntelligenceError::detection(format!("Cannot read script dir {}: {}", dir.display(), e)));
+            }
+        };
+
+        let mut present = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(SCRIPT_EXTENSION) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let Ok(modified) = entry.metadata().and_then(|meta| meta.modified()) else {
+                continue;
+            };
+            present.push(name.clone());
+
+            if self.attempted.read().await.get(&path) == Some(&modified) {
+                continue;
+            }
+            self.attempted.write().await.insert(path.clone(), modified);
+
+            match self.compile(&path) {
+                Ok(tree) => {
+                    info!("✅ Loaded strategy script {}", name);
+                    self.scripts.write().await.insert(name, Arc::new(tree));
+                }
+                Err(e) => warn!("❌ Strategy script {} rejected: {}", name, e),
+            }
+        }
+
+        let mut scripts = self.scripts.write().await;
+        scripts.retain(|name, _| {
+            let keep = present.contains(name);
+            if !keep {
+                info!("Unloaded strategy script {}", name);
+            }
+            keep
+        });
+        Ok(())
+    }
+
+    /// Read and compile one script within the size limits
+    fn compile(&self, path: &Path) -> Result<Node> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| IntelligenceError::detection(format!("read failed: {}", e)))?;
+        if source.len() > self.config.max_script_bytes {
+            return Err(IntelligenceError::detection(format!(
+                "{} bytes exceeds the {} byte limit", source.len(), self.config.max_script_bytes
+            )));
+        }
+
+        let tree = evalexpr::build_operator_tree(&source)
+            .map_err(|e| IntelligenceError::detection(format!("parse error: {}", e)))?;
+
+        let (operations, depth) = tree_size(&tree);
+        if operations > self.config.max_operations {
+            return Err(IntelligenceError::detection(format!(
+                "{} operations exceeds the {} operation limit", operations, self.config.max_operations
+            )));
+        }
+        if depth > MAX_TREE_DEPTH {
+            return Err(IntelligenceError::detection(format!(
+                "nesting depth {} exceeds {}", depth, MAX_TREE_DEPTH
+            )));
+        }
+        Ok(tree)
+    }
+
+    /// Reload if the reload interval has passed
+    async fn reload_if_due(&self) {
+        let due = self.last_reload.read().await
+            .map(|at| at.elapsed() >= Duration::from_secs(self.config.reload_interval_secs))
+            .unwrap_or(true);
+        if due {
+            if let Err(e) = self.reload().await {
+                warn!("Strategy script reload failed: {}", e);
+            }
+        }
+    }
+
+    /// Run one script against a market view
+    async fn run_script(&self, name: &str, tree: Arc<Node>, view: Arc<MarketView>) -> Result<Vec<Candidate>> {
+        let limits = self.config.clone();
+
+        // The budget is enforced inside evaluation: a timeout out here would
+        // return early but leave the blocking thread running the script
+        let run = tokio::task::spawn_blocking(move || {
+            let mut context = ScriptContext::new(view, &limits);
+            tree.eval_with_context_mut(&mut context)
+                .map(|_| context.emitted.into_inner())
+        });
+
+        match run.await {
+            Ok(Ok(candidates)) => Ok(candidates),
+            Ok(Err(e)) => Err(IntelligenceError::detection(format!("script {} failed: {}", name, e))),
+            Err(e) => Err(IntelligenceError::detection(format!("script {} panicked: {}", name, e))),
+        }
+    }
+}
+
+#[async_trait]
+impl Detector for ScriptDetector {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    async fn detect(&self) -> Result<Vec<Candidate>> {
+        self.reload_if_due().await;
+
+        let scripts: Vec<(String, Arc<Node>)> = self.scripts.read().await.iter()
+            .map(|(name, tree)| (name.clone(), tree.clone()))
+            .collect();
+        if scripts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let view = Arc::new(MarketView::capture(&self.market_state));
+        let mut candidates = Vec::new();
+
+        // One failing script must not take the others down with it
+        for (name, tree) in scripts {
+            match self.run_script(&name, tree, view.clone()).await {
+                Ok(found) => {
+                    debug!("Script {} emitted {} candidates", name, found.len());
+                    candidates.extend(found);
+                }
+                Err(e) => warn!("{}", e),
+            }
+        }
+
+        Ok(candidates)
+    }
+}
+
+/// Operation count and depth of an expression tree
+fn tree_size(node: &Node) -> (usize, usize) {
+    node.children().iter()
+        .map(tree_size)
+        .fold((1, 1), |(operations, depth), (child_ops, child_depth)| {
+            (operations + child_ops, depth.max(child_depth + 1))
+        })
+}
+
+/// Read-only copy of the fresh market state a script run sees
+struct MarketView {
+    now: DateTime<Utc>,
+    pools: HashMap<Chain, Vec<AmmState>>,
+    gas: HashMap<Chain, (f64, f64)>, // (gas price, base fee) in gwei
+    bridges: HashMap<(Chain, Chain), Vec<BridgeState>>,
+    flashloans: HashMap<(Chain, String), Vec<FlashLoanState>>,
+    healthy: HashMap<Chain, bool>,
+}
+
+impl MarketView {
+    /// Copy each chain's last sealed block, dropping stale entries
+    fn capture(market_state: &MarketState) -> Self {
+        let mut view = Self {
+            now: market_state.now(),
+            pools: HashMap::new(),
+            gas: HashMap::new(),
+            bridges: HashMap::new(),
+            flashloans: HashMap::new(),
+            healthy: HashMap::new(),
+        };
+        let fresh_since = market_state.fresh_since();
+
+        for chain in CHAINS {
+            let Some(block) = market_state.block_view(chain) else {
+                view.pools.insert(chain, Vec::new());
+                view.healthy.insert(chain, false);
+                continue;
+            };
+
+            view.pools.insert(chain, block.fresh_pools(fresh_since).cloned().collect());
+            view.healthy.insert(chain, block.is_sequencer_healthy(fresh_since));
+
+            if let (Some(gas_price), Some(state)) = (block.gas_price(fresh_since), block.gas.as_ref()) {
+                view.gas.insert(chain, (gas_price, state.base_fee));
+            }
+
+            for (to, bridge) in &block.bridges {
+                if bridge.last_update >= fresh_since {
+                    view.bridges.entry((chain, *to)).or_default().push(bridge.as_ref().clone());
+                }
+            }
+
+            for provider in &block.flashloans {
+                if provider.is_active && provider.last_update >= fresh_since {
+                    view.flashloans.entry((chain, provider.asset_symbol.clone()))
+                        .or_default()
+                        .push(provider.as_ref().clone());
+                }
+            }
+        }
+
+        view
+    }
+
+    /// Pools quoting `base` against `quote`, with the price of `base` in `quote`
+    fn pair_quotes(&self, chain: Chain, base: &str, quote: &str) -> Vec<(&AmmState, f64)> {
+        self.pools.get(&chain).into_iter().flatten()
+            .filter(|pool| pool.mid_price > 0.0)
+            .filter_map(|pool| {
+                if pool.token0_symbol == base && pool.token1_symbol == quote {
+                    Some((pool, pool.mid_price))
+                } else if pool.token1_symbol == base && pool.token0_symbol == quote {
+                    Some((pool, 1.0 / pool.mid_price))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn pool(&self, chain: Chain, address: &str) -> Option<&AmmState> {
+        self.pools.get(&chain)?.iter().find(|pool| pool.pool_address.eq_ignore_ascii_case(address))
+    }
+
+    fn active_bridges(&self, from: Chain, to: Chain) -> impl Iterator<Item = &BridgeState> {
+        self.bridges.get(&(from, to)).into_iter().flatten().filter(|bridge| bridge.is_active)
+    }
+}
+
+/// Evaluation context: market functions, bounded variables and emitted candidates
+struct ScriptContext {
+    view: Arc<MarketView>,
+    variables: HashMap<String, Value>,
+    emitted: RefCell<Vec<Candidate>>,
+    max_value_bytes: usize,
+    max_candidates: usize,
+    max_eval_ms: u64,
+    deadline: Instant,
+}
+
+impl ScriptContext {
+    fn new(view: Arc<MarketView>, limits: &ScriptingConfig) -> Self {
+        Self {
+            view,
+            variables: HashMap::new(),
+            emitted: RefCell::new(Vec::new()),
+            max_value_bytes: limits.max_value_bytes,
+            max_candidates: limits.max_candidates,
+            max_eval_ms: limits.max_eval_ms,
+            deadline: Instant::now() + Duration::from_millis(limits.max_eval_ms),
+        }
+    }
+
+    /// Fail once the run is over its time budget
+    fn check_deadline(&self) -> EvalexprResult<()> {
+        if Instant::now() >= self.deadline {
+            return Err(script_error(format!("exceeded its {}ms budget", self.max_eval_ms)));
+        }
+        Ok(())
+    }
+
+    /// Record a candidate from `emit(strategy, asset, spread_bps, confidence, domain, action, ...)`
+    fn emit(&self, args: &[Value]) -> EvalexprResult<Value> {
+        if args.len() < 4 || !args.len().is_multiple_of(2) {
+            return Err(script_error("emit expects strategy, asset, spread_bps, confidence and (domain, action) pairs"));
+        }
+
+        let spread_bps = args[2].as_number()?;
+        if !spread_bps.is_finite() {
+            return Err(script_error("emit spread_bps must be finite"));
+        }
+        let confidence = args[3].as_number()?;
+        if !(0.0..=1.0).contains(&confidence) {
+            return Err(script_error("emit confidence must be within 0..=1"));
+        }
+
+        let mut emitted = self.emitted.borrow_mut();
+        if emitted.len() >= self.max_candidates {
+            return Err(script_error(format!("more than {} candidates emitted", self.max_candidates)));
+        }
+
+        emitted.push(Candidate {
+            strategy: args[0].as_string()?,
+            asset: args[1].as_string()?,
+            spread_bps,
+            legs: args[4..].chunks(2)
+                .map(|leg| Ok((leg[0].as_string()?, leg[1].as_string()?)))
+                .collect::<EvalexprResult<_>>()?,
+            detected_at: self.view.now,
+            confidence,
+            first_seen_at: None,
+            details: None,
+        });
+        Ok(Value::Empty)
+    }
+
+    /// Market lookups; NaN when there is no fresh data
+    fn market(&self, function: &str, args: &[Value]) -> EvalexprResult<Value> {
+        let view = &self.view;
+        let float = |value: Option<f64>| Value::Float(value.unwrap_or(f64::NAN));
+
+        match (function, args) {
+            ("price", [chain, asset]) => {
+                let pools = view.pools.get(&chain_arg(chain)?).map(Vec::as_slice).unwrap_or_default();
+                Ok(float(usd_price(pools, &asset.as_string()?)))
+            }
+            ("min_price" | "max_price" | "min_price_pool" | "max_price_pool", [chain, base, quote]) => {
+                let quotes = view.pair_quotes(chain_arg(chain)?, &base.as_string()?, &quote.as_string()?);
+                let best = if function.starts_with("min") {
+                    quotes.into_iter().min_by(|a, b| a.1.total_cmp(&b.1))
+                } else {
+                    quotes.into_iter().max_by(|a, b| a.1.total_cmp(&b.1))
+                };
+                Ok(match best {
+                    Some((pool, _)) if function.ends_with("_pool") => Value::String(pool.pool_address.clone()),
+                    None if function.ends_with("_pool") => Value::String(String::new()),
+                    best => float(best.map(|(_, price)| price)),
+                })
+            }
+            ("pool_price", [chain, pool]) => {
+                Ok(float(view.pool(chain_arg(chain)?, &pool.as_string()?).map(|pool| pool.mid_price)))
+            }
+            ("slippage_bps", [chain, pool, asset_in, size_usd]) => {
+                let size_usd = size_usd.as_number()?;
+                let asset_in = asset_in.as_string()?;
+                Ok(float(view.pool(chain_arg(chain)?, &pool.as_string()?)
+                    .map(|pool| swap_slippage_bps(pool, &asset_in, size_usd))))
+            }
+            ("gas_price", [chain]) => Ok(float(view.gas.get(&chain_arg(chain)?).map(|gas| gas.0))),
+            ("base_fee", [chain]) => Ok(float(view.gas.get(&chain_arg(chain)?).map(|gas| gas.1))),
+            ("bridge_fee_bps", [from, to]) => Ok(float(
+                view.active_bridges(chain_arg(from)?, chain_arg(to)?).map(|bridge| bridge.fee_bps as f64).reduce(f64::min)
+            )),
+            ("bridge_time_secs", [from, to]) => Ok(float(
+                view.active_bridges(chain_arg(from)?, chain_arg(to)?).map(|bridge| bridge.settlement_time_secs as f64).reduce(f64::min)
+            )),
+            ("flashloan_liquidity" | "flashloan_fee_bps", [chain, asset]) => {
+                let providers = view.flashloans.get(&(chain_arg(chain)?, asset.as_string()?));
+                let providers = providers.into_iter().flatten();
+                Ok(float(if function == "flashloan_liquidity" {
+                    providers.filter_map(|provider| provider.available_liquidity.parse::<f64>().ok()).reduce(f64::max)
+                } else {
+                    providers.map(|provider| provider.fee_bps as f64).reduce(f64::min)
+                }))
+            }
+            ("sequencer_healthy", [chain]) => {
+                Ok(Value::Boolean(view.healthy.get(&chain_arg(chain)?).copied().unwrap_or(false)))
+            }
+            _ => Err(EvalexprError::FunctionIdentifierNotFound(function.to_string())),
+        }
+    }
+}
+
+impl Context for ScriptContext {
+    fn get_value(&self, identifier: &str) -> Option<&Value> {
+        self.variables.get(identifier)
+    }
+
+    fn call_function(&self, identifier: &str, argument: &Value) -> EvalexprResult<Value> {
+        self.check_deadline()?;
+        let args = match argument {
+            Value::Tuple(values) => values.clone(),
+            Value::Empty => Vec::new(),
+            value => vec![value.clone()],
+        };
+
+        match identifier {
+            "emit" => self.emit(&args),
+            "emit_if" => match args.split_first() {
+                Some((condition, rest)) if condition.as_boolean()? => self.emit(rest),
+                Some(_) => Ok(Value::Empty),
+                None => Err(script_error("emit_if expects a condition")),
+            },
+            _ => self.market(identifier, &args),
+        }
+    }
+
+    fn are_builtin_functions_disabled(&self) -> bool {
+        false
+    }
+
+    fn set_builtin_functions_disabled(&mut self, _disabled: bool) -> EvalexprResult<()> {
+        Err(script_error("builtin functions cannot be toggled"))
+    }
+}
+
+impl ContextWithMutableVariables for ScriptContext {
+    fn set_value(&mut self, identifier: String, value: Value) -> EvalexprResult<()> {
+        self.check_deadline()?;
+        if value_bytes(&value) > self.max_value_bytes {
+            return Err(script_error(format!(
+                "value of {} exceeds the {} byte limit", identifier, self.max_value_bytes
+            )));
+        }
+        self.variables.insert(identifier, value);
+        Ok(())
+    }
+}
+
+/// Approximate heap size of a script value
+fn value_bytes(value: &Value) -> usize {
+    match value {
+        Value::String(string) => string.len(),
+        Value::Tuple(values) => values.iter().map(value_bytes).sum::<usize>() + 8 * values.len(),
+        _ => 8,
+    }
+}
+
+fn chain_arg(value: &Value) -> EvalexprResult<Chain> {
+    let name = value.as_string()?;
+    name.parse().map_err(|_| script_error(format!("unknown chain {}", name)))
+}
+
+fn script_error<S: Into<String>>(message: S) -> EvalexprError {
+    EvalexprError::CustomMessage(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_dir(scripts: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("qenus-scripts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, source) in scripts {
+            std::fs::write(dir.join(format!("{}.{}", name, SCRIPT_EXTENSION)), source).unwrap();
+        }
+        dir
+    }
+
+    fn detector(dir: &Path) -> ScriptDetector {
+        let config = ScriptingConfig {
+            enabled: true,
+            dir: dir.display().to_string(),
+            reload_interval_secs: 0,
+            max_operations: 200,
+            max_value_bytes: 64,
+            ..ScriptingConfig::default()
+        };
+        ScriptDetector::new(config, Arc::new(MarketState::new(30)))
+    }
+
+    #[tokio::test]
+    async fn test_scripts_emit_candidates_and_hot_reload() {
+        let dir = script_dir(&[(
+            "always",
+            r#"spread = 12.5; emit_if(math::is_nan(gas_price("ethereum")), "dex_arb", "WETH", spread, 0.7, "uniswap_v3 on Ethereum", "buy", "curve on Ethereum", "sell"); emit_if(price("ethereum", "WETH") > 0, "dex_arb", "WETH", spread, 0.7)"#,
+        )]);
+        let detector = detector(&dir);
+
+        let candidates = detector.detect().await.unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].strategy, "dex_arb");
+        assert_eq!(candidates[0].spread_bps, 12.5);
+        assert_eq!(candidates[0].legs[1], ("curve on Ethereum".to_string(), "sell".to_string()));
+
+        // A broken edit keeps the last good version; a new script is picked up
+        std::fs::write(dir.join("always.expr"), "emit(").unwrap();
+        std::fs::write(dir.join("never.expr"), "sequencer_healthy(\"arbitrum\")").unwrap();
+        let candidates = detector.detect().await.unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(detector.script_names().await, vec!["always", "never"]);
+
+        std::fs::remove_file(dir.join("always.expr")).unwrap();
+        assert!(detector.detect().await.unwrap().is_empty());
+        assert_eq!(detector.script_names().await, vec!["never"]);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_scripts_are_sandboxed() {
+        let too_long = format!("x = 1{}", " + 1".repeat(300));
+        let dir = script_dir(&[
+            ("too_long", too_long.as_str()),
+            ("hoarder", r#"s = "0123456789abcdef"; s = s + s; s = s + s; s = s + s; emit("dex_arb", "WETH", 1, 0.5)"#),
+            ("unknown", r#"std::fs::read("secrets")"#),
+        ]);
+        let detector = detector(&dir);
+
+        assert!(detector.detect().await.unwrap().is_empty());
+        // Over the operation limit: never loaded
+        assert_eq!(detector.script_names().await, vec!["hoarder", "unknown"]);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_time_budget_is_checked_during_evaluation() {
+        let tree = evalexpr::build_operator_tree(r#"x = 1; emit("dex_arb", "WETH", 1, 0.5)"#).unwrap();
+        let view = Arc::new(MarketView::capture(&MarketState::new(30)));
+        let limits = ScriptingConfig { max_eval_ms: 0, ..ScriptingConfig::default() };
+
+        let mut context = ScriptContext::new(view, &limits);
+        let error = tree.eval_with_context_mut(&mut context).unwrap_err();
+        assert!(error.to_string().contains("exceeded its 0ms budget"));
+        assert!(context.emitted.into_inner().is_empty());
+    }
+}
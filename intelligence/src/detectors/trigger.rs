@@ -0,0 +1,203 @@
+//! Event-driven detection triggers
+//!
+//! Turns the stream of MarketState change notifications into batches, so a
+//! burst of features from one block triggers a single detection pass.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+use qenus_dataplane::Chain;
+
+use crate::state::{ChangeKey, MarketChange, MarketState};
+
+/// Batch of changes ready for detection
+#[derive(Debug, Clone)]
+pub enum ChangeBatch {
+    /// Changes coalesced from one block (per chain), one entry per key
+    Changes(Vec<MarketChange>),
+
+    /// The subscriber fell behind and missed changes; run a full scan
+    Lagged(u64),
+}
+
+/// Coalesces MarketState changes into per-block batches
+pub struct ChangeCoalescer {
+    receiver: broadcast::Receiver<MarketChange>,
+    window: Duration,
+    min_change_bps: f64,
+
+    /// Change from a later block that closed the previous batch
+    carried: Option<MarketChange>,
+}
+
+impl ChangeCoalescer {
+    /// Subscribe to `market_state`
+    ///
+    /// A batch closes `window` after its first change, or as soon as a change
+    /// from a later block arrives on a chain already in the batch. Keys whose
+    /// changes in the batch add up to less than `min_change_bps` are dropped,
+    /// so a pool drifting in small steps still triggers once it has moved.
+    pub fn new(market_state: &MarketState, window: Duration, min_change_bps: f64) -> Self {
+        Self {
+            receiver: market_state.subscribe(),
+            window,
+            min_change_bps,
+            carried: None,
+        }
+    }
+
+    /// Wait for the next batch; None once the market state is dropped
+    pub async fn next_batch(&mut self) -> Option<ChangeBatch> {
+        // Batches where nothing added up to a significant change are skipped
+        loop {
+            let first = match self.carried.take() {
+                Some(change) => change,
+                None => match self.receiver.recv().await {
+                    Ok(change) => change,
+                    Err(RecvError::Lagged(missed)) => return Some(ChangeBatch::Lagged(missed)),
+                    Err(RecvError::Closed) => return None,
+                },
+            };
+
+            let deadline = Instant::now() + self.window;
+            let mut blocks: HashMap<Chain, u64> = HashMap::new();
+            let mut batch: HashMap<(Chain, ChangeKey), MarketChange> = HashMap::new();
+            self.merge(&mut blocks, &mut batch, first);
+
+            loop {
+                let change = match tokio::time::timeout_at(deadline, self.receiver.recv()).await {
+                    Err(_) => break,
+                    Ok(Ok(change)) => change,
+                    Ok(Err(RecvError::Lagged(missed))) => return Some(ChangeBatch::Lagged(missed)),
+                    // Flush what we have; the next call reports the close
+                    Ok(Err(RecvError::Closed)) => break,
+                };
+
+                // A new block on a chain already in the batch closes it
+                if blocks.get(&change.chain).map(|block| change.block_number > *block).unwrap_or(false) {
+                    self.carried = Some(change);
+                    break;
+                }
+
+                self.merge(&mut blocks, &mut batch, change);
+            }
+
+            let changes: Vec<MarketChange> = batch.into_values().filter(|change| self.is_significant(change)).collect();
+            if !changes.is_empty() {
+                return Some(ChangeBatch::Changes(changes));
+            }
+        }
+    }
+
+    fn is_significant(&self, change: &MarketChange) -> bool {
+        change.magnitude_bps.map(|bps| bps >= self.min_change_bps).unwrap_or(true)
+    }
+
+    /// Keep one change per key, with the magnitudes seen summed
+    fn merge(
+        &self,
+        blocks: &mut HashMap<Chain, u64>,
+        batch: &mut HashMap<(Chain, ChangeKey), MarketChange>,
+        change: MarketChange,
+    ) {
+        let block = blocks.entry(change.chain).or_insert(change.block_number);
+        *block = (*block).max(change.block_number);
+
+        let key = (change.chain, change.key.clone());
+        match batch.get_mut(&key) {
+            Some(existing) => {
+                let magnitude = match (existing.magnitude_bps, change.magnitude_bps) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    _ => None,
+                };
+                *existing = MarketChange { magnitude_bps: magnitude, ..change };
+            }
+            None => {
+                batch.insert(key, change);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qenus_dataplane::{AmmFeature, DepthCurve, Feature, FeatureData, FeatureType, TokenInfo};
+
+    fn amm_feature(block: u64, pool: &str, price: f64) -> Feature {
+        Feature::new(
+            block,
+            Chain::Ethereum,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: pool.to_string(),
+                pool_type: "uniswap_v3".to_string(),
+                pool_id: None,
+                token0: TokenInfo { address: "0x1".to_string(), symbol: "WETH".to_string(), decimals: 18 },
+                token1: TokenInfo { address: "0x2".to_string(), symbol: "USDC".to_string(), decimals: 6 },
+                fee_tier: Some(500),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price: price,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve { sizes: HashMap::new(), ..Default::default() },
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_burst_within_block_is_coalesced() {
+        let market_state = MarketState::new(30);
+        let mut coalescer = ChangeCoalescer::new(&market_state, Duration::from_millis(50), 1.0);
+
+        market_state.ingest_feature(amm_feature(100, "0xa", 3000.0)).await.unwrap();
+        market_state.ingest_feature(amm_feature(100, "0xa", 3001.0)).await.unwrap();
+        market_state.ingest_feature(amm_feature(100, "0xb", 3000.0)).await.unwrap();
+        // Below the change threshold
+        market_state.ingest_feature(amm_feature(100, "0xb", 3000.01)).await.unwrap();
+        market_state.ingest_feature(amm_feature(101, "0xa", 3010.0)).await.unwrap();
+
+        match coalescer.next_batch().await {
+            Some(ChangeBatch::Changes(changes)) => {
+                assert_eq!(changes.len(), 2);
+                assert!(changes.iter().all(|c| c.block_number == 100));
+            }
+            other => panic!("unexpected batch: {:?}", other),
+        }
+
+        match coalescer.next_batch().await {
+            Some(ChangeBatch::Changes(changes)) => {
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].block_number, 101);
+                assert!(changes[0].magnitude_bps.unwrap() > 25.0);
+            }
+            other => panic!("unexpected batch: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_small_moves_add_up_within_a_batch() {
+        let market_state = MarketState::new(30);
+        market_state.ingest_feature(amm_feature(100, "0xa", 3000.0)).await.unwrap();
+        market_state.ingest_feature(amm_feature(100, "0xb", 3000.0)).await.unwrap();
+        let mut coalescer = ChangeCoalescer::new(&market_state, Duration::from_millis(50), 1.0);
+
+        // 0.6bps each: 0xa moves three times, 0xb once
+        for price in [3000.18, 3000.36, 3000.54] {
+            market_state.ingest_feature(amm_feature(101, "0xa", price)).await.unwrap();
+        }
+        market_state.ingest_feature(amm_feature(101, "0xb", 3000.18)).await.unwrap();
+
+        match coalescer.next_batch().await {
+            Some(ChangeBatch::Changes(changes)) => {
+                assert_eq!(changes.len(), 1);
+                assert!(matches!(&changes[0].key, ChangeKey::Pool { address, .. } if address == "0xa"));
+                assert!((changes[0].magnitude_bps.unwrap() - 1.8).abs() < 0.01);
+            }
+            other => panic!("unexpected batch: {:?}", other),
+        }
+    }
+}
@@ -14,6 +14,9 @@ pub enum IntelligenceError {
     #[error("Invalid candidate: {0}")]
     InvalidCandidate(String),
 
+    #[error("Invalid intent: {0}")]
+    InvalidIntent(String),
+
     #[error("Configuration error: {0}")]
     Config(#[from] config::ConfigError),
 
@@ -38,6 +41,9 @@ pub enum IntelligenceError {
     #[error("Dataplane error: {0}")]
     Dataplane(#[from] qenus_dataplane::DataplaneError),
 
+    #[error("Beta dataplane error: {0}")]
+    BetaDataplane(#[from] qenus_beta_dataplane::BetaDataplaneError),
+
     #[error("State error: {message}")]
     State { message: String },
 
@@ -0,0 +1,233 @@
+//! Readable reports of evaluated candidates
+//!
+//! Renders a candidate's simulated execution path, cost breakdown and
+//! decision as Markdown. Used by the `simulate` subcommand for fresh
+//! evaluations and by `explain` for records read back from the audit log.
+
+use std::fmt::Write;
+
+use crate::api::CandidateRecord;
+use crate::audit::{AuditOutcome, AuditRecord};
+
+/// Markdown report of a candidate record
+pub fn render_record(record: &CandidateRecord) -> String {
+    let mut out = String::new();
+    let candidate = &record.candidate;
+
+    let verdict = match record.should_execute {
+        Some(true) => "approved",
+        Some(false) => "rejected",
+        None => "stopped",
+    };
+    let _ = writeln!(out, "# {} {} ({})\n", candidate.strategy, candidate.asset, verdict);
+    render_header(&mut out, record);
+    render_body(&mut out, record);
+    out
+}
+
+/// Markdown report of an audit record, including the market inputs it was decided on
+pub fn render_audit_record(record: &AuditRecord) -> String {
+    let mut out = String::new();
+    let candidate = &record.record.candidate;
+
+    let _ = writeln!(out, "# {} {} ({})\n", candidate.strategy, candidate.asset, outcome_name(record.outcome));
+    render_header(&mut out, &record.record);
+    if !record.rejections.is_empty() {
+        let _ = writeln!(out, "- **Rejected by:** {}", record.rejections.join(", "));
+    }
+    render_body(&mut out, &record.record);
+
+    let inputs = &record.market_inputs;
+    if !inputs.gas.is_empty() || !inputs.sequencers.is_empty() || !inputs.pools.is_empty() {
+        let _ = writeln!(out, "\n## Market inputs\n");
+        for (chain, gas) in &inputs.gas {
+            let _ = writeln!(
+                out, "- Gas on {}: {:.2} gwei fast, {:.2} gwei base fee ({:.0}% full)",
+                chain, gas.fast_gas_price, gas.base_fee, gas.gas_used_ratio * 100.0
+            );
+        }
+        for (chain, sequencer) in &inputs.sequencers {
+            let _ = writeln!(out, "- Sequencer on {}: {}", chain, sequencer.status);
+        }
+        for (chain, pool) in &inputs.pools {
+            let _ = writeln!(
+                out, "- Pool {} on {} ({} {}/{}): mid price {:.6}",
+                pool.pool_address, chain, pool.pool_type, pool.token0_symbol, pool.token1_symbol, pool.mid_price
+            );
+        }
+    }
+    out
+}
+
+fn render_header(out: &mut String, record: &CandidateRecord) {
+    let candidate = &record.candidate;
+
+    let _ = writeln!(out, "- **Correlation id:** {}", record.correlation_id);
+    if let Some(intent_id) = record.intent_id {
+        let _ = writeln!(out, "- **Intent id:** {}", intent_id);
+    }
+    let _ = writeln!(out, "- **Recorded at:** {}", record.recorded_at.to_rfc3339());
+    let _ = writeln!(out, "- **Detected at:** {}", candidate.detected_at.to_rfc3339());
+    let _ = writeln!(out, "- **Spread:** {:.2} bps, confidence {:.2}", candidate.spread_bps, candidate.confidence);
+    if !candidate.legs.is_empty() {
+        let legs: Vec<String> = candidate.legs.iter()
+            .map(|(domain, action)| format!("{} {}", action, domain))
+            .collect();
+        let _ = writeln!(out, "- **Legs:** {}", legs.join(" → "));
+    }
+}
+
+fn render_body(out: &mut String, record: &CandidateRecord) {
+    if let Some(evaluation) = &record.evaluation {
+        let _ = writeln!(out, "\n## Execution path\n");
+        let _ = writeln!(out, "| # | Action | Domain | Protocol | Amount in | Amount out | Slippage (bps) | Cost (USD) |");
+        let _ = writeln!(out, "|---|---|---|---|---:|---:|---:|---:|");
+        for step in &evaluation.execution_path {
+            let protocol = match &step.pool {
+                Some(pool) => format!("{} `{}`", step.protocol, pool),
+                None => step.protocol.clone(),
+            };
+            let _ = writeln!(
+                out, "| {} | {} | {} | {} | {:.2} | {:.2} | {:.2} | {:.2} |",
+                step.step, step.action, step.domain, protocol, step.amount_in, step.amount_out, step.slippage_bps, step.cost_usd
+            );
+        }
+
+        let costs = &evaluation.costs;
+        let _ = writeln!(out, "\n## Costs\n");
+        let _ = writeln!(out, "| Cost | USD |");
+        let _ = writeln!(out, "|---|---:|");
+        for (name, usd) in [
+            ("Gas", costs.gas_usd),
+            ("Protocol fees", costs.protocol_fees_usd),
+            ("Bridge fees", costs.bridge_fees_usd),
+            ("Flash loan fees", costs.flashloan_fees_usd),
+            ("Slippage", costs.slippage_usd),
+            ("**Total**", costs.total_usd),
+        ] {
+            let _ = writeln!(out, "| {} | {:.2} |", name, usd);
+        }
+
+        let _ = writeln!(out, "\n## Result\n");
+        let _ = writeln!(out, "- **Size:** ${:.2}", evaluation.optimal_size_usd);
+        let _ = writeln!(out, "- **Net PnL:** ${:.2} ({:.2} bps)", evaluation.net_pnl_usd, evaluation.net_bps);
+        let _ = writeln!(out, "- **Success probability:** {:.1}%", evaluation.success_prob * 100.0);
+        if let Some(revert_prob) = evaluation.revert_prob {
+            let _ = writeln!(out, "- **Revert probability:** {:.1}%", revert_prob * 100.0);
+        }
+        if let Some(competition) = &evaluation.competition {
+            let _ = writeln!(
+                out, "- **Competition:** {:?}, inclusion {:.1}%, taken {:.1}%, front-run {:.1}%; bid {:.2} gwei (${:.2}), expected PnL ${:.2}",
+                competition.ordering,
+                competition.inclusion_prob * 100.0,
+                competition.taken_prob * 100.0,
+                competition.frontrun_prob * 100.0,
+                competition.recommended_priority_fee_gwei,
+                competition.priority_fee_usd,
+                competition.expected_pnl_usd,
+            );
+        }
+    }
+
+    if record.should_execute.is_some() {
+        let _ = writeln!(out, "\n## Decision\n");
+        if let Some(score) = record.score {
+            let _ = writeln!(out, "- **Score:** {:.3}", score);
+        }
+        for reason in &record.reasoning {
+            let _ = writeln!(out, "- {}", reason);
+        }
+        for warning in &record.warnings {
+            let _ = writeln!(out, "- ⚠️ {}", warning);
+        }
+
+        if !record.checks.is_empty() {
+            let _ = writeln!(out, "\n| Check | Passed | Value | Threshold |");
+            let _ = writeln!(out, "|---|---|---:|---:|");
+            for check in &record.checks {
+                let _ = writeln!(
+                    out, "| {} | {} | {:.4} | {:.4} |",
+                    check.name, if check.passed { "✅" } else { "❌" }, check.value, check.threshold
+                );
+            }
+        }
+    }
+
+    if let Some(report) = &record.verification {
+        let _ = writeln!(out, "\n## Verification\n\n{}", report.summary());
+    }
+    if let Some(error) = &record.error {
+        let _ = writeln!(out, "\n## Stopped\n\n{}", error);
+    }
+}
+
+fn outcome_name(outcome: AuditOutcome) -> &'static str {
+    match outcome {
+        AuditOutcome::Emitted => "emitted",
+        AuditOutcome::Approved => "approved",
+        AuditOutcome::Dropped => "dropped",
+        AuditOutcome::Rejected => "rejected",
+        AuditOutcome::Stopped => "stopped",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::audit::MarketInputs;
+    use crate::types::{Candidate, CostBreakdown, EvaluationResult, SimulatedStep};
+
+    #[test]
+    fn test_render_audit_record() {
+        let candidate = Candidate {
+            strategy: "dex_arb".to_string(),
+            asset: "WETH".to_string(),
+            spread_bps: 25.0,
+            legs: vec![
+                ("uniswap_v3 on Ethereum".to_string(), "buy".to_string()),
+                ("curve on Ethereum".to_string(), "sell".to_string()),
+            ],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        };
+        let evaluation = EvaluationResult {
+            net_pnl_usd: 42.0,
+            net_bps: 4.2,
+            optimal_size_usd: 100_000.0,
+            success_prob: 0.8,
+            costs: CostBreakdown { gas_usd: 12.5, total_usd: 12.5, ..Default::default() },
+            execution_path: vec![SimulatedStep {
+                step: 1,
+                action: "swap_buy".to_string(),
+                domain: "Ethereum".to_string(),
+                protocol: "uniswap_v3".to_string(),
+                amount_in: 100_000.0,
+                amount_out: 99_950.0,
+                slippage_bps: 3.0,
+                cost_usd: 12.5,
+                pool: None,
+                asset_in: None,
+                asset_out: None,
+            }],
+            competition: None,
+            revert_prob: None,
+        };
+        let mut record = CandidateRecord::stopped(candidate, Some(evaluation), "decision failed: boom");
+        record.should_execute = Some(false);
+        record.reasoning = vec!["Profit below threshold".to_string()];
+
+        let report = render_audit_record(&AuditRecord::new(record, MarketInputs::default()));
+
+        assert!(report.starts_with("# dex_arb WETH (rejected)"));
+        assert!(report.contains("buy uniswap_v3 on Ethereum → sell curve on Ethereum"));
+        assert!(report.contains("| 1 | swap_buy | Ethereum | uniswap_v3 | 100000.00 | 99950.00 | 3.00 | 12.50 |"));
+        assert!(report.contains("| **Total** | 12.50 |"));
+        assert!(report.contains("- **Net PnL:** $42.00 (4.20 bps)"));
+        assert!(report.contains("- Profit below threshold"));
+        assert!(report.contains("decision failed: boom"));
+        assert!(!report.contains("## Market inputs"));
+    }
+}
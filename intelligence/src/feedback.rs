@@ -12,6 +12,9 @@ use tracing::{info, warn, debug};
 use serde::{Deserialize, Serialize};
 
 use crate::{TradeIntent, Result, IntelligenceError};
+use crate::budget::StrategyBudgets;
+use crate::rebalance::InventoryLedger;
+use crate::simulator::CompetitionModel;
 
 /// Execution receipt from Orchestration layer
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +42,10 @@ pub struct ExecutionReceipt {
     
     /// Error message if failed
     pub error_message: Option<String>,
+    
+    /// Synthetic fill from the paper trader
+    #[serde(default)]
+    pub paper: bool,
 }
 
 /// Actual costs from execution
@@ -118,6 +125,15 @@ pub struct FeedbackProcessor {
     
     /// Adjustment factors for models
     adjustments: Arc<RwLock<ModelAdjustments>>,
+    
+    /// Inventory moved by successful executions
+    inventory: Option<Arc<InventoryLedger>>,
+    
+    /// Strategy budgets released and charged by receipts
+    budgets: Option<Arc<StrategyBudgets>>,
+    
+    /// Competition model learning inclusion outcomes from receipts
+    competition: Option<Arc<CompetitionModel>>,
 }
 
 /// Model adjustment factors learned from feedback
@@ -155,9 +171,30 @@ impl FeedbackProcessor {
             receipts: Arc::new(RwLock::new(HashMap::new())),
             error_stats: Arc::new(RwLock::new(HashMap::new())),
             adjustments: Arc::new(RwLock::new(ModelAdjustments::default())),
+            inventory: None,
+            budgets: None,
+            competition: None,
         }
     }
 
+    /// Settle successful executions into an inventory ledger
+    pub fn with_inventory(mut self, inventory: Arc<InventoryLedger>) -> Self {
+        self.inventory = Some(inventory);
+        self
+    }
+
+    /// Settle receipts into strategy budgets
+    pub fn with_budgets(mut self, budgets: Arc<StrategyBudgets>) -> Self {
+        self.budgets = Some(budgets);
+        self
+    }
+
+    /// Record receipt outcomes in the simulator's competition model
+    pub fn with_competition_model(mut self, competition: Arc<CompetitionModel>) -> Self {
+        self.competition = Some(competition);
+        self
+    }
+
     /// Register an intent for tracking
     pub async fn register_intent(&self, intent: TradeIntent) {
         let intent_id = intent.intent_id;
@@ -189,6 +226,31 @@ impl FeedbackProcessor {
         // Update model adjustments
         self.update_adjustments(intent, &receipt).await;
         
+        // Paper fills only inform the models above; no capital moved
+        let paper = intent.paper || receipt.paper;
+        
+        if let Some(inventory) = self.inventory.as_ref().filter(|_| receipt.success && !paper) {
+            if let Err(e) = inventory.settle(intent).await {
+                warn!("Intent {} not settled into inventory: {}", intent_id, e);
+            }
+        }
+        
+        if let Some(budgets) = self.budgets.as_ref().filter(|_| !paper) {
+            budgets.settle(
+                &intent.strategy,
+                intent_id,
+                receipt.actual_costs.gas_usd,
+                receipt.actual_pnl_usd,
+                intent.size_usd,
+                receipt.completed_at,
+            ).await;
+        }
+        
+        // Inclusion competition is on the chain the intent starts on
+        if let (Some(competition), Some(leg), false) = (&self.competition, intent.legs.first(), paper) {
+            competition.record_outcome(leg.domain, receipt.success).await;
+        }
+        
         // Store receipt (capture success before move)
         let success = receipt.success;
         let mut receipts = self.receipts.write().await;
@@ -401,6 +463,21 @@ impl FeedbackProcessor {
         }
     }
     
+    /// Real intents registered for tracking that have no receipt yet
+    ///
+    /// Paper intents are left out: they are never sent for execution.
+    pub async fn open_intents(&self) -> Vec<TradeIntent> {
+        let receipts = self.receipts.read().await;
+        let intents = self.intents.read().await;
+        
+        let mut open: Vec<TradeIntent> = intents.values()
+            .filter(|intent| !intent.paper && !receipts.contains_key(&intent.intent_id))
+            .cloned()
+            .collect();
+        open.sort_by_key(|intent| std::cmp::Reverse(intent.created_at));
+        open
+    }
+    
     /// Get current model adjustments
     pub async fn get_adjustments(&self) -> ModelAdjustments {
         self.adjustments.read().await.clone()
@@ -433,6 +510,8 @@ impl Default for FeedbackProcessor {
 mod tests {
     use super::*;
     use crate::{TradeLeg, TradeAction, TradeMetadata, MarketSnapshot, RiskFactor};
+    use crate::state::MarketState;
+    use qenus_dataplane::Chain;
     
     fn create_test_intent() -> TradeIntent {
         TradeIntent {
@@ -456,6 +535,7 @@ mod tests {
                 },
                 risk_factors: vec![],
             },
+            paper: false,
         }
     }
     
@@ -476,6 +556,31 @@ mod tests {
             execution_time_secs: 25.0,
             completed_at: Utc::now(),
             error_message: None,
+            paper: false,
+        }
+    }
+    
+    fn arbitrum_swap() -> TradeLeg {
+        TradeLeg {
+            domain: Chain::Arbitrum,
+            destination_domain: None,
+            action: TradeAction::Swap,
+            protocol: "uniswap_v3".to_string(),
+            asset_in: "USDC".to_string(),
+            asset_out: "WETH".to_string(),
+            amount_in: "100000000000".to_string(),
+            min_amount_out: "49950000000000000000".to_string(),
+            max_fee_bps: 30,
+            deadline: Utc::now() + Duration::seconds(30),
+            expected_out: "50000000000000000000".to_string(),
+            token_in: "0xaf88d065e77c8cc2239327c5edb3a432268e5831".to_string(),
+            token_out: "0x82af49447d8a07e3bd95bd0d56f35241523fbab1".to_string(),
+            amount_in_usd: 100_000.0,
+            expected_out_usd: 100_000.0,
+            target: None,
+            calldata: None,
+            pool: None,
+            split_of: None,
         }
     }
     
@@ -514,4 +619,44 @@ mod tests {
         // Should show negative error (actual < predicted)
         assert!(error.pnl_error_pct < 0.0);
     }
+    
+    #[tokio::test]
+    async fn test_failed_receipts_raise_competition_failure_rate() {
+        let competition = Arc::new(CompetitionModel::new(Arc::new(MarketState::new(30))));
+        let processor = FeedbackProcessor::new().with_competition_model(competition.clone());
+        let prior = competition.failure_rate(Chain::Arbitrum).await;
+        
+        for _ in 0..5 {
+            let mut intent = create_test_intent();
+            intent.legs.push(arbitrum_swap());
+            let intent_id = intent.intent_id;
+            processor.register_intent(intent).await;
+            processor.process_feedback(create_test_receipt(intent_id, false, 0.0)).await.unwrap();
+        }
+        
+        assert!(competition.failure_rate(Chain::Arbitrum).await > prior);
+        assert_eq!(competition.failure_rate(Chain::Ethereum).await, prior);
+    }
+    
+    #[tokio::test]
+    async fn test_paper_receipts_stay_out_of_capital_and_competition() {
+        let competition = Arc::new(CompetitionModel::new(Arc::new(MarketState::new(30))));
+        let processor = FeedbackProcessor::new().with_competition_model(competition.clone());
+        let prior = competition.failure_rate(Chain::Arbitrum).await;
+        
+        let mut intent = create_test_intent();
+        intent.legs.push(arbitrum_swap());
+        intent.paper = true;
+        let intent_id = intent.intent_id;
+        processor.register_intent(intent).await;
+        assert!(processor.open_intents().await.is_empty());
+        
+        let mut receipt = create_test_receipt(intent_id, false, 0.0);
+        receipt.paper = true;
+        processor.process_feedback(receipt).await.unwrap();
+        
+        // Prediction tracking still sees the fill
+        assert_eq!(processor.get_performance().await.total_intents, 1);
+        assert_eq!(competition.failure_rate(Chain::Arbitrum).await, prior);
+    }
 }
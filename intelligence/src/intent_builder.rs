@@ -2,24 +2,56 @@
 
 use std::sync::Arc;
 use std::collections::HashMap;
+use ethers::types::{H160, U256};
 use uuid::Uuid;
 use chrono::{Utc, Duration};
-use tracing::debug;
+use serde::Serialize;
+use tracing::{debug, warn};
 
 use crate::{
     TradeIntent, TradeLeg, TradeAction, TradeMetadata, MarketSnapshot, RiskFactor, RiskSeverity,
-    TradeDecision, Result, IntelligenceError,
+    TradeDecision, Result, IntelligenceError, SimulatedStep, CandidateDetails,
 };
-use crate::state::MarketState;
+use crate::calldata::{self, EncodedCall, TokenRef};
+use crate::simulator::amm::usd_price;
+use crate::state::{AmmState, MarketState};
+
+/// Pool and assets a leg trades
+struct LegRoute {
+    pool: Option<AmmState>,
+    asset_in: String,
+    asset_out: String,
+}
+
+/// On-chain form of a leg; the call stays empty when it cannot be encoded
+struct EncodedLeg {
+    token_in: TokenRef,
+    token_out: TokenRef,
+    amount_in: U256,
+    min_amount_out: U256,
+    expected_out: U256,
+    call: Option<EncodedCall>,
+}
 
 /// Intent builder
 pub struct IntentBuilder {
     market_state: Arc<MarketState>,
+    
+    /// Contract that receives swap outputs and flash loans; no calldata without it
+    executor: Option<H160>,
 }
 
 impl IntentBuilder {
     pub fn new(market_state: Arc<MarketState>) -> Self {
-        Self { market_state }
+        Self { market_state, executor: None }
+    }
+    
+    /// Encode calldata for legs executed by `executor`
+    pub fn with_executor(mut self, executor: &str) -> Result<Self> {
+        let address = calldata::parse_address(executor)
+            .ok_or_else(|| IntelligenceError::internal(format!("Invalid executor address: {}", executor)))?;
+        self.executor = Some(address);
+        Ok(self)
     }
 
     /// Build trade intent from approved decision
@@ -43,7 +75,7 @@ impl IntentBuilder {
             risk_factors,
         };
         
-        Ok(TradeIntent {
+        let intent = TradeIntent {
             intent_id,
             strategy: decision.candidate.strategy.clone(),
             asset: decision.candidate.asset.clone(),
@@ -55,51 +87,204 @@ impl IntentBuilder {
             ttl_seconds,
             created_at: Utc::now(),
             metadata,
-        })
+            paper: false,
+        };
+        
+        let flow = validate_flow(&intent)?;
+        debug!("Intent {} token flow: {:?}", intent.intent_id, flow.deltas);
+        Ok(intent)
     }
     
     async fn build_legs(&self, decision: &TradeDecision) -> Result<Vec<TradeLeg>> {
         let mut legs = Vec::new();
         let now = Utc::now();
         
-        for step in &decision.evaluation.execution_path {
-            let chain = if step.domain.contains("Ethereum") {
-                qenus_dataplane::Chain::Ethereum
-            } else if step.domain.contains("Arbitrum") {
-                qenus_dataplane::Chain::Arbitrum
-            } else if step.domain.contains("Optimism") {
-                qenus_dataplane::Chain::Optimism
-            } else {
-                qenus_dataplane::Chain::Base
+        // Legs on one chain share a deadline; bridges push it out for the legs after them
+        let mut deadline = now + Duration::seconds(30);
+        let mut after_bridge = false;
+        
+        // Calls per leg, and the flash loans to encode once their callbacks are known
+        let mut calls = Vec::new();
+        let mut loans = Vec::new();
+        
+        let path = &decision.evaluation.execution_path;
+        for (index, step) in path.iter().enumerate() {
+            let split = index.checked_sub(1).is_some_and(|previous| path[previous].splits_with(step)) ||
+                path.get(index + 1).is_some_and(|next| step.splits_with(next));
+            
+            // Bridge steps are "Source -> Destination"
+            let (chain, destination) = match step.domain.split_once("->") {
+                Some((source, destination)) => (parse_chain(step, source)?, Some(parse_chain(step, destination)?)),
+                None => (parse_chain(step, &step.domain)?, None),
             };
             
             let action = if step.action.contains("bridge") {
                 TradeAction::Bridge
+            } else if step.action == "flash_loan" {
+                TradeAction::FlashLoan
+            } else if step.action == "flash_repay" {
+                TradeAction::FlashRepay
+            } else if step.action == "liquidation_call" {
+                TradeAction::Liquidate
             } else {
                 TradeAction::Swap
             };
             
-            let deadline = now + Duration::seconds(if matches!(action, TradeAction::Bridge) { 300 } else { 30 });
+            if matches!(action, TradeAction::Bridge) {
+                deadline += Duration::seconds(300);
+                after_bridge = true;
+            } else if after_bridge {
+                deadline += Duration::seconds(30);
+                after_bridge = false;
+            }
             let min_amount_out = step.amount_out * (1.0 - (step.slippage_bps + 10.0) / 10000.0);
             let max_fee_bps = if step.protocol.contains("curve") { 10 } else { 30 };
             
+            let pools = self.market_state.get_amm_pools(chain).await;
+            // A bridge delivers the destination chain's token
+            let landing_pools = match destination {
+                Some(destination) => self.market_state.get_amm_pools(destination).await,
+                None => pools.clone(),
+            };
+            let route = resolve_route(&pools, step, &decision.candidate.asset)?;
+            let encoded = self.encode_leg(
+                chain, &action, step, &route, (&pools, &landing_pools), min_amount_out, deadline.timestamp() as u64, decision,
+            )?;
+            if matches!(action, TradeAction::FlashLoan) {
+                loans.push((legs.len(), encoded.token_out, encoded.expected_out));
+            }
+            calls.push(encoded.call.clone());
+            
             legs.push(TradeLeg {
                 domain: chain,
+                destination_domain: destination,
                 action,
                 protocol: step.protocol.clone(),
-                asset_in: decision.candidate.asset.clone(),
-                asset_out: decision.candidate.asset.clone(),
-                amount_in: format!("{:.6}", step.amount_in),
-                min_amount_out: format!("{:.6}", min_amount_out),
+                pool: step.pool.clone().or_else(|| route.pool.as_ref().map(|pool| pool.pool_address.clone())),
+                split_of: split.then_some(step.step),
+                asset_in: route.asset_in,
+                asset_out: route.asset_out,
+                amount_in: encoded.amount_in.to_string(),
+                min_amount_out: encoded.min_amount_out.to_string(),
                 max_fee_bps,
                 deadline,
-                expected_out: format!("{:.6}", step.amount_out),
+                expected_out: encoded.expected_out.to_string(),
+                token_in: format!("{:#x}", encoded.token_in.address),
+                token_out: format!("{:#x}", encoded.token_out.address),
+                amount_in_usd: step.amount_in,
+                expected_out_usd: step.amount_out,
+                target: encoded.call.as_ref().map(|call| format!("{:#x}", call.target)),
+                calldata: encoded.call.map(|call| format!("0x{}", hex::encode(&call.calldata))),
             });
         }
         
+        self.encode_flash_loans(&mut legs, &calls, &loans);
         Ok(legs)
     }
     
+    /// Encode each flash loan with the calls of the legs up to its repayment
+    ///
+    /// Those legs run inside the loan's callback. A loan whose callback legs
+    /// cannot all be encoded is left without calldata: borrowing without the
+    /// calls that repay it would only revert.
+    fn encode_flash_loans(&self, legs: &mut [TradeLeg], calls: &[Option<EncodedCall>], loans: &[(usize, TokenRef, U256)]) {
+        let Some(executor) = self.executor else {
+            return;
+        };
+        
+        for &(loan, asset, amount) in loans {
+            let (domain, protocol) = (legs[loan].domain, legs[loan].protocol.clone());
+            let repay = (loan + 1..legs.len()).find(|index| {
+                let leg = &legs[*index];
+                matches!(leg.action, TradeAction::FlashRepay) && leg.domain == domain && leg.asset_in == legs[loan].asset_out
+            });
+            let callback = repay.and_then(|repay| calls[loan + 1..=repay].iter().cloned().collect::<Option<Vec<_>>>());
+            let Some(callback) = callback else {
+                debug!("Flash loan on {} left without calldata (unencoded callback legs)", protocol);
+                continue;
+            };
+            
+            match calldata::encode_flash_loan(domain, &protocol, asset, amount, executor, &callback) {
+                Ok(call) => {
+                    legs[loan].target = Some(format!("{:#x}", call.target));
+                    legs[loan].calldata = Some(format!("0x{}", hex::encode(&call.calldata)));
+                }
+                Err(e) => warn!("Could not encode flash loan on {}: {}", protocol, e),
+            }
+        }
+    }
+    
+    /// Resolve token addresses and base-unit amounts, and encode the call
+    ///
+    /// `pools` are the leg's chain; `landing_pools` the chain its output
+    /// lands on. A token without an address or USD price there fails the leg:
+    /// its amounts could not be stated in base units.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_leg(
+        &self,
+        chain: qenus_dataplane::Chain,
+        action: &TradeAction,
+        step: &SimulatedStep,
+        route: &LegRoute,
+        (pools, landing_pools): (&[AmmState], &[AmmState]),
+        min_amount_out_usd: f64,
+        deadline: u64,
+        decision: &TradeDecision,
+    ) -> Result<EncodedLeg> {
+        let unresolved = |symbol: &str| IntelligenceError::InvalidIntent(format!(
+            "step {}: cannot resolve {} token on {}", step.step, symbol, step.domain
+        ));
+        let token = |pools: &[AmmState], symbol: &str| {
+            resolve_token(pools, route.pool.as_ref(), symbol).ok_or_else(|| unresolved(symbol))
+        };
+        let base_units = |usd: f64, (token, price): (TokenRef, f64)| {
+            calldata::to_base_units(usd / price, token.decimals).ok_or_else(|| IntelligenceError::InvalidIntent(format!(
+                "step {}: ${:.2} is not a token amount", step.step, usd
+            )))
+        };
+        
+        let token_in = token(pools, &route.asset_in)?;
+        let token_out = token(landing_pools, &route.asset_out)?;
+        let mut encoded = EncodedLeg {
+            token_in: token_in.0,
+            token_out: token_out.0,
+            amount_in: base_units(step.amount_in, token_in)?,
+            min_amount_out: base_units(min_amount_out_usd, token_out)?,
+            expected_out: base_units(step.amount_out, token_out)?,
+            call: None,
+        };
+        
+        let Some(executor) = self.executor else {
+            return Ok(encoded);
+        };
+        
+        let call = match action {
+            TradeAction::Swap => route.pool.as_ref().map(|pool| calldata::encode_swap(
+                chain, pool, encoded.token_in, encoded.token_out, encoded.amount_in, encoded.min_amount_out, executor, deadline,
+            )),
+            // Encoded with its callback once the legs up to the repayment are
+            TradeAction::FlashLoan => return Ok(encoded),
+            TradeAction::FlashRepay => Some(calldata::encode_flash_repay(chain, &step.protocol, encoded.token_in, encoded.amount_in)),
+            TradeAction::Liquidate => match &decision.candidate.details {
+                Some(CandidateDetails::Liquidation(details)) => {
+                    let (collateral, _) = token(pools, &details.collateral_asset)?;
+                    calldata::parse_address(&details.borrower).map(|borrower| {
+                        calldata::encode_liquidation(chain, collateral, encoded.token_in, borrower, encoded.amount_in)
+                    })
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        
+        match call {
+            Some(Ok(call)) => encoded.call = Some(call),
+            Some(Err(e)) => warn!("Could not encode {} leg on {}: {}", step.action, step.protocol, e),
+            None => debug!("Leg {} on {} left without calldata (no pool or borrower)", step.action, step.protocol),
+        }
+        Ok(encoded)
+    }
+    
     fn calculate_ttl(&self, decision: &TradeDecision) -> u64 {
         match decision.candidate.strategy.as_str() {
             "dex_arb" => 30,
@@ -157,6 +342,11 @@ impl IntentBuilder {
             });
         }
         
+        // Detector-specific risks (e.g. depeg severity)
+        if let Some(details) = &decision.candidate.details {
+            risks.extend(details.risk_factors());
+        }
+        
         risks
     }
 }
@@ -166,3 +356,618 @@ impl Default for IntentBuilder {
         Self::new(Arc::new(MarketState::default()))
     }
 }
+
+/// Pick the pool and assets a step trades
+///
+/// Uses the simulator's pool and assets when it recorded them; otherwise the
+/// deepest pool of the step's protocol that trades `asset`, buying `asset` on
+/// buy/enter steps and selling it on the rest. A swap with no such pool is
+/// an invalid intent rather than a no-op leg.
+fn resolve_route(pools: &[AmmState], step: &SimulatedStep, asset: &str) -> Result<LegRoute> {
+    if let (Some(asset_in), Some(asset_out)) = (&step.asset_in, &step.asset_out) {
+        let pool = match &step.pool {
+            Some(address) => pools.iter().find(|pool| &pool.pool_address == address).cloned(),
+            None => deepest(pools, |pool| matches_protocol(pool, &step.protocol) && trades(pool, asset_in, asset_out)),
+        };
+        return Ok(LegRoute { pool, asset_in: asset_in.clone(), asset_out: asset_out.clone() });
+    }
+    
+    if step.asset_in.is_some() || step.asset_out.is_some() || !step.action.starts_with("swap") {
+        let symbol = step.asset_in.clone().or_else(|| step.asset_out.clone()).unwrap_or_else(|| asset.to_string());
+        return Ok(LegRoute { pool: None, asset_in: symbol.clone(), asset_out: symbol });
+    }
+    
+    let pool = match &step.pool {
+        Some(address) => pools.iter().find(|pool| &pool.pool_address == address).cloned(),
+        None => venue_pool(pools, &step.protocol, asset),
+    };
+    let Some(pool) = pool else {
+        return Err(IntelligenceError::InvalidIntent(format!(
+            "step {}: no {} pool trading {} on {}", step.step, step.protocol, asset, step.domain
+        )));
+    };
+    
+    let other = counter_token(&pool, asset);
+    let buying = step.action.contains("buy") || step.action.contains("enter");
+    let (asset_in, asset_out) = if buying { (other, asset.to_string()) } else { (asset.to_string(), other) };
+    Ok(LegRoute { pool: Some(pool), asset_in, asset_out })
+}
+
+/// Token `symbol` on a chain and its USD price, preferring `pool`'s listing
+pub(crate) fn resolve_token(pools: &[AmmState], pool: Option<&AmmState>, symbol: &str) -> Option<(TokenRef, f64)> {
+    let token = pool.and_then(|pool| calldata::pool_token(pool, symbol))
+        .or_else(|| pools.iter().find_map(|pool| calldata::pool_token(pool, symbol)))?;
+    Some((token, usd_price(pools, symbol)?))
+}
+
+/// Token a buy of `asset` on `protocol` spends: the counter token of the pool
+/// `resolve_route` would pick for the leg
+pub(crate) fn funding_asset(pools: &[AmmState], protocol: &str, asset: &str) -> Option<String> {
+    venue_pool(pools, protocol, asset).map(|pool| counter_token(&pool, asset))
+}
+
+/// Deepest pool of `protocol` trading `a` against `b`
+pub(crate) fn pair_pool(pools: &[AmmState], protocol: &str, a: &str, b: &str) -> Option<AmmState> {
+    deepest(pools, |pool| matches_protocol(pool, protocol) && trades(pool, a, b))
+}
+
+/// Deepest pool of `protocol` that trades `asset`
+fn venue_pool(pools: &[AmmState], protocol: &str, asset: &str) -> Option<AmmState> {
+    deepest(pools, |pool| {
+        matches_protocol(pool, protocol) && (pool.token0_symbol == asset || pool.token1_symbol == asset)
+    })
+}
+
+fn deepest(pools: &[AmmState], filter: impl Fn(&AmmState) -> bool) -> Option<AmmState> {
+    let depth = |pool: &AmmState| pool.liquidity.parse::<f64>().unwrap_or(0.0);
+    pools.iter()
+        .filter(|pool| filter(pool))
+        .max_by(|a, b| depth(a).partial_cmp(&depth(b)).unwrap_or(std::cmp::Ordering::Equal))
+        .cloned()
+}
+
+fn matches_protocol(pool: &AmmState, protocol: &str) -> bool {
+    pool.pool_type.contains(protocol) || protocol.contains(&pool.pool_type)
+}
+
+fn counter_token(pool: &AmmState, asset: &str) -> String {
+    if pool.token0_symbol == asset { pool.token1_symbol.clone() } else { pool.token0_symbol.clone() }
+}
+
+fn trades(pool: &AmmState, a: &str, b: &str) -> bool {
+    (pool.token0_symbol == a && pool.token1_symbol == b) ||
+    (pool.token0_symbol == b && pool.token1_symbol == a)
+}
+
+/// Chain `name`d in a simulator step's domain
+fn parse_chain(step: &SimulatedStep, name: &str) -> Result<qenus_dataplane::Chain> {
+    name.trim().parse().map_err(|_| IntelligenceError::InvalidIntent(format!(
+        "step {}: unknown chain {:?} in domain {:?}", step.step, name.trim(), step.domain
+    )))
+}
+
+/// Share of a token's volume that rounding each leg to base units may leave over
+const FLOW_TOLERANCE: f64 = 1e-9;
+
+/// Net change of one token (an asset on one chain) over an intent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenDelta {
+    pub chain: qenus_dataplane::Chain,
+    pub asset: String,
+    
+    /// In base units of the token
+    pub delta: i128,
+    
+    /// In USD, at the prices the legs were built with
+    pub delta_usd: f64,
+    
+    /// Base units moved in or out, which rounding residues are measured against
+    #[serde(skip)]
+    volume: u128,
+}
+
+/// Token flow through an intent's legs
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IntentFlow {
+    /// Net change per (chain, asset), in order of first touch
+    pub deltas: Vec<TokenDelta>,
+}
+
+impl IntentFlow {
+    /// Net change of `asset` on `chain`, in base units
+    pub fn delta(&self, chain: qenus_dataplane::Chain, asset: &str) -> i128 {
+        self.find(chain, asset).map(|d| d.delta).unwrap_or(0)
+    }
+    
+    /// Net change of `asset` on `chain`, in USD
+    pub fn delta_usd(&self, chain: qenus_dataplane::Chain, asset: &str) -> f64 {
+        self.find(chain, asset).map(|d| d.delta_usd).unwrap_or(0.0)
+    }
+    
+    fn find(&self, chain: qenus_dataplane::Chain, asset: &str) -> Option<&TokenDelta> {
+        self.deltas.iter().find(|d| d.chain == chain && d.asset == asset)
+    }
+    
+    fn add(&mut self, chain: qenus_dataplane::Chain, asset: &str, (amount, amount_usd): (i128, f64)) {
+        let index = match self.deltas.iter().position(|d| d.chain == chain && d.asset == asset) {
+            Some(index) => index,
+            None => {
+                self.deltas.push(TokenDelta { chain, asset: asset.to_string(), delta: 0, delta_usd: 0.0, volume: 0 });
+                self.deltas.len() - 1
+            }
+        };
+        let delta = &mut self.deltas[index];
+        delta.delta += amount;
+        delta.delta_usd += amount_usd;
+        delta.volume += amount.unsigned_abs();
+    }
+}
+
+/// Open flash loan while walking the legs
+struct OpenLoan<'a> {
+    leg: usize,
+    domain: qenus_dataplane::Chain,
+    asset: &'a str,
+    amount: i128,
+}
+
+/// Check that an intent's legs form one coherent token flow
+///
+/// Each leg must consume what the previous leg delivered, on the chain it was
+/// delivered to, unless both are parts of one split swap; swaps must change
+/// asset; flash loans must be repaid on their chain with no bridge in
+/// between; deadlines must not decrease; and every token other than the
+/// funding input and the settlement output must net to zero. Deltas are
+/// kept per token in base units, so legs priced at different USD rates
+/// still have to hand over the same amounts. Returns the per-token deltas,
+/// or every violation found.
+pub fn validate_flow(intent: &TradeIntent) -> Result<IntentFlow> {
+    let mut violations = Vec::new();
+    let mut flow = IntentFlow::default();
+    let mut open_loans: Vec<OpenLoan> = Vec::new();
+    let mut previous_landing = None;
+    
+    for (index, leg) in intent.legs.iter().enumerate() {
+        let base_units = |field: &str, amount: &str, violations: &mut Vec<String>| {
+            amount.parse::<i128>().ok().filter(|amount| *amount >= 0).unwrap_or_else(|| {
+                violations.push(format!("leg {} {} {:?} is not a base-unit amount", index, field, amount));
+                0
+            })
+        };
+        let amount_in = base_units("amount_in", &leg.amount_in, &mut violations);
+        let amount_out = base_units("expected_out", &leg.expected_out, &mut violations);
+        let (spent, received) = ((-amount_in, -leg.amount_in_usd), (amount_out, leg.expected_out_usd));
+        
+        let landing = match (&leg.action, leg.destination_domain) {
+            (TradeAction::Bridge, Some(destination)) if destination != leg.domain => destination,
+            (TradeAction::Bridge, Some(_)) => {
+                violations.push(format!("leg {} bridges from {:?} to itself", index, leg.domain));
+                leg.domain
+            }
+            (TradeAction::Bridge, None) => {
+                violations.push(format!("leg {} bridges from {:?} with no destination", index, leg.domain));
+                leg.domain
+            }
+            (_, Some(destination)) => {
+                violations.push(format!("leg {} is not a bridge but lands on {:?}", index, destination));
+                leg.domain
+            }
+            (_, None) => leg.domain,
+        };
+        
+        if matches!(leg.action, TradeAction::Swap) && leg.asset_in == leg.asset_out {
+            violations.push(format!("leg {} swaps {} for itself", index, leg.asset_in));
+        }
+        
+        if let Some((previous, previous_landing)) = index.checked_sub(1).map(|i| &intent.legs[i]).zip(previous_landing) {
+            // Parts of a split swap run side by side instead of consuming each other
+            if leg.split_of.is_some() && leg.split_of == previous.split_of {
+                let same_swap = matches!(leg.action, TradeAction::Swap) && leg.domain == previous.domain &&
+                    leg.asset_in == previous.asset_in && leg.asset_out == previous.asset_out;
+                if !same_swap {
+                    violations.push(format!(
+                        "leg {} is split with leg {} but does not trade the same swap", index, index - 1
+                    ));
+                }
+            } else {
+                if previous.asset_out != leg.asset_in {
+                    violations.push(format!(
+                        "leg {} takes {} but leg {} delivers {}", index, leg.asset_in, index - 1, previous.asset_out
+                    ));
+                }
+                if previous_landing != leg.domain {
+                    violations.push(format!(
+                        "leg {} runs on {:?} but leg {} lands on {:?}", index, leg.domain, index - 1, previous_landing
+                    ));
+                }
+            }
+            if leg.deadline < previous.deadline {
+                violations.push(format!(
+                    "leg {} deadline {} is before leg {} deadline {}", index, leg.deadline, index - 1, previous.deadline
+                ));
+            }
+        }
+        
+        // Flash loans are atomic: everything until the repay stays in one transaction
+        for loan in open_loans.iter().filter(|loan| loan.domain != leg.domain || matches!(leg.action, TradeAction::Bridge)) {
+            violations.push(format!(
+                "leg {} leaves {:?} inside the leg {} flash loan", index, loan.domain, loan.leg
+            ));
+        }
+        
+        match leg.action {
+            TradeAction::FlashLoan => {
+                open_loans.push(OpenLoan { leg: index, domain: leg.domain, asset: &leg.asset_out, amount: amount_out });
+                flow.add(leg.domain, &leg.asset_out, received);
+            }
+            TradeAction::FlashRepay => {
+                match open_loans.iter().rposition(|loan| loan.domain == leg.domain && loan.asset == leg.asset_in) {
+                    Some(position) => {
+                        let loan = open_loans.remove(position);
+                        if amount_in < loan.amount {
+                            violations.push(format!(
+                                "leg {} repays {} of the {} {} borrowed in leg {}",
+                                index, amount_in, loan.amount, loan.asset, loan.leg
+                            ));
+                        }
+                    }
+                    None => violations.push(format!(
+                        "leg {} repays a {} flash loan on {:?} that was never taken", index, leg.asset_in, leg.domain
+                    )),
+                }
+                flow.add(leg.domain, &leg.asset_in, spent);
+            }
+            _ => {
+                flow.add(leg.domain, &leg.asset_in, spent);
+                flow.add(landing, &leg.asset_out, received);
+            }
+        }
+        
+        previous_landing = Some(landing);
+    }
+    
+    for loan in &open_loans {
+        violations.push(format!("leg {} flash loan of {} on {:?} is never repaid", loan.leg, loan.asset, loan.domain));
+    }
+    
+    // The intent spends its funding asset and ends in its settlement asset; nothing else may be left over
+    let funding = intent.legs.first().map(|leg| (leg.domain, leg.asset_in.as_str()));
+    let settlement = intent.legs.last().zip(previous_landing).map(|(leg, landing)| (landing, leg.asset_out.as_str()));
+    for delta in &flow.deltas {
+        let position = Some((delta.chain, delta.asset.as_str()));
+        let residue = delta.delta.unsigned_abs() as f64;
+        if position != funding && position != settlement && residue > delta.volume as f64 * FLOW_TOLERANCE {
+            violations.push(format!(
+                "{} on {:?} is left at {:+} base units after the last leg", delta.asset, delta.chain, delta.delta
+            ));
+        }
+    }
+    
+    if violations.is_empty() {
+        Ok(flow)
+    } else {
+        Err(IntelligenceError::InvalidIntent(violations.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use qenus_dataplane::{AmmFeature, Chain, DepthCurve, Feature, FeatureData, FeatureType, TokenInfo};
+    use crate::{Candidate, CostBreakdown, EvaluationResult};
+
+    fn weth_usdc_pool() -> Feature {
+        Feature::new(
+            1,
+            Chain::Ethereum,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640".to_string(),
+                pool_type: "uniswap_v3".to_string(),
+                pool_id: None,
+                token0: TokenInfo {
+                    address: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(),
+                    symbol: "WETH".to_string(),
+                    decimals: 18,
+                },
+                token1: TokenInfo {
+                    address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
+                    symbol: "USDC".to_string(),
+                    decimals: 6,
+                },
+                fee_tier: Some(500),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price: 2000.0,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve { sizes: HashMap::new(), ..Default::default() },
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        )
+    }
+
+    /// Builder over the WETH/USDC pool on Ethereum and Arbitrum
+    async fn builder() -> IntentBuilder {
+        let market_state = Arc::new(MarketState::new(30));
+        for chain in [Chain::Ethereum, Chain::Arbitrum] {
+            let mut pool = weth_usdc_pool();
+            pool.chain = chain;
+            market_state.ingest_feature(pool).await.unwrap();
+        }
+        IntentBuilder::new(market_state)
+    }
+
+    fn step(action: &str, domain: &str, assets: Option<(&str, &str)>, amount_in: f64, amount_out: f64) -> SimulatedStep {
+        SimulatedStep {
+            step: 0,
+            action: action.to_string(),
+            domain: domain.to_string(),
+            protocol: "uniswap_v3".to_string(),
+            amount_in,
+            amount_out,
+            slippage_bps: 0.0,
+            cost_usd: 0.0,
+            pool: None,
+            asset_in: assets.map(|(asset_in, _)| asset_in.to_string()),
+            asset_out: assets.map(|(_, asset_out)| asset_out.to_string()),
+        }
+    }
+
+    fn decision(steps: Vec<SimulatedStep>) -> TradeDecision {
+        TradeDecision {
+            should_execute: true,
+            evaluation: EvaluationResult {
+                net_pnl_usd: 100.0,
+                net_bps: 10.0,
+                optimal_size_usd: steps[0].amount_in,
+                success_prob: 0.9,
+                costs: CostBreakdown {
+                    gas_usd: 0.0,
+                    protocol_fees_usd: 0.0,
+                    bridge_fees_usd: 0.0,
+                    flashloan_fees_usd: 0.0,
+                    slippage_usd: 0.0,
+                    total_usd: 0.0,
+                },
+                execution_path: steps,
+                competition: None,
+                revert_prob: None,
+            },
+            candidate: Candidate {
+                strategy: "dex_arb".to_string(),
+                asset: "WETH".to_string(),
+                spread_bps: 20.0,
+                legs: vec![("Ethereum".to_string(), "swap".to_string())],
+                detected_at: Utc::now(),
+                confidence: 0.9,
+                first_seen_at: None,
+                details: None,
+            },
+            score: 1.0,
+            reasoning: Vec::new(),
+            checks: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_leg_carries_tokens_and_calldata() {
+        let market_state = Arc::new(MarketState::new(30));
+        market_state.ingest_feature(weth_usdc_pool()).await.unwrap();
+
+        let builder = IntentBuilder::new(market_state)
+            .with_executor("0x000000000000000000000000000000000000dEaD")
+            .unwrap();
+        let step = SimulatedStep {
+            step: 1,
+            action: "swap_buy".to_string(),
+            domain: "Ethereum".to_string(),
+            protocol: "uniswap_v3".to_string(),
+            amount_in: 20_000.0,
+            amount_out: 20_000.0,
+            slippage_bps: 0.0,
+            cost_usd: 0.0,
+            pool: None,
+            asset_in: None,
+            asset_out: None,
+        };
+
+        let intent = builder.build(&decision(vec![step])).await.unwrap();
+        let leg = &intent.legs[0];
+
+        assert_eq!(leg.asset_in, "USDC");
+        assert_eq!(leg.asset_out, "WETH");
+        assert_eq!(leg.token_in, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        // $20,000 of USDC
+        assert_eq!(leg.amount_in, "20000000000");
+        assert_eq!(leg.amount_in_usd, 20_000.0);
+        // 10 WETH less the 10bps buffer
+        assert_eq!(leg.min_amount_out, "9990000000000000000");
+        assert_eq!(leg.expected_out, "10000000000000000000");
+        assert_eq!(leg.target.as_deref(), Some("0xe592427a0aece92de3edee1f18e0157c05861564"));
+        assert!(leg.calldata.as_deref().unwrap().starts_with("0xc04b8d59"));
+    }
+
+    #[tokio::test]
+    async fn test_bridge_leg_lands_on_next_domain() {
+        let builder = builder().await;
+        let steps = vec![
+            step("swap_buy", "Arbitrum", Some(("USDC", "WETH")), 10_000.0, 9_990.0),
+            step("bridge", "Arbitrum -> Ethereum", None, 9_990.0, 9_980.0),
+            step("swap_sell", "Ethereum", Some(("WETH", "USDC")), 9_980.0, 10_030.0),
+        ];
+
+        let intent = builder.build(&decision(steps)).await.unwrap();
+        assert_eq!(intent.legs[1].domain, Chain::Arbitrum);
+        assert_eq!(intent.legs[1].destination_domain, Some(Chain::Ethereum));
+        assert!(intent.legs[0].deadline < intent.legs[1].deadline);
+        assert!(intent.legs[1].deadline < intent.legs[2].deadline);
+
+        let flow = validate_flow(&intent).unwrap();
+        assert_eq!(flow.delta(Chain::Arbitrum, "USDC"), -10_000_000_000);
+        assert_eq!(flow.delta(Chain::Ethereum, "WETH"), 0);
+        assert_eq!(flow.delta(Chain::Ethereum, "USDC"), 10_030_000_000);
+        assert!((flow.delta_usd(Chain::Ethereum, "USDC") - 10_030.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_swap_is_rejected() {
+        // No pool to take the swap through, so it cannot become a leg
+        let steps = vec![step("swap_sell", "Ethereum", None, 10_000.0, 10_030.0)];
+        let message = IntentBuilder::default().build(&decision(steps)).await.unwrap_err().to_string();
+        assert!(message.contains("no uniswap_v3 pool trading WETH on Ethereum"), "{}", message);
+
+        // A token with no address on the chain has no base-unit amounts
+        let steps = vec![step("swap_buy", "Ethereum", Some(("USDC", "DAI")), 10_000.0, 10_000.0)];
+        let message = builder().await.build(&decision(steps)).await.unwrap_err().to_string();
+        assert!(message.contains("step 0: cannot resolve DAI token on Ethereum"), "{}", message);
+
+        // An unknown chain is not quietly traded on another one
+        let steps = vec![step("swap_buy", "Polygon", Some(("USDC", "WETH")), 10_000.0, 10_000.0)];
+        let message = builder().await.build(&decision(steps)).await.unwrap_err().to_string();
+        assert!(message.contains(r#"step 0: unknown chain "Polygon" in domain "Polygon""#), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_flash_loan_flow_ends_flat() {
+        let builder = builder().await;
+        let steps = vec![
+            step("flash_loan", "Ethereum", Some(("USDC", "USDC")), 0.0, 50_000.0),
+            step("liquidation_call", "Ethereum", Some(("USDC", "WETH")), 50_000.0, 52_500.0),
+            step("swap_sell", "Ethereum", Some(("WETH", "USDC")), 52_500.0, 52_300.0),
+            step("flash_repay", "Ethereum", Some(("USDC", "USDC")), 50_025.0, 0.0),
+        ];
+
+        let intent = builder.build(&decision(steps)).await.unwrap();
+        let flow = validate_flow(&intent).unwrap();
+        assert_eq!(flow.delta(Chain::Ethereum, "WETH"), 0);
+        assert_eq!(flow.delta(Chain::Ethereum, "USDC"), 2_275_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_flash_loan_calldata_embeds_swap_cycle() {
+        use ethers::abi::{self, ParamType};
+        
+        let market_state = Arc::new(MarketState::new(30));
+        market_state.ingest_feature(weth_usdc_pool()).await.unwrap();
+        let mut curve = weth_usdc_pool();
+        if let FeatureData::Amm(amm) = &mut curve.data {
+            amm.pool_address = "0xbEbc44782C7dB0a1A60Cb6fe97d0b483032FF1C7".to_string();
+            amm.pool_type = "curve".to_string();
+        }
+        market_state.ingest_feature(curve).await.unwrap();
+        
+        let builder = IntentBuilder::new(market_state)
+            .with_executor("0x000000000000000000000000000000000000dEaD")
+            .unwrap();
+        let venue = |protocol: &str, step: SimulatedStep| SimulatedStep { protocol: protocol.to_string(), ..step };
+        let steps = vec![
+            venue("balancer", step("flash_loan", "Ethereum", Some(("USDC", "USDC")), 0.0, 20_000.0)),
+            step("swap_buy", "Ethereum", Some(("USDC", "WETH")), 20_000.0, 20_000.0),
+            venue("curve", step("swap_sell", "Ethereum", Some(("WETH", "USDC")), 20_000.0, 20_100.0)),
+            venue("balancer", step("flash_repay", "Ethereum", Some(("USDC", "USDC")), 20_000.0, 0.0)),
+        ];
+        
+        let intent = builder.build(&decision(steps)).await.unwrap();
+        let calldata = hex::decode(intent.legs[0].calldata.as_deref().unwrap().trim_start_matches("0x")).unwrap();
+        assert_eq!(hex::encode(&calldata[..4]), "5c38449e");
+        
+        // Vault flashLoan(recipient, tokens, amounts, userData): the callback rides in userData
+        let user_data = abi::decode(
+            &[
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::Address)),
+                ParamType::Array(Box::new(ParamType::Uint(256))),
+                ParamType::Bytes,
+            ],
+            &calldata[4..],
+        ).unwrap()[3].clone().into_bytes().unwrap();
+        let callback = calldata::decode_callback(&user_data).unwrap();
+        
+        // Both swaps, then the transfer back to the Vault, in leg order
+        assert_eq!(callback.len(), 3);
+        for (call, leg) in callback.iter().zip(&intent.legs[1..]) {
+            assert_eq!(Some(format!("{:#x}", call.target)), leg.target);
+            assert_eq!(Some(format!("0x{}", hex::encode(&call.calldata))), leg.calldata);
+        }
+        assert_eq!(hex::encode(&callback[0].calldata[..4]), "c04b8d59");
+        assert_eq!(hex::encode(&callback[1].calldata[..4]), "3df02124");
+        assert_eq!(hex::encode(&callback[2].calldata[..4]), "a9059cbb");
+        assert_eq!(intent.legs[3].token_in, format!("{:#x}", callback[2].target));
+    }
+    
+    #[tokio::test]
+    async fn test_split_swap_legs_run_side_by_side() {
+        let builder = builder().await;
+        let part = |pool: &str, amount_in: f64, amount_out: f64| SimulatedStep {
+            step: 2,
+            pool: Some(pool.to_string()),
+            ..step("swap_buy", "Ethereum", Some(("USDC", "WETH")), amount_in, amount_out)
+        };
+        let steps = vec![
+            step("flash_loan", "Ethereum", Some(("USDC", "USDC")), 0.0, 50_000.0),
+            part("0xfive", 30_000.0, 29_990.0),
+            part("0xthirty", 20_000.0, 19_980.0),
+            step("swap_sell", "Ethereum", Some(("WETH", "USDC")), 49_970.0, 50_100.0),
+            step("flash_repay", "Ethereum", Some(("USDC", "USDC")), 50_025.0, 0.0),
+        ];
+
+        let intent = builder.build(&decision(steps)).await.unwrap();
+        let splits: Vec<Option<usize>> = intent.legs.iter().map(|leg| leg.split_of).collect();
+        assert_eq!(splits, vec![None, Some(2), Some(2), None, None]);
+        assert_eq!(intent.legs[2].pool.as_deref(), Some("0xthirty"));
+
+        let flow = validate_flow(&intent).unwrap();
+        assert_eq!(flow.delta(Chain::Ethereum, "WETH"), 0);
+        assert_eq!(flow.delta(Chain::Ethereum, "USDC"), 75_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_flows_are_rejected() {
+        let builder = builder().await;
+
+        // Broken asset chain and an unrepaid flash loan
+        let steps = vec![
+            step("flash_loan", "Ethereum", Some(("USDC", "USDC")), 0.0, 50_000.0),
+            step("swap_buy", "Ethereum", Some(("WETH", "USDC")), 50_000.0, 50_000.0),
+        ];
+        let message = builder.build(&decision(steps)).await.unwrap_err().to_string();
+        assert!(message.contains("leg 1 takes WETH but leg 0 delivers USDC"), "{}", message);
+        assert!(message.contains("leg 0 flash loan of USDC on Ethereum is never repaid"), "{}", message);
+
+        // Leg run on the bridge's source chain, and deadlines out of order
+        let steps = vec![
+            step("bridge", "Arbitrum -> Ethereum", None, 10_000.0, 9_990.0),
+            step("swap_sell", "Ethereum", Some(("WETH", "USDC")), 9_990.0, 10_030.0),
+        ];
+        let mut intent = builder.build(&decision(steps)).await.unwrap();
+        intent.legs[1].domain = Chain::Arbitrum;
+        intent.legs[1].deadline = intent.legs[0].deadline - Duration::seconds(1);
+
+        let message = validate_flow(&intent).unwrap_err().to_string();
+        assert!(message.contains("leg 1 runs on Arbitrum but leg 0 lands on Ethereum"), "{}", message);
+        assert!(message.contains("leg 1 deadline"), "{}", message);
+        assert!(message.contains("WETH on Ethereum is left at +4995000000000000000 base units"), "{}", message);
+
+        // Worth the same in USD, but the sell takes more WETH than the bridge delivered
+        let steps = vec![
+            step("bridge", "Arbitrum -> Ethereum", None, 10_000.0, 9_990.0),
+            step("swap_sell", "Ethereum", Some(("WETH", "USDC")), 9_990.0, 10_030.0),
+        ];
+        let mut intent = builder.build(&decision(steps)).await.unwrap();
+        intent.legs[1].amount_in = "5000000000000000000".to_string();
+
+        let message = validate_flow(&intent).unwrap_err().to_string();
+        assert!(message.contains("WETH on Ethereum is left at -5000000000000000 base units"), "{}", message);
+
+        // Bridged in, then swapped WETH → WETH
+        let steps = vec![
+            step("bridge", "Arbitrum -> Ethereum", None, 10_000.0, 9_990.0),
+            step("swap_sell", "Ethereum", Some(("WETH", "WETH")), 9_990.0, 10_030.0),
+        ];
+        let message = builder.build(&decision(steps)).await.unwrap_err().to_string();
+        assert!(message.contains("leg 1 swaps WETH for itself"), "{}", message);
+    }
+}
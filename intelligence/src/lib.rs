@@ -14,23 +14,43 @@ pub mod state;
 pub mod detectors;
 pub mod simulator;
 pub mod decision;
+pub mod budget;
 pub mod intent_builder;
 pub mod feedback;
 pub mod ingestion;
 pub mod error;
 pub mod types;
 pub mod config;
+pub mod api;
+pub mod audit;
+pub mod calldata;
+pub mod verifier;
+pub mod backtest;
+pub mod sweep;
+pub mod paper;
+pub mod opportunity;
+pub mod rebalance;
+pub mod explain;
 
 pub use error::{IntelligenceError, Result};
 pub use types::*;
-pub use state::{MarketState, MarketStateStats, AmmState, BridgeState, GasState, FlashLoanState, SequencerState};
-pub use detectors::{TriangleArbDetector, DexArbDetector, DetectorManager};
+pub use state::{MarketState, BlockView, MarketStateStats, MarketStateSnapshot, FeedStatus, MarketChange, ChangeKey, AmmState, BridgeState, GasState, GasSample, FlashLoanState, SequencerState, WalletState};
+pub use detectors::{Detector, TriangleArbDetector, DexArbDetector, DetectorManager, DetectorMetrics, LiquidationDetector, DepegDetector, FlashArbDetector, ScriptDetector, DetectionScope, ChangeBatch, ChangeCoalescer};
 pub use ingestion::FeatureIngestionManager;
-pub use config::{IntelligenceConfig, DataplaneConnectionConfig, DetectionConfig};
+pub use config::{IntelligenceConfig, DataplaneConnectionConfig, DetectionConfig, ApiConfig, AuditConfig, ExecutionConfig, VerificationConfig, DeviationAction, BacktestConfig, PaperTradingConfig, InventoryAmount, RebalanceConfig, ScriptingConfig, ReallocationConfig};
 pub use simulator::TradeSimulator;
-pub use decision::{DecisionEngine, TradeDecision, PositionTracker};
-pub use intent_builder::IntentBuilder;
+pub use decision::{DecisionEngine, TradeDecision, PolicyCheck, PositionTracker, RiskSizing};
+pub use budget::{StrategyBudgets, BudgetUsage};
+pub use intent_builder::{IntentBuilder, IntentFlow, TokenDelta, validate_flow};
 pub use feedback::{FeedbackProcessor, ExecutionReceipt, ActualCosts, PredictionError, ModelPerformance, ModelAdjustments};
+pub use api::{OperatorState, CandidateRecord, KillSwitch};
+pub use audit::{AuditLog, AuditRecord, AuditQuery, AuditOutcome, MarketInputs};
+pub use verifier::{IntentVerifier, EvmStateCache, VerificationReport, VerificationStatus};
+pub use backtest::{Backtester, BacktestReport, BacktestFill, FillOutcome, load_features};
+pub use sweep::{SweepRunner, SweepSpec, SweepReport, SweepResult, SweepParam, WalkForwardResult};
+pub use paper::{PaperTrader, EntryQuote};
+pub use opportunity::{Opportunity, OpportunityTracker};
+pub use rebalance::{InventoryLedger, RebalancePlanner};
 
 /// Version of the intelligence layer
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
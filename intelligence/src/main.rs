@@ -2,6 +2,7 @@
 //!
 //! Consumes beta_dataplane features and generates trade intents.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use clap::{Arg, Command};
 use tokio::signal;
@@ -10,7 +11,14 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use qenus_intelligence::{
     Result, VERSION, IntelligenceConfig, MarketState, DetectorManager, FeatureIngestionManager,
+    ChangeBatch, ChangeCoalescer, Candidate, TradeSimulator, DecisionEngine, IntentBuilder, FeedbackProcessor,
+    OperatorState, CandidateRecord, AuditLog, AuditRecord, AuditQuery, AuditOutcome, AuditConfig, MarketInputs,
+    IntelligenceError, IntentVerifier, VerificationStatus, Backtester, load_features,
+    SweepRunner, SweepSpec, PaperTrader, OpportunityTracker, InventoryLedger, RebalancePlanner,
+    MarketStateSnapshot, StrategyBudgets, StrategyConfig, ReallocationConfig,
 };
+use qenus_intelligence::explain;
+use qenus_intelligence::rebalance::REBALANCE_STRATEGY;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -38,6 +46,12 @@ async fn main() -> Result<()> {
                 .help("Run in dry-run mode (no actual intent emission)")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("paper")
+                .long("paper")
+                .help("Paper-trade intents against later market state instead of emitting them")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("generate-config")
                 .long("generate-config")
@@ -52,8 +66,156 @@ async fn main() -> Result<()> {
                 .help("Log level (trace, debug, info, warn, error)")
                 .default_value("info"),
         )
+        .subcommand(
+            Command::new("audit")
+                .about("Query the decision audit log")
+                .arg(Arg::new("dir").long("dir").value_name("DIR").help("Audit log directory (default: from config)"))
+                .arg(Arg::new("strategy").long("strategy").value_name("NAME"))
+                .arg(Arg::new("asset").long("asset").value_name("SYMBOL"))
+                .arg(Arg::new("since").long("since").value_name("RFC3339"))
+                .arg(Arg::new("until").long("until").value_name("RFC3339"))
+                .arg(
+                    Arg::new("rejection")
+                        .long("rejection")
+                        .value_name("CHECK")
+                        .help("Failed check name prefix (e.g. min_profit, sequencer_healthy) or stop reason"),
+                )
+                .arg(
+                    Arg::new("outcome")
+                        .long("outcome")
+                        .value_name("OUTCOME")
+                        .value_parser(["emitted", "approved", "dropped", "rejected", "stopped"]),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Only the most recent N matches"),
+                ),
+        )
+        .subcommand(
+            Command::new("backtest")
+                .about("Replay recorded features through detection and decision, and report PnL")
+                .arg(
+                    Arg::new("features")
+                        .long("features")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("JSONL file of recorded features"),
+                )
+                .arg(Arg::new("from").long("from").value_name("RFC3339"))
+                .arg(Arg::new("until").long("until").value_name("RFC3339"))
+                .arg(Arg::new("out").long("out").value_name("DIR").help("Report directory (default: from config)"))
+                .arg(Arg::new("latency-ms").long("latency-ms").value_name("MS").value_parser(clap::value_parser!(u64)))
+                .arg(Arg::new("slippage-bps").long("slippage-bps").value_name("BPS").value_parser(clap::value_parser!(f64)))
+                .arg(Arg::new("failure-rate").long("failure-rate").value_name("P").value_parser(clap::value_parser!(f64)))
+                .arg(Arg::new("seed").long("seed").value_name("N").value_parser(clap::value_parser!(u64))),
+        )
+        .subcommand(
+            Command::new("sweep")
+                .about("Backtest a grid or random search of strategy parameters, with walk-forward folds")
+                .arg(
+                    Arg::new("features")
+                        .long("features")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("JSONL file of recorded features"),
+                )
+                .arg(
+                    Arg::new("spec")
+                        .long("spec")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("YAML sweep spec (strategy, params, search, folds, objective)"),
+                )
+                .arg(Arg::new("out").long("out").value_name("DIR").help("Results directory (default: from config)")),
+        )
+        .subcommand(
+            Command::new("simulate")
+                .about("Evaluate one candidate against a market state snapshot and print the result")
+                .arg(
+                    Arg::new("snapshot")
+                        .long("snapshot")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("Market state or operator snapshot JSON"),
+                )
+                .arg(
+                    Arg::new("candidate")
+                        .long("candidate")
+                        .value_name("FILE")
+                        .conflicts_with_all(["strategy", "asset", "leg"])
+                        .help("Candidate JSON, e.g. copied from an audit record"),
+                )
+                .arg(Arg::new("strategy").long("strategy").value_name("NAME").required_unless_present("candidate"))
+                .arg(Arg::new("asset").long("asset").value_name("SYMBOL").required_unless_present("candidate"))
+                .arg(
+                    Arg::new("leg")
+                        .long("leg")
+                        .value_name("DOMAIN:ACTION")
+                        .action(clap::ArgAction::Append)
+                        .help("Execution leg, e.g. \"uniswap_v3 on Ethereum:buy\" (repeatable)"),
+                )
+                .arg(
+                    Arg::new("spread-bps")
+                        .long("spread-bps")
+                        .value_name("BPS")
+                        .value_parser(clap::value_parser!(f64))
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::new("confidence")
+                        .long("confidence")
+                        .value_name("P")
+                        .value_parser(clap::value_parser!(f64))
+                        .default_value("0.9"),
+                )
+                .arg(report_format_arg()),
+        )
+        .subcommand(
+            Command::new("explain")
+                .about("Render one audited candidate as a readable report")
+                .arg(
+                    Arg::new("id")
+                        .value_name("ID")
+                        .required(true)
+                        .help("Intent id or correlation id"),
+                )
+                .arg(Arg::new("dir").long("dir").value_name("DIR").help("Audit log directory (default: from config)"))
+                .arg(report_format_arg()),
+        )
         .get_matches();
 
+    // Audit queries print JSONL and exit before logging is set up
+    if let Some(audit_matches) = matches.subcommand_matches("audit") {
+        let dir = match (audit_matches.get_one::<String>("dir"), matches.get_one::<String>("config")) {
+            (Some(dir), _) => dir.clone(),
+            (None, Some(config_path)) => IntelligenceConfig::from_file(config_path)?.audit.dir,
+            (None, None) => AuditConfig::default().dir,
+        };
+        return run_audit_query(&dir, audit_matches).await;
+    }
+
+    // Offline reports print to stdout and exit before logging is set up
+    if let Some(explain_matches) = matches.subcommand_matches("explain") {
+        let dir = match (explain_matches.get_one::<String>("dir"), matches.get_one::<String>("config")) {
+            (Some(dir), _) => dir.clone(),
+            (None, Some(config_path)) => IntelligenceConfig::from_file(config_path)?.audit.dir,
+            (None, None) => AuditConfig::default().dir,
+        };
+        return run_explain(&dir, explain_matches).await;
+    }
+    if let Some(simulate_matches) = matches.subcommand_matches("simulate") {
+        let config = match matches.get_one::<String>("config") {
+            Some(config_path) => IntelligenceConfig::from_file(config_path)?,
+            None => IntelligenceConfig::from_business_module_or_default(
+                matches.get_one::<String>("business-path").map(|s| s.as_str()),
+            ),
+        };
+        return run_simulate(config, simulate_matches).await;
+    }
+
     // Initialize logging
     let log_level = matches.get_one::<String>("log-level").unwrap();
     init_logging(log_level)?;
@@ -69,7 +231,7 @@ async fn main() -> Result<()> {
     info!(version = VERSION, "🧠 Qenus Intelligence Layer starting...");
 
     // Load configuration
-    let config = if let Some(config_path) = matches.get_one::<String>("config") {
+    let mut config = if let Some(config_path) = matches.get_one::<String>("config") {
         info!("Loading config from: {}", config_path);
         IntelligenceConfig::from_file(config_path)?
     } else {
@@ -78,10 +240,20 @@ async fn main() -> Result<()> {
         IntelligenceConfig::from_business_module_or_default(business_path)
     };
 
+    if let Some(backtest_matches) = matches.subcommand_matches("backtest") {
+        return run_backtest(config, backtest_matches).await;
+    }
+    if let Some(sweep_matches) = matches.subcommand_matches("sweep") {
+        return run_sweep(config, sweep_matches).await;
+    }
+
     let dry_run = matches.get_flag("dry-run");
     if dry_run {
         warn!("🔶 Running in DRY-RUN mode - no intents will be emitted");
     }
+    if matches.get_flag("paper") {
+        config.paper_trading.enabled = true;
+    }
 
     // Initialize market state
     info!("Initializing market state (TTL: {}s)", config.market_state_ttl_secs);
@@ -98,11 +270,104 @@ async fn main() -> Result<()> {
             .join(", ")
     );
 
-    let detector_manager = DetectorManager::new(
-        config.get_strategy("triangle_arb").cloned(),
-        config.get_strategy("dex_arb").cloned(),
-        market_state.clone(),
+    let detector_manager = Arc::new(DetectorManager::from_config(&config, market_state.clone()));
+
+    // Initialize simulation, decision and intent stages
+    let max_position_per_asset = config.strategies.values()
+        .map(|s| s.max_position_usd)
+        .fold(0.0, f64::max);
+    let audit = config.audit.enabled.then(|| Arc::new(AuditLog::new(&config.audit)));
+    if audit.is_some() {
+        info!("Decision audit log: {}", config.audit.dir);
+    }
+
+    let intent_builder = match &config.execution.executor_address {
+        Some(executor) => {
+            info!("Encoding leg calldata for executor {}", executor);
+            IntentBuilder::new(market_state.clone()).with_executor(executor)?
+        }
+        None => IntentBuilder::new(market_state.clone()),
+    };
+
+    let verifier = IntentVerifier::from_config(
+        &config.verification,
+        config.execution.executor_address.as_deref(),
+        market_state.evm_state(),
+    )?;
+    if verifier.is_some() {
+        info!("Verifying intents in local EVM (tolerance {}bps)", config.verification.tolerance_bps);
+    }
+
+    let inventory = config.rebalance.enabled
+        .then(|| Arc::new(InventoryLedger::new(&config.rebalance.opening_balances)));
+    let budgets = Arc::new(StrategyBudgets::new(&config.reallocation));
+    let feedback = match &inventory {
+        Some(inventory) => FeedbackProcessor::new().with_inventory(inventory.clone()),
+        None => FeedbackProcessor::new(),
+    };
+    let simulator = TradeSimulator::new(market_state.clone());
+    let feedback = Arc::new(
+        feedback
+            .with_budgets(budgets.clone())
+            .with_competition_model(simulator.competition_model().clone())
     );
+    let paper = config.paper_trading.enabled.then(|| {
+        info!("📝 Paper trading: filling intents {}ms after decision", config.paper_trading.delay_ms);
+        Arc::new(PaperTrader::new(market_state.clone(), feedback.clone(), &config.paper_trading))
+    });
+
+    let pipeline = Pipeline {
+        simulator,
+        decision_engine: Arc::new(
+            DecisionEngine::new(market_state.clone(), max_position_per_asset).with_budgets(budgets.clone())
+        ),
+        intent_builder,
+        verifier,
+        feedback,
+        paper,
+        opportunities: OpportunityTracker::new(&config.detection),
+        audit,
+    };
+
+    let operator = Arc::new(OperatorState::new(
+        &config,
+        market_state.clone(),
+        detector_manager.clone(),
+        pipeline.decision_engine.clone(),
+        pipeline.feedback.clone(),
+    ));
+
+    if let Some(inventory) = inventory {
+        info!("⚖️ Rebalancing inventory every {}s", config.rebalance.interval_secs);
+        let planner = RebalancePlanner::new(market_state.clone(), inventory, &config.rebalance);
+        tokio::spawn(run_rebalance_loop(
+            planner,
+            pipeline.feedback.clone(),
+            operator.clone(),
+            pipeline.audit.clone(),
+            market_state.clone(),
+            config.rebalance.interval_secs,
+            dry_run,
+        ));
+    }
+
+    if config.reallocation.enabled {
+        info!("💰 Reallocating strategy capital every {}s", config.reallocation.interval_secs);
+        tokio::spawn(run_reallocation_loop(budgets, config.strategies.clone(), config.reallocation.clone()));
+    }
+
+    if config.api.enabled {
+        if config.api.admin_token.is_none() {
+            warn!("Operator API has no admin_token; admin actions are disabled");
+        }
+        let operator = operator.clone();
+        let bind_addr = config.api.bind_addr.clone();
+        tokio::spawn(async move {
+            if let Err(e) = qenus_intelligence::api::serve(operator, &bind_addr).await {
+                error!("Operator API stopped: {}", e);
+            }
+        });
+    }
 
     // Initialize feature ingestion
     info!("Initializing feature ingestion (mode: {})", config.dataplane.mode);
@@ -153,7 +418,7 @@ async fn main() -> Result<()> {
     let shutdown_signal = setup_shutdown_signal();
 
     tokio::select! {
-        _ = run_detection_loop(detector_manager, market_state, config, dry_run) => {
+        _ = run_detection_loop(detector_manager, pipeline, operator, market_state, config, dry_run) => {
             info!("Detection loop stopped");
         }
         _ = shutdown_signal => {
@@ -190,9 +455,23 @@ fn init_logging(log_level: &str) -> Result<()> {
     Ok(())
 }
 
+/// Simulation, decision and intent stages run on each candidate
+struct Pipeline {
+    simulator: TradeSimulator,
+    decision_engine: Arc<DecisionEngine>,
+    intent_builder: IntentBuilder,
+    verifier: Option<IntentVerifier>,
+    feedback: Arc<FeedbackProcessor>,
+    paper: Option<Arc<PaperTrader>>,
+    opportunities: OpportunityTracker,
+    audit: Option<Arc<AuditLog>>,
+}
+
 /// Main detection loop
 async fn run_detection_loop(
-    detector_manager: DetectorManager,
+    detector_manager: Arc<DetectorManager>,
+    pipeline: Pipeline,
+    operator: Arc<OperatorState>,
     market_state: Arc<MarketState>,
     config: IntelligenceConfig,
     dry_run: bool,
@@ -201,30 +480,69 @@ async fn run_detection_loop(
         tokio::time::Duration::from_secs(config.detection.interval_secs)
     );
 
+    let mut coalescer = config.detection.event_driven.then(|| {
+        ChangeCoalescer::new(
+            &market_state,
+            tokio::time::Duration::from_millis(config.detection.coalesce_window_ms),
+            config.detection.min_change_bps,
+        )
+    });
+
     loop {
-        interval.tick().await;
+        // Interval ticks scan everything; change batches re-run affected detectors only
+        let detection = tokio::select! {
+            _ = interval.tick() => detector_manager.detect_all().await,
+            Some(batch) = next_change_batch(&mut coalescer) => match batch {
+                ChangeBatch::Changes(changes) => detector_manager.detect_changes(&changes).await,
+                ChangeBatch::Lagged(missed) => {
+                    warn!("Missed {} market changes, running full scan", missed);
+                    detector_manager.detect_all().await
+                }
+            },
+        };
+
+        pipeline.opportunities.prune(market_state.now()).await;
 
-        // Run detection
-        match detector_manager.detect_all().await {
-            Ok(candidates) => {
+        match detection {
+            Ok(mut candidates) => {
                 if !candidates.is_empty() {
                     info!("💡 Detected {} candidates", candidates.len());
 
-                    for candidate in candidates.iter().take(config.detection.max_candidates_per_cycle) {
-                        if candidate.confidence >= config.detection.min_confidence {
-                            info!(
-                                "  ✅ {} on {}: spread={:.2}bps, confidence={:.2}",
-                                candidate.strategy,
-                                candidate.asset,
-                                candidate.spread_bps,
-                                candidate.confidence
-                            );
+                    let max_candidates = config.detection.max_candidates_per_cycle;
+                    for (index, candidate) in candidates.iter_mut().enumerate() {
+                        // Inputs as the decision sees them, captured before it runs
+                        let inputs = match &pipeline.audit {
+                            Some(_) => MarketInputs::capture(&market_state, candidate).await,
+                            None => MarketInputs::default(),
+                        };
+
+                        if index >= max_candidates {
+                            let reason = format!("over max_candidates_per_cycle ({})", max_candidates);
+                            audit(pipeline.audit.as_deref(), CandidateRecord::stopped(candidate.clone(), None, reason), inputs).await;
+                            continue;
+                        }
 
-                            if !dry_run {
-                                // TODO: Phase 2-4 - Simulate, decide, build intent
-                                debug!("    Would simulate and emit intent");
-                            }
+                        pipeline.opportunities.observe(candidate).await;
+                        if candidate.confidence < config.detection.min_confidence {
+                            let reason = format!(
+                                "confidence {:.2} below min_confidence {:.2}",
+                                candidate.confidence, config.detection.min_confidence
+                            );
+                            audit(pipeline.audit.as_deref(), CandidateRecord::stopped(candidate.clone(), None, reason), inputs).await;
+                            continue;
                         }
+
+                        info!(
+                            "  ✅ {} on {}: spread={:.2}bps, confidence={:.2}",
+                            candidate.strategy,
+                            candidate.asset,
+                            candidate.spread_bps,
+                            candidate.confidence
+                        );
+
+                        let record = process_candidate(&pipeline, &operator, &config, candidate.clone(), dry_run).await;
+                        audit(pipeline.audit.as_deref(), record.clone(), inputs).await;
+                        operator.record(record).await;
                     }
                 } else {
                     // Check if feeds are stale
@@ -243,6 +561,344 @@ async fn run_detection_loop(
     }
 }
 
+/// Append one record to the decision audit log, when it is enabled
+async fn audit(audit: Option<&AuditLog>, record: CandidateRecord, inputs: MarketInputs) {
+    if let Some(audit) = audit {
+        if let Err(e) = audit.append(&AuditRecord::new(record, inputs)).await {
+            error!("Failed to write audit record: {}", e);
+        }
+    }
+}
+
+/// Periodically plan inventory transfers and emit them as intents
+async fn run_rebalance_loop(
+    planner: RebalancePlanner,
+    feedback: Arc<FeedbackProcessor>,
+    operator: Arc<OperatorState>,
+    audit_log: Option<Arc<AuditLog>>,
+    market_state: Arc<MarketState>,
+    interval_secs: u64,
+    dry_run: bool,
+) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+        if operator.is_paused(REBALANCE_STRATEGY).await || operator.kill_switch().await.is_some() {
+            continue;
+        }
+
+        for intent in planner.plan(&feedback.open_intents().await).await {
+            let leg = &intent.legs[0];
+            info!(
+                "⚖️ Rebalance ${:.0} {} {:?} → {:?} via {} (cost ${:.2})",
+                intent.size_usd,
+                intent.asset,
+                leg.domain,
+                leg.destination_domain.unwrap_or(leg.domain),
+                leg.protocol,
+                -intent.expected_pnl_usd
+            );
+            if audit_log.is_some() {
+                let record = CandidateRecord::rebalance(&intent, !dry_run);
+                let inputs = MarketInputs::capture(&market_state, &record.candidate).await;
+                audit(audit_log.as_deref(), record, inputs).await;
+            }
+            if !dry_run {
+                info!("    📤 Emitting intent {}", intent.intent_id);
+                feedback.register_intent(intent).await;
+            }
+        }
+    }
+}
+
+/// Periodically move capital between strategy budgets by trailing risk-adjusted returns
+async fn run_reallocation_loop(
+    budgets: Arc<StrategyBudgets>,
+    strategies: HashMap<String, StrategyConfig>,
+    config: ReallocationConfig,
+) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(config.interval_secs));
+
+    loop {
+        interval.tick().await;
+        for (strategy, capital_usd) in budgets.reallocate(&strategies, config.min_share, chrono::Utc::now()).await {
+            info!("💰 {} capital allocation: ${:.0}", strategy, capital_usd);
+        }
+    }
+}
+
+/// Simulate, decide and (unless halted) build an intent for one candidate
+async fn process_candidate(
+    pipeline: &Pipeline,
+    operator: &OperatorState,
+    config: &IntelligenceConfig,
+    candidate: Candidate,
+    dry_run: bool,
+) -> CandidateRecord {
+    if operator.is_paused(&candidate.strategy).await {
+        return CandidateRecord::stopped(candidate, None, "strategy paused by operator");
+    }
+
+    let Some(strategy_config) = config.get_strategy(&candidate.strategy) else {
+        return CandidateRecord::stopped(candidate, None, "no strategy config");
+    };
+
+    let paper = pipeline.paper.as_ref().filter(|_| config.paper_trading.covers(&candidate.strategy));
+
+    let fingerprint = candidate.fingerprint();
+    if let Some(reason) = pipeline.opportunities.cooldown(&fingerprint, candidate.detected_at).await {
+        debug!("    Skipping duplicate of {}: {}", fingerprint, reason);
+        return CandidateRecord::stopped(candidate, None, reason);
+    }
+
+    let evaluation = match pipeline.simulator.evaluate(&candidate).await {
+        Ok(evaluation) => evaluation,
+        Err(e) => return CandidateRecord::stopped(candidate, None, format!("simulation failed: {}", e)),
+    };
+
+    let decision = match pipeline.decision_engine.decide(candidate.clone(), evaluation.clone(), strategy_config).await {
+        Ok(decision) => decision,
+        Err(e) => return CandidateRecord::stopped(candidate, Some(evaluation), format!("decision failed: {}", e)),
+    };
+
+    if !decision.should_execute || dry_run {
+        return CandidateRecord::decided(&decision, None);
+    }
+
+    if let Some(kill_switch) = operator.kill_switch().await {
+        debug!("    Kill switch engaged ({}), not emitting intent", kill_switch.reason);
+        return CandidateRecord::decided(&decision, None);
+    }
+
+    match pipeline.intent_builder.build(&decision).await {
+        Ok(mut intent) => {
+            intent.paper = paper.is_some();
+            let verification = match &pipeline.verifier {
+                Some(verifier) => Some(verifier.verify(&mut intent).await),
+                None => None,
+            };
+
+            if let Some(report) = verification.as_ref().filter(|r| r.status == VerificationStatus::Dropped) {
+                warn!("    🛑 Dropping intent {}: {}", intent.intent_id, report.summary());
+                let mut record = CandidateRecord::decided(&decision, None);
+                record.error = Some(format!("verification dropped intent: {}", report.summary()));
+                record.verification = verification;
+                return record;
+            }
+
+            let intent_id = intent.intent_id;
+            pipeline.opportunities.record_intent(&fingerprint, intent_id, intent.ttl_seconds, intent.created_at).await;
+            // Paper intents are tracked for feedback but hold no capital and are never emitted
+            let paper_intent = match paper {
+                Some(_) => {
+                    info!("    📝 Paper-trading intent {}", intent_id);
+                    Some(intent.clone())
+                }
+                None => {
+                    info!("    📤 Emitting intent {}", intent_id);
+                    pipeline.decision_engine.reserve_budget(&decision, &intent).await;
+                    None
+                }
+            };
+            pipeline.feedback.register_intent(intent).await;
+            if let (Some(paper), Some(intent)) = (paper, paper_intent) {
+                paper.submit(intent, decision.evaluation.costs.clone());
+            }
+            let mut record = CandidateRecord::decided(&decision, Some(intent_id));
+            record.verification = verification;
+            record
+        }
+        Err(e) => {
+            error!("Failed to build intent: {}", e);
+            let mut record = CandidateRecord::decided(&decision, None);
+            record.error = Some(format!("intent build failed: {}", e));
+            record
+        }
+    }
+}
+
+/// Print audit records matching the CLI filters as JSONL
+async fn run_audit_query(dir: &str, matches: &clap::ArgMatches) -> Result<()> {
+    let parse_time = |name: &str| parse_time_arg(matches, name);
+
+    let query = AuditQuery {
+        strategy: matches.get_one::<String>("strategy").cloned(),
+        asset: matches.get_one::<String>("asset").cloned(),
+        since: parse_time("since")?,
+        until: parse_time("until")?,
+        rejection: matches.get_one::<String>("rejection").cloned(),
+        outcome: matches.get_one::<String>("outcome").map(|outcome| match outcome.as_str() {
+            "emitted" => AuditOutcome::Emitted,
+            "approved" => AuditOutcome::Approved,
+            "dropped" => AuditOutcome::Dropped,
+            "rejected" => AuditOutcome::Rejected,
+            _ => AuditOutcome::Stopped,
+        }),
+        limit: matches.get_one::<usize>("limit").copied(),
+        id: None,
+    };
+
+    for record in AuditLog::query(dir, &query).await? {
+        println!("{}", serde_json::to_string(&record)?);
+    }
+    Ok(())
+}
+
+/// Evaluate and decide one candidate against a snapshot, offline
+async fn run_simulate(config: IntelligenceConfig, matches: &clap::ArgMatches) -> Result<()> {
+    let path = matches.get_one::<String>("snapshot").unwrap();
+    let mut snapshot: serde_json::Value = serde_json::from_str(&tokio::fs::read_to_string(path).await?)?;
+    // Operator snapshots wrap the market state
+    if let Some(market) = snapshot.get_mut("market") {
+        snapshot = market.take();
+    }
+    let snapshot: MarketStateSnapshot = serde_json::from_value(snapshot)?;
+
+    let market_state = Arc::new(MarketState::new(config.market_state_ttl_secs));
+    market_state.restore(&snapshot).await;
+
+    let candidate = match matches.get_one::<String>("candidate") {
+        Some(candidate_path) => serde_json::from_str(&tokio::fs::read_to_string(candidate_path).await?)?,
+        None => Candidate {
+            strategy: matches.get_one::<String>("strategy").unwrap().clone(),
+            asset: matches.get_one::<String>("asset").unwrap().clone(),
+            spread_bps: *matches.get_one::<f64>("spread-bps").unwrap(),
+            legs: matches.get_many::<String>("leg").into_iter().flatten()
+                .map(|leg| match leg.rsplit_once(':') {
+                    Some((domain, action)) => Ok((domain.to_string(), action.to_string())),
+                    None => Err(IntelligenceError::internal(format!("Invalid --leg {}: expected DOMAIN:ACTION", leg))),
+                })
+                .collect::<Result<_>>()?,
+            detected_at: snapshot.taken_at,
+            confidence: *matches.get_one::<f64>("confidence").unwrap(),
+            first_seen_at: None,
+            details: None,
+        },
+    };
+
+    let Some(strategy_config) = config.get_strategy(&candidate.strategy) else {
+        return Err(IntelligenceError::InvalidStrategy(candidate.strategy));
+    };
+    let max_position_per_asset = config.strategies.values()
+        .map(|s| s.max_position_usd)
+        .fold(0.0, f64::max);
+
+    let record = match TradeSimulator::new(market_state.clone()).evaluate(&candidate).await {
+        Ok(evaluation) => {
+            let decision_engine = DecisionEngine::new(market_state, max_position_per_asset);
+            match decision_engine.decide(candidate.clone(), evaluation.clone(), strategy_config).await {
+                Ok(decision) => CandidateRecord::decided(&decision, None),
+                Err(e) => CandidateRecord::stopped(candidate, Some(evaluation), format!("decision failed: {}", e)),
+            }
+        }
+        Err(e) => CandidateRecord::stopped(candidate, None, format!("simulation failed: {}", e)),
+    };
+
+    match matches.get_one::<String>("format").map(|s| s.as_str()) {
+        Some("json") => println!("{}", serde_json::to_string_pretty(&record)?),
+        _ => print!("{}", explain::render_record(&record)),
+    }
+    Ok(())
+}
+
+/// Print the audit record of one intent or correlation id
+async fn run_explain(dir: &str, matches: &clap::ArgMatches) -> Result<()> {
+    let id = matches.get_one::<String>("id").unwrap();
+    let query = AuditQuery {
+        id: Some(id.parse().map_err(|e| IntelligenceError::internal(format!("Invalid id {}: {}", id, e)))?),
+        limit: Some(1),
+        ..Default::default()
+    };
+
+    let Some(record) = AuditLog::query(dir, &query).await?.pop() else {
+        return Err(IntelligenceError::internal(format!("No audit record for {} in {}", id, dir)));
+    };
+    match matches.get_one::<String>("format").map(|s| s.as_str()) {
+        Some("json") => println!("{}", serde_json::to_string_pretty(&record)?),
+        _ => print!("{}", explain::render_audit_record(&record)),
+    }
+    Ok(())
+}
+
+/// `--format` of the offline report subcommands
+fn report_format_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .value_name("FORMAT")
+        .value_parser(["markdown", "json"])
+        .default_value("markdown")
+}
+
+/// Replay a recorded feature file and write the backtest report
+async fn run_backtest(mut config: IntelligenceConfig, matches: &clap::ArgMatches) -> Result<()> {
+    if let Some(latency_ms) = matches.get_one::<u64>("latency-ms") {
+        config.backtest.latency_ms = *latency_ms;
+    }
+    if let Some(slippage_bps) = matches.get_one::<f64>("slippage-bps") {
+        config.backtest.slippage_bps = *slippage_bps;
+    }
+    if let Some(failure_rate) = matches.get_one::<f64>("failure-rate") {
+        config.backtest.failure_rate = *failure_rate;
+    }
+    if let Some(seed) = matches.get_one::<u64>("seed") {
+        config.backtest.seed = *seed;
+    }
+    let out_dir = matches.get_one::<String>("out").cloned().unwrap_or_else(|| config.backtest.output_dir.clone());
+
+    let path = matches.get_one::<String>("features").unwrap();
+    let features = load_features(path).await?;
+    info!("Backtesting {} recorded features from {}", features.len(), path);
+
+    let report = Backtester::new(config)
+        .run(features, parse_time_arg(matches, "from")?, parse_time_arg(matches, "until")?)
+        .await?;
+    let run_dir = report.write(&out_dir).await?;
+    info!("Backtest report written to {}", run_dir.display());
+    Ok(())
+}
+
+/// Run a parameter sweep and write the ranked results and best config
+async fn run_sweep(config: IntelligenceConfig, matches: &clap::ArgMatches) -> Result<()> {
+    let spec = SweepSpec::from_file(matches.get_one::<String>("spec").unwrap())?;
+    let out_dir = matches.get_one::<String>("out").cloned().unwrap_or_else(|| config.backtest.output_dir.clone());
+
+    let features = load_features(matches.get_one::<String>("features").unwrap()).await?;
+    let report = SweepRunner::new(config.clone(), features).run(spec).await?;
+    if let Some(best) = report.results.first() {
+        info!("Best parameters: {:?} (in-sample {:.4}, out-of-sample {:.4})", best.params, best.in_sample_score, best.score);
+    }
+    if let Some(walk_forward) = &report.walk_forward {
+        info!(
+            "Walk-forward out-of-sample score {:.4}, PnL ${:.2} over {} folds",
+            walk_forward.score, walk_forward.pnl_usd, walk_forward.folds.len()
+        );
+    }
+
+    let run_dir = report.write(&out_dir, &config).await?;
+    info!("Sweep results written to {}", run_dir.display());
+    Ok(())
+}
+
+/// Optional RFC 3339 timestamp argument
+fn parse_time_arg(matches: &clap::ArgMatches, name: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+    matches.get_one::<String>(name)
+        .map(|value| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .map(|t| t.with_timezone(&chrono::Utc))
+                .map_err(|e| IntelligenceError::internal(format!("Invalid --{} {}: {}", name, value, e)))
+        })
+        .transpose()
+}
+
+/// Next batch of market changes, or never when event-driven detection is off
+async fn next_change_batch(coalescer: &mut Option<ChangeCoalescer>) -> Option<ChangeBatch> {
+    match coalescer {
+        Some(coalescer) => coalescer.next_batch().await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Set up graceful shutdown signal handling
 async fn setup_shutdown_signal() {
     let ctrl_c = async {
@@ -0,0 +1,228 @@
+//! Opportunity tracking across detection cycles
+//!
+//! Detectors re-emit an open spread as a new candidate every cycle until it
+//! closes. The tracker keys candidates by fingerprint, records each
+//! opportunity's lifetime, and holds a cooldown while an intent for it is
+//! outstanding so the same spread is not emitted twice.
+
+use std::collections::HashMap;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::config::DetectionConfig;
+use crate::types::Candidate;
+
+/// One opportunity over its lifetime
+#[derive(Debug, Clone, Serialize)]
+pub struct Opportunity {
+    pub fingerprint: String,
+    pub strategy: String,
+    pub asset: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+
+    /// Widest spread seen
+    pub peak_spread_bps: f64,
+
+    /// Spread of the latest sighting
+    pub last_spread_bps: f64,
+
+    /// Detection cycles that produced it
+    pub sightings: u64,
+
+    /// Last intent emitted for it
+    pub intent_id: Option<Uuid>,
+
+    /// Further intents are suppressed until this time
+    pub cooldown_until: Option<DateTime<Utc>>,
+}
+
+impl Opportunity {
+    /// Seconds between the first and latest sighting
+    pub fn age_secs(&self) -> f64 {
+        (self.last_seen - self.first_seen).num_milliseconds() as f64 / 1000.0
+    }
+}
+
+/// Tracks opportunities by fingerprint
+pub struct OpportunityTracker {
+    opportunities: RwLock<HashMap<String, Opportunity>>,
+    cooldown: Duration,
+    expiry: Duration,
+}
+
+impl OpportunityTracker {
+    pub fn new(detection: &DetectionConfig) -> Self {
+        Self {
+            opportunities: RwLock::new(HashMap::new()),
+            cooldown: Duration::seconds(detection.cooldown_secs as i64),
+            expiry: Duration::seconds(detection.opportunity_expiry_secs as i64),
+        }
+    }
+
+    /// Record a sighting and stamp the candidate with its first-seen time
+    ///
+    /// An opportunity not seen within the expiry starts a new lifetime, unless
+    /// an intent for it is still cooling down.
+    pub async fn observe(&self, candidate: &mut Candidate) -> Opportunity {
+        let fingerprint = candidate.fingerprint();
+        let seen_at = candidate.detected_at;
+        let mut opportunities = self.opportunities.write().await;
+
+        let fresh = Opportunity {
+            fingerprint: fingerprint.clone(),
+            strategy: candidate.strategy.clone(),
+            asset: candidate.asset.clone(),
+            first_seen: seen_at,
+            last_seen: seen_at,
+            peak_spread_bps: candidate.spread_bps,
+            last_spread_bps: candidate.spread_bps,
+            sightings: 0,
+            intent_id: None,
+            cooldown_until: None,
+        };
+        let opportunity = opportunities.entry(fingerprint).or_insert_with(|| fresh.clone());
+        if self.is_expired(opportunity, seen_at) {
+            debug!("Opportunity {} reappeared after expiry", opportunity.fingerprint);
+            *opportunity = fresh;
+        }
+
+        opportunity.last_seen = opportunity.last_seen.max(seen_at);
+        opportunity.peak_spread_bps = opportunity.peak_spread_bps.max(candidate.spread_bps);
+        opportunity.last_spread_bps = candidate.spread_bps;
+        opportunity.sightings += 1;
+
+        candidate.first_seen_at = Some(opportunity.first_seen);
+        opportunity.clone()
+    }
+
+    /// Why the opportunity may not produce another intent at `now`, if it may not
+    pub async fn cooldown(&self, fingerprint: &str, now: DateTime<Utc>) -> Option<String> {
+        let opportunities = self.opportunities.read().await;
+        let opportunity = opportunities.get(fingerprint)?;
+        let until = opportunity.cooldown_until.filter(|until| now < *until)?;
+
+        Some(match opportunity.intent_id {
+            Some(intent_id) => format!("cooldown: intent {} outstanding until {}", intent_id, until),
+            None => format!("cooldown until {}", until),
+        })
+    }
+
+    /// Hold the opportunity while `intent_id` is live, plus the cooldown
+    pub async fn record_intent(&self, fingerprint: &str, intent_id: Uuid, ttl_seconds: u64, now: DateTime<Utc>) {
+        let mut opportunities = self.opportunities.write().await;
+        if let Some(opportunity) = opportunities.get_mut(fingerprint) {
+            opportunity.intent_id = Some(intent_id);
+            opportunity.cooldown_until = Some(now + Duration::seconds(ttl_seconds as i64) + self.cooldown);
+        }
+    }
+
+    /// Tracked opportunities, most recently seen first
+    pub async fn list(&self) -> Vec<Opportunity> {
+        let mut list: Vec<Opportunity> = self.opportunities.read().await.values().cloned().collect();
+        list.sort_by_key(|opportunity| std::cmp::Reverse(opportunity.last_seen));
+        list
+    }
+
+    /// Forget expired opportunities; returns how many were dropped
+    pub async fn prune(&self, now: DateTime<Utc>) -> usize {
+        let mut opportunities = self.opportunities.write().await;
+        let before = opportunities.len();
+        opportunities.retain(|_, opportunity| !self.is_expired(opportunity, now));
+        before - opportunities.len()
+    }
+
+    fn is_expired(&self, opportunity: &Opportunity, now: DateTime<Utc>) -> bool {
+        now - opportunity.last_seen > self.expiry &&
+            opportunity.cooldown_until.is_none_or(|until| until <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CandidateDetails, FlashArbDetails};
+    use qenus_dataplane::Chain;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    fn candidate(seconds: i64, spread_bps: f64, sell_pool: &str) -> Candidate {
+        Candidate {
+            strategy: "flash_arb".to_string(),
+            asset: "WETH".to_string(),
+            spread_bps,
+            legs: vec![
+                ("uniswap_v3 on Ethereum".to_string(), "buy".to_string()),
+                ("curve on Ethereum".to_string(), "sell".to_string()),
+            ],
+            detected_at: at(seconds),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: Some(CandidateDetails::FlashArb(FlashArbDetails {
+                chain: Chain::Ethereum,
+                borrow_asset: "USDC".to_string(),
+                buy_pool: "0xuni".to_string(),
+                sell_pool: sell_pool.to_string(),
+                gross_spread_bps: spread_bps,
+                size_usd: 100_000.0,
+            })),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tracks_lifetime_across_cycles() {
+        let tracker = OpportunityTracker::new(&DetectionConfig::default());
+
+        tracker.observe(&mut candidate(0, 12.0, "0xcurve")).await;
+        tracker.observe(&mut candidate(5, 20.0, "0xcurve")).await;
+        let mut latest = candidate(10, 15.0, "0xcurve");
+        let opportunity = tracker.observe(&mut latest).await;
+
+        assert_eq!(opportunity.sightings, 3);
+        assert_eq!(opportunity.first_seen, at(0));
+        assert_eq!(opportunity.last_seen, at(10));
+        assert_eq!(opportunity.peak_spread_bps, 20.0);
+        assert_eq!(opportunity.last_spread_bps, 15.0);
+        assert_eq!(latest.first_seen_at, Some(at(0)));
+        assert_eq!(latest.age_secs(), 10.0);
+
+        // A different sell pool is a different opportunity
+        let other = tracker.observe(&mut candidate(10, 15.0, "0xbalancer")).await;
+        assert_ne!(other.fingerprint, opportunity.fingerprint);
+        assert_eq!(other.sightings, 1);
+
+        // Gone longer than the expiry: seen again, it starts over
+        let reopened = tracker.observe(&mut candidate(100, 9.0, "0xcurve")).await;
+        assert_eq!(reopened.first_seen, at(100));
+        assert_eq!(reopened.peak_spread_bps, 9.0);
+
+        assert_eq!(tracker.prune(at(200)).await, 2);
+        assert!(tracker.list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cooldown_while_intent_outstanding() {
+        let tracker = OpportunityTracker::new(&DetectionConfig::default());
+        let mut first = candidate(0, 12.0, "0xcurve");
+        let fingerprint = tracker.observe(&mut first).await.fingerprint;
+        assert!(tracker.cooldown(&fingerprint, at(0)).await.is_none());
+
+        let intent_id = Uuid::new_v4();
+        tracker.record_intent(&fingerprint, intent_id, 60, at(0)).await;
+
+        // Re-detected while the intent is live: suppressed
+        tracker.observe(&mut candidate(5, 12.0, "0xcurve")).await;
+        let reason = tracker.cooldown(&fingerprint, at(5)).await.unwrap();
+        assert!(reason.contains(&intent_id.to_string()));
+
+        // Held through the TTL plus the cooldown, even past the expiry
+        assert!(tracker.cooldown(&fingerprint, at(69)).await.is_some());
+        assert_eq!(tracker.prune(at(69)).await, 0);
+        assert!(tracker.cooldown(&fingerprint, at(70)).await.is_none());
+    }
+}
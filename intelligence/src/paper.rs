@@ -0,0 +1,506 @@
+//! Paper trading
+//!
+//! "Executes" emitted intents against the market state observed after a
+//! delay: swap legs are re-quoted from the later pool state, gas from the
+//! later gas state. The synthetic receipts flow into the feedback processor,
+//! so a strategy gets hit rates and prediction errors on live data before it
+//! is given real capital.
+
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use qenus_dataplane::Chain;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::config::PaperTradingConfig;
+use crate::feedback::{ActualCosts, ExecutionReceipt, FeedbackProcessor};
+use crate::simulator::amm::{mid_rate, swap_slippage_bps};
+use crate::simulator::gas::GasEstimator;
+use crate::state::{AmmState, MarketState};
+use crate::{CostBreakdown, TradeAction, TradeIntent, TradeLeg};
+
+/// Pool a swap leg trades through, as quoted at emission
+#[derive(Debug, Clone)]
+struct LegQuote {
+    leg: usize,
+    chain: Chain,
+    pool_address: String,
+
+    /// Output per unit of input at the pool's mid price
+    rate: f64,
+    slippage_bps: f64,
+}
+
+/// Market inputs captured when an intent is emitted
+#[derive(Debug, Clone)]
+pub struct EntryQuote {
+    swaps: Vec<LegQuote>,
+
+    /// Gas of the legs at emission, from the same estimates used at fill
+    gas_usd: f64,
+
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Parts of a split swap filled so far
+///
+/// Every part trades its share of the same input, so each starts from the
+/// scale flowing into the split; their combined output sets the scale after it.
+struct SplitFill {
+    step: usize,
+    entry_scale: f64,
+    expected_out: f64,
+    actual_out: f64,
+
+    /// Weighted by each part's expected output
+    slippage_bps: f64,
+    drift_bps: f64,
+}
+
+impl SplitFill {
+    fn new(step: usize, entry_scale: f64) -> Self {
+        Self { step, entry_scale, expected_out: 0.0, actual_out: 0.0, slippage_bps: 0.0, drift_bps: 0.0 }
+    }
+
+    fn add(&mut self, expected_out: f64, actual_out: f64, slippage_bps: f64, drift_bps: f64) {
+        self.expected_out += expected_out;
+        self.actual_out += actual_out;
+        self.slippage_bps += slippage_bps * expected_out;
+        self.drift_bps += drift_bps * expected_out;
+    }
+
+    fn scale(&self) -> f64 {
+        if self.expected_out > 0.0 { self.actual_out / self.expected_out } else { self.entry_scale }
+    }
+
+    /// Output-weighted mean of an accumulated bps total
+    fn mean(&self, weighted_bps: f64) -> f64 {
+        if self.expected_out > 0.0 { weighted_bps / self.expected_out } else { 0.0 }
+    }
+}
+
+/// Fills emitted intents against later market state
+pub struct PaperTrader {
+    market_state: Arc<MarketState>,
+    feedback: Arc<FeedbackProcessor>,
+    gas_estimator: GasEstimator,
+    delay: std::time::Duration,
+}
+
+impl PaperTrader {
+    pub fn new(market_state: Arc<MarketState>, feedback: Arc<FeedbackProcessor>, config: &PaperTradingConfig) -> Self {
+        Self {
+            gas_estimator: GasEstimator::new(market_state.clone()),
+            market_state,
+            feedback,
+            delay: std::time::Duration::from_millis(config.delay_ms),
+        }
+    }
+
+    /// Quote `intent` now, fill it after the delay and feed the receipt back
+    ///
+    /// The intent must already be registered with the feedback processor;
+    /// `costs` are the simulated costs it was emitted with.
+    pub fn submit(self: &Arc<Self>, intent: TradeIntent, costs: CostBreakdown) -> JoinHandle<()> {
+        let trader = self.clone();
+        tokio::spawn(async move {
+            let entry = trader.quote(&intent).await;
+            tokio::time::sleep(trader.delay).await;
+
+            let receipt = trader.fill(&intent, &entry, &costs).await;
+            info!(
+                "📝 Paper fill for intent {}: {} pnl=${:.2} (predicted ${:.2})",
+                intent.intent_id,
+                if receipt.success { "✅" } else { "❌" },
+                receipt.actual_pnl_usd,
+                intent.expected_pnl_usd
+            );
+            if let Err(e) = trader.feedback.process_feedback(receipt).await {
+                warn!("Paper receipt for intent {} not processed: {}", intent.intent_id, e);
+            }
+        })
+    }
+
+    /// Capture the pool rates and gas the intent was priced against
+    pub async fn quote(&self, intent: &TradeIntent) -> EntryQuote {
+        let mut swaps = Vec::new();
+        for (index, leg) in intent.legs.iter().enumerate() {
+            if !matches!(leg.action, TradeAction::Swap) {
+                continue;
+            }
+            let pools = self.market_state.get_amm_pools(leg.domain).await;
+            let Some(pool) = resolve_pool(&pools, leg) else {
+                debug!("Leg {} of intent {} has no pool to paper-trade against", index, intent.intent_id);
+                continue;
+            };
+            let Some(rate) = mid_rate(&pool, &leg.asset_in) else {
+                continue;
+            };
+            swaps.push(LegQuote {
+                leg: index,
+                chain: leg.domain,
+                pool_address: pool.pool_address.clone(),
+                rate,
+                slippage_bps: swap_slippage_bps(&pool, &leg.asset_in, leg.amount_in_usd),
+            });
+        }
+
+        EntryQuote {
+            swaps,
+            gas_usd: self.legs_gas_usd(intent).await,
+            captured_at: self.market_state.now(),
+        }
+    }
+
+    /// Execute the intent against the current market state
+    ///
+    /// Each quoted swap leg's output moves with its pool's mid price and depth
+    /// slippage since `entry`; a leg that lands below its `min_amount_out`
+    /// reverts the intent and burns gas. Gas scales the simulated gas by how
+    /// much the legs' gas estimate moved.
+    pub async fn fill(&self, intent: &TradeIntent, entry: &EntryQuote, costs: &CostBreakdown) -> ExecutionReceipt {
+        let completed_at = self.market_state.now();
+        let execution_time_secs = (completed_at - entry.captured_at).num_milliseconds() as f64 / 1000.0;
+        let gas_usd = match entry.gas_usd {
+            predicted if predicted > 0.0 => costs.gas_usd * self.legs_gas_usd(intent).await / predicted,
+            _ => costs.gas_usd,
+        };
+
+        let receipt = |success: bool, actual_pnl_usd: f64, actual_costs: ActualCosts, actual_slippage_bps: f64, error_message: Option<String>| {
+            ExecutionReceipt {
+                intent_id: intent.intent_id,
+                success,
+                actual_pnl_usd,
+                actual_costs,
+                actual_slippage_bps,
+                execution_time_secs,
+                completed_at,
+                error_message,
+                paper: true,
+            }
+        };
+        let reverted = |message: String| {
+            let costs = ActualCosts {
+                gas_usd,
+                protocol_fees_usd: 0.0,
+                bridge_fees_usd: 0.0,
+                flashloan_fees_usd: 0.0,
+                slippage_usd: 0.0,
+                total_usd: gas_usd,
+            };
+            receipt(false, -gas_usd, costs, 0.0, Some(message))
+        };
+
+        if let Some(leg) = intent.legs.iter().find(|leg| leg.deadline < completed_at) {
+            // Never landed: no gas spent
+            let message = format!("deadline {} passed before the {:?} leg on {:?}", leg.deadline, leg.action, leg.domain);
+            return receipt(false, 0.0, ActualCosts {
+                gas_usd: 0.0,
+                protocol_fees_usd: 0.0,
+                bridge_fees_usd: 0.0,
+                flashloan_fees_usd: 0.0,
+                slippage_usd: 0.0,
+                total_usd: 0.0,
+            }, 0.0, Some(message));
+        }
+
+        // Actual over expected amount flowing through the legs
+        let mut scale = 1.0;
+        let mut settled_out = 0.0;
+        let mut slippage_bps = 0.0;
+        let mut slippage_drift_bps = 0.0;
+        let mut split: Option<SplitFill> = None;
+
+        for (index, leg) in intent.legs.iter().enumerate() {
+            let (amount_in, expected_out) = (leg.amount_in_usd, leg.expected_out_usd);
+            // The minimum out is in base units; value it at the expected output's rate
+            let min_out = match (leg.min_amount_out.parse::<f64>(), leg.expected_out.parse::<f64>()) {
+                (Ok(min_out), Ok(expected)) if expected > 0.0 => expected_out * min_out / expected,
+                _ => 0.0,
+            };
+
+            // A split swap ends at the first leg outside it
+            if let Some(done) = split.take_if(|open| leg.split_of != Some(open.step)) {
+                scale = done.scale();
+                slippage_bps += done.mean(done.slippage_bps);
+                slippage_drift_bps += done.mean(done.drift_bps);
+            }
+            if let (Some(step), None) = (leg.split_of, &split) {
+                split = Some(SplitFill::new(step, scale));
+            }
+            let entry_scale = split.as_ref().map_or(scale, |open| open.entry_scale);
+
+            if let Some(quote) = entry.swaps.iter().find(|quote| quote.leg == index) {
+                let pools = self.market_state.get_amm_pools(quote.chain).await;
+                let Some(pool) = pools.iter().find(|pool| pool.pool_address == quote.pool_address) else {
+                    return reverted(format!("pool {} for leg {} is no longer quoted", quote.pool_address, index));
+                };
+                let Some(rate) = mid_rate(pool, &leg.asset_in) else {
+                    return reverted(format!("pool {} no longer trades {}", quote.pool_address, leg.asset_in));
+                };
+                let slippage = swap_slippage_bps(pool, &leg.asset_in, amount_in * entry_scale);
+                let leg_scale = entry_scale * rate / quote.rate * (1.0 - slippage / 10000.0) / (1.0 - quote.slippage_bps / 10000.0);
+
+                let actual_out = expected_out * leg_scale;
+                match &mut split {
+                    Some(open) => open.add(expected_out, actual_out, slippage, slippage - quote.slippage_bps),
+                    None => {
+                        scale = leg_scale;
+                        slippage_bps += slippage;
+                        slippage_drift_bps += slippage - quote.slippage_bps;
+                    }
+                }
+
+                if actual_out < min_out {
+                    return reverted(format!(
+                        "leg {} on {} returned {:.2} below min {:.2}",
+                        index, leg.protocol, actual_out, min_out
+                    ));
+                }
+            } else if let Some(open) = &mut split {
+                open.add(expected_out, expected_out * entry_scale, 0.0, 0.0);
+            }
+
+            if !matches!(leg.action, TradeAction::FlashRepay) {
+                settled_out = split.as_ref().map_or(expected_out, |open| open.expected_out);
+            }
+        }
+        if let Some(done) = split {
+            scale = done.scale();
+            slippage_bps += done.mean(done.slippage_bps);
+            slippage_drift_bps += done.mean(done.drift_bps);
+        }
+
+        let slippage_usd = costs.slippage_usd + intent.size_usd * slippage_drift_bps / 10000.0;
+        let actual_costs = ActualCosts {
+            gas_usd,
+            protocol_fees_usd: costs.protocol_fees_usd,
+            bridge_fees_usd: costs.bridge_fees_usd,
+            flashloan_fees_usd: costs.flashloan_fees_usd,
+            slippage_usd,
+            total_usd: gas_usd + costs.protocol_fees_usd + costs.bridge_fees_usd + costs.flashloan_fees_usd + slippage_usd,
+        };
+
+        // Slippage drift is already in `scale`
+        let actual_pnl_usd = intent.expected_pnl_usd + settled_out * (scale - 1.0) - (gas_usd - costs.gas_usd);
+        receipt(true, actual_pnl_usd, actual_costs, slippage_bps, None)
+    }
+
+    /// Gas of every leg at current gas prices
+    async fn legs_gas_usd(&self, intent: &TradeIntent) -> f64 {
+        let eth_price = self.eth_price().await;
+        let mut total = 0.0;
+        for leg in &intent.legs {
+            total += match leg.action {
+                TradeAction::Swap | TradeAction::AddLiquidity | TradeAction::RemoveLiquidity => {
+                    self.gas_estimator.estimate_swap_gas(leg.domain, eth_price).await
+                }
+                TradeAction::Bridge => self.gas_estimator.estimate_bridge_gas(eth_price).await,
+                TradeAction::FlashLoan => self.gas_estimator.estimate_flashloan_gas(leg.domain, eth_price).await,
+                TradeAction::Liquidate => self.gas_estimator.estimate_liquidation_gas(leg.domain, eth_price).await,
+                TradeAction::FlashRepay => 0.0,
+            };
+        }
+        total
+    }
+
+    async fn eth_price(&self) -> f64 {
+        for chain in [Chain::Ethereum, Chain::Arbitrum, Chain::Optimism, Chain::Base] {
+            if let Some(price) = self.market_state.get_price(chain, "WETH").await {
+                return price;
+            }
+        }
+        3000.0
+    }
+}
+
+/// The leg's own pool, else the deepest pool of its protocol trading its assets
+fn resolve_pool(pools: &[AmmState], leg: &TradeLeg) -> Option<AmmState> {
+    if let Some(address) = &leg.pool {
+        return pools.iter().find(|pool| &pool.pool_address == address).cloned();
+    }
+    pools.iter()
+        .filter(|pool| pool.pool_type.contains(&leg.protocol) || leg.protocol.contains(&pool.pool_type))
+        .filter(|pool| {
+            (pool.token0_symbol == leg.asset_in && pool.token1_symbol == leg.asset_out) ||
+            (pool.token0_symbol == leg.asset_out && pool.token1_symbol == leg.asset_in)
+        })
+        .max_by(|a, b| {
+            let depth = |pool: &AmmState| pool.liquidity.parse::<f64>().unwrap_or(0.0);
+            depth(a).partial_cmp(&depth(b)).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use chrono::Duration;
+    use uuid::Uuid;
+    use qenus_dataplane::{AmmFeature, DepthCurve, Feature, FeatureData, FeatureType, GasFeature, SlippageInfo, TokenInfo};
+    use crate::{MarketSnapshot, TradeMetadata};
+
+    const T0: i64 = 1_700_000_000;
+
+    fn at(seconds: i64, feature_type: FeatureType, data: FeatureData) -> Feature {
+        let mut feature = Feature::new(1, Chain::Ethereum, feature_type, data, "paper".to_string());
+        feature.timestamp = DateTime::from_timestamp(T0 + seconds, 0).unwrap();
+        feature
+    }
+
+    fn token(symbol: &str) -> TokenInfo {
+        TokenInfo { address: String::new(), symbol: symbol.to_string(), decimals: 18 }
+    }
+
+    fn pool(seconds: i64, address: &str, pool_type: &str, mid_price: f64) -> Feature {
+        let sizes = [("1m".to_string(), SlippageInfo { slippage_bps: 20.0, price_impact: 0.002 })];
+        at(seconds, FeatureType::Amm, FeatureData::Amm(Box::new(AmmFeature {
+            pool_address: address.to_string(),
+            pool_type: pool_type.to_string(),
+            pool_id: None,
+            token0: token("WETH"),
+            token1: token("USDC"),
+            fee_tier: Some(500),
+            reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+            mid_price,
+            liquidity: "1000000".to_string(),
+            depth: DepthCurve { sizes: sizes.into_iter().collect(), ..Default::default() },
+            volume_24h: None,
+            fees_24h: None,
+        })))
+    }
+
+    fn gas(seconds: i64, gwei: f64) -> Feature {
+        at(seconds, FeatureType::Gas, FeatureData::Gas(GasFeature {
+            base_fee: gwei,
+            priority_fee: 1.0,
+            gas_used_ratio: 0.5,
+            next_base_fee_estimate: gwei,
+            fast_gas_price: gwei,
+            standard_gas_price: gwei,
+            safe_gas_price: gwei,
+            pending_tx_count: 0,
+        }))
+    }
+
+    fn swap(protocol: &str, asset_in: &str, asset_out: &str, amount_in: f64, expected_out: f64) -> TradeLeg {
+        TradeLeg {
+            domain: Chain::Ethereum,
+            destination_domain: None,
+            action: TradeAction::Swap,
+            protocol: protocol.to_string(),
+            asset_in: asset_in.to_string(),
+            asset_out: asset_out.to_string(),
+            // Tokens of 6 decimals at $1
+            amount_in: format!("{:.0}", amount_in * 1e6),
+            min_amount_out: format!("{:.0}", expected_out * 0.999 * 1e6),
+            max_fee_bps: 30,
+            deadline: DateTime::from_timestamp(T0 + 30, 0).unwrap(),
+            expected_out: format!("{:.0}", expected_out * 1e6),
+            token_in: format!("0x{}", asset_in.to_lowercase()),
+            token_out: format!("0x{}", asset_out.to_lowercase()),
+            amount_in_usd: amount_in,
+            expected_out_usd: expected_out,
+            target: None,
+            calldata: None,
+            pool: None,
+            split_of: None,
+        }
+    }
+
+    /// Buy WETH on uniswap at 2000, sell on curve at 2010
+    fn arb_intent() -> TradeIntent {
+        TradeIntent {
+            intent_id: Uuid::new_v4(),
+            strategy: "dex_arb".to_string(),
+            asset: "WETH".to_string(),
+            size_usd: 100_000.0,
+            expected_pnl_usd: 400.0,
+            net_bps: 40.0,
+            success_prob: 0.9,
+            legs: vec![
+                swap("uniswap_v3", "USDC", "WETH", 100_000.0, 100_000.0),
+                swap("curve", "WETH", "USDC", 100_000.0, 100_500.0),
+            ],
+            ttl_seconds: 30,
+            created_at: DateTime::from_timestamp(T0, 0).unwrap(),
+            metadata: TradeMetadata {
+                detected_at: DateTime::from_timestamp(T0, 0).unwrap(),
+                detector: "dex_arb".to_string(),
+                market_snapshot: MarketSnapshot {
+                    gas_prices: HashMap::new(),
+                    sequencer_health: HashMap::new(),
+                    volatility: 0.5,
+                },
+                risk_factors: vec![],
+            },
+            paper: false,
+        }
+    }
+
+    fn simulated_costs() -> CostBreakdown {
+        CostBreakdown { gas_usd: 20.0, slippage_usd: 40.0, total_usd: 60.0, ..Default::default() }
+    }
+
+    async fn market(seconds: i64, curve_mid: f64, gwei: f64, state: &MarketState) {
+        state.set_clock(DateTime::from_timestamp(T0 + seconds, 0).unwrap());
+        for feature in [pool(seconds, "0xuni", "uniswap_v3", 2000.0), pool(seconds, "0xcurve", "curve", curve_mid), gas(seconds, gwei)] {
+            state.ingest_feature(feature).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fill_reprices_from_later_state() {
+        let state = Arc::new(MarketState::new(30));
+        let feedback = Arc::new(FeedbackProcessor::new());
+        let trader = PaperTrader::new(state.clone(), feedback.clone(), &PaperTradingConfig::default());
+        let intent = arb_intent();
+
+        market(0, 2010.0, 20.0, &state).await;
+        let entry = trader.quote(&intent).await;
+        assert_eq!(entry.swaps.len(), 2);
+
+        // The curve pool gave back 2bps and gas doubled by the time the intent landed
+        market(12, 2010.0 * 0.9998, 40.0, &state).await;
+        let receipt = trader.fill(&intent, &entry, &simulated_costs()).await;
+
+        assert!(receipt.success, "{:?}", receipt.error_message);
+        assert_eq!(receipt.execution_time_secs, 12.0);
+        // Gas is priced in ETH from whichever WETH pool the state returns
+        assert!((receipt.actual_costs.gas_usd - 40.0).abs() < 0.25, "{}", receipt.actual_costs.gas_usd);
+        // 2bps of the 100.5k settled output, plus the extra 20 of gas
+        assert!((receipt.actual_pnl_usd - (400.0 - 20.1 - 20.0)).abs() < 0.25, "{}", receipt.actual_pnl_usd);
+        assert!((receipt.actual_slippage_bps - 4.0).abs() < 1e-9);
+
+        feedback.register_intent(intent).await;
+        feedback.process_feedback(receipt).await.unwrap();
+        let performance = feedback.get_performance().await;
+        assert_eq!(performance.successful_executions, 1);
+        assert_eq!(performance.hit_rate, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_fill_reverts_below_min_out_and_after_deadline() {
+        let state = Arc::new(MarketState::new(30));
+        let trader = PaperTrader::new(state.clone(), Arc::new(FeedbackProcessor::new()), &PaperTradingConfig::default());
+        let intent = arb_intent();
+
+        market(0, 2010.0, 20.0, &state).await;
+        let entry = trader.quote(&intent).await;
+
+        // The spread closed past the sell leg's 10bps tolerance: reverts and burns gas
+        market(12, 2000.0, 20.0, &state).await;
+        let receipt = trader.fill(&intent, &entry, &simulated_costs()).await;
+        assert!(!receipt.success);
+        assert!(receipt.error_message.unwrap().contains("below min"));
+        assert!((receipt.actual_pnl_usd + 20.0).abs() < 0.25, "{}", receipt.actual_pnl_usd);
+
+        // Landing after the legs' deadline never reaches the chain
+        state.set_clock(DateTime::from_timestamp(T0, 0).unwrap() + Duration::seconds(31));
+        let receipt = trader.fill(&intent, &entry, &simulated_costs()).await;
+        assert!(!receipt.success);
+        assert_eq!(receipt.actual_pnl_usd, 0.0);
+        assert!(receipt.error_message.unwrap().contains("deadline"));
+    }
+}
@@ -0,0 +1,590 @@
+//! Cross-chain inventory rebalancing
+//!
+//! Non-atomic strategies end on whichever chain their last leg lands on, so
+//! inventory drifts away from where it is needed. The ledger tracks holdings
+//! per chain as executions settle; the planner compares them with configured
+//! targets and proposes bridge transfers that restore them at least cost.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use qenus_dataplane::Chain;
+use tokio::sync::RwLock;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::calldata::{self, TokenRef};
+use crate::config::{InventoryAmount, RebalanceConfig};
+use crate::intent_builder::{resolve_token, validate_flow};
+use crate::simulator::gas::GasEstimator;
+use crate::state::{BridgeState, MarketState};
+use crate::{MarketSnapshot, Result, TradeAction, TradeIntent, TradeLeg, TradeMetadata};
+
+/// Strategy name carried by rebalancing intents
+pub const REBALANCE_STRATEGY: &str = "rebalance";
+
+/// Gas of a bridge deposit on the source chain
+const BRIDGE_GAS_UNITS: f64 = 300_000.0;
+
+const CHAINS: [Chain; 4] = [Chain::Ethereum, Chain::Arbitrum, Chain::Optimism, Chain::Base];
+
+/// Amounts per chain and asset
+type Holdings = HashMap<(Chain, String), f64>;
+
+/// Holdings per chain and asset, in USD
+pub struct InventoryLedger {
+    balances: RwLock<HashMap<(Chain, String), f64>>,
+}
+
+impl InventoryLedger {
+    pub fn new(opening_balances: &[InventoryAmount]) -> Self {
+        let mut balances = HashMap::new();
+        for holding in opening_balances {
+            *balances.entry((holding.chain, holding.asset.clone())).or_insert(0.0) += holding.amount_usd;
+        }
+        Self { balances: RwLock::new(balances) }
+    }
+
+    pub async fn balance(&self, chain: Chain, asset: &str) -> f64 {
+        self.balances.read().await.get(&(chain, asset.to_string())).copied().unwrap_or(0.0)
+    }
+
+    /// Overwrite a holding, e.g. from a wallet reconciliation
+    pub async fn set_balance(&self, chain: Chain, asset: &str, amount_usd: f64) {
+        self.balances.write().await.insert((chain, asset.to_string()), amount_usd);
+    }
+
+    pub async fn balances(&self) -> HashMap<(Chain, String), f64> {
+        self.balances.read().await.clone()
+    }
+
+    /// Apply the net token flow of an executed intent
+    pub async fn settle(&self, intent: &TradeIntent) -> Result<()> {
+        let flow = validate_flow(intent)?;
+        let mut balances = self.balances.write().await;
+        for delta in flow.deltas {
+            *balances.entry((delta.chain, delta.asset)).or_insert(0.0) += delta.delta_usd;
+        }
+        Ok(())
+    }
+}
+
+/// Cheapest bridge between two chains that settles within the time budget
+struct Route {
+    from: Chain,
+    to: Chain,
+    bridge: BridgeState,
+    gas_usd: f64,
+
+    /// Asset on the source and destination chains
+    token_in: TokenRef,
+    token_out: TokenRef,
+
+    /// USD price of the asset on the source chain
+    price_usd: f64,
+}
+
+impl Route {
+    /// Bridge fee on `amount_usd` plus the deposit gas
+    fn cost_usd(&self, amount_usd: f64) -> f64 {
+        amount_usd * self.bridge.fee_bps as f64 / 10_000.0 + self.gas_usd
+    }
+}
+
+/// Proposes bridge transfers that bring inventory back to its targets
+pub struct RebalancePlanner {
+    market_state: Arc<MarketState>,
+    ledger: Arc<InventoryLedger>,
+    gas_estimator: GasEstimator,
+    config: RebalanceConfig,
+
+    /// Holdings each proposed transfer moves, as they stood when it was planned
+    baselines: RwLock<HashMap<Uuid, Holdings>>,
+}
+
+impl RebalancePlanner {
+    pub fn new(market_state: Arc<MarketState>, ledger: Arc<InventoryLedger>, config: &RebalanceConfig) -> Self {
+        Self {
+            gas_estimator: GasEstimator::new(market_state.clone()),
+            market_state,
+            ledger,
+            config: config.clone(),
+            baselines: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn ledger(&self) -> &Arc<InventoryLedger> {
+        &self.ledger
+    }
+
+    /// Transfers from chains above target into chains below it
+    ///
+    /// Holdings are first reconciled with the wallet balances in market
+    /// state. Unsettled rebalancing intents in `in_flight` count as if they
+    /// had landed, so a transfer is not proposed twice, until the holdings
+    /// they move have shifted by their amount (within `tolerance_pct`) since
+    /// they were planned; the balances then already include them. Routes are
+    /// filled cheapest per dollar moved first, in bridge fee and gas, each
+    /// moving as much as both ends allow, and re-ranked as surpluses and
+    /// deficits are used up; chains whose sequencer is not healthy receive
+    /// nothing.
+    pub async fn plan(&self, in_flight: &[TradeIntent]) -> Vec<TradeIntent> {
+        let now = self.market_state.now();
+        let mut assets: Vec<&str> = self.config.targets.iter().map(|target| target.asset.as_str()).collect();
+        assets.sort();
+        assets.dedup();
+        self.reconcile(&assets).await;
+
+        let balances = self.ledger.balances().await;
+        let mut projected = balances.clone();
+        let mut baselines = self.baselines.write().await;
+        baselines.retain(|intent_id, _| in_flight.iter().any(|intent| intent.intent_id == *intent_id));
+        let landed_share = 1.0 - self.config.tolerance_pct / 100.0;
+        for intent in in_flight.iter().filter(|intent| {
+            intent.strategy == REBALANCE_STRATEGY && intent.created_at + Duration::seconds(intent.ttl_seconds as i64) > now
+        }) {
+            let Ok(flow) = validate_flow(intent) else {
+                continue;
+            };
+            let baseline = baselines.get(&intent.intent_id);
+            for delta in flow.deltas {
+                let key = (delta.chain, delta.asset);
+                let moved = baseline.and_then(|baseline| baseline.get(&key))
+                    .map(|before| (balances.get(&key).copied().unwrap_or(0.0) - before) * delta.delta_usd.signum());
+                if moved.is_some_and(|moved| moved >= delta.delta_usd.abs() * landed_share) {
+                    continue;
+                }
+                *projected.entry(key).or_insert(0.0) += delta.delta_usd;
+            }
+        }
+
+        let eth_price = self.eth_price().await;
+        let mut intents = Vec::new();
+        for asset in assets {
+            let mut surplus = HashMap::new();
+            let mut deficit = HashMap::new();
+            for chain in CHAINS {
+                let balance = projected.get(&(chain, asset.to_string())).copied().unwrap_or(0.0);
+                let target = self.target(chain, asset);
+                if balance < target * (1.0 - self.config.tolerance_pct / 100.0) {
+                    if self.accepts_inflow(chain).await {
+                        deficit.insert(chain, target - balance);
+                    } else {
+                        debug!("Not rebalancing {} into {:?}: sequencer not healthy", asset, chain);
+                    }
+                } else if balance > target {
+                    surplus.insert(chain, balance - target);
+                }
+            }
+
+            let mut routes = Vec::new();
+            for &from in surplus.keys() {
+                for &to in deficit.keys() {
+                    if let Some(route) = self.route(from, to, asset, eth_price).await {
+                        routes.push(route);
+                    }
+                }
+            }
+            loop {
+                let cheapest = routes.iter()
+                    .filter_map(|route| {
+                        let amount_usd = surplus[&route.from].min(deficit[&route.to]);
+                        (amount_usd > 0.0 && amount_usd >= self.config.min_transfer_usd)
+                            .then(|| (route, amount_usd, route.cost_usd(amount_usd) / amount_usd))
+                    })
+                    .min_by(|a, b| a.2.total_cmp(&b.2));
+                let Some((route, amount_usd, _)) = cheapest else {
+                    break;
+                };
+                *surplus.get_mut(&route.from).unwrap() -= amount_usd;
+                *deficit.get_mut(&route.to).unwrap() -= amount_usd;
+                intents.push(self.transfer_intent(route, asset, amount_usd, now).await);
+            }
+        }
+
+        for intent in &intents {
+            if let Ok(flow) = validate_flow(intent) {
+                let baseline = flow.deltas.into_iter()
+                    .map(|delta| {
+                        let key = (delta.chain, delta.asset);
+                        let before = balances.get(&key).copied().unwrap_or(0.0);
+                        (key, before)
+                    })
+                    .collect();
+                baselines.insert(intent.intent_id, baseline);
+            }
+        }
+
+        intents
+    }
+
+    /// Overwrite ledger holdings of `assets` with what the wallets report
+    ///
+    /// Chains without a recent wallet feature keep their ledger balance.
+    async fn reconcile(&self, assets: &[&str]) {
+        for asset in assets {
+            for chain in CHAINS {
+                if let Some(amount_usd) = self.market_state.deployable_capital_usd(chain, asset).await {
+                    self.ledger.set_balance(chain, asset, amount_usd).await;
+                }
+            }
+        }
+    }
+
+    fn target(&self, chain: Chain, asset: &str) -> f64 {
+        self.config.targets.iter()
+            .filter(|target| target.chain == chain && target.asset == asset)
+            .map(|target| target.amount_usd)
+            .sum()
+    }
+
+    async fn accepts_inflow(&self, chain: Chain) -> bool {
+        chain == Chain::Ethereum || self.market_state.is_sequencer_healthy(chain).await
+    }
+
+    async fn route(&self, from: Chain, to: Chain, asset: &str, eth_price: f64) -> Option<Route> {
+        // Bridge state keeps every update; only the latest per bridge counts
+        let mut latest: Vec<BridgeState> = Vec::new();
+        for bridge in self.market_state.get_bridges(from, to).await.into_iter().rev() {
+            if !latest.iter().any(|seen| seen.bridge_address == bridge.bridge_address && seen.token_symbol == bridge.token_symbol) {
+                latest.push(bridge);
+            }
+        }
+
+        let bridge = latest.into_iter()
+            .filter(|bridge| bridge.is_active && bridge.token_symbol == asset)
+            .filter(|bridge| bridge.settlement_time_secs <= self.config.time_budget_secs)
+            .min_by_key(|bridge| (bridge.fee_bps, bridge.settlement_time_secs))?;
+        let gas_usd = self.gas_estimator.estimate_gas_units_cost(from, BRIDGE_GAS_UNITS, eth_price).await;
+
+        // Transfers are stated in base units, so both ends need the token's address
+        let tokens = (
+            resolve_token(&self.market_state.get_amm_pools(from).await, None, asset),
+            resolve_token(&self.market_state.get_amm_pools(to).await, None, asset),
+        );
+        let (Some((token_in, price_usd)), Some((token_out, _))) = tokens else {
+            debug!("Not rebalancing {} from {:?} to {:?}: token not resolved on both chains", asset, from, to);
+            return None;
+        };
+
+        Some(Route { from, to, bridge, gas_usd, token_in, token_out, price_usd })
+    }
+
+    async fn transfer_intent(&self, route: &Route, asset: &str, amount_usd: f64, now: DateTime<Utc>) -> TradeIntent {
+        let fee_usd = amount_usd * route.bridge.fee_bps as f64 / 10_000.0;
+        let cost_usd = route.cost_usd(amount_usd);
+        let amount = amount_usd / route.price_usd;
+        let received = amount * (1.0 - route.bridge.fee_bps as f64 / 10_000.0);
+        let base_units = |amount: f64, token: TokenRef| {
+            calldata::to_base_units(amount, token.decimals).unwrap_or_default().to_string()
+        };
+
+        let mut gas_prices = HashMap::new();
+        let mut sequencer_health = HashMap::new();
+        for chain in [route.from, route.to] {
+            if let Some(gas_price) = self.market_state.get_gas_price(chain).await {
+                gas_prices.insert(format!("{:?}", chain), gas_price);
+            }
+            if let Some(sequencer) = self.market_state.get_sequencer_state(chain).await {
+                sequencer_health.insert(format!("{:?}", chain), sequencer.status);
+            }
+        }
+
+        TradeIntent {
+            intent_id: Uuid::new_v4(),
+            strategy: REBALANCE_STRATEGY.to_string(),
+            asset: asset.to_string(),
+            size_usd: amount_usd,
+            expected_pnl_usd: -cost_usd,
+            net_bps: -cost_usd / amount_usd * 10_000.0,
+            success_prob: 1.0,
+            legs: vec![TradeLeg {
+                domain: route.from,
+                destination_domain: Some(route.to),
+                action: TradeAction::Bridge,
+                protocol: route.bridge.bridge_type.clone(),
+                asset_in: asset.to_string(),
+                asset_out: asset.to_string(),
+                amount_in: base_units(amount, route.token_in),
+                min_amount_out: base_units(received, route.token_out),
+                max_fee_bps: route.bridge.fee_bps,
+                deadline: now + Duration::seconds(self.config.time_budget_secs as i64),
+                expected_out: base_units(received, route.token_out),
+                token_in: format!("{:#x}", route.token_in.address),
+                token_out: format!("{:#x}", route.token_out.address),
+                amount_in_usd: amount_usd,
+                expected_out_usd: amount_usd - fee_usd,
+                target: Some(route.bridge.bridge_address.clone()),
+                calldata: None,
+                pool: None,
+                split_of: None,
+            }],
+            ttl_seconds: self.config.time_budget_secs,
+            created_at: now,
+            metadata: TradeMetadata {
+                detected_at: now,
+                detector: REBALANCE_STRATEGY.to_string(),
+                market_snapshot: MarketSnapshot {
+                    gas_prices,
+                    sequencer_health,
+                    volatility: 0.0,
+                },
+                risk_factors: Vec::new(),
+            },
+            paper: false,
+        }
+    }
+
+    async fn eth_price(&self) -> f64 {
+        for chain in CHAINS {
+            if let Some(price) = self.market_state.get_price(chain, "WETH").await {
+                return price;
+            }
+        }
+        3000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::CandidateRecord;
+    use crate::audit::{AuditOutcome, AuditRecord, MarketInputs};
+    use crate::decision::candidate_chains;
+    use qenus_dataplane::{
+        AmmFeature, BridgeFeature, DepthCurve, Feature, FeatureData, FeatureType, SequencerHealthFeature,
+        SequencerStatus, TokenBalance, TokenInfo, WalletBalanceFeature,
+    };
+
+    fn holding(chain: Chain, amount_usd: f64) -> InventoryAmount {
+        InventoryAmount { chain, asset: "USDC".to_string(), amount_usd }
+    }
+
+    fn bridge(from: Chain, to: Chain, bridge_type: &str, fee_bps: u32, settlement_time_estimate: u64) -> Feature {
+        Feature::new(
+            1,
+            from,
+            FeatureType::Bridge,
+            FeatureData::Bridge(BridgeFeature {
+                bridge_address: format!("0x{}", bridge_type),
+                bridge_type: bridge_type.to_string(),
+                source_chain: from,
+                dest_chain: to,
+                token: TokenInfo { address: String::new(), symbol: "USDC".to_string(), decimals: 6 },
+                liquidity: "10000000".to_string(),
+                fee_bps,
+                settlement_time_estimate,
+                is_active: true,
+            }),
+            "test".to_string(),
+        )
+    }
+
+    /// WETH/USDC pool listing the chain's USDC, one address per chain
+    fn pool(chain: Chain, index: u8) -> Feature {
+        Feature::new(
+            1,
+            chain,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: format!("0x{:040x}", 0x100 + index as u32),
+                pool_type: "uniswap_v3".to_string(),
+                pool_id: None,
+                token0: TokenInfo { address: format!("0x{:040x}", 0x200 + index as u32), symbol: "WETH".to_string(), decimals: 18 },
+                token1: TokenInfo { address: format!("0x{:040x}", 0x300 + index as u32), symbol: "USDC".to_string(), decimals: 6 },
+                fee_tier: Some(500),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price: 3000.0,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve::default(),
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        )
+    }
+
+    fn sequencer(chain: Chain, status: SequencerStatus) -> Feature {
+        Feature::new(
+            1,
+            chain,
+            FeatureType::SequencerHealth,
+            FeatureData::SequencerHealth(SequencerHealthFeature {
+                sequencer_address: String::new(),
+                status,
+                block_interval_avg: 0.25,
+                block_interval_variance: 0.0,
+                uptime_percentage: 100.0,
+                last_block_time: Utc::now(),
+                pending_tx_count: 0,
+            }),
+            "test".to_string(),
+        )
+    }
+
+    fn wallet(chain: Chain, usdc_balance: &str) -> Feature {
+        Feature::new(
+            1,
+            chain,
+            FeatureType::WalletBalance,
+            FeatureData::WalletBalance(WalletBalanceFeature {
+                wallet_address: "0xexec".to_string(),
+                native_symbol: "ETH".to_string(),
+                native_balance: "1.0".to_string(),
+                token_balances: vec![TokenBalance {
+                    token: TokenInfo { address: String::new(), symbol: "USDC".to_string(), decimals: 6 },
+                    balance: usdc_balance.to_string(),
+                }],
+            }),
+            "test".to_string(),
+        )
+    }
+
+    async fn planner(features: Vec<Feature>, balances: Vec<InventoryAmount>) -> RebalancePlanner {
+        let market_state = Arc::new(MarketState::new(30));
+        let pools = CHAINS.into_iter().zip(0..).map(|(chain, index)| pool(chain, index));
+        for feature in pools.chain(features) {
+            market_state.ingest_feature(feature).await.unwrap();
+        }
+        let config = RebalanceConfig {
+            enabled: true,
+            targets: vec![
+                holding(Chain::Ethereum, 100_000.0),
+                holding(Chain::Arbitrum, 100_000.0),
+                holding(Chain::Base, 100_000.0),
+            ],
+            time_budget_secs: 1_800,
+            ..Default::default()
+        };
+        RebalancePlanner::new(market_state, Arc::new(InventoryLedger::new(&balances)), &config)
+    }
+
+    #[tokio::test]
+    async fn test_plans_cheapest_route_within_time_budget() {
+        let planner = planner(
+            vec![
+                bridge(Chain::Ethereum, Chain::Arbitrum, "canonical", 0, 600),
+                bridge(Chain::Ethereum, Chain::Arbitrum, "across", 4, 60),
+                // Cheapest, but too slow for the budget
+                bridge(Chain::Ethereum, Chain::Arbitrum, "slow", 0, 7_200),
+                sequencer(Chain::Arbitrum, SequencerStatus::Healthy),
+            ],
+            vec![holding(Chain::Ethereum, 160_000.0), holding(Chain::Arbitrum, 40_000.0)],
+        ).await;
+
+        let intents = planner.plan(&[]).await;
+        assert_eq!(intents.len(), 1);
+        let leg = &intents[0].legs[0];
+        assert_eq!(leg.protocol, "canonical");
+        assert_eq!((leg.domain, leg.destination_domain), (Chain::Ethereum, Some(Chain::Arbitrum)));
+        assert_eq!(leg.amount_in, "60000000000");
+        assert_eq!(leg.token_out, format!("0x{:040x}", 0x301));
+        assert!(intents[0].expected_pnl_usd < 0.0);
+        validate_flow(&intents[0]).unwrap();
+
+        // Audited like an emitted decision, on both chains of the transfer
+        let audited = AuditRecord::new(CandidateRecord::rebalance(&intents[0], true), MarketInputs::default());
+        assert_eq!(audited.outcome, AuditOutcome::Emitted);
+        assert_eq!(candidate_chains(&audited.record.candidate), vec![Chain::Ethereum, Chain::Arbitrum]);
+
+        // Already in flight: nothing more to do
+        assert!(planner.plan(&intents).await.is_empty());
+
+        // Settled: the ledger is back on target
+        planner.ledger().settle(&intents[0]).await.unwrap();
+        assert_eq!(planner.ledger().balance(Chain::Arbitrum, "USDC").await, 100_000.0);
+        assert!(planner.plan(&[]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ranks_routes_by_cost_after_reconciling_wallets() {
+        let planner = planner(
+            vec![
+                // No bridge fee, but L1 deposit gas
+                bridge(Chain::Ethereum, Chain::Arbitrum, "canonical", 0, 600),
+                bridge(Chain::Base, Chain::Arbitrum, "across", 3, 60),
+                sequencer(Chain::Arbitrum, SequencerStatus::Healthy),
+                wallet(Chain::Base, "130000"),
+            ],
+            vec![
+                holding(Chain::Ethereum, 130_000.0),
+                holding(Chain::Arbitrum, 70_000.0),
+                holding(Chain::Base, 100_000.0),
+            ],
+        ).await;
+
+        // The wallet shows a $30k surplus on Base the ledger did not know about,
+        // and $9 of bridge fee on it beats L1 gas
+        let intents = planner.plan(&[]).await;
+        assert_eq!(planner.ledger().balance(Chain::Base, "USDC").await, 130_000.0);
+        assert_eq!(intents.len(), 1);
+        assert_eq!(intents[0].legs[0].domain, Chain::Base);
+        assert_eq!(intents[0].legs[0].amount_in, "30000000000");
+    }
+
+    #[tokio::test]
+    async fn test_ranks_routes_by_cost_per_dollar_moved() {
+        let planner = planner(
+            vec![
+                bridge(Chain::Ethereum, Chain::Arbitrum, "canonical", 0, 600),
+                bridge(Chain::Base, Chain::Arbitrum, "across", 30, 60),
+                sequencer(Chain::Arbitrum, SequencerStatus::Healthy),
+            ],
+            vec![
+                holding(Chain::Ethereum, 300_000.0),
+                holding(Chain::Arbitrum, 0.0),
+                holding(Chain::Base, 110_000.0),
+            ],
+        ).await;
+
+        // $30 of bridge fee on Base's $10k is less than L1 gas in total, but
+        // L1 gas spread over the whole $100k deficit is cheaper per dollar
+        let intents = planner.plan(&[]).await;
+        assert_eq!(intents.len(), 1);
+        assert_eq!(intents[0].legs[0].domain, Chain::Ethereum);
+        assert_eq!(intents[0].legs[0].amount_in, "100000000000");
+    }
+
+    #[tokio::test]
+    async fn test_drops_in_flight_transfers_once_wallets_reflect_them() {
+        let planner = planner(
+            vec![
+                bridge(Chain::Ethereum, Chain::Arbitrum, "canonical", 0, 600),
+                bridge(Chain::Arbitrum, Chain::Ethereum, "canonical", 0, 600),
+                sequencer(Chain::Arbitrum, SequencerStatus::Healthy),
+            ],
+            vec![holding(Chain::Ethereum, 160_000.0), holding(Chain::Arbitrum, 40_000.0)],
+        ).await;
+        let intents = planner.plan(&[]).await;
+        assert_eq!(intents.len(), 1);
+
+        // The transfer landed but has not been settled yet: counting it again
+        // on top of the wallets would call for sending it back
+        planner.market_state.ingest_feature(wallet(Chain::Ethereum, "100000")).await.unwrap();
+        planner.market_state.ingest_feature(wallet(Chain::Arbitrum, "100000")).await.unwrap();
+        assert!(planner.plan(&intents).await.is_empty());
+        assert_eq!(planner.ledger().balance(Chain::Arbitrum, "USDC").await, 100_000.0);
+    }
+
+    #[tokio::test]
+    async fn test_skips_chains_with_degraded_sequencer() {
+        let planner = planner(
+            vec![
+                bridge(Chain::Ethereum, Chain::Arbitrum, "canonical", 0, 600),
+                bridge(Chain::Ethereum, Chain::Base, "canonical", 0, 600),
+                sequencer(Chain::Arbitrum, SequencerStatus::Degraded),
+                sequencer(Chain::Base, SequencerStatus::Healthy),
+            ],
+            vec![
+                holding(Chain::Ethereum, 200_000.0),
+                holding(Chain::Arbitrum, 20_000.0),
+                holding(Chain::Base, 20_000.0),
+            ],
+        ).await;
+
+        let intents = planner.plan(&[]).await;
+        assert_eq!(intents.len(), 1);
+        assert_eq!(intents[0].legs[0].destination_domain, Some(Chain::Base));
+        assert_eq!(intents[0].legs[0].amount_in, "80000000000");
+    }
+}
@@ -1,6 +1,15 @@
 //! AMM swap simulation models
 
 use crate::{Result, IntelligenceError};
+use qenus_dataplane::parse_size_label;
+
+use crate::state::AmmState;
+
+/// Fallback slippage per $1M traded when a pool has no depth curve
+const FALLBACK_SLIPPAGE_BPS_PER_MILLION: f64 = 30.0;
+
+/// Assets priced at $1 when converting USD amounts to token amounts
+pub const USD_STABLECOINS: &[&str] = &["USDC", "USDT", "DAI", "FRAX", "LUSD", "USDC.e"];
 
 /// Simulate Uniswap V3 concentrated liquidity swap
 pub fn simulate_uniswap_v3_swap(
@@ -62,9 +71,123 @@ pub fn simulate_balancer_swap(
     Ok((amount_out, slippage_bps))
 }
 
+/// Pool fee in basis points
+///
+/// Uniswap V3 extractors report fee tiers in hundredths of a bip (500, 3000),
+/// the other extractors report basis points.
+pub fn pool_fee_bps(pool: &AmmState) -> f64 {
+    match pool.fee_tier {
+        Some(fee) if fee >= 100 => fee as f64 / 100.0,
+        Some(fee) => fee as f64,
+        None => 30.0,
+    }
+}
+
+/// Estimate slippage for a trade of `size_usd` from a pool's depth curve
+///
+/// Uses the sampled quotes when the pool has them, taking the worse side of
+/// the book. Otherwise interpolates linearly between the labelled depth
+/// points ("100k", "1m", ...) and extrapolates past the largest one.
+pub fn depth_slippage_bps(pool: &AmmState, size_usd: f64) -> f64 {
+    let sampled = [true, false].into_iter()
+        .filter_map(|zero_for_one| sampled_slippage_bps(pool, zero_for_one, size_usd))
+        .reduce(f64::max);
+    if let Some(slippage_bps) = sampled {
+        return slippage_bps;
+    }
+    
+    let mut points: Vec<(f64, f64)> = pool.depth.iter()
+        .filter_map(|(label, (slippage_bps, _))| parse_size_label(label).map(|size| (size, *slippage_bps)))
+        .collect();
+    
+    if points.is_empty() {
+        return (size_usd / 1_000_000.0 * FALLBACK_SLIPPAGE_BPS_PER_MILLION).max(1.0);
+    }
+    
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    
+    let mut prev = (0.0, 0.0);
+    for (size, slippage) in &points {
+        if size_usd <= *size {
+            let t = (size_usd - prev.0) / (size - prev.0);
+            return prev.1 + t * (slippage - prev.1);
+        }
+        prev = (*size, *slippage);
+    }
+    
+    // Past the largest point: scale linearly with size
+    prev.1 * size_usd / prev.0
+}
+
+/// Estimate slippage for selling `size_usd` of `asset_in` into a pool
+///
+/// Prices the trade on the matching side of the sampled book; falls back to
+/// `depth_slippage_bps` when that side has no samples.
+pub fn swap_slippage_bps(pool: &AmmState, asset_in: &str, size_usd: f64) -> f64 {
+    let zero_for_one = if pool.token0_symbol == asset_in {
+        Some(true)
+    } else if pool.token1_symbol == asset_in {
+        Some(false)
+    } else {
+        None
+    };
+    
+    zero_for_one
+        .and_then(|zero_for_one| sampled_slippage_bps(pool, zero_for_one, size_usd))
+        .unwrap_or_else(|| depth_slippage_bps(pool, size_usd))
+}
+
+/// Slippage from the sampled quotes of one side of the book
+///
+/// Samples are in token units, so `size_usd` is converted to an amount of the
+/// input token through the pool's stablecoin side. None if neither side is a
+/// stablecoin.
+fn sampled_slippage_bps(pool: &AmmState, zero_for_one: bool, size_usd: f64) -> Option<f64> {
+    if !pool.depth_curve.has_samples(zero_for_one) {
+        return None;
+    }
+    let token1_usd = usd_price(std::slice::from_ref(pool), &pool.token1_symbol)?;
+    let token_in_usd = if zero_for_one { pool.mid_price * token1_usd } else { token1_usd };
+    if !(token_in_usd.is_finite() && token_in_usd > 0.0) {
+        return None;
+    }
+    pool.depth_curve.slippage_bps(zero_for_one, size_usd / token_in_usd, pool.mid_price)
+}
+
+/// Units of the other token a pool pays per unit of `asset_in` at its mid price
+pub fn mid_rate(pool: &AmmState, asset_in: &str) -> Option<f64> {
+    let rate = if pool.token0_symbol == asset_in {
+        pool.mid_price
+    } else if pool.token1_symbol == asset_in {
+        1.0 / pool.mid_price
+    } else {
+        return None;
+    };
+    Some(rate).filter(|rate| rate.is_finite() && *rate > 0.0)
+}
+
+/// USD price of `symbol` from a pool pairing it with a stablecoin
+pub fn usd_price(pools: &[AmmState], symbol: &str) -> Option<f64> {
+    if USD_STABLECOINS.contains(&symbol) {
+        return Some(1.0);
+    }
+    
+    // mid_price is token0 quoted in token1
+    pools.iter().find_map(|pool| {
+        if pool.token0_symbol == symbol && USD_STABLECOINS.contains(&pool.token1_symbol.as_str()) {
+            Some(pool.mid_price)
+        } else if pool.token1_symbol == symbol && USD_STABLECOINS.contains(&pool.token0_symbol.as_str()) {
+            Some(1.0 / pool.mid_price)
+        } else {
+            None
+        }
+    }).filter(|price| price.is_finite() && *price > 0.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use qenus_dataplane::DepthCurve;
     
     #[test]
     fn test_uniswap_v3_swap() {
@@ -90,5 +213,100 @@ mod tests {
         assert!(amount_out > 990.0); // Curve has low fees
         assert!(slippage < 5.0); // Low slippage for stables
     }
+    
+    #[test]
+    fn test_depth_slippage_interpolation() {
+        let mut depth = std::collections::HashMap::new();
+        depth.insert("100k".to_string(), (10.0, 0.001));
+        depth.insert("1m".to_string(), (100.0, 0.01));
+        
+        let pool = AmmState {
+            pool_address: "0xpool".to_string(),
+            pool_type: "uniswap_v3".to_string(),
+            pool_id: None,
+            token0_symbol: "USDC".to_string(),
+            token1_symbol: "WETH".to_string(),
+            token0_address: String::new(),
+            token1_address: String::new(),
+            token0_decimals: 18,
+            token1_decimals: 18,
+            mid_price: 3000.0,
+            liquidity: "0".to_string(),
+            fee_tier: Some(3000),
+            depth,
+            depth_curve: DepthCurve::default(),
+            last_update: chrono::Utc::now(),
+        };
+        
+        assert!((depth_slippage_bps(&pool, 50_000.0) - 5.0).abs() < 1e-9);
+        assert!((depth_slippage_bps(&pool, 550_000.0) - 55.0).abs() < 1e-9);
+        assert!((depth_slippage_bps(&pool, 2_000_000.0) - 200.0).abs() < 1e-9);
+        assert_eq!(pool_fee_bps(&pool), 30.0);
+    }
+    
+    #[test]
+    fn test_sampled_depth_prices_each_side() {
+        let mut pool = AmmState {
+            pool_address: "0xpool".to_string(),
+            pool_type: "uniswap_v3".to_string(),
+            pool_id: None,
+            token0_symbol: "WETH".to_string(),
+            token1_symbol: "USDC".to_string(),
+            token0_address: String::new(),
+            token1_address: String::new(),
+            token0_decimals: 18,
+            token1_decimals: 6,
+            mid_price: 2000.0,
+            liquidity: "0".to_string(),
+            fee_tier: Some(500),
+            depth: [("1m".to_string(), (1.0, 0.0001))].into_iter().collect(),
+            depth_curve: DepthCurve::default(),
+            last_update: chrono::Utc::now(),
+        };
+        
+        // Labels only: both directions read the labels
+        assert!((swap_slippage_bps(&pool, "WETH", 1_000_000.0) - 1.0).abs() < 1e-9);
+        
+        // Selling WETH is deeper than buying it
+        let mut curve = DepthCurve::sample(2000.0, 1.0, &[1_000_000.0], |size| size / 1_000_000.0 * 10.0);
+        curve.one_for_zero = DepthCurve::sample(2000.0, 1.0, &[1_000_000.0], |size| size / 1_000_000.0 * 40.0).one_for_zero;
+        pool.depth_curve = curve;
+        
+        assert!((swap_slippage_bps(&pool, "WETH", 500_000.0) - 10.0).abs() < 1e-9);
+        assert!((swap_slippage_bps(&pool, "USDC", 500_000.0) - 40.0).abs() < 1e-9);
+        assert!((depth_slippage_bps(&pool, 500_000.0) - 40.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_sampled_depth_converts_usd_through_non_stable_token1() {
+        // USDC/WETH: token1 is WETH at $2000, so the samples are in WETH
+        let sizes = [100_000.0, 1_000_000.0];
+        let mut curve = DepthCurve::sample(0.0005, 2000.0, &sizes, |size| size / 100_000.0 * 10.0);
+        curve.one_for_zero = DepthCurve::sample(0.0005, 2000.0, &sizes, |size| size / 100_000.0 * 20.0).one_for_zero;
+        let mut pool = AmmState {
+            pool_address: "0xpool".to_string(),
+            pool_type: "uniswap_v3".to_string(),
+            pool_id: None,
+            token0_symbol: "USDC".to_string(),
+            token1_symbol: "WETH".to_string(),
+            token0_address: String::new(),
+            token1_address: String::new(),
+            token0_decimals: 6,
+            token1_decimals: 18,
+            mid_price: 0.0005,
+            liquidity: "0".to_string(),
+            fee_tier: Some(500),
+            depth: [("1m".to_string(), (1.0, 0.0001))].into_iter().collect(),
+            depth_curve: curve,
+            last_update: chrono::Utc::now(),
+        };
+        
+        // $500k is 500k USDC or 250 WETH, between the two samples on each side
+        assert!((swap_slippage_bps(&pool, "USDC", 500_000.0) - 90.0).abs() < 1e-6);
+        assert!((swap_slippage_bps(&pool, "WETH", 500_000.0) - 180.0).abs() < 1e-6);
+        
+        // Without a stablecoin side the samples can't be sized; read the labels
+        pool.token0_symbol = "WBTC".to_string();
+        assert!((swap_slippage_bps(&pool, "WETH", 1_000_000.0) - 1.0).abs() < 1e-9);
+    }
 }
-
@@ -0,0 +1,451 @@
+//! Competition and inclusion-risk models
+//!
+//! Other searchers see the same spreads we do. This module estimates the
+//! probability that an opportunity is already gone by the time our transaction
+//! lands, or that a competitor gets ordered ahead of us, and recommends the
+//! priority fee that maximizes expected PnL under that competition.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use qenus_dataplane::Chain;
+
+use crate::state::MarketState;
+use crate::Candidate;
+
+/// Spread histories not seen for this many blocks are dropped
+const SPREAD_RETENTION_BLOCKS: u64 = 100;
+
+/// Prior failure rate used to smooth historical receipt outcomes
+const PRIOR_FAILURE_RATE: f64 = 0.2;
+
+/// Weight (in pseudo-samples) of the prior failure rate
+const PRIOR_WEIGHT: f64 = 10.0;
+
+/// Bid multipliers (relative to the competitive priority fee) evaluated when
+/// searching for the best bid
+const BID_MULTIPLIERS: [f64; 10] = [0.5, 0.75, 1.0, 1.25, 1.5, 2.0, 3.0, 5.0, 8.0, 12.0];
+
+/// How transactions are ordered on a chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderingModel {
+    /// Private orderflow to builders - ordering is a priority fee auction,
+    /// losing bundles never land and cost nothing
+    PrivateMempool,
+
+    /// Sequencer first-come-first-served - latency dominates, priority fee
+    /// only buys inclusion and losing transactions revert on-chain
+    SequencerFcfs,
+}
+
+impl OrderingModel {
+    /// Ordering model used for a chain
+    pub fn for_chain(chain: Chain) -> Self {
+        match chain {
+            Chain::Ethereum => OrderingModel::PrivateMempool,
+            Chain::Arbitrum | Chain::Optimism | Chain::Base => OrderingModel::SequencerFcfs,
+        }
+    }
+
+    /// Expected number of competing searchers per block for a $100 opportunity
+    fn base_competition(&self) -> f64 {
+        match self {
+            OrderingModel::PrivateMempool => 0.6,
+            OrderingModel::SequencerFcfs => 0.35,
+        }
+    }
+
+    /// Blocks an opportunity is exposed for between detection and inclusion
+    fn exposure_blocks(&self) -> f64 {
+        match self {
+            OrderingModel::PrivateMempool => 1.0,
+            OrderingModel::SequencerFcfs => 2.0,
+        }
+    }
+}
+
+/// Competition estimate for a single opportunity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetitionEstimate {
+    /// Ordering model of the execution chain
+    pub ordering: OrderingModel,
+
+    /// Probability the opportunity is already captured when we land
+    pub taken_prob: f64,
+
+    /// Probability a competitor is ordered ahead of us at the recommended bid
+    pub frontrun_prob: f64,
+
+    /// Probability of winning inclusion at the recommended bid
+    pub inclusion_prob: f64,
+
+    /// Number of consecutive observations of this spread
+    pub persistence: u32,
+
+    /// Recommended priority fee in gwei
+    pub recommended_priority_fee_gwei: f64,
+
+    /// Cost of the recommended priority fee in USD
+    pub priority_fee_usd: f64,
+
+    /// Expected PnL in USD at the recommended bid
+    pub expected_pnl_usd: f64,
+}
+
+impl CompetitionEstimate {
+    /// Probability that we capture the opportunity (not taken, not front-run)
+    pub fn capture_prob(&self) -> f64 {
+        (1.0 - self.taken_prob) * (1.0 - self.frontrun_prob)
+    }
+}
+
+/// Spread history for one opportunity key
+#[derive(Debug, Clone)]
+struct SpreadHistory {
+    chain: Chain,
+    last_block: u64,
+    consecutive: u32,
+}
+
+/// Inclusion outcomes observed from execution receipts
+#[derive(Debug, Clone, Default)]
+struct OutcomeStats {
+    successes: u64,
+    failures: u64,
+}
+
+impl OutcomeStats {
+    /// Failure rate smoothed towards the prior
+    fn smoothed_failure_rate(&self) -> f64 {
+        let total = (self.successes + self.failures) as f64;
+        (self.failures as f64 + PRIOR_FAILURE_RATE * PRIOR_WEIGHT) / (total + PRIOR_WEIGHT)
+    }
+}
+
+/// Competition model - tracks spread persistence and receipt outcomes
+pub struct CompetitionModel {
+    market_state: Arc<MarketState>,
+
+    /// Spread observations by opportunity key
+    spreads: Arc<RwLock<HashMap<String, SpreadHistory>>>,
+
+    /// Historical receipt outcomes by chain
+    outcomes: Arc<RwLock<HashMap<Chain, OutcomeStats>>>,
+}
+
+impl CompetitionModel {
+    pub fn new(market_state: Arc<MarketState>) -> Self {
+        Self {
+            market_state,
+            spreads: Arc::new(RwLock::new(HashMap::new())),
+            outcomes: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Record a receipt outcome for a chain (fed from execution feedback)
+    pub async fn record_outcome(&self, chain: Chain, success: bool) {
+        let mut outcomes = self.outcomes.write().await;
+        let stats = outcomes.entry(chain).or_default();
+        if success {
+            stats.successes += 1;
+        } else {
+            stats.failures += 1;
+        }
+    }
+
+    /// Estimate competition for a candidate executing on `chain`
+    ///
+    /// `pnl_before_bid_usd` is the simulated PnL before any priority fee and
+    /// `gas_units` the gas consumed by the transaction that competes for ordering.
+    pub async fn estimate(
+        &self,
+        candidate: &Candidate,
+        chain: Chain,
+        pnl_before_bid_usd: f64,
+        gas_units: f64,
+        eth_price: f64,
+    ) -> CompetitionEstimate {
+        let ordering = OrderingModel::for_chain(chain);
+        let persistence = self.observe_spread(candidate, chain).await;
+        let failure_rate = self.failure_rate(chain).await;
+
+        // Bigger opportunities attract more searchers
+        let size_factor = (1.0 + pnl_before_bid_usd.max(0.0) / 100.0).ln().max(0.1);
+
+        // A spread that survived several blocks is less contested (or stale)
+        let persistence_factor = 1.0 / (1.0 + 0.5 * (persistence.saturating_sub(1)) as f64);
+
+        // Scale by how often we lost recently versus the prior
+        let history_factor = (failure_rate / PRIOR_FAILURE_RATE).clamp(0.5, 3.0);
+
+        let intensity = ordering.base_competition() * size_factor * persistence_factor * history_factor;
+        let taken_prob = (1.0 - (-intensity * ordering.exposure_blocks()).exp()).clamp(0.0, 0.95);
+
+        let competitive_fee = self.competitive_priority_fee(chain, size_factor).await;
+        let (bid, win_prob, expected_pnl_usd) = self.best_bid(
+            ordering,
+            competitive_fee,
+            taken_prob,
+            pnl_before_bid_usd,
+            gas_units,
+            eth_price,
+        );
+
+        let frontrun_prob = match ordering {
+            // Losing the auction means someone else was ordered ahead of us
+            OrderingModel::PrivateMempool => 1.0 - win_prob,
+            // FCFS: a faster competitor gets in first regardless of our bid
+            OrderingModel::SequencerFcfs => (intensity * 0.25).min(0.5),
+        };
+
+        CompetitionEstimate {
+            ordering,
+            taken_prob,
+            frontrun_prob,
+            inclusion_prob: win_prob,
+            persistence,
+            recommended_priority_fee_gwei: bid,
+            priority_fee_usd: Self::fee_usd(bid, gas_units, eth_price),
+            expected_pnl_usd,
+        }
+    }
+
+    /// Record a spread observation and return its persistence streak
+    ///
+    /// The streak counts consecutive sealed blocks of `chain` the spread was
+    /// seen in; evaluating it again within the same block does not extend it.
+    async fn observe_spread(&self, candidate: &Candidate, chain: Chain) -> u32 {
+        let key = candidate.fingerprint();
+        let block = self.market_state.block_view(chain).map(|view| view.block_number).unwrap_or(0);
+        let mut spreads = self.spreads.write().await;
+
+        let history = spreads.entry(key.clone()).or_insert(SpreadHistory {
+            chain,
+            last_block: block,
+            consecutive: 0,
+        });
+
+        if history.consecutive == 0 || block == history.last_block + 1 {
+            history.consecutive += 1;
+        } else if block != history.last_block {
+            // Missed a block (or the chain reorged): start a new streak
+            history.consecutive = 1;
+        }
+        history.last_block = block;
+
+        // Drop histories that have not been refreshed for a while
+        spreads.retain(|_, h| h.chain != chain || h.last_block + SPREAD_RETENTION_BLOCKS >= block);
+
+        spreads
+            .get(&key)
+            .map(|h| h.consecutive)
+            .unwrap_or(1)
+    }
+
+    /// Receipt failure rate on `chain`, smoothed towards the prior
+    pub(crate) async fn failure_rate(&self, chain: Chain) -> f64 {
+        let outcomes = self.outcomes.read().await;
+        outcomes
+            .get(&chain)
+            .map(|stats| stats.smoothed_failure_rate())
+            .unwrap_or(PRIOR_FAILURE_RATE)
+    }
+
+    /// Priority fee (gwei) competitors are expected to bid for this opportunity
+    async fn competitive_priority_fee(&self, chain: Chain, size_factor: f64) -> f64 {
+        let market_fee = self
+            .market_state
+            .get_priority_fee(chain)
+            .await
+            .unwrap_or(match chain {
+                Chain::Ethereum => 2.0,
+                _ => 0.01,
+            });
+
+        market_fee * (1.0 + size_factor)
+    }
+
+    /// Search bid multipliers for the bid that maximizes expected PnL
+    ///
+    /// Returns (bid_gwei, win_probability, expected_pnl_usd).
+    fn best_bid(
+        &self,
+        ordering: OrderingModel,
+        competitive_fee: f64,
+        taken_prob: f64,
+        pnl_before_bid_usd: f64,
+        gas_units: f64,
+        eth_price: f64,
+    ) -> (f64, f64, f64) {
+        let mut best = (competitive_fee, 0.0, f64::NEG_INFINITY);
+
+        for multiplier in BID_MULTIPLIERS {
+            let bid = competitive_fee * multiplier;
+            let cost = Self::fee_usd(bid, gas_units, eth_price);
+            let win_prob = Self::win_probability(ordering, multiplier);
+            let capture = win_prob * (1.0 - taken_prob);
+
+            let expected = match ordering {
+                // Losing bundles are dropped, so the bid is only paid on success
+                OrderingModel::PrivateMempool => capture * (pnl_before_bid_usd - cost),
+                // Losing transactions still land and pay for their gas
+                OrderingModel::SequencerFcfs => capture * pnl_before_bid_usd - cost,
+            };
+
+            if expected > best.2 {
+                best = (bid, win_prob, expected);
+            }
+        }
+
+        best
+    }
+
+    /// Probability of winning ordering when bidding `multiplier` times the
+    /// competitive priority fee
+    fn win_probability(ordering: OrderingModel, multiplier: f64) -> f64 {
+        match ordering {
+            // Logistic in log-bid space, 50% at the competitive fee
+            OrderingModel::PrivateMempool => 1.0 / (1.0 + (-2.5 * multiplier.ln()).exp()),
+            // Any bid at or above the floor is included in arrival order
+            OrderingModel::SequencerFcfs => {
+                if multiplier >= 1.0 {
+                    0.95
+                } else {
+                    0.6
+                }
+            }
+        }
+    }
+
+    fn fee_usd(priority_fee_gwei: f64, gas_units: f64, eth_price: f64) -> f64 {
+        priority_fee_gwei * gas_units / 1e9 * eth_price
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, Utc};
+    use crate::types::{CandidateDetails, FlashArbDetails};
+
+    fn candidate(spread_bps: f64, detected_at: DateTime<Utc>) -> Candidate {
+        Candidate {
+            strategy: "dex_arb".to_string(),
+            asset: "WETH".to_string(),
+            spread_bps,
+            legs: vec![
+                ("uniswap_v3 on Ethereum".to_string(), "buy".to_string()),
+                ("curve on Ethereum".to_string(), "sell".to_string()),
+            ],
+            detected_at,
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        }
+    }
+
+    /// Ingest a gas feature at `block` and seal it
+    async fn seal(state: &MarketState, block: u64) {
+        use qenus_dataplane::{Feature, FeatureData, FeatureType, GasFeature};
+
+        let gas = Feature::new(block, Chain::Ethereum, FeatureType::Gas, FeatureData::Gas(GasFeature {
+            base_fee: 20.0,
+            priority_fee: 1.0,
+            gas_used_ratio: 0.5,
+            next_base_fee_estimate: 20.0,
+            fast_gas_price: 22.0,
+            standard_gas_price: 21.0,
+            safe_gas_price: 20.0,
+            pending_tx_count: 0,
+        }), "test".to_string());
+        state.ingest_feature(gas).await.unwrap();
+        state.end_block(Chain::Ethereum, block);
+    }
+
+    #[tokio::test]
+    async fn test_persistent_spread_is_less_contested() {
+        let state = Arc::new(MarketState::new(30));
+        let model = CompetitionModel::new(state.clone());
+        let start = Utc::now();
+
+        seal(&state, 1).await;
+        let first = model.estimate(&candidate(20.0, start), Chain::Ethereum, 1000.0, 300_000.0, 3000.0).await;
+        let mut last = first.clone();
+        for block in 2..=5 {
+            seal(&state, block).await;
+            let seen_at = start + Duration::seconds(12 * (block as i64 - 1));
+            last = model.estimate(&candidate(20.0, seen_at), Chain::Ethereum, 1000.0, 300_000.0, 3000.0).await;
+        }
+
+        assert_eq!(first.persistence, 1);
+        assert_eq!(last.persistence, 5);
+        assert!(last.taken_prob < first.taken_prob);
+
+        // Re-evaluating within the same block does not extend the streak
+        for _ in 0..3 {
+            last = model.estimate(&candidate(20.0, Utc::now()), Chain::Ethereum, 1000.0, 300_000.0, 3000.0).await;
+        }
+        assert_eq!(last.persistence, 5);
+
+        // A block without the spread starts a new streak
+        seal(&state, 7).await;
+        last = model.estimate(&candidate(20.0, Utc::now()), Chain::Ethereum, 1000.0, 300_000.0, 3000.0).await;
+        assert_eq!(last.persistence, 1);
+    }
+
+    #[tokio::test]
+    async fn test_spreads_in_different_pools_persist_separately() {
+        let state = Arc::new(MarketState::new(30));
+        let model = CompetitionModel::new(state.clone());
+        let start = Utc::now();
+        let in_pools = |buy_pool: &str, seen_at: DateTime<Utc>| {
+            let mut candidate = candidate(20.0, seen_at);
+            candidate.details = Some(CandidateDetails::FlashArb(FlashArbDetails {
+                chain: Chain::Ethereum,
+                borrow_asset: "USDC".to_string(),
+                buy_pool: buy_pool.to_string(),
+                sell_pool: "0xcurve".to_string(),
+                gross_spread_bps: 20.0,
+                size_usd: 100_000.0,
+            }));
+            candidate
+        };
+
+        seal(&state, 1).await;
+        model.estimate(&in_pools("0xuni5", start), Chain::Ethereum, 1000.0, 300_000.0, 3000.0).await;
+        seal(&state, 2).await;
+        let other_pool = in_pools("0xuni30", start + Duration::seconds(12));
+        let estimate = model.estimate(&other_pool, Chain::Ethereum, 1000.0, 300_000.0, 3000.0).await;
+        assert_eq!(estimate.persistence, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failures_increase_competition() {
+        let model = CompetitionModel::new(Arc::new(MarketState::new(30)));
+        let baseline = model.estimate(&candidate(20.0, Utc::now()), Chain::Arbitrum, 500.0, 300_000.0, 3000.0).await;
+
+        for _ in 0..20 {
+            model.record_outcome(Chain::Arbitrum, false).await;
+        }
+
+        let after = model.estimate(&candidate(20.0, Utc::now()), Chain::Arbitrum, 500.0, 300_000.0, 3000.0).await;
+        assert!(after.taken_prob > baseline.taken_prob);
+        assert_eq!(after.ordering, OrderingModel::SequencerFcfs);
+    }
+
+    #[test]
+    fn test_private_mempool_bid_tradeoff() {
+        let model = CompetitionModel::new(Arc::new(MarketState::new(30)));
+
+        // Large opportunity: worth overbidding the competitive fee
+        let (big_bid, big_win, _) = model.best_bid(OrderingModel::PrivateMempool, 2.0, 0.1, 5000.0, 300_000.0, 3000.0);
+        // Tiny opportunity: bidding up eats the whole profit
+        let (small_bid, _, small_ev) = model.best_bid(OrderingModel::PrivateMempool, 2.0, 0.1, 5.0, 300_000.0, 3000.0);
+
+        assert!(big_bid > small_bid);
+        assert!(big_win > 0.5);
+        assert!(small_ev < 5.0);
+    }
+}
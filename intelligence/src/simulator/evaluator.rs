@@ -4,9 +4,35 @@ use std::sync::Arc;
 use tracing::{debug, warn};
 use qenus_dataplane::Chain;
 
-use crate::{Candidate, EvaluationResult, CostBreakdown, SimulatedStep, Result, IntelligenceError};
-use crate::state::MarketState;
+use crate::{Candidate, CandidateDetails, DepegKind, EvaluationResult, CostBreakdown, SimulatedStep, Result, IntelligenceError};
+use crate::decision::candidate_chains;
+use crate::intent_builder::{funding_asset, pair_pool};
+use crate::state::{AmmState, MarketState};
 use super::{gas::GasEstimator, bridge::BridgeSimulator, flashloan::FlashLoanSimulator};
+use super::competition::{CompetitionModel, CompetitionEstimate, OrderingModel};
+use super::liquidation::LiquidationSimulator;
+use super::router::{PoolAllocation, SplitRouter, SPLIT_POOL_GAS_UNITS};
+
+/// Gas units of a single swap transaction
+const SWAP_GAS_UNITS: f64 = 150_000.0;
+
+/// Gas units of flash loan overhead
+const FLASHLOAN_GAS_UNITS: f64 = 200_000.0;
+
+/// Gas units of the flash loan callback: approvals and the repayment check
+const FLASH_CALLBACK_GAS_UNITS: f64 = 60_000.0;
+
+/// Typical adverse price move between simulation and inclusion
+const REPAY_DRIFT_BPS: f64 = 3.0;
+
+/// Age at which an opportunity's success probability is halved
+const STALE_OPPORTUNITY_SECS: f64 = 300.0;
+
+/// Venue the buy leg of DEX and triangle arbitrage routes through
+const BUY_PROTOCOL: &str = "uniswap_v3";
+
+/// Venue the sell leg of DEX and triangle arbitrage routes through
+const SELL_PROTOCOL: &str = "curve";
 
 /// Trade simulator - evaluates candidates using market state
 pub struct TradeSimulator {
@@ -14,6 +40,8 @@ pub struct TradeSimulator {
     gas_estimator: GasEstimator,
     bridge_simulator: BridgeSimulator,
     flashloan_simulator: FlashLoanSimulator,
+    competition_model: Arc<CompetitionModel>,
+    liquidation_simulator: LiquidationSimulator,
 }
 
 impl TradeSimulator {
@@ -23,6 +51,8 @@ impl TradeSimulator {
             gas_estimator: GasEstimator::new(market_state.clone()),
             bridge_simulator: BridgeSimulator::new(market_state.clone()),
             flashloan_simulator: FlashLoanSimulator::new(market_state.clone()),
+            competition_model: Arc::new(CompetitionModel::new(market_state.clone())),
+            liquidation_simulator: LiquidationSimulator::new(market_state.clone()),
             market_state,
         }
     }
@@ -38,12 +68,22 @@ impl TradeSimulator {
         match candidate.strategy.as_str() {
             "triangle_arb" => self.simulate_triangle_arb(candidate, eth_price).await,
             "dex_arb" => self.simulate_dex_arb(candidate, eth_price).await,
+            "aave_liquidation" => self.simulate_liquidation(candidate, eth_price).await,
+            "stablecoin_depeg" => self.simulate_depeg(candidate, eth_price).await,
+            "flash_arb" => self.simulate_flash_arb(candidate, eth_price).await,
             _ => Err(IntelligenceError::Simulation {
                 message: format!("Unknown strategy: {}", candidate.strategy),
             }),
         }
     }
     
+    /// Competition model used for inclusion-risk estimates
+    ///
+    /// Share it with the `FeedbackProcessor` so receipts update its outcome history.
+    pub fn competition_model(&self) -> &Arc<CompetitionModel> {
+        &self.competition_model
+    }
+    
     /// Get current ETH price from market state
     async fn get_eth_price(&self) -> Option<f64> {
         for chain in &[Chain::Ethereum, Chain::Arbitrum, Chain::Optimism, Chain::Base] {
@@ -55,8 +95,23 @@ impl TradeSimulator {
     }
     
     /// Simulate triangle arbitrage
+    ///
+    /// Buys on the source chain, bridges and sells on the destination chain;
+    /// each swap leg is split across the pools trading its pair.
     async fn simulate_triangle_arb(&self, candidate: &Candidate, eth_price: f64) -> Result<EvaluationResult> {
+        let chains = candidate_chains(candidate);
+        let buy_chain = chains.first().copied().unwrap_or(Chain::Arbitrum);
+        let sell_chain = chains.get(1).copied().unwrap_or(Chain::Ethereum);
+        let buy_pools = self.market_state.get_amm_pools(buy_chain).await;
+        let sell_pools = self.market_state.get_amm_pools(sell_chain).await;
+        
+        // Bridged trades cannot be flash-loan funded
+        let funding = funding_asset(&buy_pools, BUY_PROTOCOL, &candidate.asset);
         let optimal_size_usd = self.estimate_optimal_size(candidate).await?;
+        let optimal_size_usd = match &funding {
+            Some(funding) => self.cap_by_capital(buy_chain, funding, optimal_size_usd).await?,
+            None => optimal_size_usd,
+        };
         
         let mut execution_path = Vec::new();
         let mut costs = CostBreakdown {
@@ -69,76 +124,75 @@ impl TradeSimulator {
         };
         
         // Step 1: Swap on source chain
-        let swap1_gas = self.gas_estimator.estimate_swap_gas(Chain::Arbitrum, eth_price).await;
+        let buy_assets = funding.as_deref().map(|funding| (funding, candidate.asset.as_str()));
+        let buy_pool = buy_assets.and_then(|(asset_in, asset_out)| pair_pool(&buy_pools, BUY_PROTOCOL, asset_in, asset_out));
+        let buy_overhead_usd = self.gas_estimator.estimate_gas_units_cost(buy_chain, SPLIT_POOL_GAS_UNITS, eth_price).await;
+        let parts = split_swap_leg(
+            &buy_pools, buy_pool.as_ref(), BUY_PROTOCOL, buy_assets, optimal_size_usd, None, buy_overhead_usd, (5.0, 5.0)
+        );
+        let swap1_gas = self.gas_estimator.estimate_swap_gas(buy_chain, eth_price).await +
+                        (parts.len() - 1) as f64 * buy_overhead_usd;
         costs.gas_usd += swap1_gas;
-        
-        let swap1_slippage_bps = 5.0;
-        let swap1_fee_bps = 5.0;
-        
-        costs.slippage_usd += optimal_size_usd * swap1_slippage_bps / 10000.0;
-        costs.protocol_fees_usd += optimal_size_usd * swap1_fee_bps / 10000.0;
-        
-        execution_path.push(SimulatedStep {
-            step: 1,
-            action: "swap_buy".to_string(),
-            domain: "Arbitrum".to_string(),
-            protocol: "uniswap_v3".to_string(),
-            amount_in: optimal_size_usd,
-            amount_out: optimal_size_usd * (1.0 - (swap1_slippage_bps + swap1_fee_bps) / 10000.0),
-            slippage_bps: swap1_slippage_bps,
-            cost_usd: swap1_gas + costs.slippage_usd + costs.protocol_fees_usd,
-        });
+        let bought = push_swap_leg(
+            &mut execution_path, &mut costs, 1, "swap_buy", &format!("{:?}", buy_chain), parts, buy_assets, swap1_gas, 0.0
+        );
         
         // Step 2: Bridge
         let (bridge_fee_usd, _) = self.bridge_simulator.calculate_total_bridge_cost(
-            Chain::Arbitrum, Chain::Ethereum, &candidate.asset, optimal_size_usd, eth_price
+            buy_chain, sell_chain, &candidate.asset, optimal_size_usd, eth_price
         ).await?;
         
         costs.bridge_fees_usd += bridge_fee_usd;
         costs.gas_usd += self.gas_estimator.estimate_bridge_gas(eth_price).await;
         
+        let bridged = bought - bridge_fee_usd;
         execution_path.push(SimulatedStep {
             step: 2,
             action: "bridge".to_string(),
-            domain: "Arbitrum -> Ethereum".to_string(),
+            domain: format!("{:?} -> {:?}", buy_chain, sell_chain),
             protocol: "canonical_bridge".to_string(),
-            amount_in: execution_path[0].amount_out,
-            amount_out: execution_path[0].amount_out - bridge_fee_usd,
+            amount_in: bought,
+            amount_out: bridged,
             slippage_bps: 0.0,
             cost_usd: bridge_fee_usd,
+            pool: None,
+            asset_in: None,
+            asset_out: None,
         });
         
-        // Step 3: Swap on destination chain
-        let swap2_gas = self.gas_estimator.estimate_swap_gas(Chain::Ethereum, eth_price).await;
-        costs.gas_usd += swap2_gas;
-        
-        let swap2_slippage_bps = 5.0;
-        let swap2_fee_bps = 5.0;
+        // Step 3: Swap on destination chain, priced at gas expected once the bridge settles
+        let sell_counter = funding_asset(&sell_pools, SELL_PROTOCOL, &candidate.asset);
+        let sell_assets = sell_counter.as_deref().map(|counter| (candidate.asset.as_str(), counter));
+        let sell_pool = sell_assets.and_then(|(asset_in, asset_out)| pair_pool(&sell_pools, SELL_PROTOCOL, asset_in, asset_out));
+        let sell_overhead_usd = self.gas_estimator.estimate_gas_units_cost(sell_chain, SPLIT_POOL_GAS_UNITS, eth_price).await;
+        let parts = split_swap_leg(
+            &sell_pools, sell_pool.as_ref(), SELL_PROTOCOL, sell_assets, bridged, None, sell_overhead_usd, (5.0, 5.0)
+        );
+        let sell_splits = parts.len() - 1;
         
-        costs.slippage_usd += optimal_size_usd * swap2_slippage_bps / 10000.0;
-        costs.protocol_fees_usd += optimal_size_usd * swap2_fee_bps / 10000.0;
-        
-        let amount_out = execution_path[1].amount_out * (1.0 + candidate.spread_bps / 10000.0);
+        let settlement_secs = self.bridge_simulator.estimate_settlement_time(buy_chain, sell_chain);
+        let swap2_gas = self.gas_estimator.estimate_delayed_swap_gas(sell_chain, settlement_secs, eth_price).await +
+                        sell_splits as f64 * sell_overhead_usd;
+        costs.gas_usd += swap2_gas;
+        let sold = push_swap_leg(
+            &mut execution_path, &mut costs, 3, "swap_sell", &format!("{:?}", sell_chain), parts, sell_assets,
+            swap2_gas, candidate.spread_bps
+        );
         
-        execution_path.push(SimulatedStep {
-            step: 3,
-            action: "swap_sell".to_string(),
-            domain: "Ethereum".to_string(),
-            protocol: "curve".to_string(),
-            amount_in: execution_path[1].amount_out,
-            amount_out,
-            slippage_bps: swap2_slippage_bps,
-            cost_usd: swap2_gas + costs.slippage_usd + costs.protocol_fees_usd,
-        });
+        // Competition for ordering on the final leg
+        let competition = self.apply_competition(
+            candidate, sell_chain, &mut costs, &execution_path, optimal_size_usd,
+            SWAP_GAS_UNITS + sell_splits as f64 * SPLIT_POOL_GAS_UNITS, eth_price
+        ).await;
         
         // Calculate PnL
         costs.total_usd = costs.gas_usd + costs.protocol_fees_usd + 
                           costs.bridge_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
         
-        let net_pnl_usd = execution_path.last().unwrap().amount_out - optimal_size_usd - costs.total_usd;
+        let net_pnl_usd = sold - optimal_size_usd - costs.total_usd;
         let net_bps = (net_pnl_usd / optimal_size_usd) * 10000.0;
         
-        let success_prob = self.estimate_success_probability(candidate, &costs).await;
+        let success_prob = self.estimate_success_probability(candidate, &costs, &competition).await;
         
         Ok(EvaluationResult {
             net_pnl_usd,
@@ -147,12 +201,21 @@ impl TradeSimulator {
             success_prob,
             costs,
             execution_path,
+            competition: Some(competition),
+            revert_prob: None,
         })
     }
     
     /// Simulate DEX arbitrage
+    ///
+    /// Buys where the asset is cheap and sells it back where it is dear; each
+    /// leg is split across the pools for its pair, never the other leg's pool.
     async fn simulate_dex_arb(&self, candidate: &Candidate, eth_price: f64) -> Result<EvaluationResult> {
         let optimal_size_usd = self.estimate_optimal_size(candidate).await?;
+        let chain = candidate_chains(candidate).first().copied().unwrap_or(Chain::Ethereum);
+        let pools = self.market_state.get_amm_pools(chain).await;
+        let pool_overhead_usd = self.gas_estimator.estimate_gas_units_cost(chain, SPLIT_POOL_GAS_UNITS, eth_price).await;
+        let domain = format!("{:?}", chain);
         
         let mut execution_path = Vec::new();
         let mut costs = CostBreakdown {
@@ -164,73 +227,402 @@ impl TradeSimulator {
             total_usd: 0.0,
         };
         
-        // Check if flash loan is needed
-        let use_flashloan = optimal_size_usd > 50000.0;
+        // Own funds when the wallets hold enough, a flash loan otherwise
+        let funding = funding_asset(&pools, BUY_PROTOCOL, &candidate.asset);
+        let capital = match &funding {
+            Some(funding) => self.market_state.deployable_capital_usd(chain, funding).await,
+            None => None,
+        };
+        let use_flashloan = match capital {
+            Some(capital) => self.flashloan_simulator.needs_flashloan(optimal_size_usd, capital),
+            None => optimal_size_usd > 50000.0,
+        };
         if use_flashloan {
             costs.flashloan_fees_usd = self.flashloan_simulator.estimate_flashloan_fee("aave_v3", optimal_size_usd);
-            costs.gas_usd += self.gas_estimator.estimate_flashloan_gas(Chain::Ethereum, eth_price).await;
+            costs.gas_usd += self.gas_estimator.estimate_flashloan_gas(chain, eth_price).await;
         }
         
+        let buy_assets = funding.as_deref().map(|funding| (funding, candidate.asset.as_str()));
+        let sell_assets = funding.as_deref().map(|funding| (candidate.asset.as_str(), funding));
+        let buy_pool = buy_assets.and_then(|(asset_in, asset_out)| pair_pool(&pools, BUY_PROTOCOL, asset_in, asset_out));
+        let sell_pool = sell_assets.and_then(|(asset_in, asset_out)| pair_pool(&pools, SELL_PROTOCOL, asset_in, asset_out));
+        
         // Swap 1: Buy
-        let swap1_gas = self.gas_estimator.estimate_swap_gas(Chain::Ethereum, eth_price).await;
+        let parts = split_swap_leg(
+            &pools, buy_pool.as_ref(), BUY_PROTOCOL, buy_assets, optimal_size_usd,
+            sell_pool.as_ref().map(|pool| pool.pool_address.as_str()), pool_overhead_usd, (5.0, 3.0)
+        );
+        let mut split_pools = parts.len() - 1;
+        let swap1_gas = self.gas_estimator.estimate_swap_gas(chain, eth_price).await +
+                        (parts.len() - 1) as f64 * pool_overhead_usd;
         costs.gas_usd += swap1_gas;
+        let bought = push_swap_leg(
+            &mut execution_path, &mut costs, 1, "swap_buy", &domain, parts, buy_assets, swap1_gas, 0.0
+        );
+        
+        // Swap 2: Sell
+        let parts = split_swap_leg(
+            &pools, sell_pool.as_ref(), SELL_PROTOCOL, sell_assets, bought,
+            buy_pool.as_ref().map(|pool| pool.pool_address.as_str()), pool_overhead_usd, (4.0, 3.0)
+        );
+        split_pools += parts.len() - 1;
+        let swap2_gas = self.gas_estimator.estimate_swap_gas(chain, eth_price).await +
+                        (parts.len() - 1) as f64 * pool_overhead_usd;
+        costs.gas_usd += swap2_gas;
+        let sold = push_swap_leg(
+            &mut execution_path, &mut costs, 2, "swap_sell", &domain, parts, sell_assets, swap2_gas, candidate.spread_bps
+        );
         
-        let swap1_slippage_bps = 3.0;
-        let swap1_fee_bps = 5.0;
+        // Competition for ordering on the final leg
+        let gas_units = 2.0 * SWAP_GAS_UNITS + split_pools as f64 * SPLIT_POOL_GAS_UNITS;
+        let competition = self.apply_competition(
+            candidate, chain, &mut costs, &execution_path, optimal_size_usd, if use_flashloan {
+                gas_units + FLASHLOAN_GAS_UNITS
+            } else {
+                gas_units
+            }, eth_price
+        ).await;
         
-        costs.slippage_usd += optimal_size_usd * swap1_slippage_bps / 10000.0;
-        costs.protocol_fees_usd += optimal_size_usd * swap1_fee_bps / 10000.0;
+        // Calculate PnL
+        costs.total_usd = costs.gas_usd + costs.protocol_fees_usd + 
+                          costs.bridge_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
         
-        execution_path.push(SimulatedStep {
-            step: 1,
-            action: "swap_buy".to_string(),
-            domain: "Ethereum".to_string(),
-            protocol: "uniswap_v3".to_string(),
-            amount_in: optimal_size_usd,
-            amount_out: optimal_size_usd * (1.0 - (swap1_slippage_bps + swap1_fee_bps) / 10000.0),
-            slippage_bps: swap1_slippage_bps,
-            cost_usd: swap1_gas + costs.slippage_usd + costs.protocol_fees_usd,
-        });
+        let net_pnl_usd = sold - optimal_size_usd - costs.total_usd;
+        let net_bps = (net_pnl_usd / optimal_size_usd) * 10000.0;
         
-        // Swap 2: Sell
-        let swap2_gas = self.gas_estimator.estimate_swap_gas(Chain::Ethereum, eth_price).await;
-        costs.gas_usd += swap2_gas;
+        let success_prob = self.estimate_success_probability(candidate, &costs, &competition).await;
         
-        let swap2_slippage_bps = 3.0;
-        let swap2_fee_bps = 4.0;
+        Ok(EvaluationResult {
+            net_pnl_usd,
+            net_bps,
+            optimal_size_usd,
+            success_prob,
+            costs,
+            execution_path,
+            competition: Some(competition),
+            revert_prob: None,
+        })
+    }
+    
+    /// Simulate a flash-loan-funded Aave liquidation
+    async fn simulate_liquidation(&self, candidate: &Candidate, eth_price: f64) -> Result<EvaluationResult> {
+        let details = match &candidate.details {
+            Some(CandidateDetails::Liquidation(details)) => details,
+            _ => return Err(IntelligenceError::simulation("Liquidation candidate is missing position details")),
+        };
+        
+        let simulation = self.liquidation_simulator.simulate(details, eth_price).await;
+        let mut costs = simulation.costs;
+        let execution_path = simulation.execution_path;
+        let size_usd = details.debt_to_cover_usd;
         
-        costs.slippage_usd += optimal_size_usd * swap2_slippage_bps / 10000.0;
-        costs.protocol_fees_usd += optimal_size_usd * swap2_fee_bps / 10000.0;
+        // Liquidations are a public race: bid against other liquidators
+        let gross_usd = details.collateral_seized_usd - details.debt_to_cover_usd;
+        let costs_so_far = costs.gas_usd + costs.protocol_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
+        let competition = self.competition_model
+            .estimate(candidate, details.chain, gross_usd - costs_so_far, simulation.gas_units, eth_price)
+            .await;
+        costs.gas_usd += competition.priority_fee_usd;
         
-        let amount_out = execution_path[0].amount_out * (1.0 + candidate.spread_bps / 10000.0);
+        costs.total_usd = costs.gas_usd + costs.protocol_fees_usd + 
+                          costs.bridge_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
+        
+        let net_pnl_usd = gross_usd - costs.total_usd;
+        let net_bps = (net_pnl_usd / size_usd) * 10000.0;
+        
+        let success_prob = self.estimate_success_probability(candidate, &costs, &competition).await;
+        
+        Ok(EvaluationResult {
+            net_pnl_usd,
+            net_bps,
+            optimal_size_usd: size_usd,
+            success_prob,
+            costs,
+            execution_path,
+            competition: Some(competition),
+            revert_prob: None,
+        })
+    }
+    
+    /// Simulate a stablecoin depeg trade
+    ///
+    /// A depeg is entered and exited through the deepest pool once the peg is
+    /// restored; a pool imbalance buys in the imbalanced pool and sells in the
+    /// deepest other pool.
+    async fn simulate_depeg(&self, candidate: &Candidate, eth_price: f64) -> Result<EvaluationResult> {
+        let details = match &candidate.details {
+            Some(CandidateDetails::Depeg(details)) => details,
+            _ => return Err(IntelligenceError::simulation("Depeg candidate is missing pool details")),
+        };
+        
+        let chain = details.chain;
+        let size_usd = self.cap_by_capital(chain, &details.counter_asset, details.size_usd).await?;
+        let pools = self.market_state.get_amm_pools(chain).await;
+        let pool_overhead_usd = self.gas_estimator.estimate_gas_units_cost(chain, SPLIT_POOL_GAS_UNITS, eth_price).await;
+        
+        let mut costs = CostBreakdown {
+            gas_usd: 0.0,
+            protocol_fees_usd: 0.0,
+            bridge_fees_usd: 0.0,
+            flashloan_fees_usd: 0.0,
+            slippage_usd: 0.0,
+            total_usd: 0.0,
+        };
+        let mut execution_path = Vec::new();
+        let domain = format!("{:?}", chain);
+        
+        let (entry_action, exit_action) = match details.kind {
+            DepegKind::Depeg => ("swap_enter", "swap_exit"),
+            DepegKind::PoolImbalance => ("swap_buy", "swap_sell"),
+        };
+        
+        // Entry buys the stablecoin with the counter asset, exit sells it back
+        let entry_assets = (&details.counter_asset, &details.stablecoin);
+        let exit_assets = (&details.stablecoin, &details.counter_asset);
+        
+        // An imbalance exit must not sell back into the pool it bought from
+        let mut amount_in = size_usd;
+        for (index, (pool, other_pool, action, (asset_in, asset_out))) in [
+            (&details.entry_pool, None, entry_action, entry_assets),
+            (&details.exit_pool, Some(&details.entry_pool), exit_action, exit_assets),
+        ].iter().enumerate() {
+            let mut router = SplitRouter::new(&pools).with_pool_overhead(pool_overhead_usd);
+            if let Some(other_pool) = other_pool {
+                router = router.excluding(other_pool);
+            }
+            let parts = router
+                .route(pool, asset_in, asset_out, amount_in)
+                .unwrap_or_else(|| vec![PoolAllocation {
+                    pool_address: pool.to_string(),
+                    protocol: "curve".to_string(),
+                    amount_in,
+                    fee_bps: 4.0,
+                    slippage_bps: 5.0,
+                }]);
+            let gas = self.gas_estimator.estimate_swap_gas(chain, eth_price).await +
+                      (parts.len() - 1) as f64 * pool_overhead_usd;
+            costs.gas_usd += gas;
+            
+            let mut leg_out = 0.0;
+            for (part_index, part) in parts.into_iter().enumerate() {
+                let fee = part.fee_usd();
+                let slippage = part.slippage_usd();
+                
+                costs.protocol_fees_usd += fee;
+                costs.slippage_usd += slippage;
+                
+                // The edge is realised on the exit leg
+                let amount_out = if index == 1 {
+                    part.amount_in * (1.0 + candidate.spread_bps / 10000.0)
+                } else {
+                    part.amount_in
+                };
+                leg_out += amount_out;
+                
+                // The leg's gas is charged to its first part
+                let part_gas = if part_index == 0 { gas } else { 0.0 };
+                execution_path.push(SimulatedStep {
+                    step: index + 1,
+                    action: action.to_string(),
+                    domain: domain.clone(),
+                    protocol: part.protocol,
+                    amount_in: part.amount_in,
+                    amount_out,
+                    slippage_bps: part.slippage_bps,
+                    cost_usd: part_gas + fee + slippage,
+                    pool: Some(part.pool_address),
+                    asset_in: Some(asset_in.to_string()),
+                    asset_out: Some(asset_out.to_string()),
+                });
+            }
+            amount_in = leg_out;
+        }
+        
+        let competition = self.apply_competition(
+            candidate, chain, &mut costs, &execution_path, size_usd, SWAP_GAS_UNITS, eth_price
+        ).await;
+        
+        costs.total_usd = costs.gas_usd + costs.protocol_fees_usd + 
+                          costs.bridge_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
+        
+        let net_pnl_usd = amount_in - size_usd - costs.total_usd;
+        let net_bps = (net_pnl_usd / size_usd) * 10000.0;
+        
+        let success_prob = self.estimate_success_probability(candidate, &costs, &competition).await;
+        
+        Ok(EvaluationResult {
+            net_pnl_usd,
+            net_bps,
+            optimal_size_usd: size_usd,
+            success_prob,
+            costs,
+            execution_path,
+            competition: Some(competition),
+            revert_prob: None,
+        })
+    }
+    
+    /// Simulate a flash-loan-funded atomic arbitrage
+    ///
+    /// Borrow, buy, sell and repay execute in one transaction: if the sell
+    /// leg does not cover the loan plus fee, the whole transaction reverts.
+    async fn simulate_flash_arb(&self, candidate: &Candidate, eth_price: f64) -> Result<EvaluationResult> {
+        let details = match &candidate.details {
+            Some(CandidateDetails::FlashArb(details)) => details,
+            _ => return Err(IntelligenceError::simulation("Flash arb candidate is missing pool details")),
+        };
+        
+        let chain = details.chain;
+        let size_usd = details.size_usd;
+        let route = self.flashloan_simulator
+            .best_route(chain, &details.borrow_asset, size_usd)
+            .await
+            .ok_or_else(|| IntelligenceError::simulation(format!(
+                "No flash loan route for {:.0} {} on {:?}", size_usd, details.borrow_asset, chain
+            )))?;
+        
+        let pools = self.market_state.get_amm_pools(chain).await;
+        let pool_overhead_usd = self.gas_estimator.estimate_gas_units_cost(chain, SPLIT_POOL_GAS_UNITS, eth_price).await;
+        
+        let mut costs = CostBreakdown {
+            gas_usd: 0.0,
+            protocol_fees_usd: 0.0,
+            bridge_fees_usd: 0.0,
+            flashloan_fees_usd: route.fee_usd,
+            slippage_usd: 0.0,
+            total_usd: 0.0,
+        };
+        let mut execution_path = Vec::new();
+        let domain = format!("{:?}", chain);
         
         execution_path.push(SimulatedStep {
-            step: 2,
-            action: "swap_sell".to_string(),
-            domain: "Ethereum".to_string(),
-            protocol: "curve".to_string(),
-            amount_in: execution_path[0].amount_out,
-            amount_out,
-            slippage_bps: swap2_slippage_bps,
-            cost_usd: swap2_gas + costs.slippage_usd + costs.protocol_fees_usd,
+            step: 1,
+            action: "flash_loan".to_string(),
+            domain: domain.clone(),
+            protocol: route.provider.clone(),
+            amount_in: 0.0,
+            amount_out: size_usd,
+            slippage_bps: 0.0,
+            cost_usd: 0.0,
+            pool: None,
+            asset_in: None,
+            asset_out: Some(details.borrow_asset.clone()),
         });
         
-        // Calculate PnL
+        // Buy the asset where it is cheap, sell it back where it is dear; each
+        // leg is split across the pools for its pair, never the other leg's pool
+        let mut amount_in = size_usd;
+        let mut split_pools = 0;
+        for (index, (pool, other_pool, action, (asset_in, asset_out))) in [
+            (&details.buy_pool, &details.sell_pool, "swap_buy", (&details.borrow_asset, &candidate.asset)),
+            (&details.sell_pool, &details.buy_pool, "swap_sell", (&candidate.asset, &details.borrow_asset)),
+        ].iter().enumerate() {
+            let parts = SplitRouter::new(&pools)
+                .excluding(other_pool)
+                .with_pool_overhead(pool_overhead_usd)
+                .route(pool, asset_in, asset_out, amount_in)
+                .unwrap_or_else(|| vec![PoolAllocation {
+                    pool_address: pool.to_string(),
+                    protocol: "uniswap_v3".to_string(),
+                    amount_in,
+                    fee_bps: 30.0,
+                    slippage_bps: 10.0,
+                }]);
+            split_pools += parts.len() - 1;
+            
+            let mut leg_out = 0.0;
+            for part in parts {
+                let fee = part.fee_usd();
+                let slippage = part.slippage_usd();
+                
+                costs.protocol_fees_usd += fee;
+                costs.slippage_usd += slippage;
+                
+                // The price gap is realised on the sell leg
+                let gross_out = if index == 1 {
+                    part.amount_in * (1.0 + details.gross_spread_bps / 10000.0)
+                } else {
+                    part.amount_in
+                };
+                let amount_out = gross_out - fee - slippage;
+                leg_out += amount_out;
+                
+                execution_path.push(SimulatedStep {
+                    step: index + 2,
+                    action: action.to_string(),
+                    domain: domain.clone(),
+                    protocol: part.protocol,
+                    amount_in: part.amount_in,
+                    amount_out,
+                    slippage_bps: part.slippage_bps,
+                    cost_usd: fee + slippage,
+                    pool: Some(part.pool_address),
+                    asset_in: Some(asset_in.to_string()),
+                    asset_out: Some(asset_out.to_string()),
+                });
+            }
+            amount_in = leg_out;
+        }
+        let sell_out = amount_in;
+        
+        let repay_usd = size_usd + route.fee_usd;
+        execution_path.push(SimulatedStep {
+            step: 4,
+            action: "flash_repay".to_string(),
+            domain,
+            protocol: route.provider.clone(),
+            amount_in: repay_usd,
+            amount_out: 0.0,
+            slippage_bps: 0.0,
+            cost_usd: route.fee_usd,
+            pool: None,
+            asset_in: Some(details.borrow_asset.clone()),
+            asset_out: None,
+        });
+        
+        // One transaction: loan, callback with both swaps, repayment check
+        let gas_units = FLASHLOAN_GAS_UNITS + FLASH_CALLBACK_GAS_UNITS + 2.0 * SWAP_GAS_UNITS +
+                        split_pools as f64 * SPLIT_POOL_GAS_UNITS;
+        costs.gas_usd += self.gas_estimator.estimate_gas_units_cost(chain, gas_units, eth_price).await;
+        
+        let gross_usd = size_usd * details.gross_spread_bps / 10000.0;
+        let costs_so_far = costs.gas_usd + costs.protocol_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
+        let competition = self.competition_model
+            .estimate(candidate, chain, gross_usd - costs_so_far, gas_units, eth_price)
+            .await;
+        costs.gas_usd += competition.priority_fee_usd;
+        
         costs.total_usd = costs.gas_usd + costs.protocol_fees_usd + 
                           costs.bridge_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
         
-        let net_pnl_usd = execution_path.last().unwrap().amount_out - optimal_size_usd - costs.total_usd;
-        let net_bps = (net_pnl_usd / optimal_size_usd) * 10000.0;
+        let net_pnl_usd = gross_usd - costs.total_usd;
+        let net_bps = (net_pnl_usd / size_usd) * 10000.0;
         
-        let success_prob = self.estimate_success_probability(candidate, &costs).await;
+        // The repayment check fails if prices move past the margin before inclusion
+        let repay_margin_bps = (sell_out - repay_usd) / size_usd * 10000.0;
+        let repay_survival = if repay_margin_bps > 0.0 {
+            1.0 - (-repay_margin_bps / REPAY_DRIFT_BPS).exp()
+        } else {
+            0.0
+        };
+        
+        // Under FCFS a lost race still lands and reverts; lost auctions never land
+        let revert_prob = match competition.ordering {
+            OrderingModel::SequencerFcfs => 1.0 - competition.capture_prob() * repay_survival,
+            OrderingModel::PrivateMempool => 1.0 - repay_survival,
+        };
+        
+        let success_prob = self.estimate_success_probability(candidate, &costs, &competition).await * repay_survival;
         
         Ok(EvaluationResult {
             net_pnl_usd,
             net_bps,
-            optimal_size_usd,
+            optimal_size_usd: size_usd,
             success_prob,
             costs,
             execution_path,
+            competition: Some(competition),
+            revert_prob: Some(revert_prob),
         })
     }
     
@@ -246,8 +638,64 @@ impl TradeSimulator {
         }
     }
     
+    /// Cap a self-funded trade at what the wallets hold of its funding asset
+    ///
+    /// Sizes pass through unchanged when no wallet balances are known.
+    async fn cap_by_capital(&self, chain: Chain, asset: &str, size_usd: f64) -> Result<f64> {
+        match self.market_state.deployable_capital_usd(chain, asset).await {
+            Some(capital) if capital <= 0.0 => Err(IntelligenceError::simulation(
+                format!("No {} on {:?} to fund the trade", asset, chain)
+            )),
+            Some(capital) if capital < size_usd => {
+                debug!("Capping size ${:.0} at ${:.0} of {} on {:?}", size_usd, capital, asset, chain);
+                Ok(capital)
+            }
+            _ => Ok(size_usd),
+        }
+    }
+    
+    /// Estimate competition on the execution chain and charge the recommended
+    /// priority fee as gas
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_competition(
+        &self,
+        candidate: &Candidate,
+        chain: Chain,
+        costs: &mut CostBreakdown,
+        execution_path: &[SimulatedStep],
+        size_usd: f64,
+        gas_units: f64,
+        eth_price: f64,
+    ) -> CompetitionEstimate {
+        let costs_so_far = costs.gas_usd + costs.protocol_fees_usd + 
+                           costs.bridge_fees_usd + costs.flashloan_fees_usd + costs.slippage_usd;
+        // A split final step delivers the output of all its parts
+        let mut first = execution_path.len().saturating_sub(1);
+        while first > 0 && execution_path[first - 1].splits_with(&execution_path[first]) {
+            first -= 1;
+        }
+        let gross_out = if execution_path.is_empty() {
+            size_usd
+        } else {
+            execution_path[first..].iter().map(|step| step.amount_out).sum()
+        };
+        let pnl_before_bid = gross_out - size_usd - costs_so_far;
+        
+        let competition = self.competition_model
+            .estimate(candidate, chain, pnl_before_bid, gas_units, eth_price)
+            .await;
+        
+        costs.gas_usd += competition.priority_fee_usd;
+        competition
+    }
+    
     /// Estimate success probability
-    async fn estimate_success_probability(&self, candidate: &Candidate, costs: &CostBreakdown) -> f64 {
+    async fn estimate_success_probability(
+        &self,
+        candidate: &Candidate,
+        costs: &CostBreakdown,
+        competition: &CompetitionEstimate,
+    ) -> f64 {
         let mut prob = candidate.confidence;
         
         let cost_ratio = costs.total_usd / (costs.total_usd + 100.0);
@@ -257,8 +705,93 @@ impl TradeSimulator {
             prob *= 0.9;
         }
         
-        prob.max(0.5).min(0.99)
+        // A spread nobody has taken for minutes is usually a stale quote or unfillable depth
+        let age_factor = 1.0 - 0.5 * (candidate.age_secs() / STALE_OPPORTUNITY_SECS).min(1.0);
+        
+        // Other searchers may take or front-run the opportunity
+        prob.clamp(0.5, 0.99) * age_factor * competition.capture_prob()
+    }
+}
+
+/// Split a swap leg across the pools trading its pair
+///
+/// Routing is anchored on `reference`, the deepest pool of the leg's
+/// protocol, and never uses `excluded`. A leg that cannot be routed keeps a
+/// single part priced at the `(fee_bps, slippage_bps)` fallback.
+#[allow(clippy::too_many_arguments)]
+fn split_swap_leg(
+    pools: &[AmmState],
+    reference: Option<&AmmState>,
+    protocol: &str,
+    assets: Option<(&str, &str)>,
+    amount_in: f64,
+    excluded: Option<&str>,
+    pool_overhead_usd: f64,
+    (fee_bps, slippage_bps): (f64, f64),
+) -> Vec<PoolAllocation> {
+    let routed = match (reference, assets) {
+        (Some(reference), Some((asset_in, asset_out))) => {
+            let mut router = SplitRouter::new(pools).with_pool_overhead(pool_overhead_usd);
+            if let Some(excluded) = excluded {
+                router = router.excluding(excluded);
+            }
+            router.route(&reference.pool_address, asset_in, asset_out, amount_in)
+        }
+        _ => None,
+    };
+    routed.unwrap_or_else(|| vec![PoolAllocation {
+        pool_address: reference.map(|pool| pool.pool_address.clone()).unwrap_or_default(),
+        protocol: protocol.to_string(),
+        amount_in,
+        fee_bps,
+        slippage_bps,
+    }])
+}
+
+/// Append one sub-step per part of a swap leg and return the leg's output
+///
+/// Fees and slippage go to `costs` and the leg's gas to its first part. A
+/// part without a known pool is left for the intent builder to resolve.
+#[allow(clippy::too_many_arguments)]
+fn push_swap_leg(
+    execution_path: &mut Vec<SimulatedStep>,
+    costs: &mut CostBreakdown,
+    step: usize,
+    action: &str,
+    domain: &str,
+    parts: Vec<PoolAllocation>,
+    assets: Option<(&str, &str)>,
+    gas: f64,
+    edge_bps: f64,
+) -> f64 {
+    let mut leg_out = 0.0;
+    for (index, part) in parts.into_iter().enumerate() {
+        let fee = part.fee_usd();
+        let slippage = part.slippage_usd();
+        
+        costs.protocol_fees_usd += fee;
+        costs.slippage_usd += slippage;
+        
+        let amount_out = part.amount_in * (1.0 + edge_bps / 10000.0);
+        leg_out += amount_out;
+        
+        let routed = !part.pool_address.is_empty();
+        let part_gas = if index == 0 { gas } else { 0.0 };
+        execution_path.push(SimulatedStep {
+            step,
+            action: action.to_string(),
+            domain: domain.to_string(),
+            protocol: part.protocol,
+            amount_in: part.amount_in,
+            amount_out,
+            slippage_bps: part.slippage_bps,
+            cost_usd: part_gas + fee + slippage,
+            pool: Some(part.pool_address).filter(|_| routed),
+            asset_in: assets.filter(|_| routed).map(|(asset_in, _)| asset_in.to_string()),
+            asset_out: assets.filter(|_| routed).map(|(_, asset_out)| asset_out.to_string()),
+        });
     }
+    leg_out
 }
 
 impl Default for TradeSimulator {
@@ -287,6 +820,8 @@ mod tests {
             ],
             detected_at: Utc::now(),
             confidence: 0.9,
+            first_seen_at: None,
+            details: None,
         };
         
         let result = simulator.evaluate(&candidate).await.unwrap();
@@ -295,5 +830,254 @@ mod tests {
         assert!(result.success_prob > 0.0 && result.success_prob < 1.0);
         assert!(result.optimal_size_usd > 0.0);
     }
+    
+    #[tokio::test]
+    async fn test_large_dex_arb_splits_across_fee_tiers() {
+        use qenus_dataplane::{AmmFeature, DepthCurve, Feature, FeatureData, FeatureType, SlippageInfo, TokenInfo};
+        
+        let token = |symbol: &str, decimals: u8| TokenInfo { address: String::new(), symbol: symbol.to_string(), decimals };
+        let pool = |address: &str, pool_type: &str, fee_tier: u32, liquidity: &str, slippage_at_1m: f64| {
+            let mut depth = DepthCurve::default();
+            depth.sizes.insert("1m".to_string(), SlippageInfo { slippage_bps: slippage_at_1m, price_impact: 0.0 });
+            Feature::new(1, Chain::Ethereum, FeatureType::Amm, FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: address.to_string(),
+                pool_type: pool_type.to_string(),
+                pool_id: None,
+                token0: token("WETH", 18),
+                token1: token("USDC", 6),
+                fee_tier: Some(fee_tier),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price: 2000.0,
+                liquidity: liquidity.to_string(),
+                depth,
+                volume_24h: None,
+                fees_24h: None,
+            })), "test".to_string())
+        };
+        
+        let market_state = Arc::new(MarketState::new(30));
+        for feature in [
+            pool("0xuni5", "uniswap_v3", 500, "5000000", 40.0),
+            pool("0xuni30", "uniswap_v3", 3000, "4000000", 10.0),
+            pool("0xcurve", "curve", 4, "9000000", 1.0),
+        ] {
+            market_state.ingest_feature(feature).await.unwrap();
+        }
+        let simulator = TradeSimulator::new(market_state);
+        
+        let candidate = Candidate {
+            strategy: "dex_arb".to_string(),
+            asset: "WETH".to_string(),
+            spread_bps: 60.0,
+            legs: vec![
+                ("uniswap_v3 on Ethereum".to_string(), "buy".to_string()),
+                ("curve on Ethereum".to_string(), "sell".to_string()),
+            ],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        };
+        
+        let result = simulator.evaluate(&candidate).await.unwrap();
+        
+        // The buy is shared between both fee tiers and never touches the sell pool
+        let buys: Vec<_> = result.execution_path.iter().filter(|step| step.step == 1).collect();
+        assert_eq!(buys.len(), 2);
+        assert!(buys.iter().any(|step| step.pool.as_deref() == Some("0xuni5")));
+        assert!(buys.iter().any(|step| step.pool.as_deref() == Some("0xuni30")));
+        assert!(buys.iter().all(|step| step.asset_in.as_deref() == Some("USDC")));
+        let bought: f64 = buys.iter().map(|step| step.amount_in).sum();
+        assert!((bought - result.optimal_size_usd).abs() < 1e-6);
+        
+        // The sell goes back through the curve pool
+        let sells: Vec<_> = result.execution_path.iter().filter(|step| step.step == 2).collect();
+        assert!(sells.iter().any(|step| step.pool.as_deref() == Some("0xcurve")));
+        assert!(sells.iter().all(|step| step.asset_out.as_deref() == Some("USDC")));
+    }
+    
+    #[tokio::test]
+    async fn test_sizing_uses_wallet_balances() {
+        use qenus_dataplane::{
+            AmmFeature, DepthCurve, Feature, FeatureData, FeatureType, TokenBalance, TokenInfo, WalletBalanceFeature,
+        };
+        
+        let token = |symbol: &str, decimals: u8| TokenInfo { address: String::new(), symbol: symbol.to_string(), decimals };
+        let pool = |chain: Chain| Feature::new(
+            1,
+            chain,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: format!("0xpool{:?}", chain),
+                pool_type: "uniswap_v3".to_string(),
+                pool_id: None,
+                token0: token("WETH", 18),
+                token1: token("USDC", 6),
+                fee_tier: Some(500),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price: 2000.0,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve::default(),
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        );
+        // Plenty of the bought token everywhere; only USDC funds the buy leg
+        let wallet = |chain: Chain, usdc: &str| Feature::new(
+            1,
+            chain,
+            FeatureType::WalletBalance,
+            FeatureData::WalletBalance(WalletBalanceFeature {
+                wallet_address: "0xexec".to_string(),
+                native_symbol: "ETH".to_string(),
+                native_balance: "2.0".to_string(),
+                token_balances: vec![
+                    TokenBalance { token: token("USDC", 6), balance: usdc.to_string() },
+                    TokenBalance { token: token("WETH", 18), balance: "1000".to_string() },
+                ],
+            }),
+            "test".to_string(),
+        );
+        let candidate = |strategy: &str, buy_chain: Chain, sell_chain: Chain| Candidate {
+            strategy: strategy.to_string(),
+            asset: "WETH".to_string(),
+            spread_bps: 15.0,
+            legs: vec![
+                (format!("uniswap_v3 on {:?}", buy_chain), "buy".to_string()),
+                (format!("curve on {:?}", sell_chain), "sell".to_string()),
+            ],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: None,
+        };
+        
+        let market_state = Arc::new(MarketState::new(30));
+        for chain in [Chain::Ethereum, Chain::Base] {
+            market_state.ingest_feature(pool(chain)).await.unwrap();
+        }
+        market_state.ingest_feature(wallet(Chain::Ethereum, "500000")).await.unwrap();
+        market_state.ingest_feature(wallet(Chain::Base, "30000")).await.unwrap();
+        let simulator = TradeSimulator::new(market_state.clone());
+        
+        // Own funds cover the trade: no flash loan
+        let result = simulator.evaluate(&candidate("dex_arb", Chain::Ethereum, Chain::Ethereum)).await.unwrap();
+        assert_eq!(result.costs.flashloan_fees_usd, 0.0);
+        
+        // Funded from the candidate's chain, not Ethereum
+        let result = simulator.evaluate(&candidate("dex_arb", Chain::Base, Chain::Base)).await.unwrap();
+        assert!(result.costs.flashloan_fees_usd > 0.0);
+        assert_eq!(result.execution_path[0].domain, "Base");
+        
+        // Bridged trades are capped at the source-chain balance of the funding asset
+        let result = simulator.evaluate(&candidate("triangle_arb", Chain::Base, Chain::Ethereum)).await.unwrap();
+        assert_eq!(result.optimal_size_usd, 30_000.0);
+        
+        // Short on funds: borrow the difference
+        market_state.ingest_feature(wallet(Chain::Ethereum, "20000")).await.unwrap();
+        let result = simulator.evaluate(&candidate("dex_arb", Chain::Ethereum, Chain::Ethereum)).await.unwrap();
+        assert!(result.costs.flashloan_fees_usd > 0.0);
+        
+        // Nothing to fund a bridged trade with
+        market_state.ingest_feature(wallet(Chain::Base, "0")).await.unwrap();
+        assert!(simulator.evaluate(&candidate("triangle_arb", Chain::Base, Chain::Ethereum)).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_liquidation_simulation() {
+        let market_state = Arc::new(MarketState::new(30));
+        let simulator = TradeSimulator::new(market_state);
+        
+        let candidate = Candidate {
+            strategy: "aave_liquidation".to_string(),
+            asset: "USDC".to_string(),
+            spread_bps: 500.0,
+            legs: vec![
+                ("Arbitrum".to_string(), "flash_loan".to_string()),
+                ("Arbitrum".to_string(), "liquidate".to_string()),
+                ("Arbitrum".to_string(), "swap".to_string()),
+                ("Arbitrum".to_string(), "flash_repay".to_string()),
+            ],
+            detected_at: Utc::now(),
+            confidence: 0.85,
+            first_seen_at: None,
+            details: Some(CandidateDetails::Liquidation(crate::LiquidationDetails {
+                chain: Chain::Arbitrum,
+                borrower: "0xb0".to_string(),
+                debt_asset: "USDC".to_string(),
+                collateral_asset: "WETH".to_string(),
+                health_factor: 0.97,
+                debt_to_cover_usd: 100_000.0,
+                collateral_seized_usd: 105_000.0,
+                liquidation_bonus_bps: 500,
+            })),
+        };
+        
+        let result = simulator.evaluate(&candidate).await.unwrap();
+        
+        assert_eq!(result.execution_path.len(), 4);
+        assert!(result.costs.flashloan_fees_usd > 0.0);
+        assert!(result.net_pnl_usd < 5_000.0);
+        assert!(result.net_pnl_usd > 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_flash_arb_borrows_cheapest_route_and_repays() {
+        use qenus_dataplane::{Feature, FeatureData, FeatureType, FlashLoanFeature, TokenInfo};
+        
+        let market_state = Arc::new(MarketState::new(30));
+        for (provider, fee_bps, liquidity) in [("aave_v3", 5, "10000000"), ("balancer", 0, "500000")] {
+            market_state.ingest_feature(Feature::new(
+                1,
+                Chain::Ethereum,
+                FeatureType::FlashLoan,
+                FeatureData::FlashLoan(FlashLoanFeature {
+                    provider: provider.to_string(),
+                    provider_address: String::new(),
+                    asset: TokenInfo { address: String::new(), symbol: "USDC".to_string(), decimals: 6 },
+                    available_liquidity: liquidity.to_string(),
+                    fee_bps,
+                    max_loan_amount: liquidity.to_string(),
+                    is_active: true,
+                }),
+                "test".to_string(),
+            )).await.unwrap();
+        }
+        let simulator = TradeSimulator::new(market_state);
+        
+        let candidate = |size_usd: f64| Candidate {
+            strategy: "flash_arb".to_string(),
+            asset: "WETH".to_string(),
+            spread_bps: 40.0,
+            legs: vec![("Ethereum".to_string(), "flash_loan".to_string())],
+            detected_at: Utc::now(),
+            confidence: 0.9,
+            first_seen_at: None,
+            details: Some(CandidateDetails::FlashArb(crate::FlashArbDetails {
+                chain: Chain::Ethereum,
+                borrow_asset: "USDC".to_string(),
+                buy_pool: "0xbuy".to_string(),
+                sell_pool: "0xsell".to_string(),
+                gross_spread_bps: 120.0,
+                size_usd,
+            })),
+        };
+        
+        // Balancer lends for free when it has the liquidity
+        let result = simulator.evaluate(&candidate(100_000.0)).await.unwrap();
+        assert_eq!(result.execution_path.len(), 4);
+        assert_eq!(result.execution_path[0].protocol, "balancer");
+        assert_eq!(result.costs.flashloan_fees_usd, 0.0);
+        assert!(result.execution_path[2].amount_out > result.execution_path[3].amount_in);
+        assert!(result.revert_prob.unwrap() < 0.05);
+        
+        // Larger borrows fall back to Aave and repay its fee
+        let result = simulator.evaluate(&candidate(1_000_000.0)).await.unwrap();
+        assert_eq!(result.execution_path[3].protocol, "aave_v3");
+        assert!((result.execution_path[3].amount_in - 1_000_500.0).abs() < 1e-6);
+        
+        // No provider can lend the whole amount
+        assert!(simulator.evaluate(&candidate(50_000_000.0)).await.is_err());
+    }
 }
-
@@ -41,18 +41,43 @@ impl FlashLoanSimulator {
     
     /// Find best flash loan provider
     pub async fn find_best_provider(&self, chain: Chain, asset: &str, amount_needed: f64) -> Option<(String, f64)> {
-        // Check Aave V3
-        if let Some(aave_liquidity) = self.get_flashloan_liquidity(chain, asset).await {
-            if aave_liquidity >= amount_needed {
-                let fee = self.estimate_flashloan_fee("aave_v3", amount_needed);
-                return Some(("aave_v3".to_string(), fee));
-            }
-        }
-        
-        // Balancer has 0% fees but may have less liquidity
-        // TODO: Check Balancer liquidity from market state
+        self.best_route(chain, asset, amount_needed).await
+            .map(|route| (route.provider, route.fee_usd))
+    }
+    
+    /// Cheapest provider able to lend `amount_needed` of `asset` on `chain`
+    ///
+    /// Ties on fee go to the provider with the most spare liquidity.
+    pub async fn best_route(&self, chain: Chain, asset: &str, amount_needed: f64) -> Option<FlashLoanRoute> {
+        let parse = |amount: &str| amount.parse::<f64>().ok();
         
-        None
+        self.market_state.get_flashloan_providers(chain, asset).await
+            .into_iter()
+            .filter_map(|state| {
+                let available = parse(&state.available_liquidity)?;
+                let max_loan = parse(&state.max_loan_amount).filter(|max| *max > 0.0).unwrap_or(available);
+                (available.min(max_loan) >= amount_needed).then(|| FlashLoanRoute {
+                    fee_usd: amount_needed * state.fee_bps as f64 / 10000.0,
+                    fee_bps: state.fee_bps,
+                    available_usd: available,
+                    provider: state.provider,
+                })
+            })
+            .min_by(|a, b| {
+                a.fee_bps.cmp(&b.fee_bps)
+                    .then(b.available_usd.partial_cmp(&a.available_usd).unwrap_or(std::cmp::Ordering::Equal))
+            })
     }
 }
 
+/// Flash loan provider chosen for a borrow
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlashLoanRoute {
+    pub provider: String,
+    pub fee_bps: u32,
+    pub fee_usd: f64,
+    
+    /// Provider liquidity at selection time
+    pub available_usd: f64,
+}
+
@@ -2,7 +2,81 @@
 
 use std::sync::Arc;
 use qenus_dataplane::Chain;
-use crate::state::MarketState;
+use crate::state::{GasState, MarketState};
+
+/// EIP-1559 max base-fee change per block (1 / BASE_FEE_MAX_CHANGE_DENOMINATOR)
+const BASE_FEE_MAX_CHANGE: f64 = 0.125;
+
+/// z-score of the quantile used to price delayed legs (95th percentile)
+const FORECAST_QUANTILE_Z: f64 = 1.645;
+
+/// Per-block log volatility assumed when there is too little history
+const DEFAULT_BLOCK_VOLATILITY: f64 = 0.03;
+
+/// Minimum consecutive-sample spans needed to trust measured volatility
+const MIN_VOLATILITY_SPANS: usize = 4;
+
+/// Cap on forecast log dispersion; base fees mean-revert over long horizons
+const MAX_FORECAST_LOG_SD: f64 = 1.0;
+
+/// Base-fee distribution at a future block
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BaseFeeForecast {
+    /// Blocks until the expected execution
+    pub blocks: u64,
+    /// Median base fee (gwei)
+    pub median_gwei: f64,
+    /// Upper-quantile base fee (gwei), used for pricing
+    pub upper_gwei: f64,
+}
+
+/// Average block time of a chain in seconds
+pub fn block_time_secs(chain: Chain) -> f64 {
+    match chain {
+        Chain::Ethereum => 12.0,
+        Chain::Arbitrum => 0.25,
+        Chain::Optimism | Chain::Base => 2.0,
+    }
+}
+
+/// Forecast the base fee `delay_secs` ahead of the latest gas sample
+///
+/// The next block follows the EIP-1559 update rule from the latest gas-used
+/// ratio; beyond that the base fee is a driftless log random walk whose
+/// per-block volatility is measured from the recent history.
+pub fn forecast_base_fee(state: &GasState, chain: Chain, delay_secs: u64) -> BaseFeeForecast {
+    let blocks = (delay_secs as f64 / block_time_secs(chain)).ceil() as u64;
+    if blocks == 0 {
+        return BaseFeeForecast { blocks, median_gwei: state.base_fee, upper_gwei: state.base_fee };
+    }
+    
+    let fullness = (state.gas_used_ratio.clamp(0.0, 1.0) - 0.5) / 0.5;
+    let median_gwei = state.base_fee * (1.0 + BASE_FEE_MAX_CHANGE * fullness);
+    
+    let log_sd = (block_volatility(state) * ((blocks - 1) as f64).sqrt()).min(MAX_FORECAST_LOG_SD);
+    BaseFeeForecast {
+        blocks,
+        median_gwei,
+        upper_gwei: median_gwei * (FORECAST_QUANTILE_Z * log_sd).exp(),
+    }
+}
+
+/// Per-block log volatility of the base fee across recent samples
+fn block_volatility(state: &GasState) -> f64 {
+    let per_block: Vec<f64> = state.history.windows(2)
+        .filter(|pair| pair[0].base_fee > 0.0 && pair[1].base_fee > 0.0)
+        .filter(|pair| pair[1].block_number > pair[0].block_number)
+        .map(|pair| {
+            let gap = (pair[1].block_number - pair[0].block_number) as f64;
+            (pair[1].base_fee / pair[0].base_fee).ln() / gap.sqrt()
+        })
+        .collect();
+    
+    if per_block.len() < MIN_VOLATILITY_SPANS {
+        return DEFAULT_BLOCK_VOLATILITY;
+    }
+    (per_block.iter().map(|r| r * r).sum::<f64>() / per_block.len() as f64).sqrt()
+}
 
 /// Gas estimator
 pub struct GasEstimator {
@@ -30,6 +104,24 @@ impl GasEstimator {
         }
     }
     
+    /// Estimate gas cost for a swap that executes `delay_secs` from now
+    ///
+    /// Prices the upper quantile of the forecast base fee plus today's tip,
+    /// so legs waiting on a bridge are not priced at detection-time gas.
+    pub async fn estimate_delayed_swap_gas(&self, chain: Chain, delay_secs: u64, eth_price: f64) -> f64 {
+        let (Some(gas_price_gwei), Some(state)) = (
+            self.market_state.get_gas_price(chain).await,
+            self.market_state.get_gas_state(chain).await,
+        ) else {
+            return self.fallback_swap_gas(chain);
+        };
+        
+        let gas_units = 150_000.0;
+        let tip_gwei = (gas_price_gwei - state.base_fee).max(0.0);
+        let forecast = forecast_base_fee(&state, chain, delay_secs);
+        (forecast.upper_gwei + tip_gwei) * gas_units / 1e9 * eth_price
+    }
+    
     /// Estimate gas cost for a bridge transaction
     pub async fn estimate_bridge_gas(&self, eth_price: f64) -> f64 {
         if let Some(gas_price_gwei) = self.market_state.get_gas_price(Chain::Ethereum).await {
@@ -52,6 +144,26 @@ impl GasEstimator {
         }
     }
     
+    /// Estimate gas for an Aave V3 liquidationCall
+    pub async fn estimate_liquidation_gas(&self, chain: Chain, eth_price: f64) -> f64 {
+        if let Some(gas_price_gwei) = self.market_state.get_gas_price(chain).await {
+            let gas_units = 450_000.0; // liquidationCall touches both reserves and the oracle
+            let gas_cost_eth = (gas_price_gwei * gas_units) / 1e9;
+            gas_cost_eth * eth_price
+        } else {
+            self.fallback_swap_gas(chain) * 3.0
+        }
+    }
+    
+    /// Estimate the cost of `gas_units` of execution on `chain`
+    pub async fn estimate_gas_units_cost(&self, chain: Chain, gas_units: f64, eth_price: f64) -> f64 {
+        if let Some(gas_price_gwei) = self.market_state.get_gas_price(chain).await {
+            (gas_price_gwei * gas_units) / 1e9 * eth_price
+        } else {
+            self.fallback_swap_gas(chain) * gas_units / 150_000.0
+        }
+    }
+    
     fn fallback_swap_gas(&self, chain: Chain) -> f64 {
         match chain {
             Chain::Ethereum => 50.0,
@@ -62,3 +174,60 @@ impl GasEstimator {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::state::GasSample;
+
+    fn gas_state(base_fee: f64, gas_used_ratio: f64, history: Vec<GasSample>) -> GasState {
+        GasState {
+            base_fee,
+            priority_fee: 1.0,
+            fast_gas_price: base_fee + 2.0,
+            standard_gas_price: base_fee + 1.0,
+            gas_used_ratio,
+            pending_tx_count: 0,
+            last_update: Utc::now(),
+            history,
+        }
+    }
+
+    #[test]
+    fn test_forecast_applies_eip1559_rule_and_widens_with_delay() {
+        // Full block: next base fee rises by the maximum 12.5%
+        let state = gas_state(40.0, 1.0, Vec::new());
+        let next = forecast_base_fee(&state, Chain::Ethereum, 12);
+        assert_eq!(next.blocks, 1);
+        assert!((next.median_gwei - 45.0).abs() < 1e-9);
+        assert!((next.upper_gwei - next.median_gwei).abs() < 1e-9);
+
+        // Bridge-length delays price well above the median
+        let later = forecast_base_fee(&state, Chain::Ethereum, 3600);
+        assert_eq!(later.blocks, 300);
+        assert_eq!(later.median_gwei, next.median_gwei);
+        assert!(later.upper_gwei > later.median_gwei * 1.5);
+
+        assert_eq!(forecast_base_fee(&state, Chain::Ethereum, 0).upper_gwei, 40.0);
+    }
+
+    #[test]
+    fn test_forecast_volatility_comes_from_history() {
+        let steady: Vec<GasSample> = (0..10)
+            .map(|i| GasSample { block_number: 100 + i, base_fee: 30.0, gas_used_ratio: 0.5 })
+            .collect();
+        let choppy: Vec<GasSample> = (0..10)
+            .map(|i| GasSample {
+                block_number: 100 + i,
+                base_fee: if i % 2 == 0 { 30.0 } else { 33.0 },
+                gas_used_ratio: 0.5,
+            })
+            .collect();
+
+        let calm = forecast_base_fee(&gas_state(30.0, 0.5, steady), Chain::Ethereum, 600);
+        let rough = forecast_base_fee(&gas_state(30.0, 0.5, choppy), Chain::Ethereum, 600);
+        assert!((calm.upper_gwei - 30.0).abs() < 1e-9);
+        assert!(rough.upper_gwei > calm.upper_gwei);
+    }
+}
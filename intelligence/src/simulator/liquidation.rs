@@ -0,0 +1,177 @@
+//! Flash-loan-funded liquidation cost model
+//!
+//! Flash borrow the debt asset, call `liquidationCall`, swap the seized
+//! collateral back into the debt asset and repay the loan.
+
+use std::sync::Arc;
+use qenus_dataplane::Chain;
+
+use crate::{CostBreakdown, LiquidationDetails, SimulatedStep};
+use crate::state::{AmmState, MarketState};
+use super::amm::{depth_slippage_bps, pool_fee_bps};
+use super::flashloan::FlashLoanSimulator;
+use super::gas::GasEstimator;
+
+/// Gas units of flash loan + liquidationCall + swap in one transaction
+const LIQUIDATION_TX_GAS_UNITS: f64 = 200_000.0 + 450_000.0 + 150_000.0;
+
+/// Slippage assumed when no pool pairs collateral with debt (routed via a hop)
+const UNROUTED_SWAP_COST_BPS: f64 = 60.0;
+
+/// Simulated liquidation before competition is applied
+#[derive(Debug, Clone)]
+pub struct LiquidationSimulation {
+    pub costs: CostBreakdown,
+    pub execution_path: Vec<SimulatedStep>,
+    /// Gas units of the whole liquidation transaction
+    pub gas_units: f64,
+}
+
+/// Liquidation simulator
+pub struct LiquidationSimulator {
+    market_state: Arc<MarketState>,
+    gas_estimator: GasEstimator,
+    flashloan_simulator: FlashLoanSimulator,
+}
+
+impl LiquidationSimulator {
+    pub fn new(market_state: Arc<MarketState>) -> Self {
+        Self {
+            gas_estimator: GasEstimator::new(market_state.clone()),
+            flashloan_simulator: FlashLoanSimulator::new(market_state.clone()),
+            market_state,
+        }
+    }
+
+    /// Simulate a liquidation funded by a flash loan of the debt asset
+    pub async fn simulate(&self, details: &LiquidationDetails, eth_price: f64) -> LiquidationSimulation {
+        let chain = details.chain;
+        let domain = format!("{:?}", chain);
+        let debt_usd = details.debt_to_cover_usd;
+
+        let mut costs = CostBreakdown {
+            gas_usd: 0.0,
+            protocol_fees_usd: 0.0,
+            bridge_fees_usd: 0.0,
+            flashloan_fees_usd: 0.0,
+            slippage_usd: 0.0,
+            total_usd: 0.0,
+        };
+        let mut execution_path = Vec::new();
+
+        // Step 1: Flash borrow the debt asset
+        let (provider, flash_fee) = self.flashloan_simulator
+            .find_best_provider(chain, &details.debt_asset, debt_usd)
+            .await
+            .unwrap_or_else(|| {
+                ("aave_v3".to_string(), self.flashloan_simulator.estimate_flashloan_fee("aave_v3", debt_usd))
+            });
+        let flash_gas = self.gas_estimator.estimate_flashloan_gas(chain, eth_price).await;
+        costs.flashloan_fees_usd += flash_fee;
+        costs.gas_usd += flash_gas;
+
+        execution_path.push(SimulatedStep {
+            step: 1,
+            action: "flash_loan".to_string(),
+            domain: domain.clone(),
+            protocol: provider.clone(),
+            amount_in: 0.0,
+            amount_out: debt_usd,
+            slippage_bps: 0.0,
+            cost_usd: flash_gas,
+            pool: None,
+            asset_in: None,
+            asset_out: Some(details.debt_asset.clone()),
+        });
+
+        // Step 2: Repay the borrower's debt and seize collateral plus bonus
+        let liquidation_gas = self.gas_estimator.estimate_liquidation_gas(chain, eth_price).await;
+        costs.gas_usd += liquidation_gas;
+
+        execution_path.push(SimulatedStep {
+            step: 2,
+            action: "liquidation_call".to_string(),
+            domain: domain.clone(),
+            protocol: "aave_v3".to_string(),
+            amount_in: debt_usd,
+            amount_out: details.collateral_seized_usd,
+            slippage_bps: 0.0,
+            cost_usd: liquidation_gas,
+            pool: None,
+            asset_in: Some(details.debt_asset.clone()),
+            asset_out: Some(details.collateral_asset.clone()),
+        });
+
+        // Step 3: Swap seized collateral back into the debt asset
+        let swap_gas = self.gas_estimator.estimate_swap_gas(chain, eth_price).await;
+        let (protocol, pool, fee_bps, slippage_bps) = self
+            .swap_back_cost(chain, &details.collateral_asset, &details.debt_asset, details.collateral_seized_usd)
+            .await;
+        let swap_fee = details.collateral_seized_usd * fee_bps / 10000.0;
+        let swap_slippage = details.collateral_seized_usd * slippage_bps / 10000.0;
+        costs.gas_usd += swap_gas;
+        costs.protocol_fees_usd += swap_fee;
+        costs.slippage_usd += swap_slippage;
+
+        execution_path.push(SimulatedStep {
+            step: 3,
+            action: "swap_sell".to_string(),
+            domain: domain.clone(),
+            protocol,
+            amount_in: details.collateral_seized_usd,
+            amount_out: details.collateral_seized_usd - swap_fee - swap_slippage,
+            slippage_bps,
+            cost_usd: swap_gas + swap_fee + swap_slippage,
+            pool,
+            asset_in: Some(details.collateral_asset.clone()),
+            asset_out: Some(details.debt_asset.clone()),
+        });
+
+        // Step 4: Repay the flash loan
+        execution_path.push(SimulatedStep {
+            step: 4,
+            action: "flash_repay".to_string(),
+            domain,
+            protocol: provider,
+            amount_in: debt_usd + flash_fee,
+            amount_out: 0.0,
+            slippage_bps: 0.0,
+            cost_usd: flash_fee,
+            pool: None,
+            asset_in: Some(details.debt_asset.clone()),
+            asset_out: None,
+        });
+
+        LiquidationSimulation {
+            costs,
+            execution_path,
+            gas_units: LIQUIDATION_TX_GAS_UNITS,
+        }
+    }
+
+    /// Find the cheapest pool to sell collateral for debt
+    ///
+    /// Returns (protocol, pool address, fee bps, slippage bps).
+    async fn swap_back_cost(&self, chain: Chain, collateral: &str, debt: &str, size_usd: f64) -> (String, Option<String>, f64, f64) {
+        if collateral == debt {
+            return ("none".to_string(), None, 0.0, 0.0);
+        }
+
+        let pools = self.market_state.get_amm_pools(chain).await;
+        pools.iter()
+            .filter(|pool| pairs(pool, collateral, debt))
+            .map(|pool| (
+                pool.pool_type.clone(),
+                Some(pool.pool_address.clone()),
+                pool_fee_bps(pool),
+                depth_slippage_bps(pool, size_usd),
+            ))
+            .min_by(|a, b| (a.2 + a.3).partial_cmp(&(b.2 + b.3)).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or_else(|| ("aggregator".to_string(), None, 30.0, UNROUTED_SWAP_COST_BPS))
+    }
+}
+
+fn pairs(pool: &AmmState, a: &str, b: &str) -> bool {
+    (pool.token0_symbol == a && pool.token1_symbol == b) ||
+    (pool.token0_symbol == b && pool.token1_symbol == a)
+}
@@ -5,6 +5,13 @@ pub mod gas;
 pub mod bridge;
 pub mod flashloan;
 pub mod evaluator;
+pub mod competition;
+pub mod liquidation;
+pub mod router;
 
 pub use evaluator::TradeSimulator;
+pub use competition::{CompetitionModel, CompetitionEstimate, OrderingModel};
+pub use liquidation::{LiquidationSimulator, LiquidationSimulation};
+pub use flashloan::{FlashLoanSimulator, FlashLoanRoute};
+pub use router::{SplitRouter, PoolAllocation};
 
@@ -0,0 +1,309 @@
+//! Split-order routing across pools trading the same pair
+//!
+//! Large swaps execute better spread over every pool for a pair (Uniswap V3
+//! fee tiers, Curve, Balancer) than pushed through one. Routing `x` through a
+//! pool costs its fee, its depth slippage at `x` and its price gap to the
+//! reference pool; the router allocates the input so that every pool it uses
+//! ends at the same marginal cost, which maximises the total output.
+
+use crate::state::AmmState;
+use super::amm::{mid_rate, pool_fee_bps, swap_slippage_bps};
+
+/// Gas units of each pool beyond the first in a split swap
+pub const SPLIT_POOL_GAS_UNITS: f64 = 100_000.0;
+
+/// Bisection rounds for the marginal cost and allocation searches
+const SEARCH_ITERATIONS: usize = 60;
+
+/// Allocations below this share of the input are dropped
+const MIN_ALLOCATION_SHARE: f64 = 1e-4;
+
+/// Part of a swap routed through one pool
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolAllocation {
+    pub pool_address: String,
+    pub protocol: String,
+
+    /// Input routed through the pool (USD)
+    pub amount_in: f64,
+    pub fee_bps: f64,
+
+    /// Depth slippage plus the pool's price gap to the reference pool
+    pub slippage_bps: f64,
+}
+
+impl PoolAllocation {
+    pub fn fee_usd(&self) -> f64 {
+        self.amount_in * self.fee_bps / 10000.0
+    }
+
+    pub fn slippage_usd(&self) -> f64 {
+        self.amount_in * self.slippage_bps / 10000.0
+    }
+}
+
+/// Pool a swap can be routed through, priced against the reference pool
+struct RouteCandidate<'a> {
+    pool: &'a AmmState,
+    fee_bps: f64,
+    gap_bps: f64,
+}
+
+impl RouteCandidate<'_> {
+    fn slippage_bps(&self, asset_in: &str, amount_in: f64) -> f64 {
+        self.gap_bps + swap_slippage_bps(self.pool, asset_in, amount_in)
+    }
+
+    /// USD lost routing `amount_in` through the pool
+    fn cost(&self, asset_in: &str, amount_in: f64) -> f64 {
+        if amount_in <= 0.0 {
+            return 0.0;
+        }
+        amount_in * (self.fee_bps + self.slippage_bps(asset_in, amount_in)) / 10000.0
+    }
+}
+
+/// Splits a swap across the pools trading its pair
+pub struct SplitRouter<'a> {
+    pools: &'a [AmmState],
+    exclude: Vec<&'a str>,
+    pool_overhead_usd: f64,
+}
+
+impl<'a> SplitRouter<'a> {
+    pub fn new(pools: &'a [AmmState]) -> Self {
+        Self { pools, exclude: Vec::new(), pool_overhead_usd: 0.0 }
+    }
+
+    /// Never route through `pool_address` (e.g. the other side of an arb)
+    pub fn excluding(mut self, pool_address: &'a str) -> Self {
+        self.exclude.push(pool_address);
+        self
+    }
+
+    /// Fixed cost of each pool beyond the first, usually its gas
+    pub fn with_pool_overhead(mut self, usd: f64) -> Self {
+        self.pool_overhead_usd = usd.max(0.0);
+        self
+    }
+
+    /// Allocate `amount_in` of `asset_in` across the pools trading it for `asset_out`
+    ///
+    /// Prices are relative to the `reference` pool the detector picked; pools
+    /// quoting a worse rate pay the gap as slippage. Returns the allocations
+    /// largest first, or None when the reference pool is not quoted.
+    pub fn route(&self, reference: &str, asset_in: &str, asset_out: &str, amount_in: f64) -> Option<Vec<PoolAllocation>> {
+        let reference_pool = self.pools.iter().find(|pool| pool.pool_address == reference)?;
+        let reference_rate = mid_rate(reference_pool, asset_in)?;
+
+        let candidates: Vec<RouteCandidate> = self.pools.iter()
+            .filter(|pool| pool.pool_address == reference || !self.exclude.contains(&pool.pool_address.as_str()))
+            .filter(|pool| {
+                (pool.token0_symbol == asset_in && pool.token1_symbol == asset_out) ||
+                (pool.token0_symbol == asset_out && pool.token1_symbol == asset_in)
+            })
+            .filter_map(|pool| {
+                let rate = mid_rate(pool, asset_in)?;
+                Some(RouteCandidate {
+                    pool,
+                    fee_bps: pool_fee_bps(pool),
+                    gap_bps: ((1.0 - rate / reference_rate) * 10000.0).max(0.0),
+                })
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        // Drop the smallest pool while its share saves less than it costs to add
+        let mut active: Vec<usize> = (0..candidates.len()).collect();
+        let mut amounts = allocate(&candidates, &active, asset_in, amount_in);
+        let mut total = self.total_cost(&candidates, &amounts, asset_in);
+        while active.len() > 1 {
+            let smallest = (0..active.len())
+                .min_by(|a, b| amounts[*a].1.partial_cmp(&amounts[*b].1).unwrap_or(std::cmp::Ordering::Equal))?;
+            let mut fewer = active.clone();
+            fewer.remove(smallest);
+            let fewer_amounts = allocate(&candidates, &fewer, asset_in, amount_in);
+            let fewer_total = self.total_cost(&candidates, &fewer_amounts, asset_in);
+            if amounts[smallest].1 > 0.0 && fewer_total >= total {
+                break;
+            }
+            active = fewer;
+            amounts = fewer_amounts;
+            total = fewer_total;
+        }
+
+        let mut allocations: Vec<PoolAllocation> = amounts.iter()
+            .filter(|(_, amount)| *amount > 0.0)
+            .map(|(index, amount)| {
+                let candidate = &candidates[*index];
+                PoolAllocation {
+                    pool_address: candidate.pool.pool_address.clone(),
+                    protocol: candidate.pool.pool_type.clone(),
+                    amount_in: *amount,
+                    fee_bps: candidate.fee_bps,
+                    slippage_bps: candidate.slippage_bps(asset_in, *amount),
+                }
+            })
+            .collect();
+        allocations.sort_by(|a, b| b.amount_in.partial_cmp(&a.amount_in).unwrap_or(std::cmp::Ordering::Equal));
+        Some(allocations)
+    }
+
+    fn total_cost(&self, candidates: &[RouteCandidate], amounts: &[(usize, f64)], asset_in: &str) -> f64 {
+        let used = amounts.iter().filter(|(_, amount)| *amount > 0.0).count();
+        let swaps: f64 = amounts.iter().map(|(index, amount)| candidates[*index].cost(asset_in, *amount)).sum();
+        swaps + used.saturating_sub(1) as f64 * self.pool_overhead_usd
+    }
+}
+
+/// Equalise marginal cost across the `active` candidates
+///
+/// Bisects on the common marginal cost: each pool takes as much as it can
+/// before its marginal cost passes it, until the pools absorb `amount_in`.
+fn allocate(candidates: &[RouteCandidate], active: &[usize], asset_in: &str, amount_in: f64) -> Vec<(usize, f64)> {
+    if active.len() == 1 {
+        return vec![(active[0], amount_in)];
+    }
+
+    let step = (amount_in * 1e-6).max(1e-9);
+    let marginal = |candidate: &RouteCandidate, amount: f64| {
+        (candidate.cost(asset_in, amount + step) - candidate.cost(asset_in, amount)) / step
+    };
+
+    // Largest amount whose marginal cost stays within `lambda`
+    let absorbed = |candidate: &RouteCandidate, lambda: f64| {
+        if marginal(candidate, 0.0) > lambda {
+            return 0.0;
+        }
+        if marginal(candidate, amount_in) <= lambda {
+            return amount_in;
+        }
+        let (mut low, mut high) = (0.0, amount_in);
+        for _ in 0..SEARCH_ITERATIONS {
+            let mid = (low + high) / 2.0;
+            if marginal(candidate, mid) <= lambda {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    };
+
+    let bounds = active.iter().map(|index| &candidates[*index])
+        .flat_map(|candidate| [marginal(candidate, 0.0), marginal(candidate, amount_in)]);
+    let (mut low, mut high) = bounds.fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), m| (low.min(m), high.max(m)));
+    for _ in 0..SEARCH_ITERATIONS {
+        let lambda = (low + high) / 2.0;
+        let total: f64 = active.iter().map(|index| absorbed(&candidates[*index], lambda)).sum();
+        if total < amount_in {
+            low = lambda;
+        } else {
+            high = lambda;
+        }
+    }
+
+    let mut amounts: Vec<(usize, f64)> = active.iter().map(|index| (*index, absorbed(&candidates[*index], high))).collect();
+    let total: f64 = amounts.iter().map(|(_, amount)| amount).sum();
+    if total <= 0.0 {
+        return vec![(active[0], amount_in)];
+    }
+
+    // Bisection leaves a residue; spread it pro rata and drop dust
+    for (_, amount) in &mut amounts {
+        *amount *= amount_in / total;
+        if *amount < amount_in * MIN_ALLOCATION_SHARE {
+            *amount = 0.0;
+        }
+    }
+    let total: f64 = amounts.iter().map(|(_, amount)| amount).sum();
+    for (_, amount) in &mut amounts {
+        *amount *= amount_in / total;
+    }
+    amounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use chrono::Utc;
+
+    fn pool(address: &str, pool_type: &str, fee_tier: u32, mid_price: f64, slippage_at_1m: f64) -> AmmState {
+        let mut depth = HashMap::new();
+        depth.insert("1m".to_string(), (slippage_at_1m, slippage_at_1m));
+        AmmState {
+            pool_address: address.to_string(),
+            pool_type: pool_type.to_string(),
+            pool_id: None,
+            token0_symbol: "WETH".to_string(),
+            token1_symbol: "USDC".to_string(),
+            token0_address: String::new(),
+            token1_address: String::new(),
+            token0_decimals: 18,
+            token1_decimals: 6,
+            mid_price,
+            liquidity: "0".to_string(),
+            fee_tier: Some(fee_tier),
+            depth,
+            depth_curve: Default::default(),
+            last_update: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_split_equalises_marginal_cost() {
+        let mut pools = vec![
+            pool("0xshallow", "uniswap_v3", 500, 2000.0, 40.0),
+            pool("0xdeep", "uniswap_v3", 3000, 2000.0, 10.0),
+            pool("0xarb", "curve", 4, 2000.0, 1.0),
+            pool("0xother", "balancer", 30, 1.0, 1.0),
+        ];
+        pools[3].token1_symbol = "DAI".to_string();
+        let single = RouteCandidate { pool: &pools[0], fee_bps: 5.0, gap_bps: 0.0 }.cost("USDC", 2_000_000.0);
+
+        let allocations = SplitRouter::new(&pools)
+            .excluding("0xarb")
+            .route("0xshallow", "USDC", "WETH", 2_000_000.0)
+            .unwrap();
+
+        // The excluded pool and the pool of another pair take nothing
+        assert_eq!(allocations.len(), 2);
+        assert!(allocations.iter().all(|a| a.pool_address == "0xshallow" || a.pool_address == "0xdeep"));
+        let total: f64 = allocations.iter().map(|a| a.amount_in).sum();
+        assert!((total - 2_000_000.0).abs() < 1e-6);
+
+        // Marginal costs 5 + 80x/1M and 30 + 20x/1M bps meet at 0.65M / 1.35M
+        let shallow = allocations.iter().find(|a| a.pool_address == "0xshallow").unwrap();
+        assert!((shallow.amount_in - 650_000.0).abs() < 1_000.0);
+
+        let split: f64 = allocations.iter().map(|a| a.fee_usd() + a.slippage_usd()).sum();
+        assert!(split < single);
+    }
+
+    #[test]
+    fn test_small_or_expensive_splits_stay_in_one_pool() {
+        let pools = vec![
+            pool("0xa", "uniswap_v3", 500, 2000.0, 20.0),
+            pool("0xb", "uniswap_v3", 500, 1990.0, 20.0),
+        ];
+
+        // Selling WETH into the pool quoting 1990 pays a 50 bps gap
+        let allocations = SplitRouter::new(&pools).route("0xa", "WETH", "USDC", 100_000.0).unwrap();
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(allocations[0].pool_address, "0xa");
+        assert_eq!(allocations[0].amount_in, 100_000.0);
+
+        // Identical pools would split evenly unless each extra pool's gas outweighs it
+        let pools = vec![pools[0].clone(), pool("0xb", "uniswap_v3", 500, 2000.0, 20.0)];
+        let split = SplitRouter::new(&pools).route("0xa", "WETH", "USDC", 1_000_000.0).unwrap();
+        assert_eq!(split.len(), 2);
+        assert!((split[0].amount_in - 500_000.0).abs() < 1_000.0);
+        let kept = SplitRouter::new(&pools).with_pool_overhead(1_500.0).route("0xa", "WETH", "USDC", 1_000_000.0).unwrap();
+        assert_eq!(kept.len(), 1);
+
+        assert!(SplitRouter::new(&pools).route("0xmissing", "WETH", "USDC", 1_000.0).is_none());
+    }
+}
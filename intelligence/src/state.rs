@@ -3,57 +3,255 @@
 //! Maintains a rolling view of market conditions across all chains by consuming
 //! beta_dataplane features via Kafka or gRPC.
 //! This is the Intelligence layer's memory of the market.
+//!
+//! State is sharded by chain, and every map inside a shard locks per key, so
+//! ingestion on one chain never blocks readers of another and a reader only
+//! waits for writers touching the same entries. Entries are immutable `Arc`s
+//! that ingestion replaces rather than mutates: readers copy the pointers
+//! under the lock and clone outside it. When a chain moves to a new block its
+//! previous block is sealed into a `BlockView`, a copy-on-write snapshot that
+//! holds that whole block's features and nothing after it.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicI64, Ordering};
+use dashmap::DashMap;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use chrono::{DateTime, Utc, Duration};
-use qenus_dataplane::{Feature, FeatureData, Chain};
+use qenus_dataplane::{Feature, FeatureData, Chain, DepthCurve, EvmStateFeature, LendingFeature};
 use tracing::debug;
 
 use crate::error::{IntelligenceError, Result};
+use crate::simulator::amm::{swap_slippage_bps, usd_price};
+use crate::verifier::EvmStateCache;
+
+/// Buffered change notifications per subscriber before it lags
+const CHANGE_CHANNEL_CAPACITY: usize = 4096;
+
+/// Clock value meaning "use the wall clock"
+const WALL_CLOCK: i64 = i64::MIN;
+
+/// Lending features kept per chain for detectors that replay them
+const LENDING_LOG_LEN: usize = 1024;
+
+/// Chains with a state shard, in shard order
+const CHAINS: [Chain; 4] = [Chain::Ethereum, Chain::Arbitrum, Chain::Optimism, Chain::Base];
 
 /// Market state manager - maintains rolling state from beta_dataplane features
 pub struct MarketState {
-    /// AMM pool states by chain and pool address
-    amm_state: Arc<RwLock<HashMap<(Chain, String), AmmState>>>,
+    /// Per-chain state, indexed by `shard_index`
+    shards: [ChainShard; 4],
+    
+    /// Time-to-live for cached states
+    state_ttl: Duration,
     
-    /// Bridge states by chain pair
-    bridge_state: Arc<RwLock<HashMap<(Chain, Chain), Vec<BridgeState>>>>,
+    /// Change notifications published on every ingested feature
+    changes: broadcast::Sender<MarketChange>,
     
-    /// Gas states by chain
-    gas_state: Arc<RwLock<HashMap<Chain, GasState>>>,
+    /// Pinned clock in milliseconds, or WALL_CLOCK
+    clock: Arc<AtomicI64>,
     
-    /// Flash loan availability by chain and provider
-    flashloan_state: Arc<RwLock<HashMap<(Chain, String), FlashLoanState>>>,
+    /// Contract state the intent verifier executes against
+    evm_state: Arc<EvmStateCache>,
+}
+
+/// State of one chain
+#[derive(Default)]
+struct ChainShard {
+    /// AMM pool states by pool address
+    pools: DashMap<String, Arc<AmmState>>,
     
-    /// Sequencer health by chain
-    sequencer_state: Arc<RwLock<HashMap<Chain, SequencerState>>>,
+    /// Bridges leaving this chain, by destination chain; one entry per
+    /// (bridge address, token)
+    bridges: DashMap<Chain, Vec<Arc<BridgeState>>>,
     
-    /// Time-to-live for cached states
-    state_ttl: Duration,
+    gas: RwLock<Option<Arc<GasState>>>,
+    
+    /// Flash loan availability by provider
+    flashloans: DashMap<String, Arc<FlashLoanState>>,
+    
+    sequencer: RwLock<Option<Arc<SequencerState>>>,
+    
+    /// Execution wallet balances by lowercased wallet address
+    wallets: DashMap<String, Arc<WalletState>>,
+    
+    /// Recent lending pool features, in ingest order
+    lending: RwLock<LendingLog>,
     
-    /// Last update time
-    last_update: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
+    /// Last update time by feature type
+    last_update: DashMap<&'static str, DateTime<Utc>>,
+    
+    /// Block being ingested; held while a feature is applied so a seal
+    /// never observes half of a feature
+    open_block: Mutex<u64>,
+    
+    /// Last sealed block
+    view: RwLock<Option<Arc<BlockView>>>,
 }
 
-/// AMM pool state derived from beta_dataplane
+/// Consistent view of one chain as of a sealed block
+///
+/// Holds every feature ingested up to and including `block_number` and
+/// nothing later. Entries are shared with the live state, which replaces
+/// them on update, so taking a view copies pointers, not pools.
 #[derive(Debug, Clone)]
+pub struct BlockView {
+    pub chain: Chain,
+    pub block_number: u64,
+    pub sealed_at: DateTime<Utc>,
+    pub pools: Vec<Arc<AmmState>>,
+    /// Bridges leaving the chain, with their destination
+    pub bridges: Vec<(Chain, Arc<BridgeState>)>,
+    pub gas: Option<Arc<GasState>>,
+    pub flashloans: Vec<Arc<FlashLoanState>>,
+    pub sequencer: Option<Arc<SequencerState>>,
+    pub wallets: Vec<Arc<WalletState>>,
+}
+
+impl BlockView {
+    /// Pool by address
+    pub fn pool(&self, address: &str) -> Option<&AmmState> {
+        self.pools.iter().find(|pool| pool.pool_address == address).map(|pool| pool.as_ref())
+    }
+    
+    /// Pools trading `asset`
+    pub fn pools_trading<'a>(&'a self, asset: &'a str) -> impl Iterator<Item = &'a AmmState> + 'a {
+        self.pools.iter()
+            .map(|pool| pool.as_ref())
+            .filter(move |pool| pool.token0_symbol == asset || pool.token1_symbol == asset)
+    }
+    
+    /// Pools updated since `fresh_since`
+    pub fn fresh_pools(&self, fresh_since: DateTime<Utc>) -> impl Iterator<Item = &AmmState> + '_ {
+        self.pools.iter()
+            .map(|pool| pool.as_ref())
+            .filter(move |pool| pool.last_update >= fresh_since)
+    }
+    
+    /// Mid price of a fresh pool trading `asset`
+    pub fn price(&self, asset: &str, fresh_since: DateTime<Utc>) -> Option<f64> {
+        self.pools_trading(asset)
+            .find(|pool| pool.last_update >= fresh_since)
+            .map(|pool| pool.mid_price)
+    }
+    
+    /// Fast gas price, if reported since `fresh_since`
+    pub fn gas_price(&self, fresh_since: DateTime<Utc>) -> Option<f64> {
+        self.gas.as_ref()
+            .filter(|gas| gas.last_update >= fresh_since)
+            .map(|gas| gas.fast_gas_price)
+    }
+    
+    /// Lowest fee of the active, fresh bridges to `to_chain`
+    pub fn bridge_fee(&self, to_chain: Chain, fresh_since: DateTime<Utc>) -> Option<u32> {
+        self.bridges.iter()
+            .filter(|(destination, bridge)| {
+                *destination == to_chain && bridge.is_active && bridge.last_update >= fresh_since
+            })
+            .map(|(_, bridge)| bridge.fee_bps)
+            .min()
+    }
+    
+    /// Whether the sequencer reported healthy since `fresh_since`
+    pub fn is_sequencer_healthy(&self, fresh_since: DateTime<Utc>) -> bool {
+        self.sequencer.as_ref()
+            .is_some_and(|sequencer| sequencer.last_update >= fresh_since && sequencer.status == "healthy")
+    }
+}
+
+impl ChainShard {
+    /// Snapshot the shard as of `block_number`
+    fn seal(&self, chain: Chain, block_number: u64, sealed_at: DateTime<Utc>) {
+        let view = BlockView {
+            chain,
+            block_number,
+            sealed_at,
+            pools: self.pools.iter().map(|entry| entry.value().clone()).collect(),
+            bridges: self.bridges.iter()
+                .flat_map(|entry| {
+                    let destination = *entry.key();
+                    entry.value().iter().map(|bridge| (destination, bridge.clone())).collect::<Vec<_>>()
+                })
+                .collect(),
+            gas: self.gas.read().clone(),
+            flashloans: self.flashloans.iter().map(|entry| entry.value().clone()).collect(),
+            sequencer: self.sequencer.read().clone(),
+            wallets: self.wallets.iter().map(|entry| entry.value().clone()).collect(),
+        };
+        *self.view.write() = Some(Arc::new(view));
+    }
+}
+
+/// Lending features of one chain, numbered in ingest order
+///
+/// Unlike the other feeds these carry events, not state, so consumers read
+/// every entry since their last cursor instead of the latest value.
+#[derive(Default)]
+struct LendingLog {
+    /// Sequence number of the oldest retained entry
+    first_seq: u64,
+    entries: VecDeque<Arc<LendingFeature>>,
+}
+
+/// What part of the market state changed
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChangeKey {
+    Pool { address: String, token0: String, token1: String },
+    Bridge { dest_chain: Chain, token: String },
+    Gas,
+    FlashLoan { provider: String, asset: String },
+    Sequencer,
+    Wallet { address: String },
+    Lending { protocol: String },
+    EvmState,
+}
+
+/// Notification that a feature updated the market state
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketChange {
+    pub chain: Chain,
+    pub block_number: u64,
+    pub key: ChangeKey,
+    /// Size of the change in bps (price, base fee, bridge fee or liquidity);
+    /// None when the key was seen for the first time
+    pub magnitude_bps: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// AMM pool state derived from beta_dataplane
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AmmState {
     pub pool_address: String,
     pub pool_type: String,
+    /// Balancer pool id, which the Vault keys swaps by
+    #[serde(default)]
+    pub pool_id: Option<String>,
     pub token0_symbol: String,
     pub token1_symbol: String,
+    #[serde(default)]
+    pub token0_address: String,
+    #[serde(default)]
+    pub token1_address: String,
+    #[serde(default)]
+    pub token0_decimals: u8,
+    #[serde(default)]
+    pub token1_decimals: u8,
     pub mid_price: f64,
     pub liquidity: String,
     pub fee_tier: Option<u32>,
     /// Depth curve for slippage calculation
     pub depth: HashMap<String, (f64, f64)>, // size -> (slippage_bps, price_impact)
+    /// Sampled quotes on both sides of the book (labels live in `depth`)
+    #[serde(default)]
+    pub depth_curve: DepthCurve,
     pub last_update: DateTime<Utc>,
 }
 
 /// Bridge state derived from beta_dataplane
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeState {
     pub bridge_address: String,
     pub bridge_type: String,
@@ -66,7 +264,7 @@ pub struct BridgeState {
 }
 
 /// Gas state derived from beta_dataplane
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasState {
     pub base_fee: f64,
     pub priority_fee: f64,
@@ -75,10 +273,24 @@ pub struct GasState {
     pub gas_used_ratio: f64,
     pub pending_tx_count: u64,
     pub last_update: DateTime<Utc>,
+    /// Recent base-fee samples, oldest first, including the latest
+    #[serde(default)]
+    pub history: Vec<GasSample>,
+}
+
+/// One observed block's base fee and fullness
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasSample {
+    pub block_number: u64,
+    pub base_fee: f64,
+    pub gas_used_ratio: f64,
 }
 
+/// Number of gas samples kept per chain for base-fee forecasting
+pub const GAS_HISTORY_LEN: usize = 64;
+
 /// Flash loan state derived from beta_dataplane
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FlashLoanState {
     pub provider: String,
     pub provider_address: String,
@@ -91,7 +303,7 @@ pub struct FlashLoanState {
 }
 
 /// Sequencer state derived from beta_dataplane
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SequencerState {
     pub status: String, // "healthy", "degraded", "down"
     pub block_interval_avg: f64,
@@ -100,21 +312,58 @@ pub struct SequencerState {
     pub last_update: DateTime<Utc>,
 }
 
+/// Execution wallet balances derived from beta_dataplane
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletState {
+    pub wallet_address: String,
+    pub native_symbol: String,
+    pub native_balance: f64,
+    /// Token balances by symbol, in whole units
+    pub token_balances: HashMap<String, f64>,
+    pub last_update: DateTime<Utc>,
+}
+
 impl MarketState {
     /// Create a new market state manager
     pub fn new(state_ttl_secs: i64) -> Self {
         Self {
-            amm_state: Arc::new(RwLock::new(HashMap::new())),
-            bridge_state: Arc::new(RwLock::new(HashMap::new())),
-            gas_state: Arc::new(RwLock::new(HashMap::new())),
-            flashloan_state: Arc::new(RwLock::new(HashMap::new())),
-            sequencer_state: Arc::new(RwLock::new(HashMap::new())),
+            shards: Default::default(),
             state_ttl: Duration::seconds(state_ttl_secs),
-            last_update: Arc::new(RwLock::new(HashMap::new())),
+            changes: broadcast::channel(CHANGE_CHANNEL_CAPACITY).0,
+            clock: Arc::new(AtomicI64::new(WALL_CLOCK)),
+            evm_state: Arc::new(EvmStateCache::new()),
+        }
+    }
+    
+    fn shard(&self, chain: Chain) -> &ChainShard {
+        &self.shards[shard_index(chain)]
+    }
+    
+    /// Pin the clock staleness is measured against
+    ///
+    /// Backtests advance it with recorded feature timestamps.
+    pub fn set_clock(&self, now: DateTime<Utc>) {
+        self.clock.store(now.timestamp_millis(), Ordering::Relaxed);
+    }
+    
+    /// Current time on the state clock
+    pub fn now(&self) -> DateTime<Utc> {
+        match self.clock.load(Ordering::Relaxed) {
+            WALL_CLOCK => Utc::now(),
+            millis => DateTime::from_timestamp_millis(millis).unwrap_or_else(Utc::now),
         }
     }
     
+    /// Subscribe to change notifications
+    pub fn subscribe(&self) -> broadcast::Receiver<MarketChange> {
+        self.changes.subscribe()
+    }
+    
     /// Ingest a feature from beta_dataplane
+    ///
+    /// The first feature of a new block on a chain seals the previous block
+    /// into that chain's `BlockView`. Late features for an older block land in
+    /// the open block.
     pub async fn ingest_feature(&self, feature: Feature) -> Result<()> {
         // Validate feature
         feature.validate().map_err(|e| {
@@ -132,72 +381,115 @@ impl MarketState {
         
         // Save metadata before moving feature.data
         let chain = feature.chain;
-        let type_name = feature.type_name().to_string();
+        let type_name = feature.type_name();
         let timestamp = feature.timestamp;
         
-        // Route to appropriate state handler
-        match feature.data {
-            FeatureData::Amm(amm_data) => {
-                self.update_amm_state(chain, amm_data, timestamp).await?;
-            }
-            FeatureData::Bridge(bridge_data) => {
-                self.update_bridge_state(chain, bridge_data, timestamp).await?;
-            }
-            FeatureData::Gas(gas_data) => {
-                self.update_gas_state(chain, gas_data, timestamp).await?;
-            }
-            FeatureData::FlashLoan(flashloan_data) => {
-                self.update_flashloan_state(chain, flashloan_data, timestamp).await?;
-            }
-            FeatureData::SequencerHealth(seq_data) => {
-                self.update_sequencer_state(chain, seq_data, timestamp).await?;
+        let block_number = feature.block_number;
+        let shard = self.shard(chain);
+        
+        let (key, magnitude_bps) = {
+            let mut open_block = shard.open_block.lock();
+            if block_number > *open_block {
+                if *open_block > 0 {
+                    shard.seal(chain, *open_block, self.now());
+                }
+                *open_block = block_number;
             }
-        }
+            
+            // Route to appropriate state handler
+            let change = match feature.data {
+                FeatureData::Amm(amm_data) => {
+                    self.update_amm_state(chain, *amm_data, timestamp)
+                }
+                FeatureData::Bridge(bridge_data) => {
+                    self.update_bridge_state(bridge_data, timestamp)
+                }
+                FeatureData::Gas(gas_data) => {
+                    self.update_gas_state(chain, gas_data, block_number, timestamp)
+                }
+                FeatureData::FlashLoan(flashloan_data) => {
+                    self.update_flashloan_state(chain, flashloan_data, timestamp)
+                }
+                FeatureData::SequencerHealth(seq_data) => {
+                    self.update_sequencer_state(chain, seq_data, timestamp)
+                }
+                FeatureData::WalletBalance(wallet_data) => {
+                    self.update_wallet_state(chain, wallet_data, timestamp)
+                }
+                FeatureData::Lending(lending_data) => {
+                    self.update_lending_state(chain, lending_data)
+                }
+                FeatureData::EvmState(evm_state_data) => {
+                    self.update_evm_state(chain, evm_state_data)
+                }
+            };
+            
+            // Update last update time
+            shard.last_update.insert(type_name, timestamp);
+            change
+        };
         
-        // Update last update time
-        let key = format!("{:?}_{}", chain, type_name);
-        let mut last_update = self.last_update.write().await;
-        last_update.insert(key, timestamp);
+        // No subscribers is not an error
+        let _ = self.changes.send(MarketChange {
+            chain,
+            block_number,
+            key,
+            magnitude_bps,
+            timestamp,
+        });
         
         Ok(())
     }
     
     /// Update AMM state
-    async fn update_amm_state(
+    fn update_amm_state(
         &self,
         chain: Chain,
         amm_data: qenus_dataplane::AmmFeature,
         timestamp: DateTime<Utc>,
-    ) -> Result<()> {
-        let mut amm_state = self.amm_state.write().await;
-        
+    ) -> (ChangeKey, Option<f64>) {
         let state = AmmState {
             pool_address: amm_data.pool_address.clone(),
             pool_type: amm_data.pool_type,
+            pool_id: amm_data.pool_id,
             token0_symbol: amm_data.token0.symbol,
             token1_symbol: amm_data.token1.symbol,
+            token0_address: amm_data.token0.address,
+            token1_address: amm_data.token1.address,
+            token0_decimals: amm_data.token0.decimals,
+            token1_decimals: amm_data.token1.decimals,
             mid_price: amm_data.mid_price,
             liquidity: amm_data.liquidity,
             fee_tier: amm_data.fee_tier,
             depth: amm_data.depth.sizes.into_iter().map(|(k, v)| {
                 (k, (v.slippage_bps, v.price_impact))
             }).collect(),
+            depth_curve: DepthCurve {
+                zero_for_one: amm_data.depth.zero_for_one,
+                one_for_zero: amm_data.depth.one_for_zero,
+                ..DepthCurve::default()
+            },
             last_update: timestamp,
         };
         
-        amm_state.insert((chain, amm_data.pool_address), state);
-        Ok(())
+        let key = ChangeKey::Pool {
+            address: state.pool_address.clone(),
+            token0: state.token0_symbol.clone(),
+            token1: state.token1_symbol.clone(),
+        };
+        let mid_price = state.mid_price;
+        let magnitude = self.shard(chain).pools.insert(amm_data.pool_address, Arc::new(state))
+            .map(|previous| change_bps(previous.mid_price, mid_price));
+        
+        (key, magnitude)
     }
     
     /// Update bridge state
-    async fn update_bridge_state(
+    fn update_bridge_state(
         &self,
-        chain: Chain,
         bridge_data: qenus_dataplane::BridgeFeature,
         timestamp: DateTime<Utc>,
-    ) -> Result<()> {
-        let mut bridge_state = self.bridge_state.write().await;
-        
+    ) -> (ChangeKey, Option<f64>) {
         let state = BridgeState {
             bridge_address: bridge_data.bridge_address,
             bridge_type: bridge_data.bridge_type,
@@ -209,20 +501,44 @@ impl MarketState {
             last_update: timestamp,
         };
         
-        let key = (bridge_data.source_chain, bridge_data.dest_chain);
-        bridge_state.entry(key).or_insert_with(Vec::new).push(state);
+        let change_key = ChangeKey::Bridge {
+            dest_chain: bridge_data.dest_chain,
+            token: state.token_symbol.clone(),
+        };
+        
+        let mut bridges = self.shard(bridge_data.source_chain).bridges
+            .entry(bridge_data.dest_chain)
+            .or_default();
+        let fee_bps = state.fee_bps;
+        let magnitude = put_bridge(&mut bridges, Arc::new(state))
+            .map(|previous| (fee_bps as f64 - previous.fee_bps as f64).abs());
         
-        Ok(())
+        (change_key, magnitude)
     }
     
     /// Update gas state
-    async fn update_gas_state(
+    fn update_gas_state(
         &self,
         chain: Chain,
         gas_data: qenus_dataplane::GasFeature,
+        block_number: u64,
         timestamp: DateTime<Utc>,
-    ) -> Result<()> {
-        let mut gas_state = self.gas_state.write().await;
+    ) -> (ChangeKey, Option<f64>) {
+        let mut gas_state = self.shard(chain).gas.write();
+        
+        // Carry history forward; replayed or duplicate blocks replace their sample
+        let mut history = gas_state.as_ref()
+            .map(|previous| previous.history.clone())
+            .unwrap_or_default();
+        history.retain(|sample| sample.block_number < block_number);
+        history.push(GasSample {
+            block_number,
+            base_fee: gas_data.base_fee,
+            gas_used_ratio: gas_data.gas_used_ratio,
+        });
+        if history.len() > GAS_HISTORY_LEN {
+            history.drain(..history.len() - GAS_HISTORY_LEN);
+        }
         
         let state = GasState {
             base_fee: gas_data.base_fee,
@@ -232,21 +548,23 @@ impl MarketState {
             gas_used_ratio: gas_data.gas_used_ratio,
             pending_tx_count: gas_data.pending_tx_count,
             last_update: timestamp,
+            history,
         };
         
-        gas_state.insert(chain, state);
-        Ok(())
+        let magnitude = gas_state.as_ref()
+            .map(|previous| change_bps(previous.base_fee, state.base_fee));
+        
+        *gas_state = Some(Arc::new(state));
+        (ChangeKey::Gas, magnitude)
     }
     
     /// Update flash loan state
-    async fn update_flashloan_state(
+    fn update_flashloan_state(
         &self,
         chain: Chain,
         flashloan_data: qenus_dataplane::FlashLoanFeature,
         timestamp: DateTime<Utc>,
-    ) -> Result<()> {
-        let mut flashloan_state = self.flashloan_state.write().await;
-        
+    ) -> (ChangeKey, Option<f64>) {
         let state = FlashLoanState {
             provider: flashloan_data.provider.clone(),
             provider_address: flashloan_data.provider_address,
@@ -258,19 +576,27 @@ impl MarketState {
             last_update: timestamp,
         };
         
-        flashloan_state.insert((chain, flashloan_data.provider), state);
-        Ok(())
+        let key = ChangeKey::FlashLoan {
+            provider: state.provider.clone(),
+            asset: state.asset_symbol.clone(),
+        };
+        let after = state.available_liquidity.parse::<f64>().unwrap_or(0.0);
+        let magnitude = self.shard(chain).flashloans.insert(flashloan_data.provider, Arc::new(state))
+            .map(|previous| {
+                let before = previous.available_liquidity.parse::<f64>().unwrap_or(0.0);
+                change_bps(before, after)
+            });
+        
+        (key, magnitude)
     }
     
     /// Update sequencer state
-    async fn update_sequencer_state(
+    fn update_sequencer_state(
         &self,
         chain: Chain,
         seq_data: qenus_dataplane::SequencerHealthFeature,
         timestamp: DateTime<Utc>,
-    ) -> Result<()> {
-        let mut sequencer_state = self.sequencer_state.write().await;
-        
+    ) -> (ChangeKey, Option<f64>) {
         let status = match seq_data.status {
             qenus_dataplane::SequencerStatus::Healthy => "healthy",
             qenus_dataplane::SequencerStatus::Degraded => "degraded",
@@ -286,36 +612,148 @@ impl MarketState {
             last_update: timestamp,
         };
         
-        sequencer_state.insert(chain, state);
-        Ok(())
+        // A status flip is a full-size change, heartbeats are not
+        let previous = self.shard(chain).sequencer.write().replace(Arc::new(state));
+        let magnitude = previous
+            .map(|previous| if previous.status == status { 0.0 } else { 10000.0 });
+        
+        (ChangeKey::Sequencer, magnitude)
     }
     
-    /// Get price for an asset on a specific chain (from AMM state)
-    pub async fn get_price(&self, chain: Chain, asset: &str) -> Option<f64> {
-        let amm_state = self.amm_state.read().await;
+    /// Update execution wallet balances
+    fn update_wallet_state(
+        &self,
+        chain: Chain,
+        wallet_data: qenus_dataplane::WalletBalanceFeature,
+        timestamp: DateTime<Utc>,
+    ) -> (ChangeKey, Option<f64>) {
+        let parse = |amount: &str| amount.parse::<f64>().unwrap_or(0.0);
+        let state = WalletState {
+            wallet_address: wallet_data.wallet_address.clone(),
+            native_symbol: wallet_data.native_symbol,
+            native_balance: parse(&wallet_data.native_balance),
+            token_balances: wallet_data.token_balances.iter()
+                .map(|balance| (balance.token.symbol.clone(), parse(&balance.balance)))
+                .collect(),
+            last_update: timestamp,
+        };
         
-        // Find any pool containing this asset and return mid price
-        for ((pool_chain, _), state) in amm_state.iter() {
-            if *pool_chain == chain {
-                if state.token0_symbol == asset || state.token1_symbol == asset {
-                    if !self.is_stale(&state.last_update) {
-                        return Some(state.mid_price);
-                    }
-                }
+        let native_balance = state.native_balance;
+        let magnitude = self.shard(chain).wallets.insert(wallet_data.wallet_address.to_lowercase(), Arc::new(state))
+            .map(|previous| change_bps(previous.native_balance, native_balance));
+        
+        (ChangeKey::Wallet { address: wallet_data.wallet_address }, magnitude)
+    }
+    
+    /// Append to the lending log
+    fn update_lending_state(
+        &self,
+        chain: Chain,
+        lending_data: LendingFeature,
+    ) -> (ChangeKey, Option<f64>) {
+        let mut log = self.shard(chain).lending.write();
+        
+        // Position events are full-size changes, otherwise the largest oracle
+        // move since the reserve was last read; reserves are not on every feature
+        let magnitude = (!log.entries.is_empty()).then(|| {
+            if !lending_data.events.is_empty() {
+                return 10000.0;
             }
+            lending_data.reserves.iter()
+                .filter_map(|reserve| {
+                    let before = log.entries.iter().rev()
+                        .find_map(|previous| previous.reserves.iter().find(|old| old.token.address == reserve.token.address))?
+                        .oracle_price_usd?;
+                    Some(change_bps(before, reserve.oracle_price_usd?))
+                })
+                .fold(0.0, f64::max)
+        });
+        
+        let key = ChangeKey::Lending { protocol: lending_data.protocol.clone() };
+        log.entries.push_back(Arc::new(lending_data));
+        if log.entries.len() > LENDING_LOG_LEN {
+            log.entries.pop_front();
+            log.first_seq += 1;
         }
         
-        None
+        (key, magnitude)
+    }
+    
+    /// Apply touched contract state to the EVM state cache
+    ///
+    /// Reported as a zero-size change: state diffs feed verification, they
+    /// do not trigger detection.
+    fn update_evm_state(
+        &self,
+        chain: Chain,
+        evm_state_data: EvmStateFeature,
+    ) -> (ChangeKey, Option<f64>) {
+        self.evm_state.apply(chain, &evm_state_data);
+        (ChangeKey::EvmState, Some(0.0))
+    }
+    
+    /// Lending features ingested on `chain` from sequence number `cursor` on
+    ///
+    /// Returns them with the cursor to pass next time. Entries that fell out
+    /// of the retained log are skipped, so fewer than `next - cursor` come back.
+    pub fn lending_since(&self, chain: Chain, cursor: u64) -> (Vec<Arc<LendingFeature>>, u64) {
+        let log = self.shard(chain).lending.read();
+        let next = log.first_seq + log.entries.len() as u64;
+        let skip = cursor.saturating_sub(log.first_seq) as usize;
+        (log.entries.iter().skip(skip).cloned().collect(), next)
+    }
+    
+    /// Contract state fed by the dataplane, for the intent verifier
+    pub fn evm_state(&self) -> Arc<EvmStateCache> {
+        self.evm_state.clone()
+    }
+    
+    /// View of `chain` as of its last sealed block
+    pub fn block_view(&self, chain: Chain) -> Option<Arc<BlockView>> {
+        self.shard(chain).view.read().clone()
+    }
+    
+    /// Mark `block_number` on `chain` complete and seal it
+    ///
+    /// For feeds that know when they have delivered a block's last feature,
+    /// e.g. a replay. Does nothing unless `block_number` is the block being
+    /// ingested, so a block is never sealed half-applied.
+    pub fn end_block(&self, chain: Chain, block_number: u64) {
+        let shard = self.shard(chain);
+        let open_block = shard.open_block.lock();
+        if block_number > 0 && *open_block == block_number {
+            shard.seal(chain, block_number, self.now());
+        }
+    }
+    
+    /// Get price for an asset on a specific chain (from AMM state)
+    pub async fn get_price(&self, chain: Chain, asset: &str) -> Option<f64> {
+        // Find any pool containing this asset and return mid price
+        self.shard(chain).pools.iter()
+            .find(|entry| {
+                let state = entry.value();
+                (state.token0_symbol == asset || state.token1_symbol == asset) && !self.is_stale(&state.last_update)
+            })
+            .map(|entry| entry.value().mid_price)
     }
     
     /// Get slippage for a trade size
     pub async fn get_slippage(&self, chain: Chain, pool_address: &str, size_usd: &str) -> Option<f64> {
-        let amm_state = self.amm_state.read().await;
+        let state = self.shard(chain).pools.get(pool_address).map(|entry| entry.value().clone())?;
         
-        if let Some(state) = amm_state.get(&(chain, pool_address.to_string())) {
-            if !self.is_stale(&state.last_update) {
-                return state.depth.get(size_usd).map(|(slippage_bps, _)| *slippage_bps);
-            }
+        if !self.is_stale(&state.last_update) {
+            return state.depth.get(size_usd).map(|(slippage_bps, _)| *slippage_bps);
+        }
+        
+        None
+    }
+    
+    /// Get slippage for selling `size_usd` of `asset_in` into a pool, from its depth curve
+    pub async fn get_swap_slippage(&self, chain: Chain, pool_address: &str, asset_in: &str, size_usd: f64) -> Option<f64> {
+        let state = self.shard(chain).pools.get(pool_address).map(|entry| entry.value().clone())?;
+        
+        if !self.is_stale(&state.last_update) {
+            return Some(swap_slippage_bps(&state, asset_in, size_usd));
         }
         
         None
@@ -323,9 +761,9 @@ impl MarketState {
     
     /// Get gas price for a chain
     pub async fn get_gas_price(&self, chain: Chain) -> Option<f64> {
-        let gas_state = self.gas_state.read().await;
+        let gas_state = self.shard(chain).gas.read();
         
-        if let Some(state) = gas_state.get(&chain) {
+        if let Some(state) = gas_state.as_ref() {
             if !self.is_stale(&state.last_update) {
                 return Some(state.fast_gas_price);
             }
@@ -334,42 +772,58 @@ impl MarketState {
         None
     }
     
+    /// Get priority fee (gwei) for a chain
+    pub async fn get_priority_fee(&self, chain: Chain) -> Option<f64> {
+        let gas_state = self.shard(chain).gas.read();
+
+        if let Some(state) = gas_state.as_ref() {
+            if !self.is_stale(&state.last_update) {
+                return Some(state.priority_fee);
+            }
+        }
+
+        None
+    }
+
     /// Get bridge fee between chains
     pub async fn get_bridge_fee(&self, from_chain: Chain, to_chain: Chain, _asset: &str) -> Option<u32> {
-        let bridge_state = self.bridge_state.read().await;
-        
-        if let Some(bridges) = bridge_state.get(&(from_chain, to_chain)) {
-            // Return the best (lowest) fee from active bridges
-            bridges.iter()
-                .filter(|b| b.is_active && !self.is_stale(&b.last_update))
-                .map(|b| b.fee_bps)
-                .min()
-        } else {
-            None
-        }
+        let bridges = self.shard(from_chain).bridges.get(&to_chain)?;
+        
+        // Return the best (lowest) fee from active bridges
+        bridges.iter()
+            .filter(|b| b.is_active && !self.is_stale(&b.last_update))
+            .map(|b| b.fee_bps)
+            .min()
     }
     
     /// Get flash loan liquidity
     pub async fn get_flashloan_liquidity(&self, chain: Chain, asset: &str) -> Option<String> {
-        let flashloan_state = self.flashloan_state.read().await;
-        
         // Find any active provider with this asset
-        for ((fl_chain, _), state) in flashloan_state.iter() {
-            if *fl_chain == chain && state.asset_symbol == asset {
-                if state.is_active && !self.is_stale(&state.last_update) {
-                    return Some(state.available_liquidity.clone());
-                }
-            }
-        }
-        
-        None
+        self.shard(chain).flashloans.iter()
+            .find(|entry| {
+                let state = entry.value();
+                state.asset_symbol == asset && state.is_active && !self.is_stale(&state.last_update)
+            })
+            .map(|entry| entry.value().available_liquidity.clone())
+    }
+    
+    /// Active, fresh flash loan providers lending `asset` on `chain`
+    pub async fn get_flashloan_providers(&self, chain: Chain, asset: &str) -> Vec<FlashLoanState> {
+        let providers: Vec<Arc<FlashLoanState>> = self.shard(chain).flashloans.iter()
+            .filter(|entry| {
+                let state = entry.value();
+                state.asset_symbol == asset && state.is_active && !self.is_stale(&state.last_update)
+            })
+            .map(|entry| entry.value().clone())
+            .collect();
+        providers.iter().map(|state| state.as_ref().clone()).collect()
     }
     
     /// Check if sequencer is healthy
     pub async fn is_sequencer_healthy(&self, chain: Chain) -> bool {
-        let sequencer_state = self.sequencer_state.read().await;
+        let sequencer_state = self.shard(chain).sequencer.read();
         
-        if let Some(state) = sequencer_state.get(&chain) {
+        if let Some(state) = sequencer_state.as_ref() {
             if !self.is_stale(&state.last_update) {
                 return state.status == "healthy";
             }
@@ -381,41 +835,79 @@ impl MarketState {
     
     /// Get all AMM pools for a chain
     pub async fn get_amm_pools(&self, chain: Chain) -> Vec<AmmState> {
-        let amm_state = self.amm_state.read().await;
-        
-        amm_state.iter()
-            .filter(|((pool_chain, _), state)| {
-                *pool_chain == chain && !self.is_stale(&state.last_update)
-            })
-            .map(|(_, state)| state.clone())
-            .collect()
+        // Copy the pointers under the shard locks, the pools outside them
+        let pools: Vec<Arc<AmmState>> = self.shard(chain).pools.iter()
+            .filter(|entry| !self.is_stale(&entry.value().last_update))
+            .map(|entry| entry.value().clone())
+            .collect();
+        pools.iter().map(|state| state.as_ref().clone()).collect()
     }
     
     /// Get all bridges between two chains
     pub async fn get_bridges(&self, from_chain: Chain, to_chain: Chain) -> Vec<BridgeState> {
-        let bridge_state = self.bridge_state.read().await;
-        
-        if let Some(bridges) = bridge_state.get(&(from_chain, to_chain)) {
-            bridges.iter()
+        let bridges: Vec<Arc<BridgeState>> = match self.shard(from_chain).bridges.get(&to_chain) {
+            Some(bridges) => bridges.iter()
                 .filter(|b| !self.is_stale(&b.last_update))
                 .cloned()
-                .collect()
-        } else {
-            Vec::new()
-        }
+                .collect(),
+            None => Vec::new(),
+        };
+        bridges.iter().map(|bridge| bridge.as_ref().clone()).collect()
+    }
+    
+    /// Latest gas state for a chain, even if stale
+    pub async fn get_gas_state(&self, chain: Chain) -> Option<GasState> {
+        let state = self.shard(chain).gas.read().clone();
+        state.map(|state| state.as_ref().clone())
+    }
+    
+    /// Balance of `asset` held across our wallets on a chain, in whole units
+    ///
+    /// None when no wallet on the chain has reported recently.
+    pub async fn get_wallet_balance(&self, chain: Chain, asset: &str) -> Option<f64> {
+        let wallet_state = &self.shard(chain).wallets;
+        
+        let mut wallets = wallet_state.iter()
+            .filter(|entry| !self.is_stale(&entry.value().last_update))
+            .peekable();
+        wallets.peek()?;
+        
+        Some(wallets
+            .map(|entry| entry.value().token_balances.get(asset).copied().unwrap_or(0.0))
+            .sum())
+    }
+    
+    /// USD value of `asset` our wallets can deploy on a chain
+    ///
+    /// Native balances are kept for gas and never count as deployable.
+    pub async fn deployable_capital_usd(&self, chain: Chain, asset: &str) -> Option<f64> {
+        let balance = self.get_wallet_balance(chain, asset).await?;
+        let price = usd_price(&self.get_amm_pools(chain).await, asset)?;
+        Some(balance * price)
+    }
+    
+    /// Latest sequencer state for a chain, even if stale
+    pub async fn get_sequencer_state(&self, chain: Chain) -> Option<SequencerState> {
+        let state = self.shard(chain).sequencer.read().clone();
+        state.map(|state| state.as_ref().clone())
+    }
+    
+    /// Oldest update time still considered fresh
+    ///
+    /// Pass to the `BlockView` accessors to filter a view like the getters do.
+    pub fn fresh_since(&self) -> DateTime<Utc> {
+        self.now() - self.state_ttl
     }
     
     /// Check if state is stale
     fn is_stale(&self, last_update: &DateTime<Utc>) -> bool {
-        Utc::now() - *last_update > self.state_ttl
+        *last_update < self.fresh_since()
     }
     
     /// Check if feed is stale for a specific chain/type
     pub async fn is_feed_stale(&self, chain: Chain, feature_type: &str) -> bool {
-        let last_update = self.last_update.read().await;
-        
-        if let Some(timestamp) = last_update.get(&format!("{:?}_{}", chain, feature_type)) {
-            self.is_stale(timestamp)
+        if let Some(timestamp) = self.shard(chain).last_update.get(feature_type) {
+            self.is_stale(&timestamp)
         } else {
             true // No data yet = stale
         }
@@ -423,22 +915,128 @@ impl MarketState {
     
     /// Get state statistics for monitoring
     pub async fn get_stats(&self) -> MarketStateStats {
-        let amm_state = self.amm_state.read().await;
-        let bridge_state = self.bridge_state.read().await;
-        let gas_state = self.gas_state.read().await;
-        let flashloan_state = self.flashloan_state.read().await;
-        let sequencer_state = self.sequencer_state.read().await;
-        
-        MarketStateStats {
-            total_amm_pools: amm_state.len(),
-            total_bridges: bridge_state.values().map(|v| v.len()).sum(),
-            total_gas_states: gas_state.len(),
-            total_flashloan_providers: flashloan_state.len(),
-            total_sequencers: sequencer_state.len(),
+        let mut stats = MarketStateStats {
+            total_amm_pools: 0,
+            total_bridges: 0,
+            total_gas_states: 0,
+            total_flashloan_providers: 0,
+            total_sequencers: 0,
+        };
+        for shard in &self.shards {
+            stats.total_amm_pools += shard.pools.len();
+            stats.total_bridges += shard.bridges.iter().map(|entry| entry.value().len()).sum::<usize>();
+            stats.total_gas_states += shard.gas.read().is_some() as usize;
+            stats.total_flashloan_providers += shard.flashloans.len();
+            stats.total_sequencers += shard.sequencer.read().is_some() as usize;
+        }
+        stats
+    }
+    
+    /// Last update and staleness of every feed seen so far
+    pub async fn feed_status(&self) -> Vec<FeedStatus> {
+        let now = self.now();
+        
+        let mut feeds: Vec<FeedStatus> = CHAINS.iter()
+            .flat_map(|chain| {
+                self.shard(*chain).last_update.iter()
+                    .map(|entry| {
+                        let timestamp = *entry.value();
+                        FeedStatus {
+                            chain: format!("{:?}", chain),
+                            feature_type: entry.key().to_string(),
+                            last_update: timestamp,
+                            age_secs: (now - timestamp).num_milliseconds() as f64 / 1000.0,
+                            stale: self.is_stale(&timestamp),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        feeds.sort_by(|a, b| (&a.chain, &a.feature_type).cmp(&(&b.chain, &b.feature_type)));
+        feeds
+    }
+    
+    /// Load the state captured in a snapshot, pinning the clock to when it was taken
+    pub async fn restore(&self, snapshot: &MarketStateSnapshot) {
+        self.set_clock(snapshot.taken_at);
+        
+        for (chain, pool) in &snapshot.amm_pools {
+            self.shard(*chain).pools.insert(pool.pool_address.clone(), Arc::new(pool.clone()));
+        }
+        for (from, to, bridge) in &snapshot.bridges {
+            put_bridge(&mut self.shard(*from).bridges.entry(*to).or_default(), Arc::new(bridge.clone()));
+        }
+        for (chain, gas) in &snapshot.gas {
+            *self.shard(*chain).gas.write() = Some(Arc::new(gas.clone()));
+        }
+        for (chain, provider) in &snapshot.flashloans {
+            self.shard(*chain).flashloans.insert(provider.provider.clone(), Arc::new(provider.clone()));
+        }
+        for (chain, sequencer) in &snapshot.sequencers {
+            *self.shard(*chain).sequencer.write() = Some(Arc::new(sequencer.clone()));
+        }
+        for (chain, wallet) in &snapshot.wallets {
+            self.shard(*chain).wallets.insert(wallet.wallet_address.to_lowercase(), Arc::new(wallet.clone()));
+        }
+    }
+    
+    /// Copy of the full state, including stale entries
+    pub async fn snapshot(&self) -> MarketStateSnapshot {
+        let mut snapshot = MarketStateSnapshot {
+            taken_at: self.now(),
+            amm_pools: Vec::new(),
+            bridges: Vec::new(),
+            gas: Vec::new(),
+            flashloans: Vec::new(),
+            sequencers: Vec::new(),
+            wallets: Vec::new(),
+            stats: self.get_stats().await,
+        };
+        for chain in CHAINS {
+            let shard = self.shard(chain);
+            snapshot.amm_pools.extend(shard.pools.iter().map(|entry| (chain, entry.value().as_ref().clone())));
+            for entry in shard.bridges.iter() {
+                snapshot.bridges.extend(entry.value().iter().map(|b| (chain, *entry.key(), b.as_ref().clone())));
+            }
+            snapshot.gas.extend(shard.gas.read().as_ref().map(|state| (chain, state.as_ref().clone())));
+            snapshot.flashloans.extend(shard.flashloans.iter().map(|entry| (chain, entry.value().as_ref().clone())));
+            snapshot.sequencers.extend(shard.sequencer.read().as_ref().map(|state| (chain, state.as_ref().clone())));
+            snapshot.wallets.extend(shard.wallets.iter().map(|entry| (chain, entry.value().as_ref().clone())));
+        }
+        snapshot
+    }
+}
+
+/// Position of a chain's shard in `MarketState::shards`
+fn shard_index(chain: Chain) -> usize {
+    match chain {
+        Chain::Ethereum => 0,
+        Chain::Arbitrum => 1,
+        Chain::Optimism => 2,
+        Chain::Base => 3,
+    }
+}
+
+/// Replace the entry for the same bridge and token, returning it, or append
+fn put_bridge(bridges: &mut Vec<Arc<BridgeState>>, state: Arc<BridgeState>) -> Option<Arc<BridgeState>> {
+    match bridges.iter_mut().find(|b| b.bridge_address == state.bridge_address && b.token_symbol == state.token_symbol) {
+        Some(entry) => Some(std::mem::replace(entry, state)),
+        None => {
+            bridges.push(state);
+            None
         }
     }
 }
 
+/// Relative change between two values in bps
+fn change_bps(before: f64, after: f64) -> f64 {
+    if before == 0.0 {
+        if after == 0.0 { 0.0 } else { 10000.0 }
+    } else {
+        ((after - before) / before * 10000.0).abs()
+    }
+}
+
 impl Default for MarketState {
     fn default() -> Self {
         Self::new(30) // 30 seconds default TTL
@@ -446,7 +1044,7 @@ impl Default for MarketState {
 }
 
 /// Statistics about the market state
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketStateStats {
     pub total_amm_pools: usize,
     pub total_bridges: usize,
@@ -454,3 +1052,186 @@ pub struct MarketStateStats {
     pub total_flashloan_providers: usize,
     pub total_sequencers: usize,
 }
+
+/// Freshness of one chain/feature-type feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedStatus {
+    pub chain: String,
+    pub feature_type: String,
+    pub last_update: DateTime<Utc>,
+    pub age_secs: f64,
+    pub stale: bool,
+}
+
+/// Point-in-time copy of the market state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketStateSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub amm_pools: Vec<(Chain, AmmState)>,
+    pub bridges: Vec<(Chain, Chain, BridgeState)>,
+    pub gas: Vec<(Chain, GasState)>,
+    pub flashloans: Vec<(Chain, FlashLoanState)>,
+    pub sequencers: Vec<(Chain, SequencerState)>,
+    #[serde(default)]
+    pub wallets: Vec<(Chain, WalletState)>,
+    pub stats: MarketStateStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qenus_dataplane::{AmmFeature, FeatureType, TokenInfo};
+
+    fn pool(block_number: u64, mid_price: f64) -> Feature {
+        Feature::new(
+            block_number,
+            Chain::Ethereum,
+            FeatureType::Amm,
+            FeatureData::Amm(Box::new(AmmFeature {
+                pool_address: "0xpool".to_string(),
+                pool_type: "uniswap_v3".to_string(),
+                pool_id: None,
+                token0: TokenInfo { address: "0xweth".to_string(), symbol: "WETH".to_string(), decimals: 18 },
+                token1: TokenInfo { address: "0xusdc".to_string(), symbol: "USDC".to_string(), decimals: 6 },
+                fee_tier: Some(500),
+                reserves: [("WETH".to_string(), "1000.0".to_string())].into_iter().collect(),
+                mid_price,
+                liquidity: "1000000".to_string(),
+                depth: DepthCurve::default(),
+                volume_24h: None,
+                fees_24h: None,
+            })),
+            "test".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_slippage_by_size_label() {
+        let state = MarketState::new(30);
+        let mut feature = pool(1, 2000.0);
+        if let FeatureData::Amm(amm) = &mut feature.data {
+            amm.depth.sizes.insert("1m".to_string(), qenus_dataplane::SlippageInfo { slippage_bps: 15.0, price_impact: 0.0015 });
+        }
+        state.ingest_feature(feature).await.unwrap();
+
+        assert_eq!(state.get_slippage(Chain::Ethereum, "0xpool", "1m").await, Some(15.0));
+        assert_eq!(state.get_slippage(Chain::Ethereum, "0xpool", "10m").await, None);
+        assert!(state.get_swap_slippage(Chain::Ethereum, "0xpool", "WETH", 1_000_000.0).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_block_view_seals_whole_blocks() {
+        let state = MarketState::new(30);
+        state.ingest_feature(pool(1, 2000.0)).await.unwrap();
+        assert!(state.block_view(Chain::Ethereum).is_none());
+
+        // The first feature of block 2 seals block 1; the view keeps its price
+        state.ingest_feature(pool(2, 2100.0)).await.unwrap();
+        let view = state.block_view(Chain::Ethereum).unwrap();
+        assert_eq!(view.block_number, 1);
+        assert_eq!(view.pool("0xpool").unwrap().mid_price, 2000.0);
+        assert_eq!(state.get_price(Chain::Ethereum, "WETH").await, Some(2100.0));
+        assert!(state.block_view(Chain::Arbitrum).is_none());
+
+        // Marking another block complete does not seal the open one
+        state.end_block(Chain::Ethereum, 1);
+        assert_eq!(state.block_view(Chain::Ethereum).unwrap().block_number, 1);
+        state.end_block(Chain::Ethereum, 2);
+        let view = state.block_view(Chain::Ethereum).unwrap();
+        assert_eq!(view.block_number, 2);
+        assert_eq!(view.pools_trading("USDC").map(|pool| pool.mid_price).collect::<Vec<_>>(), vec![2100.0]);
+        assert_eq!(view.price("WETH", state.fresh_since()), Some(2100.0));
+        assert_eq!(state.get_stats().await.total_amm_pools, 1);
+
+        // Views keep stale entries; readers filter them the way the getters do
+        state.set_clock(Utc::now() + Duration::seconds(60));
+        assert_eq!(view.price("WETH", state.fresh_since()), None);
+        assert_eq!(state.get_price(Chain::Ethereum, "WETH").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_updates_replace_bridges_and_wallets() {
+        use qenus_dataplane::{BridgeFeature, TokenBalance, WalletBalanceFeature};
+
+        let usdc = TokenInfo { address: "0xusdc".to_string(), symbol: "USDC".to_string(), decimals: 6 };
+        let bridge = |block_number: u64, fee_bps: u32| Feature::new(
+            block_number,
+            Chain::Arbitrum,
+            FeatureType::Bridge,
+            FeatureData::Bridge(BridgeFeature {
+                bridge_address: "0xbridge".to_string(),
+                bridge_type: "canonical".to_string(),
+                source_chain: Chain::Arbitrum,
+                dest_chain: Chain::Ethereum,
+                token: usdc.clone(),
+                liquidity: "1000000".to_string(),
+                fee_bps,
+                settlement_time_estimate: 600,
+                is_active: true,
+            }),
+            "test".to_string(),
+        );
+        let wallet = |address: &str, balance: &str| Feature::new(
+            1,
+            Chain::Ethereum,
+            FeatureType::WalletBalance,
+            FeatureData::WalletBalance(WalletBalanceFeature {
+                wallet_address: address.to_string(),
+                native_symbol: "ETH".to_string(),
+                native_balance: "1.0".to_string(),
+                token_balances: vec![TokenBalance { token: usdc.clone(), balance: balance.to_string() }],
+            }),
+            "test".to_string(),
+        );
+
+        let state = MarketState::new(30);
+        for (block_number, fee_bps) in [(1, 10), (2, 8), (3, 12)] {
+            state.ingest_feature(bridge(block_number, fee_bps)).await.unwrap();
+        }
+        state.end_block(Chain::Arbitrum, 3);
+        assert_eq!(state.get_stats().await.total_bridges, 1);
+        assert_eq!(state.get_bridge_fee(Chain::Arbitrum, Chain::Ethereum, "USDC").await, Some(12));
+        assert_eq!(state.block_view(Chain::Arbitrum).unwrap().bridges.len(), 1);
+
+        // A restored checksummed wallet is the same wallet as the lowercased feed
+        state.ingest_feature(wallet("0xAbC", "100")).await.unwrap();
+        let restored = MarketState::new(30);
+        restored.restore(&state.snapshot().await).await;
+        restored.ingest_feature(wallet("0xAbC", "250")).await.unwrap();
+        assert_eq!(restored.get_wallet_balance(Chain::Ethereum, "USDC").await, Some(250.0));
+        assert_eq!(restored.get_stats().await.total_bridges, 1);
+    }
+
+    #[tokio::test]
+    async fn test_lending_oracle_move_spans_features_without_reserves() {
+        use qenus_dataplane::{LendingFeature, LendingReserve};
+
+        let lending = |block_number: u64, price: Option<f64>| Feature::new(
+            block_number,
+            Chain::Ethereum,
+            FeatureType::Lending,
+            FeatureData::Lending(LendingFeature {
+                protocol: "aave_v3".to_string(),
+                pool_address: "0xpool".to_string(),
+                events: Vec::new(),
+                reserves: price.map(|price| LendingReserve {
+                    token: TokenInfo { address: "0xweth".to_string(), symbol: "WETH".to_string(), decimals: 18 },
+                    configuration: "0x0".to_string(),
+                    oracle_price_usd: Some(price),
+                }).into_iter().collect(),
+            }),
+            "test".to_string(),
+        );
+
+        let state = MarketState::new(30);
+        let mut changes = state.subscribe();
+        // Block 2 carries no reserves; block 3 is measured against block 1
+        for (block_number, price) in [(1, Some(2000.0)), (2, None), (3, Some(2100.0))] {
+            state.ingest_feature(lending(block_number, price)).await.unwrap();
+        }
+
+        let magnitudes: Vec<_> = (0..3).map(|_| changes.try_recv().unwrap().magnitude_bps).collect();
+        assert_eq!(magnitudes[..2], [None, Some(0.0)]);
+        assert!((magnitudes[2].unwrap() - 500.0).abs() < 1e-9);
+    }
+}
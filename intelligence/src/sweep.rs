@@ -0,0 +1,587 @@
+//! Parameter sweeps and walk-forward optimization
+//!
+//! Backtests a grid or random sample of `StrategyConfig` parameters over
+//! recorded features, in parallel. With walk-forward folds, each fold picks
+//! the parameter set that did best on its train window and scores that set on
+//! the test window after it. Those test scores form the walk-forward result:
+//! an out-of-sample estimate of the selection itself, rather than of a set
+//! chosen with hindsight. Results are ranked, and the best config chosen, by
+//! in-sample score.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use qenus_dataplane::Feature;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::info;
+
+use crate::backtest::{BacktestReport, Backtester};
+use crate::config::IntelligenceConfig;
+use crate::error::{IntelligenceError, Result};
+use crate::types::StrategyConfig;
+
+/// Grid points generated for a range without explicit steps
+const DEFAULT_GRID_STEPS: usize = 5;
+
+/// Tunable `StrategyConfig` parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepParam {
+    MinProfitUsd,
+    MinProfitBps,
+    MaxPositionUsd,
+    MaxSlippageBps,
+    MaxGasPct,
+    MaxBridgeLatencySecs,
+    MinSuccessProb,
+    MaxRevertProb,
+}
+
+impl SweepParam {
+    /// Set this parameter on a strategy
+    pub fn apply(&self, strategy: &mut StrategyConfig, value: f64) {
+        let limits = &mut strategy.risk_limits;
+        match self {
+            SweepParam::MinProfitUsd => strategy.min_profit_usd = value,
+            SweepParam::MinProfitBps => strategy.min_profit_bps = value,
+            SweepParam::MaxPositionUsd => strategy.max_position_usd = value,
+            SweepParam::MaxSlippageBps => limits.max_slippage_bps = value,
+            SweepParam::MaxGasPct => limits.max_gas_pct = value,
+            SweepParam::MaxBridgeLatencySecs => limits.max_bridge_latency_secs = value.max(0.0).round() as u64,
+            SweepParam::MinSuccessProb => limits.min_success_prob = value,
+            SweepParam::MaxRevertProb => limits.max_revert_prob = value,
+        }
+    }
+
+    fn name(&self) -> String {
+        serde_json::to_value(self).ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+}
+
+/// Values a parameter is searched over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParamRange {
+    /// Explicit values
+    Values { values: Vec<f64> },
+
+    /// Evenly spaced grid points, or uniform draws in random search
+    Range { min: f64, max: f64, steps: Option<usize> },
+}
+
+impl ParamRange {
+    fn grid(&self) -> Vec<f64> {
+        match self {
+            ParamRange::Values { values } => values.clone(),
+            ParamRange::Range { min, max, steps } => {
+                let steps = steps.unwrap_or(DEFAULT_GRID_STEPS).max(1);
+                if steps == 1 {
+                    return vec![*min];
+                }
+                (0..steps).map(|i| min + (max - min) * i as f64 / (steps - 1) as f64).collect()
+            }
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> f64 {
+        match self {
+            ParamRange::Values { values } => values[rng.gen_range(0..values.len())],
+            ParamRange::Range { min, max, .. } if max > min => rng.gen_range(*min..=*max),
+            ParamRange::Range { min, .. } => *min,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, ParamRange::Values { values } if values.is_empty())
+    }
+}
+
+/// How parameter sets are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Every combination of grid values
+    #[default]
+    Grid,
+
+    /// `samples` independent draws
+    Random,
+}
+
+/// What a parameter set is ranked by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    /// Realized PnL
+    #[default]
+    TotalPnl,
+
+    /// Realized PnL per dollar of max drawdown
+    PnlOverDrawdown,
+
+    /// Share of winning fills
+    HitRate,
+}
+
+impl Objective {
+    fn score(&self, report: &BacktestReport) -> f64 {
+        match self {
+            Objective::TotalPnl => report.total_pnl_usd,
+            Objective::PnlOverDrawdown => report.total_pnl_usd / (1.0 + report.max_drawdown_usd),
+            Objective::HitRate => report.hit_rate,
+        }
+    }
+}
+
+/// Sweep definition, read from YAML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepSpec {
+    /// Strategy whose parameters are searched; it is the only one enabled
+    pub strategy: String,
+
+    #[serde(default)]
+    pub search: SearchMode,
+
+    /// Parameter sets drawn in random search
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+
+    #[serde(default)]
+    pub seed: u64,
+
+    #[serde(default)]
+    pub objective: Objective,
+
+    /// Walk-forward folds (0 = score on the whole range)
+    #[serde(default)]
+    pub folds: usize,
+
+    /// Train on everything before the test window instead of the previous window only
+    #[serde(default)]
+    pub anchored: bool,
+
+    /// Concurrent backtests (default: available cores)
+    #[serde(default)]
+    pub parallelism: Option<usize>,
+
+    pub params: BTreeMap<SweepParam, ParamRange>,
+}
+
+fn default_samples() -> usize {
+    50
+}
+
+impl SweepSpec {
+    /// Load a sweep spec from a YAML file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| IntelligenceError::internal(format!("Invalid sweep spec: {}", e)))
+    }
+
+    /// Parameter sets to evaluate
+    pub fn parameter_sets(&self) -> Vec<BTreeMap<SweepParam, f64>> {
+        if self.params.values().any(ParamRange::is_empty) {
+            return Vec::new();
+        }
+
+        match self.search {
+            SearchMode::Grid => {
+                let mut sets = vec![BTreeMap::new()];
+                for (param, range) in &self.params {
+                    sets = sets.into_iter()
+                        .flat_map(|set| {
+                            range.grid().into_iter().map(move |value| {
+                                let mut set = set.clone();
+                                set.insert(*param, value);
+                                set
+                            })
+                        })
+                        .collect();
+                }
+                sets
+            }
+            SearchMode::Random => {
+                let mut rng = StdRng::seed_from_u64(self.seed);
+                (0..self.samples)
+                    .map(|_| self.params.iter().map(|(param, range)| (*param, range.sample(&mut rng))).collect())
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Train and test windows of one walk-forward fold
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Fold {
+    pub train: (DateTime<Utc>, DateTime<Utc>),
+    pub test: (DateTime<Utc>, DateTime<Utc>),
+}
+
+/// Split `[start, end)` into `folds + 1` equal windows; fold i tests on window i + 1
+pub fn walk_forward_folds(start: DateTime<Utc>, end: DateTime<Utc>, folds: usize, anchored: bool) -> Vec<Fold> {
+    let window = (end - start) / (folds as i32 + 1);
+    let bound = |i: usize| start + window * i as i32;
+
+    (0..folds)
+        .map(|i| Fold {
+            train: (if anchored { start } else { bound(i) }, bound(i + 1)),
+            test: (bound(i + 1), if i + 1 == folds { end } else { bound(i + 2) }),
+        })
+        .collect()
+}
+
+/// Score of one parameter set on one window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowScore {
+    pub score: f64,
+    pub pnl_usd: f64,
+    pub max_drawdown_usd: f64,
+    pub fills: usize,
+}
+
+impl WindowScore {
+    fn from_report(objective: Objective, report: &BacktestReport) -> Self {
+        Self {
+            score: objective.score(report),
+            pnl_usd: report.total_pnl_usd,
+            max_drawdown_usd: report.max_drawdown_usd,
+            fills: report.fills.len(),
+        }
+    }
+}
+
+/// Result of one parameter set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepResult {
+    pub params: BTreeMap<SweepParam, f64>,
+
+    /// Mean out-of-sample score (whole-range score without folds); a large
+    /// gap to `in_sample_score` signals overfitting
+    pub score: f64,
+
+    /// Mean in-sample score (whole-range score without folds); the ranking key
+    pub in_sample_score: f64,
+
+    /// Out-of-sample PnL summed over test windows
+    pub pnl_usd: f64,
+    pub max_drawdown_usd: f64,
+    pub fills: usize,
+
+    /// (train, test) score per fold
+    pub folds: Vec<(WindowScore, WindowScore)>,
+}
+
+/// Parameter set picked on one fold's train window, with its scores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoldSelection {
+    pub fold: Fold,
+    pub params: BTreeMap<SweepParam, f64>,
+    pub train: WindowScore,
+    pub test: WindowScore,
+}
+
+/// Out-of-sample result of picking the best train-window set on every fold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardResult {
+    pub folds: Vec<FoldSelection>,
+
+    /// Mean test score of the picked sets
+    pub score: f64,
+
+    /// Test-window PnL of the picked sets, summed over folds
+    pub pnl_usd: f64,
+    pub max_drawdown_usd: f64,
+    pub fills: usize,
+}
+
+impl WalkForwardResult {
+    /// Pick the best set on each fold's train window; None without folds
+    fn select(folds: &[Fold], results: &[SweepResult]) -> Option<Self> {
+        let selections: Vec<FoldSelection> = folds.iter()
+            .enumerate()
+            .filter_map(|(i, fold)| {
+                let (result, (train, test)) = results.iter()
+                    .filter_map(|result| result.folds.get(i).map(|scores| (result, scores)))
+                    .max_by(|a, b| a.1.0.score.partial_cmp(&b.1.0.score).unwrap_or(std::cmp::Ordering::Equal))?;
+                Some(FoldSelection { fold: *fold, params: result.params.clone(), train: train.clone(), test: test.clone() })
+            })
+            .collect();
+        if selections.is_empty() {
+            return None;
+        }
+
+        let n = selections.len() as f64;
+        Some(Self {
+            score: selections.iter().map(|selection| selection.test.score).sum::<f64>() / n,
+            pnl_usd: selections.iter().map(|selection| selection.test.pnl_usd).sum(),
+            max_drawdown_usd: selections.iter().map(|selection| selection.test.max_drawdown_usd).fold(0.0, f64::max),
+            fills: selections.iter().map(|selection| selection.test.fills).sum(),
+            folds: selections,
+        })
+    }
+}
+
+/// Ranked sweep results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepReport {
+    pub spec: SweepSpec,
+    pub folds: Vec<Fold>,
+
+    /// Best in-sample first
+    pub results: Vec<SweepResult>,
+
+    /// Out-of-sample result of selecting on train windows; None without folds
+    #[serde(default)]
+    pub walk_forward: Option<WalkForwardResult>,
+}
+
+impl SweepReport {
+    /// Base config with the swept strategy set to the best in-sample parameters
+    pub fn best_config(&self, base: &IntelligenceConfig) -> Option<IntelligenceConfig> {
+        let best = self.results.first()?;
+        let mut config = base.clone();
+        let strategy = config.strategies.get_mut(&self.spec.strategy)?;
+        strategy.enabled = true;
+        for (param, value) in &best.params {
+            param.apply(strategy, *value);
+        }
+        Some(config)
+    }
+
+    /// Write results.json, ranked.csv and best_config.yaml into a new run directory under `dir`
+    pub async fn write(&self, dir: impl AsRef<Path>, base: &IntelligenceConfig) -> Result<PathBuf> {
+        let run_dir = dir.as_ref().join(format!("sweep-{}", Utc::now().format("%Y%m%dT%H%M%SZ")));
+        tokio::fs::create_dir_all(&run_dir).await?;
+
+        tokio::fs::write(run_dir.join("results.json"), serde_json::to_vec_pretty(self)?).await?;
+        tokio::fs::write(run_dir.join("ranked.csv"), self.ranked_csv()).await?;
+        if let Some(config) = self.best_config(base) {
+            config.save_to_file(run_dir.join("best_config.yaml"))?;
+        }
+
+        Ok(run_dir)
+    }
+
+    fn ranked_csv(&self) -> String {
+        let params: Vec<SweepParam> = self.spec.params.keys().copied().collect();
+        let mut csv = String::from("rank,");
+        for param in &params {
+            csv.push_str(&param.name());
+            csv.push(',');
+        }
+        csv.push_str("score,in_sample_score,pnl_usd,max_drawdown_usd,fills\n");
+
+        for (rank, result) in self.results.iter().enumerate() {
+            csv.push_str(&format!("{},", rank + 1));
+            for param in &params {
+                csv.push_str(&format!("{},", result.params.get(param).copied().unwrap_or_default()));
+            }
+            csv.push_str(&format!(
+                "{:.6},{:.6},{:.6},{:.6},{}\n",
+                result.score, result.in_sample_score, result.pnl_usd, result.max_drawdown_usd, result.fills
+            ));
+        }
+        csv
+    }
+}
+
+/// Runs a sweep of backtests over one recorded feature set
+pub struct SweepRunner {
+    base: IntelligenceConfig,
+    features: Arc<Vec<Feature>>,
+}
+
+impl SweepRunner {
+    /// Features must be sorted oldest first
+    pub fn new(base: IntelligenceConfig, features: Vec<Feature>) -> Self {
+        Self { base, features: Arc::new(features) }
+    }
+
+    pub async fn run(&self, spec: SweepSpec) -> Result<SweepReport> {
+        if !self.base.strategies.contains_key(&spec.strategy) {
+            return Err(IntelligenceError::InvalidStrategy(spec.strategy.clone()));
+        }
+        let (Some(start), Some(end)) = (self.features.first(), self.features.last()) else {
+            return Err(IntelligenceError::internal("No recorded features to sweep over"));
+        };
+        let (start, end) = (start.timestamp, end.timestamp);
+
+        let folds = walk_forward_folds(start, end, spec.folds, spec.anchored);
+        let windows: Vec<(DateTime<Utc>, DateTime<Utc>)> = if folds.is_empty() {
+            vec![(start, end)]
+        } else {
+            folds.iter().flat_map(|fold| [fold.train, fold.test]).collect()
+        };
+
+        let parameter_sets = spec.parameter_sets();
+        let parallelism = spec.parallelism
+            .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+            .max(1);
+        info!(
+            "Sweeping {} parameter sets of {} over {} windows ({} at a time)",
+            parameter_sets.len(), spec.strategy, windows.len(), parallelism
+        );
+
+        let permits = Arc::new(Semaphore::new(parallelism));
+        let mut jobs = JoinSet::new();
+        for (set_index, params) in parameter_sets.iter().enumerate() {
+            for (window_index, window) in windows.iter().enumerate() {
+                let config = self.config_for(&spec.strategy, params);
+                let features = self.features.clone();
+                let permits = permits.clone();
+                let (window, last) = (*window, window_index + 1 == windows.len() || folds.is_empty());
+                let objective = spec.objective;
+
+                jobs.spawn(async move {
+                    let _permit = permits.acquire_owned().await;
+                    // Windows are half-open except the last, which keeps the final feature
+                    let in_window: Vec<Feature> = features.iter()
+                        .filter(|f| f.timestamp >= window.0 && (f.timestamp < window.1 || (last && f.timestamp == window.1)))
+                        .cloned()
+                        .collect();
+                    let report = Backtester::new(config).run(in_window, None, None).await?;
+                    Ok::<_, IntelligenceError>((set_index, window_index, WindowScore::from_report(objective, &report)))
+                });
+            }
+        }
+
+        let mut scores = vec![vec![WindowScore::default(); windows.len()]; parameter_sets.len()];
+        while let Some(joined) = jobs.join_next().await {
+            let (set_index, window_index, score) = joined
+                .map_err(|e| IntelligenceError::internal(format!("Sweep backtest panicked: {}", e)))??;
+            scores[set_index][window_index] = score;
+        }
+
+        let mut results: Vec<SweepResult> = parameter_sets.into_iter()
+            .zip(scores)
+            .map(|(params, scores)| summarize(params, scores, !folds.is_empty()))
+            .collect();
+        results.sort_by(|a, b| b.in_sample_score.partial_cmp(&a.in_sample_score).unwrap_or(std::cmp::Ordering::Equal));
+        let walk_forward = WalkForwardResult::select(&folds, &results);
+
+        Ok(SweepReport { spec, folds, results, walk_forward })
+    }
+
+    /// Base config with only the swept strategy enabled, set to `params`
+    fn config_for(&self, strategy_name: &str, params: &BTreeMap<SweepParam, f64>) -> IntelligenceConfig {
+        let mut config = self.base.clone();
+        for strategy in config.strategies.values_mut() {
+            strategy.enabled = strategy.name == strategy_name;
+            if strategy.enabled {
+                for (param, value) in params {
+                    param.apply(strategy, *value);
+                }
+            }
+        }
+        config
+    }
+}
+
+/// Collapse per-window scores (train, test, train, test, ... or one whole-range score)
+fn summarize(params: BTreeMap<SweepParam, f64>, scores: Vec<WindowScore>, walk_forward: bool) -> SweepResult {
+    if !walk_forward {
+        let whole = scores.into_iter().next().unwrap_or_default();
+        return SweepResult {
+            params,
+            score: whole.score,
+            in_sample_score: whole.score,
+            pnl_usd: whole.pnl_usd,
+            max_drawdown_usd: whole.max_drawdown_usd,
+            fills: whole.fills,
+            folds: Vec::new(),
+        };
+    }
+
+    let folds: Vec<(WindowScore, WindowScore)> = scores.chunks(2)
+        .map(|pair| (pair[0].clone(), pair.get(1).cloned().unwrap_or_default()))
+        .collect();
+    let n = folds.len().max(1) as f64;
+
+    SweepResult {
+        params,
+        score: folds.iter().map(|(_, test)| test.score).sum::<f64>() / n,
+        in_sample_score: folds.iter().map(|(train, _)| train.score).sum::<f64>() / n,
+        pnl_usd: folds.iter().map(|(_, test)| test.pnl_usd).sum(),
+        max_drawdown_usd: folds.iter().map(|(_, test)| test.max_drawdown_usd).fold(0.0, f64::max),
+        fills: folds.iter().map(|(_, test)| test.fills).sum(),
+        folds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(search: SearchMode) -> SweepSpec {
+        serde_yaml::from_str::<SweepSpec>(&format!(
+            "strategy: dex_arb\nsearch: {}\nsamples: 20\nseed: 7\nparams:\n  min_profit_bps: {{ values: [5, 10, 20] }}\n  max_position_usd: {{ min: 100000, max: 500000, steps: 3 }}\n",
+            if search == SearchMode::Grid { "grid" } else { "random" }
+        )).unwrap()
+    }
+
+    #[test]
+    fn test_grid_and_random_parameter_sets() {
+        let grid = spec(SearchMode::Grid).parameter_sets();
+        assert_eq!(grid.len(), 9);
+        assert!(grid.iter().any(|set| set[&SweepParam::MinProfitBps] == 20.0 && set[&SweepParam::MaxPositionUsd] == 300_000.0));
+
+        let random = spec(SearchMode::Random).parameter_sets();
+        assert_eq!(random.len(), 20);
+        assert!(random.iter().all(|set| (100_000.0..=500_000.0).contains(&set[&SweepParam::MaxPositionUsd])));
+        assert!(random.iter().all(|set| [5.0, 10.0, 20.0].contains(&set[&SweepParam::MinProfitBps])));
+        // Seeded: the same spec draws the same sets
+        assert_eq!(
+            random.iter().map(|set| set[&SweepParam::MaxPositionUsd]).collect::<Vec<_>>(),
+            spec(SearchMode::Random).parameter_sets().iter().map(|set| set[&SweepParam::MaxPositionUsd]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_walk_forward_windows() {
+        let start = DateTime::from_timestamp(0, 0).unwrap();
+        let end = DateTime::from_timestamp(400, 0).unwrap();
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+
+        let rolling = walk_forward_folds(start, end, 3, false);
+        assert_eq!(rolling.len(), 3);
+        assert_eq!(rolling[0], Fold { train: (at(0), at(100)), test: (at(100), at(200)) });
+        assert_eq!(rolling[2], Fold { train: (at(200), at(300)), test: (at(300), at(400)) });
+
+        let anchored = walk_forward_folds(start, end, 3, true);
+        assert_eq!(anchored[2].train, (at(0), at(300)));
+    }
+
+    #[test]
+    fn test_walk_forward_selects_on_train_and_scores_on_test() {
+        let window = |score: f64| WindowScore { score, pnl_usd: score, max_drawdown_usd: 0.0, fills: 1 };
+        // Fold 0 train favours 5bps, fold 1 train favours 15bps
+        let a = summarize([(SweepParam::MinProfitBps, 5.0)].into(), vec![window(900.0), window(-50.0), window(100.0), window(400.0)], true);
+        let b = summarize([(SweepParam::MinProfitBps, 15.0)].into(), vec![window(300.0), window(120.0), window(200.0), window(80.0)], true);
+        assert!(a.in_sample_score > b.in_sample_score);
+
+        let start = DateTime::from_timestamp(0, 0).unwrap();
+        let folds = walk_forward_folds(start, DateTime::from_timestamp(300, 0).unwrap(), 2, false);
+        let mut results = vec![b, a];
+        results.sort_by(|a, b| b.in_sample_score.partial_cmp(&a.in_sample_score).unwrap());
+
+        // The picked sets' test scores, not the best test score in hindsight (120 and 400)
+        let walk_forward = WalkForwardResult::select(&folds, &results).unwrap();
+        let picked: Vec<f64> = walk_forward.folds.iter().map(|selection| selection.params[&SweepParam::MinProfitBps]).collect();
+        assert_eq!(picked, vec![5.0, 15.0]);
+        assert_eq!(walk_forward.score, (-50.0 + 80.0) / 2.0);
+        assert_eq!(walk_forward.pnl_usd, 30.0);
+
+        let report = SweepReport { spec: spec(SearchMode::Grid), folds, results, walk_forward: Some(walk_forward) };
+        let best = report.best_config(&IntelligenceConfig::default()).unwrap();
+        assert_eq!(best.strategies["dex_arb"].min_profit_bps, 5.0);
+        assert!(report.ranked_csv().lines().nth(1).unwrap().starts_with("1,5,0,"));
+    }
+}
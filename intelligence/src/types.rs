@@ -5,6 +5,8 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use qenus_dataplane::Chain;
 
+use crate::simulator::CompetitionEstimate;
+
 /// Trade intent - output of Intelligence layer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeIntent {
@@ -40,6 +42,11 @@ pub struct TradeIntent {
     
     /// Metadata
     pub metadata: TradeMetadata,
+    
+    /// Paper-traded only: never sent to Orchestration, and kept out of
+    /// inventory, strategy budgets and the competition model
+    #[serde(default)]
+    pub paper: bool,
 }
 
 /// Single leg of a multi-step trade
@@ -48,22 +55,36 @@ pub struct TradeLeg {
     /// Execution domain (chain)
     pub domain: Chain,
     
+    /// Domain a bridge leg delivers to
+    #[serde(default)]
+    pub destination_domain: Option<Chain>,
+    
     /// Action type
     pub action: TradeAction,
     
     /// Protocol/venue
     pub protocol: String,
     
+    /// Pool the leg trades through, when the simulator picked one
+    #[serde(default)]
+    pub pool: Option<String>,
+    
+    /// Simulator step this leg is part of, when a swap is split across pools
+    ///
+    /// Legs of one split run side by side, each on its share of the input.
+    #[serde(default)]
+    pub split_of: Option<usize>,
+    
     /// Input asset
     pub asset_in: String,
     
     /// Output asset
     pub asset_out: String,
     
-    /// Amount in
+    /// Amount in, in base units of the input token
     pub amount_in: String,
     
-    /// Minimum amount out (slippage protection)
+    /// Minimum amount out (slippage protection), in base units of the output token
     pub min_amount_out: String,
     
     /// Maximum fee in basis points
@@ -72,8 +93,34 @@ pub struct TradeLeg {
     /// Deadline timestamp
     pub deadline: DateTime<Utc>,
     
-    /// Expected output
+    /// Expected output, in base units of the output token
     pub expected_out: String,
+    
+    /// Input token address
+    pub token_in: String,
+    
+    /// Output token address (on the destination domain for bridges)
+    pub token_out: String,
+    
+    /// USD value of `amount_in` when the leg was built
+    #[serde(default)]
+    pub amount_in_usd: f64,
+    
+    /// USD value of `expected_out` when the leg was built
+    #[serde(default)]
+    pub expected_out_usd: f64,
+    
+    /// Contract the calldata is sent to
+    #[serde(default)]
+    pub target: Option<String>,
+    
+    /// ABI-encoded call (0x-prefixed hex)
+    ///
+    /// Legs between a flash loan and its repayment run from the loan's
+    /// callback: their calls are embedded in the flash-loan leg's calldata
+    /// and are not submitted on their own.
+    #[serde(default)]
+    pub calldata: Option<String>,
 }
 
 /// Trade action types
@@ -92,6 +139,9 @@ pub enum TradeAction {
     /// Repay flash loan
     FlashRepay,
     
+    /// Liquidate a lending position
+    Liquidate,
+    
     /// Add liquidity
     AddLiquidity,
     
@@ -137,7 +187,7 @@ pub struct RiskFactor {
 }
 
 /// Risk severity levels
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RiskSeverity {
     Low,
@@ -147,7 +197,7 @@ pub enum RiskSeverity {
 }
 
 /// Arbitrage candidate detected by detectors
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Candidate {
     /// Strategy type
     pub strategy: String,
@@ -166,10 +216,205 @@ pub struct Candidate {
     
     /// Confidence score
     pub confidence: f64,
+    
+    /// When the opportunity was first detected, across detection cycles
+    #[serde(default)]
+    pub first_seen_at: Option<DateTime<Utc>>,
+    
+    /// Strategy-specific details needed downstream
+    pub details: Option<CandidateDetails>,
+}
+
+impl Candidate {
+    /// Stable key for the opportunity across detection cycles
+    ///
+    /// Built from the strategy, asset, leg domains (chain and venue) and the
+    /// pools or position named in the details, so the same spread seen again
+    /// maps to the same fingerprint.
+    pub fn fingerprint(&self) -> String {
+        let path: Vec<String> = self.legs.iter()
+            .map(|(domain, action)| format!("{}:{}", domain, action))
+            .collect();
+        let mut fingerprint = format!("{}|{}|{}", self.strategy, self.asset, path.join(">"));
+        
+        let pools = match &self.details {
+            Some(CandidateDetails::FlashArb(details)) => vec![details.buy_pool.as_str(), details.sell_pool.as_str()],
+            Some(CandidateDetails::Depeg(details)) => vec![details.entry_pool.as_str(), details.exit_pool.as_str()],
+            Some(CandidateDetails::Liquidation(details)) => vec![details.borrower.as_str()],
+            None => Vec::new(),
+        };
+        if !pools.is_empty() {
+            fingerprint.push('|');
+            fingerprint.push_str(&pools.join(">"));
+        }
+        fingerprint
+    }
+    
+    /// Seconds since the opportunity was first detected
+    pub fn age_secs(&self) -> f64 {
+        self.first_seen_at
+            .map(|first_seen| (self.detected_at - first_seen).num_milliseconds().max(0) as f64 / 1000.0)
+            .unwrap_or(0.0)
+    }
+    
+    /// Whether the candidate executes atomically on borrowed funds, holding no inventory
+    pub fn is_atomic(&self) -> bool {
+        matches!(self.details, Some(CandidateDetails::FlashArb(_)))
+    }
+}
+
+/// Strategy-specific candidate details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CandidateDetails {
+    /// Aave V3 liquidation of an unhealthy borrower
+    Liquidation(LiquidationDetails),
+    
+    /// Stablecoin trading away from its peg
+    Depeg(DepegDetails),
+    
+    /// Same-chain price gap captured with borrowed funds in one transaction
+    FlashArb(FlashArbDetails),
+}
+
+impl CandidateDetails {
+    /// Risk factors the detector attaches to the candidate
+    pub fn risk_factors(&self) -> Vec<RiskFactor> {
+        match self {
+            CandidateDetails::Liquidation(_) | CandidateDetails::FlashArb(_) => Vec::new(),
+            CandidateDetails::Depeg(details) => {
+                let mut risks = vec![RiskFactor {
+                    factor: match details.kind {
+                        DepegKind::Depeg => "stablecoin_depeg".to_string(),
+                        DepegKind::PoolImbalance => "stable_pool_imbalance".to_string(),
+                    },
+                    severity: details.severity,
+                    message: format!(
+                        "{} {:+.1}bps from peg across {} pools ({:+.1}bps in entry pool)",
+                        details.stablecoin, details.deviation_bps, details.confirming_pools, details.pool_deviation_bps
+                    ),
+                }];
+                
+                if details.kind == DepegKind::Depeg && !details.recovering {
+                    risks.push(RiskFactor {
+                        factor: "depeg_not_recovering".to_string(),
+                        severity: details.severity,
+                        message: format!("Off peg for {}s with no recovery yet", details.depeg_duration_secs),
+                    });
+                }
+                
+                risks
+            }
+        }
+    }
+}
+
+/// Details of a liquidation candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidationDetails {
+    /// Chain the borrower position lives on
+    pub chain: Chain,
+    
+    /// Borrower address
+    pub borrower: String,
+    
+    /// Debt asset repaid by the liquidator
+    pub debt_asset: String,
+    
+    /// Collateral asset seized by the liquidator
+    pub collateral_asset: String,
+    
+    /// Health factor at detection time
+    pub health_factor: f64,
+    
+    /// Debt repaid in USD (bounded by the close factor)
+    pub debt_to_cover_usd: f64,
+    
+    /// Collateral seized in USD (including the liquidation bonus)
+    pub collateral_seized_usd: f64,
+    
+    /// Liquidation bonus in basis points
+    pub liquidation_bonus_bps: u32,
+}
+
+/// Details of a flash-loan-funded atomic arbitrage candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlashArbDetails {
+    /// Chain the whole cycle executes on
+    pub chain: Chain,
+    
+    /// Asset borrowed, traded through the cycle and repaid
+    pub borrow_asset: String,
+    
+    /// Pool the candidate asset is bought in
+    pub buy_pool: String,
+    
+    /// Pool the candidate asset is sold in
+    pub sell_pool: String,
+    
+    /// Price gap between the pools before fees and slippage
+    pub gross_spread_bps: f64,
+    
+    /// Borrow size bounded by depth and flash loan liquidity
+    pub size_usd: f64,
+}
+
+/// Whether a peg deviation is market-wide or local to one pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepegKind {
+    /// Most pools agree the stablecoin is off peg
+    Depeg,
+    
+    /// A single pool is imbalanced while the rest hold the peg
+    PoolImbalance,
+}
+
+/// Details of a stablecoin depeg candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepegDetails {
+    /// Chain the pools live on
+    pub chain: Chain,
+    
+    /// Stablecoin off peg
+    pub stablecoin: String,
+    
+    /// Stablecoin traded against
+    pub counter_asset: String,
+    
+    /// Depeg or single-pool imbalance
+    pub kind: DepegKind,
+    
+    /// Consensus deviation from peg across pools (negative = below peg)
+    pub deviation_bps: f64,
+    
+    /// Deviation in the entry pool
+    pub pool_deviation_bps: f64,
+    
+    /// Pools agreeing on the deviation
+    pub confirming_pools: usize,
+    
+    /// Pool the position is entered through
+    pub entry_pool: String,
+    
+    /// Pool the position is exited through
+    pub exit_pool: String,
+    
+    /// Position size bounded by pool depth and severity
+    pub size_usd: f64,
+    
+    /// Severity of the deviation
+    pub severity: RiskSeverity,
+    
+    /// How long the stablecoin has been off peg
+    pub depeg_duration_secs: i64,
+    
+    /// Whether the deviation has narrowed from its worst point
+    pub recovering: bool,
 }
 
 /// Evaluation result from simulator
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvaluationResult {
     /// Net profit in USD
     pub net_pnl_usd: f64,
@@ -188,10 +433,42 @@ pub struct EvaluationResult {
     
     /// Simulated execution path
     pub execution_path: Vec<SimulatedStep>,
+    
+    /// Competition and inclusion-risk estimate
+    pub competition: Option<CompetitionEstimate>,
+    
+    /// Probability the transaction lands and reverts (atomic strategies)
+    #[serde(default)]
+    pub revert_prob: Option<f64>,
+}
+
+impl EvaluationResult {
+    /// Slippage summed over the execution path
+    ///
+    /// Parts of a split swap count once, weighted by the input each carries.
+    pub fn total_slippage_bps(&self) -> f64 {
+        // (input-weighted slippage, input, worst part) per step
+        let mut steps: Vec<(f64, f64, f64)> = Vec::new();
+        let mut previous: Option<&SimulatedStep> = None;
+        for step in &self.execution_path {
+            match steps.last_mut() {
+                Some((weighted, amount, worst)) if previous.is_some_and(|previous| previous.splits_with(step)) => {
+                    *weighted += step.slippage_bps * step.amount_in;
+                    *amount += step.amount_in;
+                    *worst = worst.max(step.slippage_bps);
+                }
+                _ => steps.push((step.slippage_bps * step.amount_in, step.amount_in, step.slippage_bps)),
+            }
+            previous = Some(step);
+        }
+        steps.iter()
+            .map(|(weighted, amount, worst)| if *amount > 0.0 { weighted / amount } else { *worst })
+            .sum()
+    }
 }
 
 /// Cost breakdown
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CostBreakdown {
     /// Gas costs in USD
     pub gas_usd: f64,
@@ -213,7 +490,7 @@ pub struct CostBreakdown {
 }
 
 /// Simulated execution step
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulatedStep {
     pub step: usize,
     pub action: String,
@@ -223,6 +500,25 @@ pub struct SimulatedStep {
     pub amount_out: f64,
     pub slippage_bps: f64,
     pub cost_usd: f64,
+    /// Pool the step trades through, when the simulator picked one
+    #[serde(default)]
+    pub pool: Option<String>,
+    /// Asset symbols in and out, when known to the simulator
+    #[serde(default)]
+    pub asset_in: Option<String>,
+    #[serde(default)]
+    pub asset_out: Option<String>,
+}
+
+impl SimulatedStep {
+    /// Whether `next` is another part of this step, split across pools
+    ///
+    /// Parts of a split swap follow each other with the same step number and
+    /// action, each through its own pool.
+    pub fn splits_with(&self, next: &SimulatedStep) -> bool {
+        self.step == next.step && self.action == next.action &&
+            self.pool.is_some() && next.pool.is_some() && self.pool != next.pool
+    }
 }
 
 /// Strategy configuration
@@ -251,6 +547,27 @@ pub struct StrategyConfig {
     
     /// Risk limits
     pub risk_limits: RiskLimits,
+    
+    /// Capital, gas and concurrency budget
+    #[serde(default)]
+    pub budget: StrategyBudget,
+}
+
+/// Budget of a single strategy; unset limits are not enforced
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrategyBudget {
+    /// Capital allocated to the strategy: open exposure of its non-atomic intents, in USD
+    pub capital_usd: Option<f64>,
+    
+    /// Gas the strategy may spend over a rolling hour, in USD
+    pub max_gas_usd_per_hour: Option<f64>,
+    
+    /// Gas the strategy may spend over a rolling day, in USD
+    pub max_gas_usd_per_day: Option<f64>,
+    
+    /// Intents the strategy may have open at once
+    pub max_concurrent_intents: Option<usize>,
 }
 
 /// Risk limits per strategy
@@ -267,6 +584,22 @@ pub struct RiskLimits {
     
     /// Minimum success probability
     pub min_success_prob: f64,
+    
+    /// Maximum revert probability of atomic strategies
+    #[serde(default = "default_max_revert_prob")]
+    pub max_revert_prob: f64,
+    
+    /// Fraction of the Kelly stake to take; None sizes at the simulator's optimum
+    #[serde(default)]
+    pub kelly_fraction: Option<f64>,
+    
+    /// Largest expected tail loss (CVaR at 95%) a single trade may carry, in USD
+    #[serde(default)]
+    pub max_cvar_usd: Option<f64>,
+}
+
+fn default_max_revert_prob() -> f64 {
+    0.05
 }
 
 impl Default for RiskLimits {
@@ -276,6 +609,9 @@ impl Default for RiskLimits {
             max_gas_pct: 50.0,          // Gas can be up to 50% of profit
             max_bridge_latency_secs: 300, // 5 min max
             min_success_prob: 0.8,      // 80% min success probability
+            max_revert_prob: default_max_revert_prob(),
+            kelly_fraction: None,
+            max_cvar_usd: None,
         }
     }
 }
@@ -0,0 +1,669 @@
+//! Pre-emission intent verification
+//!
+//! Executes an intent's encoded legs in an in-process revm instance before the
+//! intent is emitted. The EVM reads from an [`EvmStateCache`] of account code,
+//! balances and storage slots for the contracts the legs touch, populated by the
+//! dataplane or seeded from an archive-node dump. Legs that revert, or whose
+//! simulated output misses the predicted output by more than the tolerance,
+//! get the intent dropped or repriced.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use chrono::Utc;
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Bytes, H160, U256};
+use parking_lot::RwLock;
+use qenus_dataplane::{Chain, EvmStateFeature};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{
+    AccountInfo, Address, Bytecode, ExecutionResult, Output, TxKind, KECCAK_EMPTY, U256 as EvmU256,
+};
+use revm::Evm;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::config::{DeviationAction, VerificationConfig};
+use crate::error::{IntelligenceError, Result};
+use crate::types::{TradeAction, TradeIntent};
+
+/// Error(string) selector
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Panic(uint256) selector
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Cached state of one account
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachedAccount {
+    #[serde(default)]
+    pub balance: U256,
+    #[serde(default)]
+    pub nonce: u64,
+    #[serde(default)]
+    pub code: Bytes,
+    #[serde(default)]
+    pub storage: HashMap<U256, U256>,
+}
+
+/// Account code and storage the verifier executes against, per chain
+///
+/// Kept current by the dataplane's touched-state feed, which MarketState
+/// applies on ingest; a JSON dump can seed contracts the feed has not
+/// touched yet.
+#[derive(Default)]
+pub struct EvmStateCache {
+    chains: RwLock<HashMap<Chain, HashMap<H160, CachedAccount>>>,
+}
+
+impl EvmStateCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge a JSON dump of `{chain: {address: account}}`
+    ///
+    /// Returns the number of accounts loaded.
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let contents = std::fs::read_to_string(path.as_ref())?;
+        let dump: HashMap<Chain, HashMap<H160, CachedAccount>> = serde_json::from_str(&contents)?;
+        let loaded = dump.values().map(HashMap::len).sum::<usize>();
+
+        let mut chains = self.chains.write();
+        for (chain, accounts) in dump {
+            chains.entry(chain).or_default().extend(accounts);
+        }
+        info!("Loaded EVM state for {} accounts from {}", loaded, path.as_ref().display());
+        Ok(loaded)
+    }
+
+    /// Apply the post-block state of the accounts and slots a block touched
+    pub fn apply(&self, chain: Chain, evm_state: &EvmStateFeature) {
+        let mut chains = self.chains.write();
+        let accounts = chains.entry(chain).or_default();
+
+        for touched in &evm_state.accounts {
+            let Some(address) = crate::calldata::parse_address(&touched.address) else {
+                warn!("Ignoring EVM state for invalid address {}", touched.address);
+                continue;
+            };
+            let account = accounts.entry(address).or_default();
+            if let Some(balance) = touched.balance.as_deref().and_then(parse_word) {
+                account.balance = balance;
+            }
+            if let Some(nonce) = touched.nonce {
+                account.nonce = nonce;
+            }
+            if let Some(code) = touched.code.as_deref().and_then(|code| hex::decode(code.trim_start_matches("0x")).ok()) {
+                account.code = code.into();
+            }
+            for (slot, value) in &touched.storage {
+                if let (Some(slot), Some(value)) = (parse_word(slot), parse_word(value)) {
+                    account.storage.insert(slot, value);
+                }
+            }
+        }
+    }
+
+    /// Set an account's balance, nonce and code, keeping its storage
+    pub fn set_account(&self, chain: Chain, address: H160, balance: U256, nonce: u64, code: Bytes) {
+        let mut chains = self.chains.write();
+        let account = chains.entry(chain).or_default().entry(address).or_default();
+        account.balance = balance;
+        account.nonce = nonce;
+        account.code = code;
+    }
+
+    /// Set one storage slot
+    pub fn set_storage(&self, chain: Chain, address: H160, slot: U256, value: U256) {
+        let mut chains = self.chains.write();
+        chains.entry(chain).or_default().entry(address).or_default().storage.insert(slot, value);
+    }
+
+    pub fn account(&self, chain: Chain, address: H160) -> Option<CachedAccount> {
+        self.chains.read().get(&chain)?.get(&address).cloned()
+    }
+
+    /// Whether any state is cached for `chain`
+    pub fn has_state(&self, chain: Chain) -> bool {
+        self.chains.read().get(&chain).is_some_and(|accounts| !accounts.is_empty())
+    }
+
+    /// Fresh EVM database holding a copy of the chain's cached state
+    fn database(&self, chain: Chain) -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let chains = self.chains.read();
+
+        for (address, account) in chains.get(&chain).into_iter().flatten() {
+            let address = evm_address(*address);
+            let code = (!account.code.is_empty()).then(|| Bytecode::new_raw(account.code.0.clone().into()));
+            db.insert_account_info(address, AccountInfo {
+                balance: evm_u256(account.balance),
+                nonce: account.nonce,
+                code_hash: KECCAK_EMPTY,
+                code,
+            });
+            for (slot, value) in &account.storage {
+                // EmptyDB cannot fail
+                let _ = db.insert_account_storage(address, evm_u256(*slot), evm_u256(*value));
+            }
+        }
+
+        db
+    }
+}
+
+/// Outcome of verifying one intent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    /// Every simulated leg succeeded within tolerance
+    Passed,
+
+    /// Outputs missed the prediction; expected outputs and PnL were repriced
+    Repriced,
+
+    /// A leg reverted or missed the prediction; the intent must not be emitted
+    Dropped,
+
+    /// No leg carried calldata to execute, or there is no state to execute against
+    Skipped,
+}
+
+/// Simulated execution of one leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegSimulation {
+    /// Index into the intent's legs
+    pub leg: usize,
+    pub success: bool,
+    pub gas_used: u64,
+
+    /// Output predicted by the off-chain model, in base units
+    pub predicted_out: Option<String>,
+
+    /// Output returned by the call, in base units
+    pub simulated_out: Option<String>,
+
+    /// Simulated vs predicted output (negative = less than predicted)
+    pub deviation_bps: Option<f64>,
+
+    pub revert_reason: Option<String>,
+}
+
+/// Result of executing an intent's legs in the local EVM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub status: VerificationStatus,
+    pub legs: Vec<LegSimulation>,
+
+    /// Revert reason of the first failing leg
+    pub revert_reason: Option<String>,
+
+    /// Largest absolute deviation across legs
+    pub max_deviation_bps: Option<f64>,
+
+    /// Expected PnL after repricing
+    pub repriced_pnl_usd: Option<f64>,
+}
+
+impl VerificationReport {
+    /// One-line explanation of a dropped or repriced intent
+    pub fn summary(&self) -> String {
+        match (&self.revert_reason, self.max_deviation_bps, self.repriced_pnl_usd) {
+            (Some(reason), _, _) => format!("reverted: {}", reason),
+            (None, Some(deviation), Some(pnl)) => {
+                format!("output deviated {:.1}bps from prediction, repriced PnL ${:.2}", deviation, pnl)
+            }
+            (None, Some(deviation), None) => format!("output deviated {:.1}bps from prediction", deviation),
+            (None, None, _) => format!("{:?}", self.status).to_lowercase(),
+        }
+    }
+}
+
+/// Executes intents in a local EVM before emission
+pub struct IntentVerifier {
+    cache: Arc<EvmStateCache>,
+
+    /// Sender of every simulated leg
+    executor: H160,
+
+    tolerance_bps: f64,
+    on_deviation: DeviationAction,
+    gas_limit: u64,
+}
+
+impl IntentVerifier {
+    pub fn new(cache: Arc<EvmStateCache>, executor: H160, config: &VerificationConfig) -> Self {
+        Self {
+            cache,
+            executor,
+            tolerance_bps: config.tolerance_bps,
+            on_deviation: config.on_deviation,
+            gas_limit: config.gas_limit,
+        }
+    }
+
+    /// Verifier for the configured executor, if verification is enabled
+    ///
+    /// Executes against `cache`, seeded from `state_file` when one is configured.
+    pub fn from_config(
+        config: &VerificationConfig,
+        executor: Option<&str>,
+        cache: Arc<EvmStateCache>,
+    ) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let executor = executor
+            .and_then(crate::calldata::parse_address)
+            .ok_or_else(|| IntelligenceError::internal("Intent verification needs execution.executor_address"))?;
+        match &config.state_file {
+            Some(path) => {
+                cache.load_file(path)?;
+            }
+            None => info!("No verification.state_file: executing against the dataplane's EVM state feed only"),
+        }
+
+        Ok(Some(Self::new(cache, executor, config)))
+    }
+
+    /// Execute the intent's legs in order, repricing the intent in place when configured
+    ///
+    /// Legs on the same chain share state, so a leg sees the balances left by
+    /// the legs before it. Execution stops at the first revert. Intents with a
+    /// leg on a chain without cached state are skipped: an empty state would
+    /// pass any call to a codeless target.
+    pub async fn verify(&self, intent: &mut TradeIntent) -> VerificationReport {
+        let missing_state = intent.legs.iter()
+            .filter(|leg| leg.calldata.is_some())
+            .any(|leg| !self.cache.has_state(leg.domain));
+        if missing_state {
+            return VerificationReport {
+                status: VerificationStatus::Skipped,
+                legs: Vec::new(),
+                revert_reason: None,
+                max_deviation_bps: None,
+                repriced_pnl_usd: None,
+            };
+        }
+
+        let mut databases: HashMap<Chain, CacheDB<EmptyDB>> = HashMap::new();
+        let mut simulations = Vec::new();
+        let mut revert_reason = None;
+
+        let mut in_flash_loan = false;
+        for (index, leg) in intent.legs.iter().enumerate() {
+            // A flash loan's callback runs the legs up to its repayment
+            if in_flash_loan {
+                in_flash_loan = !matches!(leg.action, TradeAction::FlashRepay);
+                continue;
+            }
+            in_flash_loan = matches!(leg.action, TradeAction::FlashLoan) && leg.calldata.is_some();
+
+            let (Some(target), Some(calldata)) = (&leg.target, &leg.calldata) else {
+                continue;
+            };
+            let (Some(target), Ok(calldata)) = (
+                crate::calldata::parse_address(target),
+                hex::decode(calldata.trim_start_matches("0x")),
+            ) else {
+                revert_reason = Some(format!("leg {} has malformed target or calldata", index));
+                break;
+            };
+
+            let db = match databases.entry(leg.domain) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(self.cache.database(leg.domain)),
+            };
+
+            let predicted = U256::from_dec_str(&leg.expected_out).ok();
+            let mut simulation = LegSimulation {
+                leg: index,
+                success: false,
+                gas_used: 0,
+                predicted_out: predicted.map(|amount| amount.to_string()),
+                simulated_out: None,
+                deviation_bps: None,
+                revert_reason: None,
+            };
+
+            match self.execute(db, target, calldata) {
+                Ok((gas_used, output)) => {
+                    simulation.success = true;
+                    simulation.gas_used = gas_used;
+                    // Swap entry points return the output amount as their first word
+                    let simulated = (output.len() >= 32).then(|| U256::from_big_endian(&output[..32]));
+                    simulation.simulated_out = simulated.map(|amount| amount.to_string());
+                    simulation.deviation_bps = match (predicted, simulated) {
+                        (Some(predicted), Some(simulated)) if !predicted.is_zero() => {
+                            Some((to_f64(simulated) / to_f64(predicted) - 1.0) * 10_000.0)
+                        }
+                        _ => None,
+                    };
+                }
+                Err((gas_used, reason)) => {
+                    simulation.gas_used = gas_used;
+                    simulation.revert_reason = Some(reason.clone());
+                    revert_reason = Some(format!("leg {}: {}", index, reason));
+                }
+            }
+
+            simulations.push(simulation);
+            if revert_reason.is_some() {
+                break;
+            }
+        }
+
+        let max_deviation_bps = simulations.iter()
+            .filter_map(|simulation| simulation.deviation_bps)
+            .max_by(|a, b| a.abs().total_cmp(&b.abs()));
+        let mut report = VerificationReport {
+            status: VerificationStatus::Passed,
+            legs: simulations,
+            revert_reason,
+            max_deviation_bps,
+            repriced_pnl_usd: None,
+        };
+
+        if report.revert_reason.is_some() {
+            report.status = VerificationStatus::Dropped;
+        } else if report.legs.is_empty() {
+            report.status = VerificationStatus::Skipped;
+        } else if max_deviation_bps.is_some_and(|deviation| deviation.abs() > self.tolerance_bps) {
+            report.status = match self.on_deviation {
+                DeviationAction::Drop => VerificationStatus::Dropped,
+                DeviationAction::Reprice => reprice(intent, &mut report),
+            };
+        }
+
+        debug!("Verified intent {}: {}", intent.intent_id, report.summary());
+        report
+    }
+
+    /// Execute one call and commit its state changes
+    ///
+    /// Returns gas used and return data, or gas used and the revert reason.
+    fn execute(
+        &self,
+        db: &mut CacheDB<EmptyDB>,
+        target: H160,
+        calldata: Vec<u8>,
+    ) -> std::result::Result<(u64, Vec<u8>), (u64, String)> {
+        let mut evm = Evm::builder()
+            .with_db(db)
+            // The executor is a contract; allow it to originate simulated calls
+            .modify_cfg_env(|cfg| cfg.disable_eip3607 = true)
+            .modify_block_env(|block| block.timestamp = EvmU256::from(Utc::now().timestamp().max(0) as u64))
+            .modify_tx_env(|tx| {
+                tx.caller = evm_address(self.executor);
+                tx.transact_to = TxKind::Call(evm_address(target));
+                tx.data = calldata.into();
+                tx.gas_limit = self.gas_limit;
+                tx.gas_price = EvmU256::ZERO;
+            })
+            .build();
+
+        match evm.transact_commit() {
+            Ok(ExecutionResult::Success { gas_used, output, .. }) => {
+                let data = match output {
+                    Output::Call(data) => data.to_vec(),
+                    Output::Create(data, _) => data.to_vec(),
+                };
+                Ok((gas_used, data))
+            }
+            Ok(ExecutionResult::Revert { gas_used, output }) => Err((gas_used, decode_revert_reason(&output))),
+            Ok(ExecutionResult::Halt { reason, gas_used }) => Err((gas_used, format!("halted: {:?}", reason))),
+            Err(e) => Err((0, format!("invalid transaction: {:?}", e))),
+        }
+    }
+}
+
+/// Scale expected outputs to the simulated ones and charge each leg's shortfall against PnL
+fn reprice(intent: &mut TradeIntent, report: &mut VerificationReport) -> VerificationStatus {
+    let mut pnl_delta_usd = 0.0;
+
+    for simulation in &report.legs {
+        let (Some(deviation_bps), Some(simulated)) = (simulation.deviation_bps, &simulation.simulated_out) else {
+            continue;
+        };
+        let leg = &mut intent.legs[simulation.leg];
+        let repriced_out_usd = leg.expected_out_usd * (1.0 + deviation_bps / 10_000.0);
+
+        pnl_delta_usd += repriced_out_usd - leg.expected_out_usd;
+        leg.expected_out_usd = repriced_out_usd;
+        leg.expected_out = simulated.clone();
+    }
+
+    intent.expected_pnl_usd += pnl_delta_usd;
+    if intent.size_usd > 0.0 {
+        intent.net_bps = intent.expected_pnl_usd / intent.size_usd * 10_000.0;
+    }
+    report.repriced_pnl_usd = Some(intent.expected_pnl_usd);
+
+    if intent.expected_pnl_usd > 0.0 {
+        VerificationStatus::Repriced
+    } else {
+        VerificationStatus::Dropped
+    }
+}
+
+/// Human-readable revert reason from revert data
+fn decode_revert_reason(output: &[u8]) -> String {
+    if output.is_empty() {
+        return "reverted without reason".to_string();
+    }
+    if output.len() < 4 {
+        return format!("reverted with 0x{}", hex::encode(output));
+    }
+
+    let (selector, data) = output.split_at(4);
+    if selector == ERROR_SELECTOR {
+        if let Ok(tokens) = abi::decode(&[ParamType::String], data) {
+            if let Some(Token::String(reason)) = tokens.into_iter().next() {
+                return reason;
+            }
+        }
+    } else if selector == PANIC_SELECTOR && data.len() >= 32 {
+        return format!("panic 0x{:x}", U256::from_big_endian(&data[..32]));
+    }
+
+    format!("custom error 0x{}", hex::encode(selector))
+}
+
+/// 0x-prefixed hex word, as the dataplane encodes balances and storage
+fn parse_word(word: &str) -> Option<U256> {
+    U256::from_str_radix(word.trim_start_matches("0x"), 16).ok()
+}
+
+fn evm_address(address: H160) -> Address {
+    Address::from(address.0)
+}
+
+fn evm_u256(value: U256) -> EvmU256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    EvmU256::from_be_bytes(bytes)
+}
+
+/// Lossy conversion, only used for ratios
+fn to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(f64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TradeAction, TradeLeg, TradeMetadata, MarketSnapshot};
+    use uuid::Uuid;
+
+    const TARGET: H160 = H160([0x11; 20]);
+    const EXECUTOR: H160 = H160([0x22; 20]);
+
+    /// Contract returning `value` as a single word
+    fn returns(value: U256) -> Bytes {
+        let mut word = [0u8; 32];
+        value.to_big_endian(&mut word);
+        // PUSH32 value, PUSH1 0, MSTORE, PUSH1 32, PUSH1 0, RETURN
+        let mut code = vec![0x7f];
+        code.extend_from_slice(&word);
+        code.extend_from_slice(&[0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+        code.into()
+    }
+
+    /// Contract reverting with Error(reason)
+    fn reverts(reason: &str) -> Bytes {
+        let mut payload = ERROR_SELECTOR.to_vec();
+        payload.extend(abi::encode(&[Token::String(reason.to_string())]));
+        let len = payload.len() as u8;
+        // CODECOPY the payload appended after these 12 bytes, then REVERT with it
+        let mut code = vec![0x60, len, 0x60, 12, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xfd];
+        code.extend(payload);
+        code.into()
+    }
+
+    fn intent(expected_out: u64) -> TradeIntent {
+        TradeIntent {
+            intent_id: Uuid::new_v4(),
+            strategy: "dex_arb".to_string(),
+            asset: "WETH".to_string(),
+            size_usd: 10_000.0,
+            expected_pnl_usd: 100.0,
+            net_bps: 100.0,
+            success_prob: 0.9,
+            legs: vec![TradeLeg {
+                domain: Chain::Ethereum,
+                destination_domain: None,
+                action: TradeAction::Swap,
+                protocol: "uniswap_v3".to_string(),
+                asset_in: "USDC".to_string(),
+                asset_out: "WETH".to_string(),
+                amount_in: "10000000000".to_string(),
+                min_amount_out: (expected_out * 999 / 1000).to_string(),
+                max_fee_bps: 30,
+                deadline: Utc::now(),
+                expected_out: expected_out.to_string(),
+                token_in: "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(),
+                token_out: "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".to_string(),
+                amount_in_usd: 10_000.0,
+                expected_out_usd: 10_000.0,
+                target: Some(format!("{:#x}", TARGET)),
+                calldata: Some("0xc04b8d59".to_string()),
+                pool: None,
+                split_of: None,
+            }],
+            ttl_seconds: 30,
+            created_at: Utc::now(),
+            metadata: TradeMetadata {
+                detected_at: Utc::now(),
+                detector: "dex_arb".to_string(),
+                market_snapshot: MarketSnapshot {
+                    gas_prices: HashMap::new(),
+                    sequencer_health: HashMap::new(),
+                    volatility: 0.0,
+                },
+                risk_factors: Vec::new(),
+            },
+            paper: false,
+        }
+    }
+
+    async fn verifier_with(code: Bytes, on_deviation: DeviationAction) -> IntentVerifier {
+        let cache = EvmStateCache::new();
+        cache.set_account(Chain::Ethereum, TARGET, U256::zero(), 1, code);
+        let config = VerificationConfig { enabled: true, on_deviation, ..Default::default() };
+        IntentVerifier::new(Arc::new(cache), EXECUTOR, &config)
+    }
+
+    #[tokio::test]
+    async fn test_matching_output_passes() {
+        let verifier = verifier_with(returns(U256::from(1_000_000u64)), DeviationAction::Drop).await;
+        let mut intent = intent(1_000_000);
+
+        let report = verifier.verify(&mut intent).await;
+        assert_eq!(report.status, VerificationStatus::Passed);
+        assert!(report.legs[0].success);
+        assert_eq!(report.legs[0].simulated_out.as_deref(), Some("1000000"));
+        assert_eq!(report.max_deviation_bps, Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_deviation_drops_or_reprices() {
+        // Simulated output 0.5% short of the prediction
+        let verifier = verifier_with(returns(U256::from(995_000u64)), DeviationAction::Drop).await;
+        let report = verifier.verify(&mut intent(1_000_000)).await;
+        assert_eq!(report.status, VerificationStatus::Dropped);
+        assert!((report.max_deviation_bps.unwrap() + 50.0).abs() < 1e-6);
+
+        let verifier = verifier_with(returns(U256::from(995_000u64)), DeviationAction::Reprice).await;
+        let mut intent = intent(1_000_000);
+        let report = verifier.verify(&mut intent).await;
+        assert_eq!(report.status, VerificationStatus::Repriced);
+        // $10,000 expected out, $50 short
+        assert!((intent.expected_pnl_usd - 50.0).abs() < 1e-6);
+        assert_eq!(intent.legs[0].expected_out, "995000");
+        assert_eq!(report.repriced_pnl_usd, Some(intent.expected_pnl_usd));
+    }
+
+    #[tokio::test]
+    async fn test_revert_reason_is_recorded() {
+        let verifier = verifier_with(reverts("Too little received"), DeviationAction::Reprice).await;
+        let mut intent = intent(1_000_000);
+
+        let report = verifier.verify(&mut intent).await;
+        assert_eq!(report.status, VerificationStatus::Dropped);
+        assert_eq!(report.legs[0].revert_reason.as_deref(), Some("Too little received"));
+        assert_eq!(report.summary(), "reverted: leg 0: Too little received");
+        // Reverts are never repriced
+        assert_eq!(intent.expected_pnl_usd, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_skips_without_state_file() {
+        let config = VerificationConfig { enabled: true, ..Default::default() };
+        let cache = Arc::new(EvmStateCache::new());
+        let verifier = IntentVerifier::from_config(&config, Some(&format!("{:#x}", EXECUTOR)), cache).unwrap().unwrap();
+
+        let report = verifier.verify(&mut intent(1_000_000)).await;
+        assert_eq!(report.status, VerificationStatus::Skipped);
+        assert!(report.legs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_executes_against_dataplane_state_feed() {
+        use qenus_dataplane::{EvmAccountState, Feature, FeatureData, FeatureType};
+        use crate::state::MarketState;
+
+        let touched = |block_number: u64, out: u64| Feature::new(
+            block_number,
+            Chain::Ethereum,
+            FeatureType::EvmState,
+            FeatureData::EvmState(EvmStateFeature {
+                accounts: vec![EvmAccountState {
+                    address: format!("{:#x}", TARGET),
+                    balance: Some("0x0".to_string()),
+                    nonce: Some(1),
+                    code: Some(format!("0x{}", hex::encode(returns(U256::from(out))))),
+                    storage: [("0x1".to_string(), format!("{:#x}", out))].into_iter().collect(),
+                }],
+            }),
+            "test".to_string(),
+        );
+
+        let market_state = MarketState::new(30);
+        let config = VerificationConfig { enabled: true, ..Default::default() };
+        let executor = format!("{:#x}", EXECUTOR);
+        let verifier = IntentVerifier::from_config(&config, Some(&executor), market_state.evm_state()).unwrap().unwrap();
+        assert_eq!(verifier.verify(&mut intent(1_000_000)).await.status, VerificationStatus::Skipped);
+
+        // Each block's touched state replaces what the previous one left
+        market_state.ingest_feature(touched(1, 900_000)).await.unwrap();
+        market_state.ingest_feature(touched(2, 1_000_000)).await.unwrap();
+        let report = verifier.verify(&mut intent(1_000_000)).await;
+        assert_eq!(report.status, VerificationStatus::Passed);
+
+        let account = market_state.evm_state().account(Chain::Ethereum, TARGET).unwrap();
+        assert_eq!(account.storage[&U256::one()], U256::from(1_000_000u64));
+    }
+}
@@ -18,9 +18,10 @@ fn create_uniswap_feature(chain: Chain, price: f64) -> Feature {
         chain,
         timestamp: Utc::now(),
         feature_type: qenus_dataplane::FeatureType::Amm,
-        data: FeatureData::Amm(AmmFeature {
+        data: FeatureData::Amm(Box::new(AmmFeature {
             pool_address: "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640".to_string(),
             pool_type: "uniswap_v3".to_string(),
+            pool_id: None,
             token0: TokenInfo {
                 address: "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(),
                 symbol: "USDC".to_string(),
@@ -42,10 +43,11 @@ fn create_uniswap_feature(chain: Chain, price: f64) -> Feature {
             liquidity: "5000000.0".to_string(),
             depth: DepthCurve {
                 sizes: HashMap::new(),
+                ..Default::default()
             },
             volume_24h: None,
             fees_24h: None,
-        }),
+        })),
         source: "test".to_string(),
         version: "1.0".to_string(),
     }
@@ -96,7 +98,11 @@ async fn test_full_pipeline_dex_arb() {
                 max_gas_pct: 80.0,
                 max_bridge_latency_secs: 0,
                 min_success_prob: 0.7,
+                max_revert_prob: 0.05,
+                kelly_fraction: None,
+                max_cvar_usd: None,
             },
+            budget: Default::default(),
         }),
         market_state.clone(),
     );
@@ -176,6 +182,7 @@ async fn test_full_pipeline_dex_arb() {
         approved_assets: vec!["USDC".to_string(), "WETH".to_string()],
         approved_chains: vec![Chain::Ethereum],
         risk_limits: RiskLimits::default(),
+        budget: Default::default(),
     };
     
     for (candidate, evaluation) in evaluations {
@@ -253,6 +260,7 @@ async fn test_full_pipeline_dex_arb() {
             execution_time_secs: 25.0,
             completed_at: Utc::now(),
             error_message: None,
+            paper: false,
         };
         
         feedback_processor.process_feedback(receipt).await.unwrap();
@@ -292,6 +300,8 @@ async fn test_pipeline_with_rejection() {
         legs: vec![],
         detected_at: Utc::now(),
         confidence: 0.9,
+        first_seen_at: None,
+        details: None,
     };
     
     // Simulate
@@ -308,6 +318,7 @@ async fn test_pipeline_with_rejection() {
         approved_assets: vec!["USDC".to_string()],
         approved_chains: vec![Chain::Ethereum],
         risk_limits: RiskLimits::default(),
+        budget: Default::default(),
     };
     
     let decision = decision_engine.decide(candidate, evaluation, &config).await.unwrap();
@@ -338,6 +349,8 @@ async fn test_performance_benchmark() {
         legs: vec![],
         detected_at: Utc::now(),
         confidence: 0.85,
+        first_seen_at: None,
+        details: None,
     }).collect();
     
     // Benchmark simulation
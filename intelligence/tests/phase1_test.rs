@@ -19,9 +19,10 @@ async fn test_market_state_ingestion() {
         chain: Chain::Ethereum,
         timestamp: Utc::now(),
         feature_type: qenus_dataplane::FeatureType::Amm,
-        data: FeatureData::Amm(AmmFeature {
+        data: FeatureData::Amm(Box::new(AmmFeature {
             pool_address: "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640".to_string(),
             pool_type: "uniswap_v3".to_string(),
+            pool_id: None,
             token0: TokenInfo {
                 address: "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".to_string(),
                 symbol: "WETH".to_string(),
@@ -43,10 +44,11 @@ async fn test_market_state_ingestion() {
             liquidity: "1000000.0".to_string(),
             depth: DepthCurve {
                 sizes: HashMap::new(),
+                ..Default::default()
             },
             volume_24h: None,
             fees_24h: None,
-        }),
+        })),
         source: "test".to_string(),
         version: "1.0".to_string(),
     };
@@ -79,6 +81,7 @@ async fn test_detector_manager_creation() {
         approved_assets: vec!["WETH".to_string(), "USDC".to_string()],
         approved_chains: vec![Chain::Ethereum, Chain::Arbitrum],
         risk_limits: Default::default(),
+        budget: Default::default(),
     };
     
     // Create detector manager